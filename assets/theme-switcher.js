@@ -8,6 +8,12 @@ const AVAILABLE_THEMES = [
    'swiss', 'swiss-dark'
 ];
 
+// Pseudo-theme that follows the OS color scheme (no data-theme attribute)
+const AUTO_THEME = 'auto';
+
+// Cookie read by the server to render the saved theme without a flash
+const THEME_COOKIE = 'theme';
+
 /**
  * Changes the current theme
  * @param {string} theme - The theme name to apply
@@ -46,13 +52,14 @@ function changeTheme(theme, persist = true) {
             }
         });
 
-        // Persist theme to localStorage if requested
+        // Persist theme to localStorage and the server cookie if requested
         if (persist) {
             try {
                 localStorage.setItem('daisy-theme', normalizedTheme);
             } catch (storageError) {
                 console.warn('Theme switcher: Could not save theme to localStorage:', storageError);
             }
+            document.cookie = `${THEME_COOKIE}=${normalizedTheme}; path=/; max-age=31536000; SameSite=Lax`;
         }
 
         // Dispatch custom event for other components to listen to
@@ -69,12 +76,37 @@ function changeTheme(theme, persist = true) {
     }
 }
 
+/**
+ * Switches to the "auto" theme: forgets the saved theme (localStorage and cookie)
+ * and removes data-theme so the stylesheet follows prefers-color-scheme
+ */
+function useAutoTheme() {
+    const previousTheme = getCurrentTheme();
+
+    try {
+        localStorage.removeItem('daisy-theme');
+    } catch (storageError) {
+        console.warn('Theme switcher: Could not clear theme from localStorage:', storageError);
+    }
+    document.cookie = `${THEME_COOKIE}=; path=/; max-age=0; SameSite=Lax`;
+
+    document.documentElement.removeAttribute('data-theme');
+    document.body.removeAttribute('data-theme');
+    document.querySelectorAll('input.theme-controller').forEach(controller => {
+        controller.checked = false;
+    });
+
+    document.dispatchEvent(new CustomEvent('themeChanged', {
+        detail: { theme: AUTO_THEME, previousTheme }
+    }));
+}
+
 /**
  * Gets the current active theme
- * @returns {string} - The current theme name
+ * @returns {string} - The current theme name, or "auto" when none is set
  */
 function getCurrentTheme() {
-    return document.documentElement.getAttribute('data-theme') || 'swiss';
+    return document.documentElement.getAttribute('data-theme') || AUTO_THEME;
 }
 
 /**
@@ -92,16 +124,14 @@ function getSavedTheme() {
 
 /**
  * Initializes the theme system
- * Loads the saved theme or applies a default theme
+ * Loads the saved theme, otherwise keeps the theme the server rendered
+ * (no data-theme means "auto")
  */
 function initializeTheme() {
     const savedTheme = getSavedTheme();
-    const defaultTheme = 'swiss'; // Swiss design as default
 
     if (savedTheme && AVAILABLE_THEMES.includes(savedTheme)) {
-        changeTheme(savedTheme, false); // Don't persist since it's already saved
-    } else {
-        changeTheme(defaultTheme, true);
+        changeTheme(savedTheme, true); // Re-persist so the server cookie stays in sync
     }
     
     // Initialize toggle state after theme is set
@@ -109,14 +139,21 @@ function initializeTheme() {
 }
 
 /**
- * Toggles between Swiss light and dark themes only
+ * Cycles Swiss light -> Swiss dark -> auto (follow the OS) -> Swiss light
  */
 function toggleTheme() {
     const currentTheme = getCurrentTheme();
-    const newTheme = currentTheme === 'swiss-dark' ? 'swiss' : 'swiss-dark';
-    
-    changeTheme(newTheme);
-    
+
+    if (currentTheme === 'swiss-dark') {
+        useAutoTheme();
+    } else if (currentTheme === AUTO_THEME) {
+        changeTheme('swiss');
+    } else {
+        changeTheme('swiss-dark');
+    }
+
+    const newTheme = getCurrentTheme();
+
     // Update the checkbox state to match the theme
     const themeToggle = document.getElementById('theme-toggle');
     if (themeToggle) {
@@ -194,11 +231,13 @@ if (typeof module !== 'undefined' && module.exports) {
         initializeTheme,
         toggleDarkMode,
         toggleTheme,
+        useAutoTheme,
         initializeThemeToggle,
         applyRandomTheme,
         getRandomTheme,
         getAvailableThemes,
-        AVAILABLE_THEMES
+        AVAILABLE_THEMES,
+        AUTO_THEME
     };
 }
 
@@ -209,6 +248,7 @@ window.getSavedTheme = getSavedTheme;
 window.initializeTheme = initializeTheme;
 window.toggleDarkMode = toggleDarkMode;
 window.toggleTheme = toggleTheme;
+window.useAutoTheme = useAutoTheme;
 window.initializeThemeToggle = initializeThemeToggle;
 window.applyRandomTheme = applyRandomTheme;
 window.getRandomTheme = getRandomTheme;
@@ -0,0 +1,221 @@
+//! Webhook ingestion benchmarks.
+//!
+//! The payload benchmarks run anywhere. The end-to-end ingestion and filter
+//! query benchmarks need a PostgreSQL database and only compile with the
+//! `bench-db` feature:
+//!
+//! ```text
+//! cargo bench --bench ingest
+//! DATABASE_URL=postgres://... cargo bench --bench ingest --features bench-db
+//! ```
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use hmac::{Hmac, Mac};
+use serde_json::Value as JsonValue;
+use sha2::Sha256;
+use uuid::Uuid;
+
+use cross_bow::services::convert_github_webhook_to_event;
+use cross_bow::utils::verify_github_signature;
+
+const SECRET: &str = "bench_secret";
+
+/// A GitHub push delivery with three commits, roughly the size of a typical push
+fn push_payload() -> JsonValue {
+    let commits: Vec<JsonValue> = (0..3)
+        .map(|i| {
+            serde_json::json!({
+                "id": format!("{i:0>40}"),
+                "message": format!("Fix flaky deploy step {i}"),
+                "timestamp": "2026-10-16T12:00:00Z",
+                "url": format!("https://github.com/octocat/Hello-World/commit/{i:0>40}"),
+                "author": { "name": "Octo Cat", "email": "octocat@github.com" },
+                "committer": { "name": "Octo Cat", "email": "octocat@github.com" },
+                "added": [],
+                "removed": [],
+                "modified": ["src/deploy.rs", "README.md"]
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "ref": "refs/heads/main",
+        "before": "0".repeat(40),
+        "after": format!("{:0>40}", 2),
+        "repository": {
+            "id": 1296269,
+            "name": "Hello-World",
+            "full_name": "octocat/Hello-World",
+            "owner": { "login": "octocat" },
+            "html_url": "https://github.com/octocat/Hello-World",
+            "description": "My first repository on GitHub!",
+            "private": false
+        },
+        "pusher": { "name": "octocat", "email": "octocat@github.com" },
+        "sender": {
+            "login": "octocat",
+            "id": 1,
+            "avatar_url": "https://github.com/images/error/octocat_happy.gif"
+        },
+        "commits": commits,
+        "head_commit": { "message": "Fix flaky deploy step 2" }
+    })
+}
+
+fn sign(body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// The CPU-bound part of ingestion: verify, parse and convert a delivery
+fn bench_payload(c: &mut Criterion) {
+    let body = serde_json::to_vec(&push_payload()).unwrap();
+    let signature = sign(&body);
+
+    let mut group = c.benchmark_group("payload");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("verify_parse_convert_push", |b| {
+        b.iter(|| {
+            assert!(verify_github_signature(SECRET, &body, &signature));
+            let payload: JsonValue = serde_json::from_slice(&body).unwrap();
+            convert_github_webhook_to_event(
+                "push".to_string(),
+                None,
+                payload,
+                Uuid::new_v4(),
+                Some(signature.clone()),
+                None,
+            )
+        })
+    });
+    group.finish();
+}
+
+#[cfg(feature = "bench-db")]
+mod db {
+    use actix_web::rt::{System, SystemRunner};
+    use actix_web::{test as actix_test, web, App};
+    use criterion::{BatchSize, Criterion, Throughput};
+    use secrecy::SecretString;
+    use sqlx::PgPool;
+
+    use cross_bow::config::Config;
+    use cross_bow::handlers::github_webhook;
+    use cross_bow::models::{Event, EventFilterBuilder, FilterTable};
+    use cross_bow::services::{Metrics, OrderedDispatcher, RepositoryCache, SchemaRegistry};
+
+    use super::{push_payload, sign, SECRET};
+
+    /// Events seeded before measuring the filter query
+    const SEEDED_EVENTS: i64 = 10_000;
+
+    fn connect(system: &SystemRunner) -> PgPool {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL is required for bench-db");
+        system
+            .block_on(cross_bow::db::create_pool(&url, 5))
+            .expect("Failed to connect to the benchmark database")
+    }
+
+    /// Full `POST /webhooks/github` round trip: signature check, both inserts and dispatch
+    pub fn bench_ingest(c: &mut Criterion) {
+        let system = System::new();
+        let pool = connect(&system);
+
+        let mut config = Config::test_default();
+        config.github_webhook_secret = SecretString::new(SECRET.to_string());
+
+        let app = system.block_on(actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(0)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .route("/webhooks/github", web::post().to(github_webhook)),
+        ));
+
+        let body = serde_json::to_vec(&push_payload()).unwrap();
+        let signature = sign(&body);
+
+        let mut group = c.benchmark_group("ingest");
+        group.throughput(Throughput::Elements(1));
+        group.bench_function("github_push", |b| {
+            b.iter_batched(
+                || {
+                    // Delivery ids are unique, so every request needs a fresh one
+                    actix_test::TestRequest::post()
+                        .uri("/webhooks/github")
+                        .insert_header(("X-GitHub-Event", "push"))
+                        .insert_header(("X-GitHub-Delivery", uuid::Uuid::new_v4().to_string()))
+                        .insert_header(("X-Hub-Signature-256", signature.as_str()))
+                        .set_payload(body.clone())
+                        .to_request()
+                },
+                |req| {
+                    let resp = system.block_on(actix_test::call_service(&app, req));
+                    assert!(resp.status().is_success());
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.finish();
+    }
+
+    /// The events page select and count over a seeded table
+    pub fn bench_filter_query(c: &mut Criterion) {
+        let system = System::new();
+        let pool = connect(&system);
+
+        system
+            .block_on(
+                sqlx::query(
+                    r#"
+                    INSERT INTO events (source, event_type, action, actor_name, raw_event, delivery_id)
+                    SELECT
+                        CASE WHEN n % 4 = 0 THEN 'gitlab' ELSE 'github' END,
+                        CASE WHEN n % 3 = 0 THEN 'issues' ELSE 'push' END,
+                        CASE WHEN n % 3 = 0 THEN 'opened' END,
+                        'actor-' || (n % 50),
+                        jsonb_build_object('message', 'deploy ' || n),
+                        gen_random_uuid()
+                    FROM generate_series(1, $1) AS n
+                    "#,
+                )
+                .bind(SEEDED_EVENTS)
+                .execute(&pool),
+            )
+            .expect("Failed to seed events");
+
+        let filters = EventFilterBuilder::new(FilterTable::Events)
+            .source(Some("github"))
+            .event_type(Some("push"))
+            .search(Some("deploy 42"));
+
+        let mut group = c.benchmark_group("filter");
+        group.bench_function("select_and_count", |b| {
+            b.iter(|| {
+                system.block_on(async {
+                    let events = Event::search_and_filter(&pool, &filters, 300, 0)
+                        .await
+                        .unwrap();
+                    let total = Event::count_filtered(&pool, &filters).await.unwrap();
+                    (events, total)
+                })
+            })
+        });
+        group.finish();
+    }
+}
+
+#[cfg(feature = "bench-db")]
+criterion_group!(
+    benches,
+    bench_payload,
+    db::bench_ingest,
+    db::bench_filter_query
+);
+#[cfg(not(feature = "bench-db"))]
+criterion_group!(benches, bench_payload);
+criterion_main!(benches);
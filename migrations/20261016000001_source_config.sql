@@ -0,0 +1,9 @@
+-- Per-source webhook verification settings managed from the admin interface
+CREATE TABLE source_config (
+    id BIGSERIAL PRIMARY KEY,
+    source VARCHAR(50) NOT NULL UNIQUE,
+    signature_mode VARCHAR(50) NOT NULL DEFAULT 'none', -- none, hmac_sha256, token
+    secret TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,4 @@
+-- Optional tenant for webhooks received on /webhook/{tenant}/{source}
+ALTER TABLE events ADD COLUMN tenant VARCHAR(100);
+
+CREATE INDEX idx_events_tenant ON events(tenant);
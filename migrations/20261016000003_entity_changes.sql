@@ -0,0 +1,13 @@
+-- Audit trail of field changes to tracked GitHub entities (pull requests, issues)
+CREATE TABLE entity_changes (
+    id BIGSERIAL PRIMARY KEY,
+    entity_type VARCHAR(50) NOT NULL, -- pull_request, issue
+    entity_id BIGINT NOT NULL,
+    field VARCHAR(100) NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    event_id BIGINT REFERENCES events(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_entity_changes_entity ON entity_changes(entity_type, entity_id);
@@ -0,0 +1,2 @@
+-- Avatar URL of the event actor, when the source provides one
+ALTER TABLE events ADD COLUMN actor_avatar_url VARCHAR(500);
@@ -0,0 +1,19 @@
+-- Publish new events on the `events_inserted` channel for LISTEN/NOTIFY consumers
+CREATE OR REPLACE FUNCTION notify_event_inserted() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify(
+        'events_inserted',
+        json_build_object(
+            'id', NEW.id,
+            'source', NEW.source,
+            'event_type', NEW.event_type,
+            'tenant', NEW.tenant
+        )::text
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER events_notify_insert
+    AFTER INSERT ON events
+    FOR EACH ROW EXECUTE FUNCTION notify_event_inserted();
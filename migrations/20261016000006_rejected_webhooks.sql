@@ -0,0 +1,14 @@
+-- Webhooks rejected before storage (bad signature, malformed JSON), kept for diagnosing senders
+CREATE TABLE rejected_webhooks (
+    id BIGSERIAL PRIMARY KEY,
+    source VARCHAR(50) NOT NULL,
+    tenant VARCHAR(100),
+    reason TEXT NOT NULL,
+    client_ip VARCHAR(100),
+    headers JSONB NOT NULL DEFAULT '{}'::jsonb,
+    body TEXT NOT NULL, -- truncated to a fixed size by the application
+    body_truncated BOOLEAN NOT NULL DEFAULT FALSE,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_rejected_webhooks_received_at ON rejected_webhooks(received_at DESC);
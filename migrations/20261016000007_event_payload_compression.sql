@@ -0,0 +1,13 @@
+-- Optional gzip-compressed payload storage. Compressed rows leave raw_event NULL and
+-- keep a tsvector of the payload's string and numeric values for search instead.
+ALTER TABLE events ALTER COLUMN raw_event DROP NOT NULL;
+ALTER TABLE events ADD COLUMN raw_event_gz BYTEA;
+ALTER TABLE events ADD COLUMN raw_event_compressed BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE events ADD COLUMN raw_event_search TSVECTOR;
+
+ALTER TABLE events ADD CONSTRAINT events_raw_event_present CHECK (
+    (raw_event_compressed AND raw_event_gz IS NOT NULL)
+    OR (NOT raw_event_compressed AND raw_event IS NOT NULL)
+);
+
+CREATE INDEX idx_events_raw_event_search ON events USING gin(raw_event_search);
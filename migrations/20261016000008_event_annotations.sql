@@ -0,0 +1,19 @@
+-- Operator triage annotations on events
+CREATE TABLE event_tags (
+    event_id BIGINT NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    tag VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, tag)
+);
+
+CREATE INDEX idx_event_tags_tag ON event_tags(tag);
+
+CREATE TABLE event_notes (
+    id BIGSERIAL PRIMARY KEY,
+    event_id BIGINT NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    note TEXT NOT NULL,
+    author VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_event_notes_event ON event_notes(event_id, created_at);
@@ -0,0 +1,2 @@
+-- Why processing an event failed; failed events stay unprocessed
+ALTER TABLE events ADD COLUMN processing_error TEXT;
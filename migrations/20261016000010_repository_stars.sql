@@ -0,0 +1,13 @@
+-- Star/unstar history for popularity tracking
+ALTER TABLE repositories ADD COLUMN stars INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE repository_stars (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL UNIQUE REFERENCES events(id) ON DELETE CASCADE,
+    action VARCHAR(20) NOT NULL,
+    actor VARCHAR(255) NOT NULL,
+    at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_repository_stars_repository ON repository_stars(repository_id, at DESC);
@@ -0,0 +1,3 @@
+-- The `{source}` path segment a webhook arrived on, before SOURCE_ALIASES mapped it
+-- to the canonical source stored in `source`
+ALTER TABLE events ADD COLUMN raw_source TEXT;
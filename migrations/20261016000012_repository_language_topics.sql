@@ -0,0 +1,5 @@
+-- Primary language and topics reported on GitHub repository objects
+ALTER TABLE repositories ADD COLUMN language TEXT;
+ALTER TABLE repositories ADD COLUMN topics TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_repositories_topics ON repositories USING GIN (topics);
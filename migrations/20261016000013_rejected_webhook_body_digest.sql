@@ -0,0 +1,4 @@
+-- Full length and SHA-256 of a rejected body; comparing the digest with what the sender
+-- signed tells proxy tampering apart from a secret mismatch
+ALTER TABLE rejected_webhooks ADD COLUMN body_bytes BIGINT;
+ALTER TABLE rejected_webhooks ADD COLUMN body_sha256 TEXT;
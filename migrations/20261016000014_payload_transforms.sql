@@ -0,0 +1,5 @@
+-- Optional built-in transform (e.g. unwrap:data) applied to a source's payloads before storage
+ALTER TABLE source_config ADD COLUMN transform TEXT;
+
+-- The payload as received, kept only when a transform reshaped raw_event
+ALTER TABLE events ADD COLUMN raw_original JSONB;
@@ -0,0 +1,5 @@
+-- SHA-256 of each delivery's body, so a retry that arrives under a new delivery id
+-- can be recognised as a duplicate
+ALTER TABLE events ADD COLUMN payload_sha256 TEXT;
+
+CREATE INDEX idx_events_payload_sha256 ON events(payload_sha256, received_at DESC);
@@ -0,0 +1,5 @@
+-- Branch a commit was first pushed to, and the pull request opened from that branch
+ALTER TABLE commits ADD COLUMN branch TEXT;
+ALTER TABLE commits ADD COLUMN pull_request_id BIGINT REFERENCES pull_requests(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_commits_repo_branch ON commits(repository_id, branch);
@@ -0,0 +1,10 @@
+-- Processed events past the configured age can drop their payload. Compacted rows keep
+-- payload_sha256 for reference and record when the payload was removed.
+ALTER TABLE events ADD COLUMN raw_event_compacted_at TIMESTAMPTZ;
+
+ALTER TABLE events DROP CONSTRAINT events_raw_event_present;
+ALTER TABLE events ADD CONSTRAINT events_raw_event_present CHECK (
+    raw_event_compacted_at IS NOT NULL
+    OR (raw_event_compressed AND raw_event_gz IS NOT NULL)
+    OR (NOT raw_event_compressed AND raw_event IS NOT NULL)
+);
@@ -0,0 +1,21 @@
+-- Batches of stored events re-sent to the FORWARD_URLS targets at a fixed rate.
+-- Filters use the same comma-separated lists as the events page; events are replayed
+-- in id order up to max_event_id, so events arriving later do not extend a job.
+CREATE TABLE replay_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    source VARCHAR(255),
+    event_type VARCHAR(255),
+    action VARCHAR(255),
+    rate_per_minute INTEGER NOT NULL CHECK (rate_per_minute > 0),
+    status VARCHAR(20) NOT NULL DEFAULT 'pending', -- pending, running, completed
+    max_event_id BIGINT NOT NULL,
+    total_events BIGINT NOT NULL,
+    last_event_id BIGINT NOT NULL DEFAULT 0,
+    replayed_events BIGINT NOT NULL DEFAULT 0,
+    failed_events BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_replay_jobs_status ON replay_jobs(status, id);
@@ -0,0 +1,6 @@
+-- Globs marking the paths of a repository worth following, e.g. one service of a monorepo
+ALTER TABLE repositories ADD COLUMN watched_paths TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE repositories ADD COLUMN skip_unwatched_commits BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Whether a commit changed a watched path; NULL when the repository watches no paths
+ALTER TABLE commits ADD COLUMN touches_watched BOOLEAN;
@@ -0,0 +1,12 @@
+-- Former names of renamed repositories, so lookups by an old full name still resolve
+CREATE TABLE repository_renames (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL UNIQUE REFERENCES events(id) ON DELETE CASCADE,
+    old_full_name VARCHAR(255) NOT NULL,
+    new_full_name VARCHAR(255) NOT NULL,
+    renamed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_repository_renames_old_full_name ON repository_renames(old_full_name);
+CREATE INDEX idx_repository_renames_repository ON repository_renames(repository_id, renamed_at DESC);
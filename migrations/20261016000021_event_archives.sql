@@ -0,0 +1,14 @@
+-- Contiguous id ranges of events exported to the object storage archive
+CREATE TABLE event_archives (
+    id BIGSERIAL PRIMARY KEY,
+    object_key TEXT NOT NULL UNIQUE,
+    first_event_id BIGINT NOT NULL,
+    last_event_id BIGINT NOT NULL,
+    event_count BIGINT NOT NULL,
+    -- Events received before this were eligible when the range was archived
+    cutoff TIMESTAMPTZ NOT NULL,
+    pruned BOOLEAN NOT NULL DEFAULT FALSE,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_event_archives_last_event_id ON event_archives(last_event_id DESC);
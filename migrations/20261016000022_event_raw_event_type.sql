@@ -0,0 +1,3 @@
+-- The event type as the sender named it (header or payload), before EVENT_TYPE_ALIASES
+-- mapped it to the canonical type stored in `event_type`
+ALTER TABLE events ADD COLUMN raw_event_type TEXT;
@@ -0,0 +1,3 @@
+-- GitHub lists at most 20 commits in a push payload; set on processed push events to
+-- whether the push contained more commits than it listed
+ALTER TABLE events ADD COLUMN commits_truncated BOOLEAN;
@@ -0,0 +1,7 @@
+-- List queries break received_at ties by id so pages stay stable when many events share a
+-- timestamp; index both so those queries keep reading the index in order
+DROP INDEX IF EXISTS idx_events_received;
+CREATE INDEX idx_events_received ON events(received_at DESC, id DESC);
+
+DROP INDEX IF EXISTS idx_webhook_events_received;
+CREATE INDEX idx_webhook_events_received ON webhook_events(received_at DESC, id DESC);
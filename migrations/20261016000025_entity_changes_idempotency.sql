@@ -0,0 +1,12 @@
+-- Reprocessing or replaying an event must not record its field changes twice; keep the
+-- first of any duplicates already recorded, then key changes by event, entity and field
+DELETE FROM entity_changes a
+USING entity_changes b
+WHERE a.event_id = b.event_id
+  AND a.entity_type = b.entity_type
+  AND a.entity_id = b.entity_id
+  AND a.field = b.field
+  AND a.id > b.id;
+
+CREATE UNIQUE INDEX idx_entity_changes_event_field
+    ON entity_changes(event_id, entity_type, entity_id, field);
@@ -0,0 +1,2 @@
+-- Content type a source must send (e.g. application/json); other deliveries get 415
+ALTER TABLE source_config ADD COLUMN expected_content_type TEXT;
@@ -0,0 +1,19 @@
+-- GitHub deployments and the latest deployment_status reported for each
+CREATE TABLE deployments (
+    id BIGSERIAL PRIMARY KEY,
+    repository_id BIGINT NOT NULL REFERENCES repositories(id) ON DELETE CASCADE,
+    event_id BIGINT REFERENCES events(id) ON DELETE SET NULL,
+    github_id BIGINT NOT NULL UNIQUE,
+    environment VARCHAR(255) NOT NULL,
+    git_ref VARCHAR(255) NOT NULL,
+    sha VARCHAR(64) NOT NULL,
+    creator VARCHAR(255) NOT NULL,
+    state VARCHAR(50),
+    url TEXT,
+    deployed_at TIMESTAMPTZ NOT NULL,
+    status_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_deployments_repository ON deployments(repository_id, deployed_at DESC);
@@ -0,0 +1,3 @@
+-- Values of the configured SEARCH_FIELDS paths, extracted when an event is stored.
+-- Search matches these instead of the whole payload; NULL for events stored without them.
+ALTER TABLE events ADD COLUMN searchable_text TEXT;
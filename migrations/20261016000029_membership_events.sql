@@ -0,0 +1,16 @@
+-- Append-only log of GitHub member, membership and organization events for access
+-- audits. Rows outlive the events they came from, so pruning events keeps the trail.
+CREATE TABLE membership_events (
+    id BIGSERIAL PRIMARY KEY,
+    event_id BIGINT UNIQUE REFERENCES events(id) ON DELETE SET NULL,
+    action VARCHAR(50) NOT NULL,
+    member VARCHAR(255) NOT NULL,
+    scope_type VARCHAR(20) NOT NULL,
+    scope VARCHAR(255) NOT NULL,
+    organization VARCHAR(255),
+    actor VARCHAR(255) NOT NULL,
+    at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_membership_events_at ON membership_events(at DESC, id DESC);
+CREATE INDEX idx_membership_events_member ON membership_events(member, at DESC, id DESC);
@@ -0,0 +1,7 @@
+-- When the row was inserted, as opposed to received_at (when the HTTP request arrived).
+-- The two drift apart under queueing and for imported or backfilled events.
+-- Existing rows take their received_at, since their insert time was not recorded.
+ALTER TABLE events ADD COLUMN created_at TIMESTAMPTZ;
+UPDATE events SET created_at = received_at;
+ALTER TABLE events ALTER COLUMN created_at SET DEFAULT NOW();
+ALTER TABLE events ALTER COLUMN created_at SET NOT NULL;
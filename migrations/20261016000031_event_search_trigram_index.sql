@@ -0,0 +1,7 @@
+-- Trigram index on the expression the events search matches with ILIKE '%term%', so
+-- searches over searchable_text (or the payload of events stored without it) use an
+-- index instead of scanning every row.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_events_search_text_trgm ON events
+    USING gin ((COALESCE(searchable_text, raw_event::text)) gin_trgm_ops);
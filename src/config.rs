@@ -1,37 +1,676 @@
+use std::collections::HashMap;
 use std::env;
+use std::net::IpAddr;
+
+use secrecy::{ExposeSecret, SecretString};
+use serde_json::Value as JsonValue;
+
+use crate::handlers::layout::Theme;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub host: String,
     pub port: u16,
-    pub database_url: String,
-    pub github_webhook_secret: String,
+    /// Accept cleartext HTTP/2 (h2c) next to HTTP/1.1 on the same port
+    pub http2: bool,
+    /// Milliseconds a client has to send the request head; 0 waits forever
+    pub client_request_timeout_ms: u64,
+    /// Milliseconds a client has to send a webhook body once its head arrived; 0 waits forever
+    pub body_read_timeout_ms: u64,
+    /// May embed database credentials, so it is kept secret like the webhook secrets
+    pub database_url: SecretString,
+    pub github_webhook_secret: SecretString,
+    /// HMAC secret for Gitea/Gogs webhooks; their deliveries are rejected until it is set
+    pub gitea_webhook_secret: Option<SecretString>,
+    /// Verifies `X-Auth0-Signature` on `/webhook/auth0`; Auth0 events are unauthenticated when unset
+    pub auth0_webhook_secret: Option<SecretString>,
     pub max_connections: u32,
+    /// How often the `db_pool_*` gauges are refreshed; 0 disables them
+    pub pool_metrics_interval_secs: u64,
+    /// Per-source response bodies returned when a webhook is accepted
+    pub ack_templates: HashMap<String, JsonValue>,
+    /// Store the complete webhook payload in `events.raw_event`
+    pub store_full_payload: bool,
+    /// Extra dotted payload paths kept when full payload storage is disabled
+    pub payload_field_allowlist: Vec<String>,
+    /// Dotted payload paths (`[]` walks arrays) copied into `events.searchable_text` and
+    /// searched instead of the whole payload; empty searches the whole payload
+    pub search_fields: Vec<String>,
+    /// `X-GitHub-Event` types accepted on `/webhooks/github`; empty accepts all
+    pub github_allowed_events: Vec<String>,
+    /// Enforce webhook signatures. Only for replaying captured payloads in staging: when
+    /// false anyone can post events to every source.
+    pub verify_signatures: bool,
+    /// Skip storing a delivery whose body matches an event from the same source within the window
+    pub dedup_by_payload: bool,
+    /// How far back `dedup_by_payload` looks for an identical body
+    pub dedup_window_secs: u64,
+    /// Store payloads gzip-compressed in `events.raw_event_gz` instead of JSONB
+    pub compress_payloads: bool,
+    pub admin_username: String,
+    /// Admin pages are disabled unless a password is configured
+    pub admin_password: Option<SecretString>,
+    /// Bearer key required by mutating `/api` routes; they are disabled unless it is set
+    pub admin_api_key: Option<SecretString>,
+    /// Directory of JSON Schemas used to validate incoming payloads
+    pub schema_dir: Option<String>,
+    /// Processing lag alerts are POSTed here; unset disables the lag monitor
+    pub alert_webhook_url: Option<SecretString>,
+    /// Alert when more events than this are waiting to be processed
+    pub alert_max_pending: i64,
+    /// Alert when the oldest pending event has waited longer than this
+    pub alert_max_pending_age_secs: u64,
+    /// Minimum time between two lag alerts
+    pub alert_cooldown_secs: u64,
+    /// `/readyz` reports not ready while more events than this are pending; 0 ignores the backlog
+    pub ready_max_pending: i64,
+    /// Worker shards used to process events for the same entity in order (0 disables ordering)
+    pub processing_shards: usize,
+    /// A source with no events for this many seconds is reported as stale
+    pub source_stale_after_secs: u64,
+    /// Fail processing of GitHub event types without a handler instead of marking them processed
+    pub strict_processing: bool,
+    /// Sources whose events are processed before the webhook response instead of on the dispatcher
+    pub sync_processing_sources: Vec<String>,
+    /// Largest `per_page` accepted by `/api/events`, independent of the events page cap
+    pub api_max_per_page: i64,
+    /// Milliseconds the events page waits for its search before giving up; 0 waits forever
+    pub search_timeout_ms: u64,
+    /// Webhook payloads nested deeper than this are rejected with 400
+    pub max_json_depth: usize,
+    /// Webhook deliveries accepted per caller IP in each window; 0 disables the limit
+    pub webhook_rate_limit: u32,
+    pub webhook_rate_limit_window_secs: u64,
+    /// Proxies whose `X-Forwarded-For` names the caller for rate limiting; other peers
+    /// are limited by their own address whatever headers they send
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Canonical sources accepted on `/webhook/{source}`; empty accepts any source
+    pub allowed_sources: Vec<String>,
+    /// Webhook path sources mapped to the canonical source events are stored under
+    pub source_aliases: HashMap<String, String>,
+    /// Per source, sender event type names mapped to the canonical type stored in `event_type`
+    pub event_type_aliases: HashMap<String, HashMap<String, String>>,
+    /// Days to keep each source's events; sources without an entry are kept forever
+    pub retention_days: HashMap<String, u32>,
+    /// Days after which processed events lose their raw payload; `None` keeps payloads
+    pub compact_payloads_after_days: Option<u32>,
+    /// Downstream URLs replay jobs POST events to; replay is disabled when empty
+    pub forward_urls: Vec<String>,
+    /// Page theme rendered when the visitor has no saved preference
+    pub default_theme: Theme,
+    /// Per-route default themes as `(path prefix, theme)`; the longest matching prefix wins
+    pub route_themes: Vec<(String, Theme)>,
+    /// Page served at `/`
+    pub home_route: HomeRoute,
+    /// Bucket old events are archived to as NDJSON; archiving is disabled when unset
+    pub archive_s3_bucket: Option<String>,
+    /// Endpoint of an S3-compatible store such as MinIO; AWS when unset
+    pub archive_s3_endpoint: Option<String>,
+    pub archive_s3_region: String,
+    /// Credentials for the archive bucket; the standard `AWS_*` variables apply when unset
+    pub archive_s3_access_key_id: Option<String>,
+    pub archive_s3_secret_access_key: Option<SecretString>,
+    /// Key prefix of archive objects within the bucket
+    pub archive_s3_prefix: String,
+    /// Events received more than this many days ago are archived
+    pub archive_after_days: u32,
+    /// Delete events from the database once their archive object is written
+    pub archive_prune: bool,
+}
+
+/// What `/` shows: the dashboard itself, or a redirect to another page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HomeRoute {
+    #[default]
+    Dashboard,
+    Events,
+    Repositories,
+}
+
+impl HomeRoute {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dashboard" => Some(HomeRoute::Dashboard),
+            "events" => Some(HomeRoute::Events),
+            "repositories" => Some(HomeRoute::Repositories),
+            _ => None,
+        }
+    }
+
+    /// Page `/` redirects to; `None` renders the dashboard in place
+    pub fn redirect_path(self) -> Option<&'static str> {
+        match self {
+            HomeRoute::Dashboard => None,
+            HomeRoute::Events => Some("/events"),
+            HomeRoute::Repositories => Some("/repositories"),
+        }
+    }
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, ConfigError> {
         dotenvy::dotenv().ok();
 
-        Ok(Config {
+        let config = Config {
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             port: env::var("PORT")
                 .unwrap_or_else(|_| "3010".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidPort)?,
-            database_url: env::var("DATABASE_URL").map_err(|_| ConfigError::MissingDatabaseUrl)?,
+            http2: Self::parse_bool("HTTP2", false)?,
+            client_request_timeout_ms: env::var("CLIENT_REQUEST_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .unwrap_or(5000),
+            body_read_timeout_ms: env::var("BODY_READ_TIMEOUT_MS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            database_url: env::var("DATABASE_URL")
+                .map(SecretString::new)
+                .map_err(|_| ConfigError::MissingDatabaseUrl)?,
             github_webhook_secret: env::var("GITHUB_WEBHOOK_SECRET")
+                .map(SecretString::new)
                 .map_err(|_| ConfigError::MissingWebhookSecret)?,
+            gitea_webhook_secret: env::var("GITEA_WEBHOOK_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(SecretString::new),
+            auth0_webhook_secret: env::var("AUTH0_WEBHOOK_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(SecretString::new),
+            pool_metrics_interval_secs: env::var("POOL_METRICS_INTERVAL_SECS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .unwrap_or(15),
             max_connections: env::var("MAX_CONNECTIONS")
                 .unwrap_or_else(|_| "5".to_string())
                 .parse()
                 .unwrap_or(5),
-        })
+            ack_templates: match env::var("WEBHOOK_ACK_TEMPLATES") {
+                Ok(raw) => {
+                    serde_json::from_str(&raw).map_err(|_| ConfigError::InvalidAckTemplates)?
+                }
+                Err(_) => HashMap::new(),
+            },
+            store_full_payload: Self::parse_bool("STORE_FULL_PAYLOAD", true)?,
+            payload_field_allowlist: env::var("PAYLOAD_FIELD_ALLOWLIST")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(|f| f.trim().to_string())
+                        .filter(|f| !f.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            search_fields: env::var("SEARCH_FIELDS")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(|f| f.trim().to_string())
+                        .filter(|f| !f.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            github_allowed_events: env::var("GITHUB_ALLOWED_EVENTS")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(|e| e.trim().to_string())
+                        .filter(|e| !e.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            verify_signatures: Self::parse_bool("VERIFY_SIGNATURES", true)?,
+            dedup_by_payload: Self::parse_bool("DEDUP_BY_PAYLOAD", false)?,
+            dedup_window_secs: env::var("DEDUP_WINDOW_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            compress_payloads: Self::parse_bool("COMPRESS_PAYLOADS", false)?,
+            admin_username: env::var("ADMIN_USERNAME").unwrap_or_else(|_| "admin".to_string()),
+            admin_password: env::var("ADMIN_PASSWORD")
+                .ok()
+                .filter(|p| !p.is_empty())
+                .map(SecretString::new),
+            admin_api_key: env::var("ADMIN_API_KEY")
+                .ok()
+                .filter(|k| !k.is_empty())
+                .map(SecretString::new),
+            schema_dir: env::var("PAYLOAD_SCHEMA_DIR").ok(),
+            alert_webhook_url: env::var("ALERT_WEBHOOK_URL")
+                .ok()
+                .filter(|u| !u.is_empty())
+                .map(SecretString::new),
+            alert_max_pending: env::var("ALERT_MAX_PENDING")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            alert_max_pending_age_secs: env::var("ALERT_MAX_PENDING_AGE_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .unwrap_or(900),
+            alert_cooldown_secs: env::var("ALERT_COOLDOWN_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            ready_max_pending: env::var("READY_MAX_PENDING")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            processing_shards: env::var("PROCESSING_SHARDS")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .unwrap_or(8),
+            source_stale_after_secs: env::var("SOURCE_STALE_AFTER_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .unwrap_or(86400),
+            strict_processing: Self::parse_bool("STRICT_PROCESSING", false)?,
+            sync_processing_sources: env::var("SYNC_PROCESSING")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            api_max_per_page: env::var("API_MAX_PER_PAGE")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse::<i64>()
+                .unwrap_or(1000)
+                .max(1),
+            search_timeout_ms: env::var("SEARCH_TIMEOUT_MS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            max_json_depth: env::var("MAX_JSON_DEPTH")
+                .unwrap_or_else(|_| "64".to_string())
+                .parse()
+                .unwrap_or(64),
+            webhook_rate_limit: env::var("WEBHOOK_RATE_LIMIT")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            webhook_rate_limit_window_secs: env::var("WEBHOOK_RATE_LIMIT_WINDOW_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            trusted_proxies: match env::var("TRUSTED_PROXIES") {
+                Ok(raw) => parse_trusted_proxies(&raw)?,
+                Err(_) => Vec::new(),
+            },
+            allowed_sources: env::var("ALLOWED_SOURCES")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            source_aliases: match env::var("SOURCE_ALIASES") {
+                Ok(raw) => parse_source_aliases(&raw)?,
+                Err(_) => HashMap::new(),
+            },
+            event_type_aliases: {
+                let mut aliases = default_event_type_aliases();
+                if let Ok(raw) = env::var("EVENT_TYPE_ALIASES") {
+                    for (source, types) in parse_event_type_aliases(&raw)? {
+                        aliases.entry(source).or_default().extend(types);
+                    }
+                }
+                aliases
+            },
+            retention_days: match env::var("RETENTION_DAYS") {
+                Ok(raw) => parse_retention_days(&raw)?,
+                Err(_) => HashMap::new(),
+            },
+            compact_payloads_after_days: env::var("COMPACT_PAYLOADS_AFTER_DAYS")
+                .ok()
+                .and_then(|raw| raw.parse().ok())
+                .filter(|days| *days > 0),
+            forward_urls: env::var("FORWARD_URLS")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            default_theme: match env::var("DEFAULT_THEME") {
+                Ok(raw) => Theme::parse(&raw).ok_or(ConfigError::InvalidTheme)?,
+                Err(_) => Theme::Dark,
+            },
+            route_themes: match env::var("ROUTE_THEMES") {
+                Ok(raw) => parse_route_themes(&raw)?,
+                Err(_) => Vec::new(),
+            },
+            home_route: match env::var("HOME_ROUTE") {
+                Ok(raw) => HomeRoute::parse(&raw).ok_or(ConfigError::InvalidHomeRoute)?,
+                Err(_) => HomeRoute::Dashboard,
+            },
+            archive_s3_bucket: env::var("ARCHIVE_S3_BUCKET").ok().filter(|b| !b.is_empty()),
+            archive_s3_endpoint: env::var("ARCHIVE_S3_ENDPOINT")
+                .ok()
+                .filter(|e| !e.is_empty()),
+            archive_s3_region: env::var("ARCHIVE_S3_REGION")
+                .unwrap_or_else(|_| "us-east-1".to_string()),
+            archive_s3_access_key_id: env::var("ARCHIVE_S3_ACCESS_KEY_ID")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            archive_s3_secret_access_key: env::var("ARCHIVE_S3_SECRET_ACCESS_KEY")
+                .ok()
+                .filter(|k| !k.is_empty())
+                .map(SecretString::new),
+            archive_s3_prefix: env::var("ARCHIVE_S3_PREFIX")
+                .unwrap_or_else(|_| "events/".to_string()),
+            archive_after_days: env::var("ARCHIVE_AFTER_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse()
+                .unwrap_or(90),
+            archive_prune: Self::parse_bool("ARCHIVE_PRUNE", false)?,
+        };
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Read a boolean flag from the environment, falling back to `default` when unset
+    pub fn parse_bool(key: &str, default: bool) -> Result<bool, ConfigError> {
+        match env::var(key) {
+            Ok(raw) => parse_bool_value(key, &raw),
+            Err(_) => Ok(default),
+        }
+    }
+
+    /// Enforce invariants the server relies on before any routes are mounted
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        // The GitHub routes are always mounted, so an empty secret would make
+        // signature verification meaningless
+        if self.github_webhook_secret.expose_secret().trim().is_empty() {
+            return Err(ConfigError::EmptyWebhookSecret);
+        }
+
+        Ok(())
+    }
+
+    pub fn source_stale_after(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.source_stale_after_secs.min(i64::MAX as u64) as i64)
+    }
+
+    /// Whether `/webhooks/github` accepts this `X-GitHub-Event` type
+    pub fn github_event_allowed(&self, event_type: &str) -> bool {
+        self.github_allowed_events.is_empty()
+            || self.github_allowed_events.iter().any(|e| e == event_type)
+    }
+
+    pub fn webhook_rate_limit_window(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.webhook_rate_limit_window_secs.min(i64::MAX as u64) as i64)
+    }
+
+    /// The `searchable_text` stored with a payload; `None` when no search fields are set
+    pub fn searchable_text(&self, payload: &JsonValue) -> Option<String> {
+        (!self.search_fields.is_empty())
+            .then(|| crate::utils::searchable_text(payload, &self.search_fields))
+    }
+
+    /// The payload as stored: whole with `STORE_FULL_PAYLOAD`, otherwise pruned to the
+    /// extracted fields plus `PAYLOAD_FIELD_ALLOWLIST`
+    pub fn payload_for_storage(&self, payload: &JsonValue) -> JsonValue {
+        if self.store_full_payload {
+            payload.clone()
+        } else {
+            crate::utils::prune_payload(payload, &self.payload_field_allowlist)
+        }
+    }
+
+    pub fn client_request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.client_request_timeout_ms)
+    }
+
+    pub fn body_read_timeout(&self) -> Option<std::time::Duration> {
+        (self.body_read_timeout_ms > 0)
+            .then(|| std::time::Duration::from_millis(self.body_read_timeout_ms))
+    }
+
+    pub fn search_timeout(&self) -> Option<std::time::Duration> {
+        (self.search_timeout_ms > 0)
+            .then(|| std::time::Duration::from_millis(self.search_timeout_ms))
+    }
+
+    pub fn dedup_window(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.dedup_window_secs.min(i64::MAX as u64) as i64)
+    }
+
+    /// Whether `/webhook/{source}` accepts this canonical source
+    pub fn source_allowed(&self, source: &str) -> bool {
+        self.allowed_sources.is_empty() || self.allowed_sources.iter().any(|s| s == source)
+    }
+
+    /// Whether the source's webhooks wait for processing and report its outcome
+    pub fn processes_synchronously(&self, source: &str) -> bool {
+        self.sync_processing_sources.iter().any(|s| s == source)
+    }
+
+    /// The canonical name for a webhook path source; unaliased sources map to themselves
+    pub fn canonical_source<'a>(&'a self, source: &'a str) -> &'a str {
+        self.source_aliases
+            .get(source)
+            .map(String::as_str)
+            .unwrap_or(source)
+    }
+
+    /// The canonical name for a source's event type; unmapped types are kept as sent
+    pub fn canonical_event_type<'a>(&'a self, source: &str, event_type: &'a str) -> &'a str {
+        self.event_type_aliases
+            .get(source)
+            .and_then(|types| types.get(event_type))
+            .map(String::as_str)
+            .unwrap_or(event_type)
+    }
+
+    /// Default theme for a request path, honoring per-route overrides
+    pub fn theme_for(&self, path: &str) -> Theme {
+        self.route_themes
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, theme)| *theme)
+            .unwrap_or(self.default_theme)
     }
 
     pub fn server_address(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
+
+    #[cfg(any(test, feature = "bench-db"))]
+    pub fn test_default() -> Self {
+        Config {
+            host: "127.0.0.1".to_string(),
+            port: 3010,
+            http2: false,
+            client_request_timeout_ms: 5000,
+            body_read_timeout_ms: 10000,
+            database_url: SecretString::new("postgres://localhost/cross_bow_test".to_string()),
+            github_webhook_secret: SecretString::new("test_secret".to_string()),
+            gitea_webhook_secret: Some(SecretString::new("gitea_secret".to_string())),
+            auth0_webhook_secret: None,
+            max_connections: 1,
+            pool_metrics_interval_secs: 0,
+            ack_templates: HashMap::new(),
+            store_full_payload: true,
+            payload_field_allowlist: Vec::new(),
+            search_fields: Vec::new(),
+            github_allowed_events: Vec::new(),
+            verify_signatures: true,
+            dedup_by_payload: false,
+            dedup_window_secs: 3600,
+            compress_payloads: false,
+            admin_username: "admin".to_string(),
+            admin_password: Some(SecretString::new("admin-pw-value".to_string())),
+            admin_api_key: Some(SecretString::new("admin-key-value".to_string())),
+            schema_dir: None,
+            alert_webhook_url: None,
+            alert_max_pending: 1000,
+            alert_max_pending_age_secs: 900,
+            alert_cooldown_secs: 3600,
+            ready_max_pending: 10000,
+            processing_shards: 1,
+            source_stale_after_secs: 86400,
+            strict_processing: false,
+            sync_processing_sources: Vec::new(),
+            api_max_per_page: 1000,
+            search_timeout_ms: 10000,
+            max_json_depth: 64,
+            webhook_rate_limit: 0,
+            webhook_rate_limit_window_secs: 60,
+            trusted_proxies: Vec::new(),
+            allowed_sources: Vec::new(),
+            source_aliases: HashMap::new(),
+            event_type_aliases: default_event_type_aliases(),
+            retention_days: HashMap::new(),
+            compact_payloads_after_days: None,
+            forward_urls: Vec::new(),
+            default_theme: Theme::Dark,
+            route_themes: Vec::new(),
+            home_route: HomeRoute::Dashboard,
+            archive_s3_bucket: None,
+            archive_s3_endpoint: None,
+            archive_s3_region: "us-east-1".to_string(),
+            archive_s3_access_key_id: None,
+            archive_s3_secret_access_key: None,
+            archive_s3_prefix: "events/".to_string(),
+            archive_after_days: 90,
+            archive_prune: false,
+        }
+    }
+}
+
+/// Accepts true/false, 1/0, yes/no and on/off in any case
+fn parse_bool_value(key: &str, raw: &str) -> Result<bool, ConfigError> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(ConfigError::InvalidBool {
+            key: key.to_string(),
+            value: raw.to_string(),
+        }),
+    }
+}
+
+/// Parse `ROUTE_THEMES`, a comma-separated list of `prefix=theme` pairs
+fn parse_route_themes(raw: &str) -> Result<Vec<(String, Theme)>, ConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (prefix, theme) = entry.split_once('=').ok_or(ConfigError::InvalidTheme)?;
+            let theme = Theme::parse(theme).ok_or(ConfigError::InvalidTheme)?;
+            Ok((prefix.trim().to_string(), theme))
+        })
+        .collect()
+}
+
+/// Parse `TRUSTED_PROXIES`, a comma-separated list of IP addresses
+fn parse_trusted_proxies(raw: &str) -> Result<Vec<IpAddr>, ConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse()
+                .map_err(|_| ConfigError::InvalidTrustedProxies)
+        })
+        .collect()
+}
+
+/// Parse `SOURCE_ALIASES`, a comma-separated list of `alias=canonical` pairs
+fn parse_source_aliases(raw: &str) -> Result<HashMap<String, String>, ConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (alias, canonical) = entry
+                .split_once('=')
+                .ok_or(ConfigError::InvalidSourceAliases)?;
+            let (alias, canonical) = (alias.trim(), canonical.trim());
+            if alias.is_empty() || canonical.is_empty() {
+                return Err(ConfigError::InvalidSourceAliases);
+            }
+            Ok((alias.to_string(), canonical.to_string()))
+        })
+        .collect()
+}
+
+/// GitLab names events differently in the `X-Gitlab-Event` header and `object_kind`,
+/// and calls pull requests merge requests; these map both onto the GitHub names
+const DEFAULT_EVENT_TYPE_ALIASES: &[(&str, &str, &str)] = &[
+    ("gitlab", "Push Hook", "push"),
+    ("gitlab", "Tag Push Hook", "tag_push"),
+    ("gitlab", "Merge Request Hook", "pull_request"),
+    ("gitlab", "merge_request", "pull_request"),
+    ("gitlab", "Issue Hook", "issues"),
+    ("gitlab", "issue", "issues"),
+    ("gitlab", "Note Hook", "note"),
+    ("gitlab", "Pipeline Hook", "pipeline"),
+];
+
+fn default_event_type_aliases() -> HashMap<String, HashMap<String, String>> {
+    let mut aliases: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for (source, raw, canonical) in DEFAULT_EVENT_TYPE_ALIASES {
+        aliases
+            .entry(source.to_string())
+            .or_default()
+            .insert(raw.to_string(), canonical.to_string());
+    }
+    aliases
+}
+
+/// Parse `EVENT_TYPE_ALIASES`, a comma-separated list of `source:type=canonical` entries
+fn parse_event_type_aliases(
+    raw: &str,
+) -> Result<HashMap<String, HashMap<String, String>>, ConfigError> {
+    let mut aliases: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (key, canonical) = entry
+            .split_once('=')
+            .ok_or(ConfigError::InvalidEventTypeAliases)?;
+        let (source, event_type) = key
+            .split_once(':')
+            .ok_or(ConfigError::InvalidEventTypeAliases)?;
+        let (source, event_type, canonical) = (source.trim(), event_type.trim(), canonical.trim());
+        if source.is_empty() || event_type.is_empty() || canonical.is_empty() {
+            return Err(ConfigError::InvalidEventTypeAliases);
+        }
+        aliases
+            .entry(source.to_string())
+            .or_default()
+            .insert(event_type.to_string(), canonical.to_string());
+    }
+
+    Ok(aliases)
+}
+
+/// Parse `RETENTION_DAYS`, a comma-separated list of `source=days` pairs
+fn parse_retention_days(raw: &str) -> Result<HashMap<String, u32>, ConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (source, days) = entry
+                .split_once('=')
+                .ok_or(ConfigError::InvalidRetentionDays)?;
+            let source = source.trim();
+            let days: u32 = days
+                .trim()
+                .parse()
+                .map_err(|_| ConfigError::InvalidRetentionDays)?;
+            if source.is_empty() || days == 0 {
+                return Err(ConfigError::InvalidRetentionDays);
+            }
+            Ok((source.to_string(), days))
+        })
+        .collect()
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -40,6 +679,200 @@ pub enum ConfigError {
     MissingDatabaseUrl,
     #[error("GITHUB_WEBHOOK_SECRET environment variable is required")]
     MissingWebhookSecret,
+    #[error("GITHUB_WEBHOOK_SECRET must not be empty")]
+    EmptyWebhookSecret,
     #[error("Invalid PORT value")]
     InvalidPort,
+    #[error("WEBHOOK_ACK_TEMPLATES must be a JSON object keyed by source")]
+    InvalidAckTemplates,
+    #[error("DEFAULT_THEME and ROUTE_THEMES themes must be light, dark or auto")]
+    InvalidTheme,
+    #[error("HOME_ROUTE must be dashboard, events or repositories")]
+    InvalidHomeRoute,
+    #[error("SOURCE_ALIASES must be a comma-separated list of alias=source pairs")]
+    InvalidSourceAliases,
+    #[error("EVENT_TYPE_ALIASES must be a comma-separated list of source:type=canonical entries")]
+    InvalidEventTypeAliases,
+    #[error("RETENTION_DAYS must be a comma-separated list of source=days pairs with days > 0")]
+    InvalidRetentionDays,
+    #[error("TRUSTED_PROXIES must be a comma-separated list of IP addresses")]
+    InvalidTrustedProxies,
+    #[error("{key} must be true/false, 1/0, yes/no or on/off, got {value:?}")]
+    InvalidBool { key: String, value: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let mut config = Config::test_default();
+        config.auth0_webhook_secret = Some(SecretString::new("auth0_secret".to_string()));
+        config.alert_webhook_url = Some(SecretString::new(
+            "https://hooks.example.com/alert_token".to_string(),
+        ));
+        config.archive_s3_secret_access_key =
+            Some(SecretString::new("archive_secret_key".to_string()));
+
+        let debug = format!("{config:?}");
+        for secret in [
+            "postgres://localhost/cross_bow_test",
+            "test_secret",
+            "gitea_secret",
+            "auth0_secret",
+            "admin-pw-value",
+            "admin-key-value",
+            "alert_token",
+            "archive_secret_key",
+        ] {
+            assert!(!debug.contains(secret), "{secret} leaked into {debug}");
+        }
+        assert!(debug.contains("REDACTED"));
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_github_secret() {
+        let mut config = Config::test_default();
+        assert!(config.validate().is_ok());
+
+        config.github_webhook_secret = SecretString::new("   ".to_string());
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::EmptyWebhookSecret)
+        ));
+    }
+
+    #[test]
+    fn test_route_themes_longest_prefix_wins() {
+        let mut config = Config::test_default();
+        config.route_themes = parse_route_themes("/events=auto, /events/diff=light").unwrap();
+
+        assert_eq!(config.theme_for("/"), Theme::Dark);
+        assert_eq!(config.theme_for("/events"), Theme::Auto);
+        assert_eq!(config.theme_for("/events/diff"), Theme::Light);
+        assert!(matches!(
+            parse_route_themes("/events=sepia"),
+            Err(ConfigError::InvalidTheme)
+        ));
+    }
+
+    #[test]
+    fn test_home_route_parse() {
+        assert_eq!(HomeRoute::parse(" Events "), Some(HomeRoute::Events));
+        assert_eq!(HomeRoute::parse("dashboard"), Some(HomeRoute::Dashboard));
+        assert_eq!(HomeRoute::parse("settings"), None);
+        assert_eq!(
+            HomeRoute::Repositories.redirect_path(),
+            Some("/repositories")
+        );
+        assert_eq!(HomeRoute::Dashboard.redirect_path(), None);
+    }
+
+    #[test]
+    fn test_parse_trusted_proxies() {
+        assert_eq!(
+            parse_trusted_proxies(" 10.0.0.1, ::1,").unwrap(),
+            vec![
+                "10.0.0.1".parse::<IpAddr>().unwrap(),
+                "::1".parse::<IpAddr>().unwrap()
+            ]
+        );
+        assert!(parse_trusted_proxies("10.0.0.0/8").is_err());
+    }
+
+    #[test]
+    fn test_source_aliases_resolve_to_canonical_source() {
+        let mut config = Config::test_default();
+        config.source_aliases =
+            parse_source_aliases("gh=github, github-enterprise = github,,").unwrap();
+
+        assert_eq!(config.canonical_source("gh"), "github");
+        assert_eq!(config.canonical_source("github-enterprise"), "github");
+        assert_eq!(config.canonical_source("github"), "github");
+        assert_eq!(config.canonical_source("gitlab"), "gitlab");
+
+        for raw in ["gh", "gh=", "=github"] {
+            assert!(matches!(
+                parse_source_aliases(raw),
+                Err(ConfigError::InvalidSourceAliases)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_gitlab_merge_request_normalizes_to_pull_request() {
+        let mut config = Config::test_default();
+        assert_eq!(
+            config.canonical_event_type("gitlab", "merge_request"),
+            "pull_request"
+        );
+        assert_eq!(
+            config.canonical_event_type("gitlab", "Merge Request Hook"),
+            "pull_request"
+        );
+        assert_eq!(config.canonical_event_type("gitlab", "Push Hook"), "push");
+        // Other sources and unmapped types are left alone
+        assert_eq!(
+            config.canonical_event_type("gitea", "merge_request"),
+            "merge_request"
+        );
+        assert_eq!(
+            config.canonical_event_type("gitlab", "Job Hook"),
+            "Job Hook"
+        );
+
+        let extra =
+            parse_event_type_aliases("gitlab:Job Hook = build, ci:deploy_done=deploy,").unwrap();
+        for (source, types) in extra {
+            config
+                .event_type_aliases
+                .entry(source)
+                .or_default()
+                .extend(types);
+        }
+        assert_eq!(config.canonical_event_type("gitlab", "Job Hook"), "build");
+        assert_eq!(config.canonical_event_type("ci", "deploy_done"), "deploy");
+        assert_eq!(
+            config.canonical_event_type("gitlab", "merge_request"),
+            "pull_request"
+        );
+
+        for raw in ["gitlab=push", "gitlab:push", ":push=push", "gitlab:=push"] {
+            assert!(matches!(
+                parse_event_type_aliases(raw),
+                Err(ConfigError::InvalidEventTypeAliases)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_retention_days() {
+        let retention = parse_retention_days("ci=7, gitlab = 30").unwrap();
+        assert_eq!(retention.get("ci"), Some(&7));
+        assert_eq!(retention.get("gitlab"), Some(&30));
+        assert_eq!(retention.get("github"), None);
+
+        for raw in ["ci", "ci=0", "ci=-1", "ci=week", "=7"] {
+            assert!(matches!(
+                parse_retention_days(raw),
+                Err(ConfigError::InvalidRetentionDays)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_bool_value_spellings() {
+        for raw in ["true", "TRUE", "1", "yes", "Yes", "on", " On "] {
+            assert!(parse_bool_value("FLAG", raw).unwrap(), "{raw}");
+        }
+        for raw in ["false", "False", "0", "no", "NO", "off"] {
+            assert!(!parse_bool_value("FLAG", raw).unwrap(), "{raw}");
+        }
+        assert!(matches!(
+            parse_bool_value("STRICT_PROCESSING", "enabled"),
+            Err(ConfigError::InvalidBool { key, value })
+                if key == "STRICT_PROCESSING" && value == "enabled"
+        ));
+    }
 }
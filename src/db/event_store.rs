@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::models::{CreateEvent, Event, EventFilterBuilder};
+
+/// Persistence for events as the events pages and API read it
+///
+/// PostgreSQL is the only backend; the trait lets tests wrap or stand in for it.
+pub trait EventStore: Send + Sync {
+    fn create(&self, data: CreateEvent) -> BoxFuture<'_, Result<Event, sqlx::Error>>;
+
+    fn find_by_id(&self, id: i64) -> BoxFuture<'_, Result<Option<Event>, sqlx::Error>>;
+
+    /// Newest first, like the events page
+    fn search_and_filter<'a>(
+        &'a self,
+        filters: &'a EventFilterBuilder,
+        limit: i64,
+        offset: i64,
+    ) -> BoxFuture<'a, Result<Vec<Event>, sqlx::Error>>;
+
+    fn count_filtered<'a>(
+        &'a self,
+        filters: &'a EventFilterBuilder,
+    ) -> BoxFuture<'a, Result<i64, sqlx::Error>>;
+
+    fn mark_processed(&self, id: i64) -> BoxFuture<'_, Result<(), sqlx::Error>>;
+
+    fn mark_failed<'a>(&'a self, id: i64, error: &'a str)
+        -> BoxFuture<'a, Result<(), sqlx::Error>>;
+
+    fn count(&self) -> BoxFuture<'_, Result<i64, sqlx::Error>>;
+}
+
+/// The PostgreSQL event store, backed by the `Event` model queries
+#[derive(Debug, Clone)]
+pub struct PgEventStore {
+    pool: PgPool,
+    search_statement_timeout: Option<Duration>,
+}
+
+impl PgEventStore {
+    pub fn new(pool: PgPool) -> Self {
+        PgEventStore {
+            pool,
+            search_statement_timeout: None,
+        }
+    }
+
+    /// Have PostgreSQL cancel event searches and counts running longer than `timeout`,
+    /// so an abandoned search does not keep running after the page gave up on it
+    pub fn with_search_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.search_statement_timeout = timeout;
+        self
+    }
+}
+
+/// Limit the statements of `tx` to `timeout`; `SET LOCAL` ends with the transaction
+async fn set_statement_timeout(
+    tx: &mut Transaction<'_, Postgres>,
+    timeout: Duration,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "SET LOCAL statement_timeout = {}",
+        timeout.as_millis()
+    ))
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+impl EventStore for PgEventStore {
+    fn create(&self, data: CreateEvent) -> BoxFuture<'_, Result<Event, sqlx::Error>> {
+        Box::pin(Event::create(&self.pool, data))
+    }
+
+    fn find_by_id(&self, id: i64) -> BoxFuture<'_, Result<Option<Event>, sqlx::Error>> {
+        Box::pin(Event::find_by_id(&self.pool, id))
+    }
+
+    fn search_and_filter<'a>(
+        &'a self,
+        filters: &'a EventFilterBuilder,
+        limit: i64,
+        offset: i64,
+    ) -> BoxFuture<'a, Result<Vec<Event>, sqlx::Error>> {
+        Box::pin(async move {
+            let Some(timeout) = self.search_statement_timeout else {
+                return Event::search_and_filter(&self.pool, filters, limit, offset).await;
+            };
+
+            let mut tx = self.pool.begin().await?;
+            set_statement_timeout(&mut tx, timeout).await?;
+            let events = Event::search_and_filter(&mut *tx, filters, limit, offset).await?;
+            tx.commit().await?;
+            Ok(events)
+        })
+    }
+
+    fn count_filtered<'a>(
+        &'a self,
+        filters: &'a EventFilterBuilder,
+    ) -> BoxFuture<'a, Result<i64, sqlx::Error>> {
+        Box::pin(async move {
+            let Some(timeout) = self.search_statement_timeout else {
+                return Event::count_filtered(&self.pool, filters).await;
+            };
+
+            let mut tx = self.pool.begin().await?;
+            set_statement_timeout(&mut tx, timeout).await?;
+            let count = Event::count_filtered(&mut *tx, filters).await?;
+            tx.commit().await?;
+            Ok(count)
+        })
+    }
+
+    fn mark_processed(&self, id: i64) -> BoxFuture<'_, Result<(), sqlx::Error>> {
+        Box::pin(Event::mark_processed(&self.pool, id))
+    }
+
+    fn mark_failed<'a>(
+        &'a self,
+        id: i64,
+        error: &'a str,
+    ) -> BoxFuture<'a, Result<(), sqlx::Error>> {
+        Box::pin(Event::mark_failed(&self.pool, id, error))
+    }
+
+    fn count(&self) -> BoxFuture<'_, Result<i64, sqlx::Error>> {
+        Box::pin(Event::count(&self.pool))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Actor, FilterTable};
+
+    fn event(source: &str, actor: &str) -> CreateEvent {
+        CreateEvent {
+            actor: Actor {
+                name: Some(actor.to_string()),
+                ..Actor::default()
+            },
+            raw_event: serde_json::json!({ "head_commit": { "message": "Fix flaky deploy" } }),
+            ..CreateEvent::for_test(source, "push")
+        }
+    }
+
+    /// Behaviour the events routes rely on
+    async fn exercise_store(store: &dyn EventStore) {
+        let first = store.create(event("github", "octocat")).await.unwrap();
+        store.create(event("github", "hubot")).await.unwrap();
+        store.create(event("gitlab", "octocat")).await.unwrap();
+
+        let found = store.find_by_id(first.id).await.unwrap().unwrap();
+        assert_eq!(found.delivery_id, first.delivery_id);
+        assert_eq!(found.raw_event, first.raw_event);
+        assert!(store.find_by_id(first.id + 1000).await.unwrap().is_none());
+
+        let cases = [
+            (EventFilterBuilder::new(FilterTable::Events), 3),
+            (
+                EventFilterBuilder::new(FilterTable::Events).source(Some("github")),
+                2,
+            ),
+            (
+                EventFilterBuilder::new(FilterTable::Events).source(Some("github,gitlab")),
+                3,
+            ),
+            (
+                EventFilterBuilder::new(FilterTable::Events).source(Some("gitlab, bitbucket")),
+                1,
+            ),
+            (
+                EventFilterBuilder::new(FilterTable::Events)
+                    .actor_name(Some("octocat"))
+                    .search(Some("flaky")),
+                2,
+            ),
+            (
+                EventFilterBuilder::new(FilterTable::Events).search(Some("nothing like this")),
+                0,
+            ),
+        ];
+        for (filters, expected) in cases {
+            let events = store.search_and_filter(&filters, 10, 0).await.unwrap();
+            assert_eq!(events.len(), expected);
+            assert_eq!(
+                store.count_filtered(&filters).await.unwrap(),
+                expected as i64
+            );
+        }
+
+        store.mark_failed(first.id, "boom").await.unwrap();
+        let failed = store.find_by_id(first.id).await.unwrap().unwrap();
+        assert!(!failed.processed);
+        assert_eq!(failed.processing_error.as_deref(), Some("boom"));
+
+        store.mark_processed(first.id).await.unwrap();
+        let processed = store.find_by_id(first.id).await.unwrap().unwrap();
+        assert!(processed.processed);
+        assert!(processed.processed_at.is_some());
+        assert_eq!(processed.processing_error, None);
+
+        let unprocessed = EventFilterBuilder::new(FilterTable::Events).processed(Some(false));
+        assert_eq!(store.count_filtered(&unprocessed).await.unwrap(), 2);
+        assert_eq!(store.count().await.unwrap(), 3);
+
+        // Stored `searchable_text` is searched instead of the payload
+        store
+            .create(CreateEvent {
+                raw_event: serde_json::json!({ "note": "only in the payload" }),
+                searchable_text: Some("octocat/Hello-World".to_string()),
+                ..CreateEvent::for_test("github", "push")
+            })
+            .await
+            .unwrap();
+        let search = |term| EventFilterBuilder::new(FilterTable::Events).search(Some(term));
+        assert_eq!(
+            store.count_filtered(&search("hello-world")).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            store.count_filtered(&search("only in the")).await.unwrap(),
+            0
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_postgres_event_store(pool: PgPool) {
+        exercise_store(&PgEventStore::new(pool)).await;
+    }
+}
@@ -1,3 +1,5 @@
+pub mod event_store;
 pub mod pool;
 
+pub use event_store::{EventStore, PgEventStore};
 pub use pool::create_pool;
@@ -11,3 +11,20 @@ pub async fn create_pool(database_url: &str, max_connections: u32) -> Result<PgP
 
     Ok(pool)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_closed_pool_rejects_queries(pool: PgPool) {
+        sqlx::query("SELECT 1").execute(&pool).await.unwrap();
+
+        pool.close().await;
+
+        assert!(pool.is_closed());
+        let err = sqlx::query("SELECT 1").execute(&pool).await.unwrap_err();
+        assert!(matches!(err, sqlx::Error::PoolClosed), "{err}");
+    }
+}
@@ -0,0 +1,1131 @@
+use std::collections::BTreeMap;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Result};
+use maud::{html, DOCTYPE};
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::handlers::errors::AppError;
+use crate::handlers::layout::Theme;
+use crate::handlers::webhook::{has_processor, KNOWN_SOURCES};
+use crate::models::{
+    source_config::SIGNATURE_MODES, CreateReplayJob, Event, MembershipEvent, RejectedWebhook,
+    ReplayJob, Repository, SourceConfig, SourceProcessingCounts, UnprocessedTypeCount,
+    UpsertSourceConfig,
+};
+use crate::utils::{verify_basic_auth, PayloadTransform, WatchedPaths};
+
+#[derive(Debug, Deserialize)]
+pub struct SourceConfigForm {
+    pub source: String,
+    pub signature_mode: String,
+    #[serde(default)]
+    pub secret: String,
+    #[serde(default)]
+    pub transform: String,
+    #[serde(default)]
+    pub expected_content_type: String,
+}
+
+/// Why [`require_admin`] refused a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAuthError {
+    /// No `ADMIN_PASSWORD` is configured
+    Disabled,
+    Unauthorized,
+}
+
+impl From<AdminAuthError> for HttpResponse {
+    fn from(error: AdminAuthError) -> Self {
+        match error {
+            AdminAuthError::Disabled => {
+                HttpResponse::NotFound().body("Admin interface is disabled")
+            }
+            AdminAuthError::Unauthorized => HttpResponse::Unauthorized()
+                .insert_header((header::WWW_AUTHENTICATE, "Basic realm=\"Cross Bow Admin\""))
+                .body("Authentication required"),
+        }
+    }
+}
+
+/// Ensure the request carries valid admin credentials
+pub fn require_admin(req: &HttpRequest, config: &Config) -> Result<(), AdminAuthError> {
+    let Some(password) = config
+        .admin_password
+        .as_ref()
+        .map(|p| p.expose_secret().as_str())
+    else {
+        return Err(AdminAuthError::Disabled);
+    };
+
+    let auth_header = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok());
+
+    if verify_basic_auth(auth_header, &config.admin_username, password) {
+        Ok(())
+    } else {
+        Err(AdminAuthError::Unauthorized)
+    }
+}
+
+pub async fn list_sources(
+    req: HttpRequest,
+    theme: Theme,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let sources = SourceConfig::list_all(pool.get_ref()).await?;
+
+    let markup = html! {
+        (DOCTYPE)
+        html lang="en" data-theme=[theme.data_theme()] {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Sources - Cross Bow" }
+                link rel="stylesheet" href="/assets/daisy.css";
+                link rel="stylesheet" href="/assets/themes.css";
+                script src="/assets/htmx.js" {}
+                script src="/assets/tw.js" {}
+                script src="/assets/theme-switcher.js" {}
+            }
+            body {
+                div class="navbar bg-base-100 shadow-lg" {
+                    div class="flex-1" {
+                        a class="btn btn-ghost text-xl" href="/" { "Cross Bow" }
+                    }
+                    div class="flex-none gap-2" {
+                        ul class="menu menu-horizontal px-1" {
+                            li { a href="/dashboard" { "Dashboard" } }
+                            li { a href="/events" { "Events" } }
+                            li { a href="/admin/sources" class="active" { "Sources" } }
+                            li { a href="/admin/rejections" { "Rejections" } }
+                            li { a href="/admin/processors" { "Processors" } }
+                            li { a href="/admin/replays" { "Replays" } }
+                            li { a href="/admin/memberships" { "Memberships" } }
+                        }
+                    }
+                }
+
+                div class="container mx-auto px-4 py-8" {
+                    h1 class="text-4xl font-bold mb-8" { "Webhook Sources" }
+
+                    div class="card bg-base-100 shadow-xl mb-6" {
+                        div class="card-body p-0" {
+                            div class="overflow-x-auto" {
+                                table class="table table-zebra" {
+                                    thead {
+                                        tr {
+                                            th { "Source" }
+                                            th { "Signature Mode" }
+                                            th { "Secret" }
+                                            th { "Transform" }
+                                            th { "Content Type" }
+                                            th { "Updated" }
+                                        }
+                                    }
+                                    tbody {
+                                        @if sources.is_empty() {
+                                            tr {
+                                                td colspan="6" class="text-center text-base-content/60 py-8" {
+                                                    "No sources configured. Environment settings are used for all sources."
+                                                }
+                                            }
+                                        } @else {
+                                            @for source in &sources {
+                                                tr {
+                                                    td { span class="badge badge-secondary" { (source.source) } }
+                                                    td { (source.signature_mode) }
+                                                    td {
+                                                        @if source.has_secret() {
+                                                            span class="badge badge-success" { "Set" }
+                                                        } @else {
+                                                            span class="badge badge-ghost" { "Not set" }
+                                                        }
+                                                    }
+                                                    td {
+                                                        @if let Some(transform) = &source.transform {
+                                                            code class="text-sm" { (transform) }
+                                                        } @else {
+                                                            span class="text-base-content/60" { "-" }
+                                                        }
+                                                    }
+                                                    td {
+                                                        @if let Some(content_type) = &source.expected_content_type {
+                                                            code class="text-sm" { (content_type) }
+                                                        } @else {
+                                                            span class="text-base-content/60" { "any" }
+                                                        }
+                                                    }
+                                                    td class="text-sm" { (source.updated_at.format("%Y-%m-%d %H:%M:%S UTC")) }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div class="card bg-base-100 shadow-xl" {
+                        div class="card-body" {
+                            h2 class="card-title mb-4" { "Add or Update Source" }
+                            form method="post" action="/admin/sources" class="grid grid-cols-1 md:grid-cols-6 gap-4" {
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Source" } }
+                                    input type="text" name="source" required placeholder="github" class="input input-bordered";
+                                }
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Signature Mode" } }
+                                    select name="signature_mode" class="select select-bordered" {
+                                        @for mode in SIGNATURE_MODES {
+                                            option value=(mode) { (mode) }
+                                        }
+                                    }
+                                }
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Secret" } }
+                                    input type="password" name="secret" placeholder="Leave empty to keep current" class="input input-bordered" autocomplete="off";
+                                }
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Transform" } }
+                                    input type="text" name="transform" placeholder="unwrap:data or extract:payload" class="input input-bordered";
+                                }
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Content Type" } }
+                                    input type="text" name="expected_content_type" placeholder="application/json (empty accepts any)" class="input input-bordered";
+                                }
+                                div class="form-control flex items-end" {
+                                    button type="submit" class="btn btn-primary" { "Save" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+pub async fn save_source(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    form: web::Form<SourceConfigForm>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let form = form.into_inner();
+    let source = form.source.trim().to_string();
+
+    if source.is_empty() {
+        return Err(AppError::bad_request("Source is required"));
+    }
+
+    if !SIGNATURE_MODES.contains(&form.signature_mode.as_str()) {
+        return Err(AppError::bad_request("Unsupported signature mode"));
+    }
+
+    let transform = Some(form.transform.trim().to_string()).filter(|t| !t.is_empty());
+    if transform
+        .as_deref()
+        .is_some_and(|t| PayloadTransform::parse(t).is_none())
+    {
+        return Err(AppError::bad_request(
+            "Unsupported transform, expected unwrap:<field> or extract:<path>",
+        ));
+    }
+
+    let expected_content_type =
+        Some(form.expected_content_type.trim().to_lowercase()).filter(|t| !t.is_empty());
+    if expected_content_type
+        .as_deref()
+        .is_some_and(|t| t.parse::<actix_web::mime::Mime>().is_err())
+    {
+        return Err(AppError::bad_request(
+            "Invalid content type, expected a media type such as application/json",
+        ));
+    }
+
+    let secret = Some(form.secret).filter(|s| !s.is_empty());
+
+    SourceConfig::upsert(
+        pool.get_ref(),
+        UpsertSourceConfig {
+            source: source.clone(),
+            signature_mode: form.signature_mode,
+            secret,
+            transform,
+            expected_content_type,
+        },
+    )
+    .await?;
+
+    log::info!("Updated source config for {source}");
+
+    Ok(HttpResponse::SeeOther()
+        .insert_header((header::LOCATION, "/admin/sources"))
+        .finish())
+}
+
+/// Number of rejected webhooks shown on the audit page
+const REJECTIONS_PAGE_SIZE: i64 = 100;
+
+pub async fn list_rejections(
+    req: HttpRequest,
+    theme: Theme,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let rejections = RejectedWebhook::list_recent(pool.get_ref(), REJECTIONS_PAGE_SIZE).await?;
+
+    let markup = html! {
+        (DOCTYPE)
+        html lang="en" data-theme=[theme.data_theme()] {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Rejected Webhooks - Cross Bow" }
+                link rel="stylesheet" href="/assets/daisy.css";
+                link rel="stylesheet" href="/assets/themes.css";
+                script src="/assets/htmx.js" {}
+                script src="/assets/tw.js" {}
+                script src="/assets/theme-switcher.js" {}
+            }
+            body {
+                div class="navbar bg-base-100 shadow-lg" {
+                    div class="flex-1" {
+                        a class="btn btn-ghost text-xl" href="/" { "Cross Bow" }
+                    }
+                    div class="flex-none gap-2" {
+                        ul class="menu menu-horizontal px-1" {
+                            li { a href="/dashboard" { "Dashboard" } }
+                            li { a href="/events" { "Events" } }
+                            li { a href="/admin/sources" { "Sources" } }
+                            li { a href="/admin/rejections" class="active" { "Rejections" } }
+                            li { a href="/admin/processors" { "Processors" } }
+                            li { a href="/admin/replays" { "Replays" } }
+                            li { a href="/admin/memberships" { "Memberships" } }
+                        }
+                    }
+                }
+
+                div class="container mx-auto px-4 py-8" {
+                    h1 class="text-4xl font-bold mb-2" { "Rejected Webhooks" }
+                    p class="text-base-content/60 mb-8" {
+                        "The " (REJECTIONS_PAGE_SIZE) " most recent deliveries refused for a bad signature or malformed payload."
+                    }
+
+                    div class="card bg-base-100 shadow-xl" {
+                        div class="card-body p-0" {
+                            div class="overflow-x-auto" {
+                                table class="table table-zebra" {
+                                    thead {
+                                        tr {
+                                            th { "Received" }
+                                            th { "Source" }
+                                            th { "Reason" }
+                                            th { "Client IP" }
+                                            th { "Request" }
+                                        }
+                                    }
+                                    tbody {
+                                        @if rejections.is_empty() {
+                                            tr {
+                                                td colspan="5" class="text-center text-base-content/60 py-8" {
+                                                    "No rejected webhooks."
+                                                }
+                                            }
+                                        } @else {
+                                            @for rejection in &rejections {
+                                                tr {
+                                                    td class="text-sm whitespace-nowrap" { (rejection.received_at.format("%Y-%m-%d %H:%M:%S UTC")) }
+                                                    td {
+                                                        span class="badge badge-secondary" { (rejection.source) }
+                                                        @if let Some(tenant) = &rejection.tenant {
+                                                            " " span class="badge badge-outline" { (tenant) }
+                                                        }
+                                                    }
+                                                    td class="text-error" { (rejection.reason) }
+                                                    td class="font-mono text-sm" { (rejection.client_ip.as_deref().unwrap_or("-")) }
+                                                    td {
+                                                        details {
+                                                            summary class="cursor-pointer text-sm" {
+                                                                "Headers and body"
+                                                                @if rejection.body_truncated {
+                                                                    " " span class="badge badge-warning badge-sm" { "Truncated" }
+                                                                }
+                                                            }
+                                                            pre class="text-xs mt-2 whitespace-pre-wrap break-all" {
+                                                                (serde_json::to_string_pretty(&rejection.headers).unwrap_or_default())
+                                                            }
+                                                            @if let (Some(bytes), Some(digest)) = (rejection.body_bytes, &rejection.body_sha256) {
+                                                                p class="text-xs mt-2 font-mono" { (bytes) " bytes, sha256 " (digest) }
+                                                            }
+                                                            pre class="text-xs mt-2 whitespace-pre-wrap break-all max-w-xl" { (rejection.body) }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+/// Number of membership changes shown on the audit page
+const MEMBERSHIPS_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct MembershipFilters {
+    pub member: Option<String>,
+}
+
+/// Access audit trail of GitHub member, team membership and organization changes
+pub async fn list_memberships(
+    req: HttpRequest,
+    theme: Theme,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    query: web::Query<MembershipFilters>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let member = query
+        .member
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty());
+    let memberships =
+        MembershipEvent::list_recent(pool.get_ref(), member, MEMBERSHIPS_PAGE_SIZE).await?;
+
+    let markup = html! {
+        (DOCTYPE)
+        html lang="en" data-theme=[theme.data_theme()] {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Membership Changes - Cross Bow" }
+                link rel="stylesheet" href="/assets/daisy.css";
+                link rel="stylesheet" href="/assets/themes.css";
+                script src="/assets/htmx.js" {}
+                script src="/assets/tw.js" {}
+                script src="/assets/theme-switcher.js" {}
+            }
+            body {
+                div class="navbar bg-base-100 shadow-lg" {
+                    div class="flex-1" {
+                        a class="btn btn-ghost text-xl" href="/" { "Cross Bow" }
+                    }
+                    div class="flex-none gap-2" {
+                        ul class="menu menu-horizontal px-1" {
+                            li { a href="/dashboard" { "Dashboard" } }
+                            li { a href="/events" { "Events" } }
+                            li { a href="/admin/sources" { "Sources" } }
+                            li { a href="/admin/rejections" { "Rejections" } }
+                            li { a href="/admin/processors" { "Processors" } }
+                            li { a href="/admin/replays" { "Replays" } }
+                            li { a href="/admin/memberships" class="active" { "Memberships" } }
+                        }
+                    }
+                }
+
+                div class="container mx-auto px-4 py-8" {
+                    h1 class="text-4xl font-bold mb-2" { "Membership Changes" }
+                    p class="text-base-content/60 mb-4" {
+                        "The " (MEMBERSHIPS_PAGE_SIZE) " most recent changes to repository collaborators, team members and organization members."
+                    }
+
+                    form method="get" action="/admin/memberships" class="flex items-end gap-2 mb-8" {
+                        label class="form-control" {
+                            span class="label-text" { "Member" }
+                            input type="text" name="member" class="input input-bordered input-sm" placeholder="login" value=[member];
+                        }
+                        button type="submit" class="btn btn-sm btn-primary" { "Filter" }
+                        @if member.is_some() {
+                            a class="btn btn-sm btn-ghost" href="/admin/memberships" { "Clear" }
+                        }
+                    }
+
+                    div class="card bg-base-100 shadow-xl" {
+                        div class="card-body p-0" {
+                            div class="overflow-x-auto" {
+                                table class="table table-zebra" {
+                                    thead {
+                                        tr {
+                                            th { "At" }
+                                            th { "Action" }
+                                            th { "Member" }
+                                            th { "Scope" }
+                                            th { "By" }
+                                        }
+                                    }
+                                    tbody {
+                                        @if memberships.is_empty() {
+                                            tr {
+                                                td colspan="5" class="text-center text-base-content/60 py-8" {
+                                                    "No membership changes recorded."
+                                                }
+                                            }
+                                        } @else {
+                                            @for membership in &memberships {
+                                                tr {
+                                                    td class="text-sm whitespace-nowrap" { (membership.at.format("%Y-%m-%d %H:%M:%S UTC")) }
+                                                    td {
+                                                        @if membership.action.contains("removed") {
+                                                            span class="badge badge-error" { (membership.action) }
+                                                        } @else {
+                                                            span class="badge badge-success" { (membership.action) }
+                                                        }
+                                                    }
+                                                    td class="font-bold" { (membership.member) }
+                                                    td {
+                                                        span class="badge badge-outline" { (membership.scope_type) }
+                                                        " " (membership.scope)
+                                                        @if let Some(org) = membership.organization.as_ref().filter(|org| **org != membership.scope) {
+                                                            span class="text-base-content/60" { " (" (org) ")" }
+                                                        }
+                                                    }
+                                                    td { (membership.actor) }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+/// One source's row on the processor status page
+#[derive(Debug, Default, PartialEq)]
+pub struct ProcessorStatus {
+    pub source: String,
+    pub implemented: bool,
+    pub total: i64,
+    pub processed: i64,
+    /// Unprocessed events by type, most frequent first
+    pub unprocessed_types: Vec<(String, i64)>,
+}
+
+/// Known sources plus any source that has sent events, in name order
+fn processor_statuses(
+    counts: Vec<SourceProcessingCounts>,
+    unprocessed: Vec<UnprocessedTypeCount>,
+) -> Vec<ProcessorStatus> {
+    let mut statuses: BTreeMap<String, ProcessorStatus> = KNOWN_SOURCES
+        .iter()
+        .map(|source| (source.to_string(), ProcessorStatus::default()))
+        .collect();
+
+    for count in counts {
+        let status = statuses.entry(count.source).or_default();
+        status.total = count.total;
+        status.processed = count.processed;
+    }
+    // Rows arrive ordered by count within each source
+    for row in unprocessed {
+        statuses
+            .entry(row.source)
+            .or_default()
+            .unprocessed_types
+            .push((row.event_type, row.count));
+    }
+
+    statuses
+        .into_iter()
+        .map(|(source, status)| ProcessorStatus {
+            implemented: has_processor(&source),
+            source,
+            ..status
+        })
+        .collect()
+}
+
+/// Per-source processor coverage: which sources have a processor and which event
+/// types are piling up unprocessed
+pub async fn list_processors(
+    req: HttpRequest,
+    theme: Theme,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let counts = Event::processing_counts_per_source(pool.get_ref()).await?;
+    let unprocessed = Event::unprocessed_types_per_source(pool.get_ref()).await?;
+    let statuses = processor_statuses(counts, unprocessed);
+
+    let markup = html! {
+        (DOCTYPE)
+        html lang="en" data-theme=[theme.data_theme()] {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Processors - Cross Bow" }
+                link rel="stylesheet" href="/assets/daisy.css";
+                link rel="stylesheet" href="/assets/themes.css";
+                script src="/assets/htmx.js" {}
+                script src="/assets/tw.js" {}
+                script src="/assets/theme-switcher.js" {}
+            }
+            body {
+                div class="navbar bg-base-100 shadow-lg" {
+                    div class="flex-1" {
+                        a class="btn btn-ghost text-xl" href="/" { "Cross Bow" }
+                    }
+                    div class="flex-none gap-2" {
+                        ul class="menu menu-horizontal px-1" {
+                            li { a href="/dashboard" { "Dashboard" } }
+                            li { a href="/events" { "Events" } }
+                            li { a href="/admin/sources" { "Sources" } }
+                            li { a href="/admin/rejections" { "Rejections" } }
+                            li { a href="/admin/processors" class="active" { "Processors" } }
+                            li { a href="/admin/replays" { "Replays" } }
+                            li { a href="/admin/memberships" { "Memberships" } }
+                        }
+                    }
+                }
+
+                div class="container mx-auto px-4 py-8" {
+                    h1 class="text-4xl font-bold mb-2" { "Processors" }
+                    p class="text-base-content/60 mb-8" {
+                        "Sources without a processor are stored and marked processed without touching the domain tables."
+                    }
+
+                    div class="card bg-base-100 shadow-xl" {
+                        div class="card-body p-0" {
+                            div class="overflow-x-auto" {
+                                table class="table table-zebra" {
+                                    thead {
+                                        tr {
+                                            th { "Source" }
+                                            th { "Processor" }
+                                            th { "Events" }
+                                            th { "Processed" }
+                                            th { "Unprocessed by type" }
+                                        }
+                                    }
+                                    tbody {
+                                        @for status in &statuses {
+                                            tr {
+                                                td { span class="badge badge-secondary" { (status.source) } }
+                                                td {
+                                                    @if status.implemented {
+                                                        span class="badge badge-success" { "Implemented" }
+                                                    } @else {
+                                                        span class="badge badge-ghost" { "Not implemented" }
+                                                    }
+                                                }
+                                                td { (status.total) }
+                                                td { (status.processed) }
+                                                td {
+                                                    @if status.unprocessed_types.is_empty() {
+                                                        span class="text-base-content/60" { "-" }
+                                                    } @else {
+                                                        div class="flex flex-wrap gap-1" {
+                                                            @for (event_type, count) in &status.unprocessed_types {
+                                                                span class="badge badge-warning" { (event_type) ": " (count) }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+/// Replay jobs listed on the replays page
+const REPLAY_JOBS_PAGE_SIZE: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayJobForm {
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub event_type: String,
+    #[serde(default)]
+    pub action: String,
+    pub rate_per_minute: i32,
+}
+
+pub async fn list_replays(
+    req: HttpRequest,
+    theme: Theme,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let jobs = ReplayJob::list_recent(pool.get_ref(), REPLAY_JOBS_PAGE_SIZE).await?;
+
+    let markup = html! {
+        (DOCTYPE)
+        html lang="en" data-theme=[theme.data_theme()] {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Replays - Cross Bow" }
+                link rel="stylesheet" href="/assets/daisy.css";
+                link rel="stylesheet" href="/assets/themes.css";
+                script src="/assets/htmx.js" {}
+                script src="/assets/tw.js" {}
+                script src="/assets/theme-switcher.js" {}
+            }
+            body {
+                div class="navbar bg-base-100 shadow-lg" {
+                    div class="flex-1" {
+                        a class="btn btn-ghost text-xl" href="/" { "Cross Bow" }
+                    }
+                    div class="flex-none gap-2" {
+                        ul class="menu menu-horizontal px-1" {
+                            li { a href="/dashboard" { "Dashboard" } }
+                            li { a href="/events" { "Events" } }
+                            li { a href="/admin/sources" { "Sources" } }
+                            li { a href="/admin/rejections" { "Rejections" } }
+                            li { a href="/admin/processors" { "Processors" } }
+                            li { a href="/admin/replays" class="active" { "Replays" } }
+                            li { a href="/admin/memberships" { "Memberships" } }
+                        }
+                    }
+                }
+
+                div class="container mx-auto px-4 py-8" {
+                    h1 class="text-4xl font-bold mb-2" { "Replays" }
+                    p class="text-base-content/60 mb-8" {
+                        "Stored events re-sent to the forward targets at a fixed rate, one job at a time."
+                    }
+
+                    @if config.forward_urls.is_empty() {
+                        div class="alert alert-warning mb-6" {
+                            span { "FORWARD_URLS is not set, so queued jobs will not run." }
+                        }
+                    } @else {
+                        div class="alert alert-info mb-6" {
+                            span { "Forwarding to " (config.forward_urls.join(", ")) }
+                        }
+                    }
+
+                    div class="card bg-base-100 shadow-xl mb-6" {
+                        div class="card-body p-0" {
+                            div class="overflow-x-auto" {
+                                table class="table table-zebra" {
+                                    thead {
+                                        tr {
+                                            th { "Job" }
+                                            th { "Filters" }
+                                            th { "Rate" }
+                                            th { "Status" }
+                                            th { "Progress" }
+                                            th { "Created" }
+                                        }
+                                    }
+                                    tbody {
+                                        @if jobs.is_empty() {
+                                            tr {
+                                                td colspan="6" class="text-center text-base-content/60 py-8" {
+                                                    "No replay jobs."
+                                                }
+                                            }
+                                        }
+                                        @for job in &jobs {
+                                            tr {
+                                                td { "#" (job.id) }
+                                                td class="text-sm" {
+                                                    @for (label, value) in [("source", &job.source), ("type", &job.event_type), ("action", &job.action)] {
+                                                        @if let Some(value) = value {
+                                                            div { span class="text-base-content/60" { (label) ": " } (value) }
+                                                        }
+                                                    }
+                                                    @if job.source.is_none() && job.event_type.is_none() && job.action.is_none() {
+                                                        span class="text-base-content/60" { "All events" }
+                                                    }
+                                                }
+                                                td { (job.rate_per_minute) "/min" }
+                                                td {
+                                                    @if job.status == "completed" {
+                                                        span class="badge badge-success" { "Completed" }
+                                                    } @else if job.status == "running" {
+                                                        span class="badge badge-info" { "Running" }
+                                                    } @else {
+                                                        span class="badge badge-ghost" { "Pending" }
+                                                    }
+                                                }
+                                                td {
+                                                    progress class="progress progress-primary w-32" value=(job.attempted_events()) max=(job.total_events.max(1)) {}
+                                                    div class="text-xs" {
+                                                        (job.attempted_events()) " / " (job.total_events)
+                                                        @if job.failed_events > 0 {
+                                                            " " span class="text-error" { "(" (job.failed_events) " failed)" }
+                                                        }
+                                                    }
+                                                }
+                                                td class="text-sm whitespace-nowrap" { (job.created_at.format("%Y-%m-%d %H:%M:%S UTC")) }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div class="card bg-base-100 shadow-xl" {
+                        div class="card-body" {
+                            h2 class="card-title mb-4" { "Queue a Replay" }
+                            form method="post" action="/admin/replays" class="grid grid-cols-1 md:grid-cols-5 gap-4" {
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Source" } }
+                                    input type="text" name="source" placeholder="github,gitlab" class="input input-bordered";
+                                }
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Event Type" } }
+                                    input type="text" name="event_type" placeholder="push" class="input input-bordered";
+                                }
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Action" } }
+                                    input type="text" name="action" placeholder="opened" class="input input-bordered";
+                                }
+                                div class="form-control" {
+                                    label class="label" { span class="label-text" { "Events per minute" } }
+                                    input type="number" name="rate_per_minute" min="1" value="60" required class="input input-bordered";
+                                }
+                                div class="form-control flex items-end" {
+                                    button type="submit" class="btn btn-primary" { "Queue" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+pub async fn create_replay(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    form: web::Form<ReplayJobForm>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let form = form.into_inner();
+    if form.rate_per_minute < 1 {
+        return Err(AppError::bad_request(
+            "Rate must be at least one event per minute",
+        ));
+    }
+
+    let filter = |value: String| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+    let job = ReplayJob::create(
+        pool.get_ref(),
+        CreateReplayJob {
+            source: filter(form.source),
+            event_type: filter(form.event_type),
+            action: filter(form.action),
+            rate_per_minute: form.rate_per_minute,
+        },
+    )
+    .await?;
+
+    log::info!(
+        "Queued replay job {} for {} events at {} per minute",
+        job.id,
+        job.total_events,
+        job.rate_per_minute
+    );
+
+    Ok(HttpResponse::SeeOther()
+        .insert_header((header::LOCATION, "/admin/replays"))
+        .finish())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchedPathsForm {
+    /// One glob per line
+    #[serde(default)]
+    pub watched_paths: String,
+    #[serde(default)]
+    pub skip_unwatched_commits: bool,
+}
+
+pub async fn save_watched_paths(
+    req: HttpRequest,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    path: web::Path<i64>,
+    form: web::Form<WatchedPathsForm>,
+) -> Result<HttpResponse, AppError> {
+    if let Err(error) = require_admin(&req, &config) {
+        return Ok(error.into());
+    }
+
+    let repo_id = path.into_inner();
+    let form = form.into_inner();
+    let globs: Vec<String> = form
+        .watched_paths
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if let Err(e) = WatchedPaths::new(&globs) {
+        return Err(AppError::bad_request(format!("Invalid watched path: {e}")));
+    }
+
+    let repository =
+        Repository::set_watched_paths(pool.get_ref(), repo_id, &globs, form.skip_unwatched_commits)
+            .await?
+            .ok_or_else(|| AppError::not_found("Repository not found"))?;
+
+    log::info!(
+        "Updated watched paths of {}: {}",
+        repository.full_name,
+        globs.join(", ")
+    );
+
+    Ok(HttpResponse::SeeOther()
+        .insert_header((header::LOCATION, format!("/repositories/{repo_id}")))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateEvent;
+    use actix_web::{test as actix_test, App};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    fn admin_auth() -> (header::HeaderName, String) {
+        (
+            header::AUTHORIZATION,
+            format!("Basic {}", STANDARD.encode("admin:admin-pw-value")),
+        )
+    }
+
+    #[test]
+    fn test_processor_statuses_merge_known_and_seen_sources() {
+        let counts = vec![SourceProcessingCounts {
+            source: "ci".to_string(),
+            total: 3,
+            processed: 1,
+        }];
+        let unprocessed = vec![UnprocessedTypeCount {
+            source: "ci".to_string(),
+            event_type: "build".to_string(),
+            count: 2,
+        }];
+
+        let statuses = processor_statuses(counts, unprocessed);
+
+        let sources: Vec<_> = statuses.iter().map(|s| s.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            ["auth0", "ci", "gitea", "github", "gitlab", "gogs"]
+        );
+        assert_eq!(
+            statuses[1],
+            ProcessorStatus {
+                source: "ci".to_string(),
+                implemented: false,
+                total: 3,
+                processed: 1,
+                unprocessed_types: vec![("build".to_string(), 2)],
+            }
+        );
+        assert!(statuses[3].implemented);
+        assert_eq!(statuses[3].total, 0);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_processors_page_shows_counts_for_seeded_source(pool: PgPool) {
+        for (event_type, processed) in [("pipeline", true), ("merge_request", false)] {
+            let event = Event::create(&pool, CreateEvent::for_test("gitlab", event_type))
+                .await
+                .unwrap();
+            if processed {
+                Event::mark_processed(&pool, event.id).await.unwrap();
+            }
+        }
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .route("/admin/processors", web::get().to(list_processors)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri("/admin/processors")
+            .insert_header(admin_auth())
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let row = body
+            .split("<tr>")
+            .find(|row| row.contains(">gitlab<"))
+            .unwrap();
+        assert!(row.contains("Not implemented"));
+        assert!(row.contains("<td>2</td><td>1</td>"));
+        assert!(row.contains("merge_request: 1"));
+        assert!(!row.contains("pipeline"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_save_source_requires_auth_and_persists(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .route("/admin/sources", web::post().to(save_source)),
+        )
+        .await;
+
+        let form = [
+            ("source", "gitlab"),
+            ("signature_mode", "token"),
+            ("secret", "abc"),
+        ];
+
+        let unauthenticated = actix_test::TestRequest::post()
+            .uri("/admin/sources")
+            .set_form(form)
+            .to_request();
+        let resp = actix_test::call_service(&app, unauthenticated).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let authenticated = actix_test::TestRequest::post()
+            .uri("/admin/sources")
+            .insert_header(admin_auth())
+            .set_form(form)
+            .to_request();
+        let resp = actix_test::call_service(&app, authenticated).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::SEE_OTHER);
+
+        let stored = SourceConfig::find_by_source(&pool, "gitlab")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.signature_mode, "token");
+        assert_eq!(stored.secret.as_deref(), Some("abc"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_save_watched_paths_validates_globs(pool: PgPool) {
+        let repo = Repository::create(
+            &pool,
+            crate::models::github::CreateRepository {
+                github_id: 1296269,
+                name: "Hello-World".to_string(),
+                full_name: "octocat/Hello-World".to_string(),
+                owner: "octocat".to_string(),
+                description: None,
+                url: "https://github.com/octocat/Hello-World".to_string(),
+                is_private: false,
+                language: None,
+                topics: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .route(
+                    "/admin/repositories/{id}/watched-paths",
+                    web::post().to(save_watched_paths),
+                ),
+        )
+        .await;
+        let save = |watched_paths: &str| {
+            actix_test::TestRequest::post()
+                .uri(&format!("/admin/repositories/{}/watched-paths", repo.id))
+                .insert_header(admin_auth())
+                .set_form([
+                    ("watched_paths", watched_paths),
+                    ("skip_unwatched_commits", "true"),
+                ])
+                .to_request()
+        };
+
+        let resp = actix_test::call_service(&app, save("src/[a-")).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let resp = actix_test::call_service(&app, save("services/api/**\n\n  *.md  \n")).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::SEE_OTHER);
+
+        let stored = Repository::find_by_id(&pool, repo.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.watched_paths, vec!["services/api/**", "*.md"]);
+        assert!(stored.skip_unwatched_commits);
+    }
+}
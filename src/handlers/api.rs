@@ -0,0 +1,904 @@
+use std::collections::HashSet;
+
+use actix_web::{web, HttpResponse, Result};
+use chrono::{DateTime, Utc};
+use futures_util::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::EventStore;
+use crate::handlers::errors::AppError;
+use crate::handlers::events::EventFilters;
+use crate::handlers::webhook::{process_event_by_source, record_processing_failure};
+use crate::models::{
+    Actor, Commit, CreateEvent, Event, ImportedEvent, Issue, PullRequest, Repository,
+};
+use crate::services::{Metrics, RepositoryCache};
+use crate::utils::PaginationParams;
+
+/// Number of serialized events buffered between the database cursor and the response
+const EXPORT_BUFFER: usize = 64;
+
+/// Page size for `/api/events` when the request does not set `per_page`
+const DEFAULT_EVENTS_PER_PAGE: i64 = 100;
+
+/// Unprocessed events loaded per batch while reprocessing
+const REPROCESS_BATCH: i64 = 100;
+
+/// Imported lines inserted per transaction
+const IMPORT_BATCH: usize = 500;
+
+#[derive(Debug, Serialize)]
+pub struct RepositoryStats {
+    pub commits: i64,
+    pub pull_requests: i64,
+    pub issues: i64,
+}
+
+pub async fn list_repositories_json(
+    pool: web::Data<PgPool>,
+    query: web::Query<PaginationParams>,
+) -> Result<HttpResponse, AppError> {
+    let params = query.into_inner();
+
+    let repositories =
+        Repository::list_all(pool.get_ref(), params.limit(), params.offset()).await?;
+
+    let total = Repository::count(pool.get_ref()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "repositories": repositories,
+        "page": params.page,
+        "per_page": params.limit(),
+        "total": total
+    })))
+}
+
+pub async fn repository_json(
+    pool: web::Data<PgPool>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let repo_id = path.into_inner();
+
+    let Some(repository) = Repository::find_by_id(pool.get_ref(), repo_id).await? else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Repository not found"
+        })));
+    };
+
+    let stats = RepositoryStats {
+        commits: Commit::count_by_repository(pool.get_ref(), repo_id).await?,
+        pull_requests: PullRequest::count_by_repository(pool.get_ref(), repo_id).await?,
+        issues: Issue::count_by_repository(pool.get_ref(), repo_id).await?,
+    };
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "repository": repository,
+        "stats": stats
+    })))
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceHealth {
+    pub source: String,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+    pub stale: bool,
+}
+
+/// Most recent event per source, flagging sources silent past the configured threshold
+pub async fn sources_health_json(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let now = chrono::Utc::now();
+    let threshold = config.source_stale_after();
+
+    let sources: Vec<SourceHealth> = Event::last_seen_per_source(pool.get_ref())
+        .await?
+        .into_iter()
+        .map(|s| SourceHealth {
+            stale: s.is_stale(now, threshold),
+            source: s.source,
+            last_seen: s.last_seen,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "stale_after_seconds": config.source_stale_after_secs,
+        "sources": sources
+    })))
+}
+
+/// Liveness: the process is up and serving requests, whatever the database's state
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness: 503 when the database is unreachable or more than `READY_MAX_PENDING`
+/// events are waiting to be processed, so load balancers send deliveries elsewhere
+pub async fn readyz(pool: web::Data<PgPool>, config: web::Data<Config>) -> HttpResponse {
+    let summary = match Event::pending_summary(pool.get_ref()).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            log::warn!("Readiness check could not reach the database: {e}");
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "status": "unavailable",
+                "reason": "database unreachable"
+            }));
+        }
+    };
+
+    let max_pending = config.ready_max_pending;
+    if max_pending > 0 && summary.pending > max_pending {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "status": "unavailable",
+            "reason": "processing backlog",
+            "pending": summary.pending,
+            "max_pending": max_pending
+        }));
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "status": "ready",
+        "pending": summary.pending
+    }))
+}
+
+/// Prometheus scrape endpoint
+pub async fn metrics(metrics: web::Data<Metrics>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics.render())
+}
+
+/// Events matching the events page filters, with `per_page` clamped to `API_MAX_PER_PAGE`
+/// rather than the HTML page's smaller cap
+pub async fn list_events_json(
+    store: web::Data<dyn EventStore>,
+    config: web::Data<Config>,
+    query: web::Query<EventFilters>,
+) -> Result<HttpResponse, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page(
+        DEFAULT_EVENTS_PER_PAGE.min(config.api_max_per_page),
+        config.api_max_per_page,
+    );
+
+    let filters = query.to_filter_builder();
+    let events = store
+        .search_and_filter(&filters, per_page, (page - 1) * per_page)
+        .await?;
+    let total = store.count_filtered(&filters).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "events": events,
+        "page": page,
+        "per_page": per_page,
+        "total": total
+    })))
+}
+
+/// One event as MessagePack, for consumers that cannot parse large JSON efficiently
+pub async fn event_msgpack(
+    store: web::Data<dyn EventStore>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    let event = store
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Event {id} not found")))?;
+
+    match event.to_msgpack() {
+        Ok(body) => Ok(HttpResponse::Ok()
+            .content_type("application/msgpack")
+            .body(body)),
+        Err(e) => {
+            log::error!("Failed to encode event {id} as MessagePack: {e}");
+            Ok(HttpResponse::InternalServerError().finish())
+        }
+    }
+}
+
+/// Stream every event matching the events page filters as newline-delimited JSON
+pub async fn export_events_ndjson(
+    pool: web::Data<PgPool>,
+    query: web::Query<EventFilters>,
+) -> HttpResponse {
+    let filters = query.into_inner();
+    let pool = pool.get_ref().clone();
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<web::Bytes, std::io::Error>>(EXPORT_BUFFER);
+
+    // The bounded channel applies backpressure, so rows are only read from the
+    // database cursor as fast as the client consumes them
+    tokio::spawn(async move {
+        let mut query = filters.to_filter_builder().select_query();
+        query.push(" ORDER BY id ASC");
+
+        let mut rows = query.build_query_as::<Event>().fetch(&pool);
+
+        while let Some(row) = rows.next().await {
+            let line = row
+                .map_err(|e| {
+                    log::error!("Failed to stream events for export: {e}");
+                    std::io::Error::other(e)
+                })
+                .and_then(|event| {
+                    let mut line = serde_json::to_vec(&event).map_err(std::io::Error::other)?;
+                    line.push(b'\n');
+                    Ok(web::Bytes::from(line))
+                });
+
+            let failed = line.is_err();
+            if tx.send(line).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    let body = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|line| (line, rx))
+    });
+
+    HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(body)
+}
+
+/// One line of an events import. Exported events parse as-is; their ids, repository
+/// links and processing state are not carried over.
+#[derive(Debug, Deserialize)]
+struct ImportLine {
+    source: String,
+    event_type: String,
+    action: Option<String>,
+    actor_name: Option<String>,
+    actor_email: Option<String>,
+    actor_id: Option<String>,
+    actor_avatar_url: Option<String>,
+    raw_event: JsonValue,
+    /// Lines without one get a fresh id, so importing them twice stores them twice
+    delivery_id: Option<Uuid>,
+    signature: Option<String>,
+    received_at: Option<DateTime<Utc>>,
+    tenant: Option<String>,
+    raw_source: Option<String>,
+    raw_event_type: Option<String>,
+    raw_original: Option<JsonValue>,
+    payload_sha256: Option<String>,
+}
+
+impl ImportLine {
+    /// Parse and validate one NDJSON line
+    fn parse(line: &[u8]) -> Result<ImportedEvent, String> {
+        let line: ImportLine = serde_json::from_slice(line).map_err(|e| e.to_string())?;
+
+        if line.source.trim().is_empty() {
+            return Err("source must not be empty".to_string());
+        }
+        if line.event_type.trim().is_empty() {
+            return Err("event_type must not be empty".to_string());
+        }
+        if line.raw_event.is_null() {
+            return Err("raw_event must not be null".to_string());
+        }
+
+        let mut actor = Actor {
+            name: line.actor_name,
+            email: line.actor_email,
+            id: line.actor_id,
+            avatar_url: line.actor_avatar_url,
+        };
+        if actor == Actor::default() {
+            actor = Actor::from_payload(&line.source, &line.raw_event);
+        }
+
+        Ok(ImportedEvent {
+            event: CreateEvent {
+                source: line.source,
+                event_type: line.event_type,
+                action: line.action,
+                actor,
+                raw_event: line.raw_event,
+                delivery_id: line.delivery_id.unwrap_or_else(Uuid::new_v4),
+                signature: line.signature,
+                repository_id: None,
+                tenant: line.tenant,
+                raw_source: line.raw_source,
+                raw_event_type: line.raw_event_type,
+                raw_original: line.raw_original,
+                payload_sha256: line.payload_sha256,
+                searchable_text: None,
+            },
+            received_at: line.received_at.unwrap_or_else(Utc::now),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ImportLineError {
+    line: usize,
+    error: String,
+}
+
+/// Bulk-load events from newline-delimited JSON, e.g. when migrating from another tool.
+/// Lines are inserted in batches of `IMPORT_BATCH`, each in one transaction; duplicate
+/// delivery ids are skipped and invalid lines reported by number. Payloads are pruned and
+/// compressed like webhook deliveries, and imported events are stored unprocessed.
+pub async fn import_events_ndjson(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    mut body: web::Payload,
+) -> Result<HttpResponse, AppError> {
+    let mut buffer = Vec::new();
+    let mut batch = Vec::with_capacity(IMPORT_BATCH);
+    let mut line_number = 0;
+    let mut valid: u64 = 0;
+    let mut imported = 0;
+    let mut errors = Vec::new();
+
+    let mut finished = false;
+    while !finished {
+        match body.next().await {
+            Some(chunk) => {
+                let chunk = chunk.map_err(|e| AppError::bad_request(e.to_string()))?;
+                buffer.extend_from_slice(&chunk);
+            }
+            None => finished = true,
+        }
+
+        // Whole lines are parsed as they arrive; the last line may lack its newline
+        loop {
+            let end = match buffer.iter().position(|b| *b == b'\n') {
+                Some(newline) => newline + 1,
+                None if finished && !buffer.is_empty() => buffer.len(),
+                None => break,
+            };
+            let line: Vec<u8> = buffer.drain(..end).collect();
+            line_number += 1;
+            if line.trim_ascii().is_empty() {
+                continue;
+            }
+
+            match ImportLine::parse(&line) {
+                Ok(mut imported_event) => {
+                    let event = &mut imported_event.event;
+                    // Search fields may lie outside the pruned payload
+                    event.searchable_text = config.searchable_text(&event.raw_event);
+                    event.raw_event = config.payload_for_storage(&event.raw_event);
+                    valid += 1;
+                    batch.push(imported_event);
+                }
+                Err(error) => errors.push(ImportLineError {
+                    line: line_number,
+                    error,
+                }),
+            }
+
+            if batch.len() >= IMPORT_BATCH {
+                imported += Event::create_many(
+                    pool.get_ref(),
+                    std::mem::take(&mut batch),
+                    config.compress_payloads,
+                )
+                .await?;
+            }
+        }
+    }
+
+    if !batch.is_empty() {
+        imported += Event::create_many(pool.get_ref(), batch, config.compress_payloads).await?;
+    }
+
+    log::info!(
+        "Imported {imported} event(s), {} duplicate(s) skipped, {} invalid line(s)",
+        valid - imported,
+        errors.len()
+    );
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "imported": imported,
+        "skipped_duplicates": valid - imported,
+        "errors": errors
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReprocessQuery {
+    pub source: String,
+    pub event_type: String,
+}
+
+/// Run unprocessed events of one source and type through processing again,
+/// e.g. after adding a handler for that type.
+pub async fn reprocess_events(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    repos: web::Data<RepositoryCache>,
+    query: web::Query<ReprocessQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut reprocessed = 0;
+    let mut failed = 0;
+    let mut entities_written = 0;
+    let mut seen = HashSet::new();
+
+    'batches: loop {
+        // Processed events leave the unprocessed set, so only failures are skipped over
+        let batch = Event::list_by_type_unprocessed(
+            pool.get_ref(),
+            &query.source,
+            &query.event_type,
+            REPROCESS_BATCH,
+            failed,
+        )
+        .await?;
+
+        if batch.is_empty() {
+            break;
+        }
+
+        for event in &batch {
+            // An event seen twice was processed without being marked; stop rather than spin
+            if !seen.insert(event.id) {
+                log::warn!("Event {} is still unprocessed after reprocessing", event.id);
+                break 'batches;
+            }
+
+            match process_event_by_source(
+                pool.get_ref(),
+                repos.get_ref(),
+                event,
+                &query.source,
+                config.strict_processing,
+            )
+            .await
+            {
+                Ok(result) => {
+                    reprocessed += 1;
+                    entities_written += result.entities_written;
+                }
+                Err(e) => {
+                    log::error!("Failed to reprocess event {}: {e}", event.id);
+                    record_processing_failure(pool.get_ref(), event.id, &e.to_string()).await;
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    log::info!(
+        "Reprocessed {reprocessed} {} {} event(s), {failed} failed",
+        query.source,
+        query.event_type
+    );
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "source": query.source,
+        "event_type": query.event_type,
+        "reprocessed": reprocessed,
+        "failed": failed,
+        "entities_written": entities_written
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::github::CreateRepository;
+    use crate::models::{EventFilterBuilder, FilterTable};
+    use actix_web::{test as actix_test, App};
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_event_msgpack_decodes_to_the_stored_event(pool: PgPool) {
+        let store: std::sync::Arc<dyn EventStore> =
+            std::sync::Arc::new(crate::db::PgEventStore::new(pool));
+        let event = store
+            .create(CreateEvent {
+                action: Some("finished".to_string()),
+                raw_event: serde_json::json!({ "status": "green", "steps": [1, 2, 3] }),
+                ..CreateEvent::for_test("ci", "build")
+            })
+            .await
+            .unwrap();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::from(store))
+                .route("/api/events/{id}.msgpack", web::get().to(event_msgpack)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/api/events/{}.msgpack", event.id))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "application/msgpack"
+        );
+        let body = actix_test::read_body(resp).await;
+        let decoded = Event::from_msgpack(&body).unwrap();
+        assert_eq!(decoded.id, event.id);
+        assert_eq!(decoded.delivery_id, event.delivery_id);
+        assert_eq!(decoded.raw_event, event.raw_event);
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/api/events/{}.msgpack", event.id + 1))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_list_events_json_allows_large_pages(pool: PgPool) {
+        let store: std::sync::Arc<dyn EventStore> =
+            std::sync::Arc::new(crate::db::PgEventStore::new(pool));
+        for _ in 0..350 {
+            store
+                .create(CreateEvent::for_test("custom", "deploy"))
+                .await
+                .unwrap();
+        }
+
+        let mut config = Config::test_default();
+        config.api_max_per_page = 1000;
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::from(store))
+                .app_data(web::Data::new(config))
+                .route("/api/events", web::get().to(list_events_json)),
+        )
+        .await;
+
+        // Larger than the events page cap, so one call returns every event
+        let req = actix_test::TestRequest::get()
+            .uri("/api/events?per_page=1000")
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["per_page"], 1000);
+        assert_eq!(body["total"], 350);
+        assert_eq!(body["events"].as_array().unwrap().len(), 350);
+
+        let req = actix_test::TestRequest::get()
+            .uri("/api/events?per_page=5000")
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["per_page"], 1000);
+
+        let req = actix_test::TestRequest::get()
+            .uri("/api/events?page=2")
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["per_page"], DEFAULT_EVENTS_PER_PAGE);
+        assert_eq!(body["events"].as_array().unwrap().len(), 100);
+    }
+
+    async fn create_repository(pool: &PgPool, github_id: i64, name: &str) -> Repository {
+        Repository::create(
+            pool,
+            CreateRepository {
+                github_id,
+                name: name.to_string(),
+                full_name: format!("octocat/{name}"),
+                owner: "octocat".to_string(),
+                description: None,
+                url: format!("https://github.com/octocat/{name}"),
+                is_private: false,
+                language: None,
+                topics: Vec::new(),
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_export_events_ndjson(pool: PgPool) {
+        for source in ["github", "github", "gitlab"] {
+            Event::create(
+                &pool,
+                CreateEvent {
+                    raw_event: serde_json::json!({ "ref": "refs/heads/main" }),
+                    ..CreateEvent::for_test(source, "push")
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let app =
+            actix_test::init_service(App::new().app_data(web::Data::new(pool.clone())).route(
+                "/api/events/export.ndjson",
+                web::get().to(export_events_ndjson),
+            ))
+            .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri("/api/events/export.ndjson?source=github")
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.ends_with('\n'));
+        let lines: Vec<&str> = body.lines().collect();
+        let github = EventFilterBuilder::new(FilterTable::Events).source(Some("github"));
+        let expected = Event::count_filtered(&pool, &github).await.unwrap();
+        assert_eq!(lines.len() as i64, expected);
+
+        for line in lines {
+            let event: JsonValue = serde_json::from_str(line).unwrap();
+            assert_eq!(event["source"], "github");
+        }
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_import_events_ndjson(pool: PgPool) {
+        let existing = uuid::Uuid::new_v4();
+        let fresh = uuid::Uuid::new_v4();
+        let ndjson = [
+            format!(
+                r#"{{"source":"github","event_type":"push","raw_event":{{"ref":"refs/heads/main"}},"delivery_id":"{fresh}","received_at":"2024-03-01T12:00:00Z"}}"#
+            ),
+            "not json".to_string(),
+            String::new(),
+            r#"{"source":"","event_type":"push","raw_event":{}}"#.to_string(),
+            format!(
+                r#"{{"source":"ci","event_type":"build","raw_event":{{"status":"green"}},"delivery_id":"{existing}"}}"#
+            ),
+            // Repeats the first line's delivery id; the last line has no trailing newline
+            format!(
+                r#"{{"source":"ci","event_type":"build","raw_event":{{"status":"green"}},"delivery_id":"{fresh}"}}"#
+            ),
+        ]
+        .join("\n");
+
+        // Already stored, as after an earlier import
+        Event::create(
+            &pool,
+            CreateEvent {
+                raw_event: serde_json::json!({ "status": "green" }),
+                delivery_id: existing,
+                ..CreateEvent::for_test("ci", "build")
+            },
+        )
+        .await
+        .unwrap();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .route("/api/events/import", web::post().to(import_events_ndjson)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/api/events/import")
+            .set_payload(ndjson)
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(body["imported"], 1);
+        assert_eq!(body["skipped_duplicates"], 2);
+        let error_lines: Vec<u64> = body["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|e| e["line"].as_u64())
+            .collect();
+        assert_eq!(error_lines, [2, 4]);
+
+        let imported = Event::find_by_delivery_id(&pool, fresh)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(imported.source, "github");
+        assert_eq!(imported.raw_event["ref"], "refs/heads/main");
+        assert_eq!(
+            imported.received_at.to_rfc3339(),
+            "2024-03-01T12:00:00+00:00"
+        );
+        assert!(!imported.processed);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_import_stores_payloads_like_webhooks(pool: PgPool) {
+        let config = Config {
+            store_full_payload: false,
+            compress_payloads: true,
+            ..Config::test_default()
+        };
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .route("/api/events/import", web::post().to(import_events_ndjson)),
+        )
+        .await;
+
+        let delivery_id = uuid::Uuid::new_v4();
+        let req = actix_test::TestRequest::post()
+            .uri("/api/events/import")
+            .set_payload(format!(
+                r#"{{"source":"github","event_type":"push","raw_event":{{"ref":"refs/heads/main","hook":{{"config":{{}}}}}},"delivery_id":"{delivery_id}"}}"#
+            ))
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["imported"], 1);
+
+        let compressed: bool =
+            sqlx::query_scalar("SELECT raw_event_compressed FROM events WHERE delivery_id = $1")
+                .bind(delivery_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(compressed);
+
+        let imported = Event::find_by_delivery_id(&pool, delivery_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            imported.raw_event,
+            serde_json::json!({ "ref": "refs/heads/main" })
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_list_repositories_json(pool: PgPool) {
+        create_repository(&pool, 1, "one").await;
+        create_repository(&pool, 2, "two").await;
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/api/repositories", web::get().to(list_repositories_json)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri("/api/repositories?per_page=1")
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["per_page"], 1);
+        assert_eq!(body["repositories"].as_array().unwrap().len(), 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_repository_json_and_not_found(pool: PgPool) {
+        let repo = create_repository(&pool, 1, "one").await;
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/api/repositories/{id}", web::get().to(repository_json)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/api/repositories/{}", repo.id))
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(body["repository"]["full_name"], "octocat/one");
+        assert_eq!(body["stats"]["commits"], 0);
+        assert_eq!(body["stats"]["issues"], 0);
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/api/repositories/{}", repo.id + 1))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_readiness_follows_the_processing_backlog(pool: PgPool) {
+        let config = Config {
+            ready_max_pending: 2,
+            ..Config::test_default()
+        };
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .route("/healthz", web::get().to(healthz))
+                .route("/readyz", web::get().to(readyz)),
+        )
+        .await;
+        let status = |uri: &'static str| {
+            let app = &app;
+            async move {
+                let req = actix_test::TestRequest::get().uri(uri).to_request();
+                actix_test::call_service(app, req).await.status()
+            }
+        };
+
+        let mut pending = Vec::new();
+        for _ in 0..3 {
+            let event = Event::create(&pool, CreateEvent::for_test("github", "push"))
+                .await
+                .unwrap();
+            pending.push(event.id);
+        }
+
+        // Three pending events exceed the threshold of two
+        assert_eq!(
+            status("/readyz").await,
+            actix_web::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(status("/healthz").await, actix_web::http::StatusCode::OK);
+
+        Event::mark_processed(&pool, pending[0]).await.unwrap();
+        assert_eq!(status("/readyz").await, actix_web::http::StatusCode::OK);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_reprocess_only_matching_unprocessed_events(pool: PgPool) {
+        let mut ids = Vec::new();
+        for (source, event_type) in [
+            ("github", "gollum"),
+            ("github", "gollum"),
+            ("github", "fork"),
+            ("gitlab", "gollum"),
+        ] {
+            let event = Event::create(&pool, CreateEvent::for_test(source, event_type))
+                .await
+                .unwrap();
+            ids.push(event.id);
+        }
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .wrap(crate::handlers::AdminApiKey::new(
+                    Config::test_default().admin_api_key,
+                ))
+                .route("/api/events/reprocess", web::post().to(reprocess_events)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/api/events/reprocess?source=github&event_type=gollum")
+            .insert_header((
+                actix_web::http::header::AUTHORIZATION,
+                "Bearer admin-key-value",
+            ))
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["reprocessed"], 2);
+        assert_eq!(body["failed"], 0);
+
+        let mut processed = Vec::new();
+        for id in ids {
+            processed.push(
+                Event::find_by_id(&pool, id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .processed,
+            );
+        }
+        assert_eq!(processed, vec![true, true, false, false]);
+
+        let remaining = Event::list_by_type_unprocessed(&pool, "github", "gollum", 10, 0)
+            .await
+            .unwrap();
+        assert!(remaining.is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use std::future::{ready, Ready};
+
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{header, Method};
+use actix_web::{Error, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use secrecy::{ExposeSecret, SecretString};
+
+use crate::utils::verify_bearer_token;
+
+/// Requires `Authorization: Bearer <ADMIN_API_KEY>` on mutating `/api` routes, keeping
+/// operator actions apart from the read-only API and the basic-auth admin pages.
+///
+/// Without a configured key those routes answer 404; `GET`, `HEAD` and `OPTIONS`
+/// requests and non-`/api` paths pass through untouched.
+#[derive(Debug, Clone)]
+pub struct AdminApiKey {
+    key: Option<SecretString>,
+}
+
+impl AdminApiKey {
+    pub fn new(key: Option<SecretString>) -> Self {
+        AdminApiKey { key }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for AdminApiKey
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = AdminApiKeyMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AdminApiKeyMiddleware {
+            service,
+            key: self.key.clone(),
+        }))
+    }
+}
+
+pub struct AdminApiKeyMiddleware<S> {
+    service: S,
+    key: Option<SecretString>,
+}
+
+impl<S, B> Service<ServiceRequest> for AdminApiKeyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let read_only = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+        if read_only || !req.path().starts_with("/api/") {
+            let fut = self.service.call(req);
+            return Box::pin(async move { Ok(fut.await?.map_into_left_body()) });
+        }
+
+        let Some(key) = &self.key else {
+            let response = HttpResponse::NotFound()
+                .json(serde_json::json!({ "error": "Admin API is disabled" }));
+            return Box::pin(ready(Ok(req.into_response(response).map_into_right_body())));
+        };
+
+        let auth_header = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok());
+        if !verify_bearer_token(auth_header, key.expose_secret()) {
+            log::warn!(
+                "Rejected {} {}: missing or invalid API key",
+                req.method(),
+                req.path()
+            );
+            let response = HttpResponse::Unauthorized()
+                .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                .json(serde_json::json!({ "error": "Invalid or missing API key" }));
+            return Box::pin(ready(Ok(req.into_response(response).map_into_right_body())));
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { Ok(fut.await?.map_into_left_body()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test as actix_test, web, App};
+
+    #[actix_web::test]
+    async fn test_mutating_api_routes_require_the_key() {
+        let key = SecretString::new("operator-key".to_string());
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(AdminApiKey::new(Some(key)))
+                .route("/api/events/reprocess", web::post().to(HttpResponse::Ok))
+                .route("/api/events", web::get().to(HttpResponse::Ok))
+                .route("/webhook/{source}", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+        let post = |authorization: Option<&str>| {
+            let mut req = actix_test::TestRequest::post().uri("/api/events/reprocess");
+            if let Some(value) = authorization {
+                req = req.insert_header((header::AUTHORIZATION, value));
+            }
+            req.to_request()
+        };
+
+        let missing = actix_test::call_service(&app, post(None)).await;
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            missing.headers().get(header::WWW_AUTHENTICATE).unwrap(),
+            "Bearer"
+        );
+
+        for wrong in ["Bearer wrong-key", "operator-key", "Basic b3BlcmF0b3Ita2V5"] {
+            let resp = actix_test::call_service(&app, post(Some(wrong))).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{wrong}");
+        }
+
+        let correct = actix_test::call_service(&app, post(Some("Bearer operator-key"))).await;
+        assert_eq!(correct.status(), StatusCode::OK);
+
+        // Reads and webhooks need no key
+        let req = actix_test::TestRequest::get()
+            .uri("/api/events")
+            .to_request();
+        assert_eq!(
+            actix_test::call_service(&app, req).await.status(),
+            StatusCode::OK
+        );
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/ci")
+            .to_request();
+        assert_eq!(
+            actix_test::call_service(&app, req).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_mutating_api_routes_are_disabled_without_a_key() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(AdminApiKey::new(None))
+                .route("/api/events/reprocess", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/api/events/reprocess")
+            .insert_header((header::AUTHORIZATION, "Bearer anything"))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,167 @@
+use std::cell::Cell;
+use std::future::{ready, Ready};
+use std::io;
+use std::rc::Rc;
+use std::time::Duration;
+
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::PayloadError;
+use actix_web::{Error, HttpMessage, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use futures_util::stream::{self, StreamExt};
+use tokio::time::Instant;
+
+/// Drops webhook deliveries whose body is not fully received within `timeout` of the
+/// request reaching the app, so slow-loris senders cannot hold a worker indefinitely.
+///
+/// The head is covered by the server's `client_request_timeout`; this bounds the body
+/// the webhook handlers then wait for. Only `/webhook...` paths are affected, and the
+/// body is streamed through rather than buffered, so payload size limits still apply.
+#[derive(Debug, Clone)]
+pub struct BodyReadTimeout {
+    timeout: Option<Duration>,
+}
+
+impl BodyReadTimeout {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        BodyReadTimeout { timeout }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for BodyReadTimeout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = BodyReadTimeoutMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(BodyReadTimeoutMiddleware {
+            service,
+            timeout: self.timeout,
+        }))
+    }
+}
+
+pub struct BodyReadTimeoutMiddleware<S> {
+    service: S,
+    timeout: Option<Duration>,
+}
+
+impl<S, B> Service<ServiceRequest> for BodyReadTimeoutMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let timeout = match self.timeout {
+            Some(timeout) if req.path().starts_with("/webhook") => timeout,
+            _ => {
+                let fut = self.service.call(req);
+                return Box::pin(async move { Ok(fut.await?.map_into_left_body()) });
+            }
+        };
+
+        let timed_out = Rc::new(Cell::new(false));
+        let payload = with_deadline(
+            req.take_payload(),
+            Instant::now() + timeout,
+            Rc::clone(&timed_out),
+        );
+        req.set_payload(payload);
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+            if !timed_out.get() {
+                return Ok(res.map_into_left_body());
+            }
+
+            log::warn!(
+                "Dropped {} after its body took longer than {}ms",
+                res.request().path(),
+                timeout.as_millis()
+            );
+            let response = HttpResponse::RequestTimeout()
+                .json(serde_json::json!({ "error": "Request body not received in time" }));
+            Ok(res.into_response(response).map_into_right_body())
+        })
+    }
+}
+
+/// `payload`, ending with an error and setting `timed_out` once `deadline` passes
+/// before the last chunk
+fn with_deadline(payload: Payload, deadline: Instant, timed_out: Rc<Cell<bool>>) -> Payload {
+    let chunks = stream::unfold(Some(payload), move |payload| {
+        let timed_out = Rc::clone(&timed_out);
+        async move {
+            let mut payload = payload?;
+            match tokio::time::timeout_at(deadline, payload.next()).await {
+                Ok(Some(chunk)) => Some((chunk, Some(payload))),
+                Ok(None) => None,
+                Err(_) => {
+                    timed_out.set(true);
+                    let err = io::Error::new(io::ErrorKind::TimedOut, "body read timed out");
+                    Some((Err(PayloadError::Io(err)), None))
+                }
+            }
+        }
+    });
+    Payload::from(chunks.boxed_local())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::web::{self, Bytes};
+    use actix_web::{test as actix_test, App};
+
+    #[actix_web::test]
+    async fn test_stalled_webhook_body_is_dropped_after_the_timeout() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(BodyReadTimeout::new(Some(Duration::from_millis(50))))
+                .route(
+                    "/webhook/{source}",
+                    web::post().to(|body: Bytes| async move { HttpResponse::Ok().body(body) }),
+                ),
+        )
+        .await;
+
+        // The sender stops partway through the body and never finishes
+        let stalled =
+            stream::once(async { Ok::<_, PayloadError>(Bytes::from_static(b"{\"action\":")) })
+                .chain(stream::pending());
+        let (req, _) = actix_test::TestRequest::post()
+            .uri("/webhook/ci")
+            .to_request()
+            .replace_payload(Payload::from(stalled.boxed_local()));
+
+        let started = std::time::Instant::now();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::REQUEST_TIMEOUT);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Bodies that arrive in time pass through untouched
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/ci")
+            .set_payload("{\"action\":\"opened\"}")
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        assert_eq!(body, Bytes::from_static(b"{\"action\":\"opened\"}"));
+    }
+}
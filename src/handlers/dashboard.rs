@@ -1,34 +1,45 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{http::header, web, HttpResponse, Result};
 use maud::{html, DOCTYPE};
 use sqlx::PgPool;
 
-pub async fn dashboard(pool: web::Data<PgPool>) -> Result<HttpResponse> {
-    let repo_count = crate::models::Repository::count(pool.get_ref())
-        .await
-        .unwrap_or(0);
-    let event_count = crate::models::Event::count(pool.get_ref())
-        .await
-        .unwrap_or(0);
-    let commit_count = crate::models::Commit::count(pool.get_ref())
-        .await
-        .unwrap_or(0);
-    let pr_count = crate::models::PullRequest::count(pool.get_ref())
-        .await
-        .unwrap_or(0);
-    let issue_count = crate::models::Issue::count(pool.get_ref())
-        .await
-        .unwrap_or(0);
+use crate::config::Config;
+use crate::handlers::errors::AppError;
+use crate::handlers::layout::Theme;
 
-    let open_pr_count = crate::models::PullRequest::count_by_state(pool.get_ref(), "open")
-        .await
-        .unwrap_or(0);
-    let open_issue_count = crate::models::Issue::count_by_state(pool.get_ref(), "open")
+/// `/`: the dashboard, or a redirect to the page chosen with `HOME_ROUTE`
+pub async fn home(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    theme: Theme,
+) -> Result<HttpResponse, AppError> {
+    match config.home_route.redirect_path() {
+        Some(path) => Ok(HttpResponse::Found()
+            .insert_header((header::LOCATION, path))
+            .finish()),
+        None => dashboard(pool, config, theme).await,
+    }
+}
+
+pub async fn dashboard(
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    theme: Theme,
+) -> Result<HttpResponse, AppError> {
+    // A failed count should not take the whole dashboard down
+    let stats = crate::models::stats_overview(pool.get_ref())
         .await
-        .unwrap_or(0);
+        .unwrap_or_else(|e| {
+            log::error!("Failed to load dashboard stats: {e}");
+            crate::models::StatsOverview::default()
+        });
+
+    let source_health = crate::models::Event::last_seen_per_source(pool.get_ref()).await?;
+    let now = chrono::Utc::now();
+    let stale_after = config.source_stale_after();
 
     let markup = html! {
         (DOCTYPE)
-        html lang="en" data-theme="dark" {
+        html lang="en" data-theme=[theme.data_theme()] {
             head {
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
@@ -49,13 +60,13 @@ pub async fn dashboard(pool: web::Data<PgPool>) -> Result<HttpResponse> {
                     }
                     div class="flex-none gap-2" {
                         ul class="menu menu-horizontal px-1" {
-                            li { a href="/" { "Dashboard" } }
+                            li { a href="/dashboard" { "Dashboard" } }
                             li { a href="/events" { "Events" } }
                         }
                         button
                             class="btn btn-ghost btn-circle"
                             onclick="toggleTheme()"
-                            title="Toggle theme"
+                            title="Toggle theme (light, dark, auto)"
                         {
                             // Sun icon for light mode
                             svg
@@ -81,28 +92,64 @@ pub async fn dashboard(pool: web::Data<PgPool>) -> Result<HttpResponse> {
                     div class="stats stats-vertical lg:stats-horizontal shadow w-full mb-8" {
                         div class="stat" {
                             div class="stat-title" { "Repositories" }
-                            div class="stat-value text-primary" { (repo_count) }
+                            div class="stat-value text-primary" { (stats.repositories) }
                         }
                         div class="stat" {
                             div class="stat-title" { "Total Events" }
-                            div class="stat-value" { (event_count) }
+                            div class="stat-value" { (stats.events) }
                         }
                         div class="stat" {
                             div class="stat-title" { "Commits" }
-                            div class="stat-value text-accent" { (commit_count) }
+                            div class="stat-value text-accent" { (stats.commits) }
                         }
                     }
 
                     div class="stats stats-vertical lg:stats-horizontal shadow w-full mb-8" {
                         div class="stat" {
                             div class="stat-title" { "Pull Requests" }
-                            div class="stat-value" { (pr_count) }
-                            div class="stat-desc" { "Open: " (open_pr_count) }
+                            div class="stat-value" { (stats.pull_requests) }
+                            div class="stat-desc" { "Open: " (stats.open_pull_requests) }
                         }
                         div class="stat" {
                             div class="stat-title" { "Issues" }
-                            div class="stat-value" { (issue_count) }
-                            div class="stat-desc" { "Open: " (open_issue_count) }
+                            div class="stat-value" { (stats.issues) }
+                            div class="stat-desc" { "Open: " (stats.open_issues) }
+                        }
+                    }
+
+                    h2 class="text-2xl font-bold mb-4" { "Source Health" }
+                    @if source_health.is_empty() {
+                        div class="alert alert-info mb-8" {
+                            span { "No events received yet." }
+                        }
+                    } @else {
+                        div class="card bg-base-100 shadow-xl mb-8" {
+                            div class="card-body p-0" {
+                                table class="table" {
+                                    thead {
+                                        tr {
+                                            th { "Source" }
+                                            th { "Last Event" }
+                                            th { "Status" }
+                                        }
+                                    }
+                                    tbody {
+                                        @for source in &source_health {
+                                            tr {
+                                                td { span class="badge badge-secondary" { (source.source) } }
+                                                td class="text-sm" { (source.last_seen.format("%Y-%m-%d %H:%M:%S UTC")) }
+                                                td {
+                                                    @if source.is_stale(now, stale_after) {
+                                                        span class="badge badge-warning" { "Stale" }
+                                                    } @else {
+                                                        span class="badge badge-success" { "Active" }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
                 }
@@ -114,3 +161,59 @@ pub async fn dashboard(pool: web::Data<PgPool>) -> Result<HttpResponse> {
         .content_type("text/html")
         .body(markup.into_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HomeRoute;
+    use actix_web::{http::StatusCode, test as actix_test, App};
+    use sqlx::postgres::PgPoolOptions;
+
+    #[actix_web::test]
+    async fn test_home_redirects_to_configured_route() {
+        // Redirects never touch the database
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/cross_bow_test")
+            .unwrap();
+
+        for (home_route, location) in [
+            (HomeRoute::Events, "/events"),
+            (HomeRoute::Repositories, "/repositories"),
+        ] {
+            let mut config = Config::test_default();
+            config.home_route = home_route;
+            let app = actix_test::init_service(
+                App::new()
+                    .app_data(web::Data::new(pool.clone()))
+                    .app_data(web::Data::new(config))
+                    .route("/", web::get().to(home)),
+            )
+            .await;
+
+            let req = actix_test::TestRequest::get().uri("/").to_request();
+            let resp = actix_test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            assert_eq!(resp.headers().get(header::LOCATION).unwrap(), location);
+        }
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_home_renders_dashboard_by_default(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .app_data(web::Data::new(Config::test_default()))
+                .route("/", web::get().to(home)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get().uri("/").to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = actix_test::read_body(resp).await;
+        assert!(String::from_utf8(body.to_vec())
+            .unwrap()
+            .contains("Dashboard"));
+    }
+}
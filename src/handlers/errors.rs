@@ -0,0 +1,247 @@
+use actix_web::dev::ServiceResponse;
+use actix_web::http::{header, StatusCode};
+use actix_web::middleware::{ErrorHandlerResponse, ErrorHandlers};
+use actix_web::{HttpResponse, ResponseError, Result};
+use maud::{html, Markup};
+
+use crate::handlers::layout::{self, Theme};
+
+/// Error returned by web and API handlers.
+///
+/// Database failures are logged with their cause and answered with a bare 500, which
+/// `error_handlers` turns into the styled page or a JSON body depending on the route.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        AppError::BadRequest(message.into())
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let AppError::Database(e) = self {
+            log::error!("Database error while handling request: {e}");
+        }
+
+        HttpResponse::build(self.status_code())
+            .content_type("text/plain; charset=utf-8")
+            .body(self.to_string())
+    }
+}
+
+/// Replace bare 404/500 responses with a styled HTML page, or a JSON body for API clients
+pub fn error_handlers<B: 'static>() -> ErrorHandlers<B> {
+    ErrorHandlers::new()
+        .handler(StatusCode::NOT_FOUND, render_error)
+        .handler(StatusCode::INTERNAL_SERVER_ERROR, render_error)
+}
+
+fn render_error<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<B>> {
+    // Handlers that already built a JSON or HTML error body keep it
+    let has_body = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json") || ct.starts_with("text/html"));
+    if has_body {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    let status = res.status();
+    let message = error_message(status, res.response().error());
+
+    let response = if wants_json(res.request().path()) {
+        HttpResponse::build(status).json(serde_json::json!({ "error": message }))
+    } else {
+        HttpResponse::build(status).content_type("text/html").body(
+            render_error_page(status, &message, Theme::for_request(res.request())).into_string(),
+        )
+    };
+
+    Ok(ErrorHandlerResponse::Response(
+        res.into_response(response).map_into_right_body(),
+    ))
+}
+
+/// API and webhook routes are consumed by machines and always get JSON errors
+fn wants_json(path: &str) -> bool {
+    path.starts_with("/api/") || path.starts_with("/webhook")
+}
+
+/// User-facing message; internal error details are only shown for 404s
+fn error_message(status: StatusCode, error: Option<&actix_web::Error>) -> String {
+    match (status, error) {
+        (StatusCode::NOT_FOUND, Some(error)) => error.to_string(),
+        (StatusCode::NOT_FOUND, None) => "Page not found".to_string(),
+        _ => "Something went wrong".to_string(),
+    }
+}
+
+fn render_error_page(status: StatusCode, message: &str, theme: Theme) -> Markup {
+    let title = status.canonical_reason().unwrap_or("Error");
+
+    layout::page(
+        title,
+        theme,
+        html! {
+            div class="hero min-h-[50vh]" {
+                div class="hero-content text-center" {
+                    div class="max-w-md" {
+                        h1 class="text-6xl font-bold text-primary" { (status.as_u16()) }
+                        p class="text-2xl font-semibold mt-4" { (title) }
+                        p class="py-6 text-base-content/70" { (message) }
+                        a class="btn btn-primary" href="/" { "Back to Dashboard" }
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::repository_detail;
+    use actix_web::{test as actix_test, web, App};
+    use sqlx::PgPool;
+
+    #[actix_web::test]
+    async fn test_unknown_routes_render_html_or_json() {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(error_handlers())
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get().uri("/missing").to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/html"
+        );
+
+        let req = actix_test::TestRequest::get()
+            .uri("/api/missing")
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value = actix_test::read_body_json(resp).await;
+        assert_eq!(body, serde_json::json!({ "error": "Page not found" }));
+    }
+
+    #[actix_web::test]
+    async fn test_internal_errors_hide_details() {
+        let app = actix_test::init_service(App::new().wrap(error_handlers()).route(
+            "/",
+            web::get().to(|| async {
+                Err::<HttpResponse, _>(actix_web::error::ErrorInternalServerError(
+                    "connection refused",
+                ))
+            }),
+        ))
+        .await;
+
+        let req = actix_test::TestRequest::get().uri("/").to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("Something went wrong"));
+        assert!(!body.contains("connection refused"));
+    }
+
+    #[actix_web::test]
+    async fn test_app_error_database_failure_is_500() {
+        let failing =
+            || async { Err::<HttpResponse, _>(AppError::from(sqlx::Error::PoolTimedOut)) };
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(error_handlers())
+                .route("/events", web::get().to(failing))
+                .route("/api/events", web::get().to(failing)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get().uri("/events").to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = actix_test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("Something went wrong"));
+        assert!(!body.contains("timed out"));
+
+        let req = actix_test::TestRequest::get()
+            .uri("/api/events")
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body: serde_json::Value = actix_test::read_body_json(resp).await;
+        assert_eq!(body, serde_json::json!({ "error": "Something went wrong" }));
+    }
+
+    #[actix_web::test]
+    async fn test_app_error_not_found_shows_message() {
+        let app =
+            actix_test::init_service(App::new().wrap(error_handlers()).route(
+                "/api/things/{id}",
+                web::get().to(|| async {
+                    Err::<HttpResponse, _>(AppError::not_found("Thing not found"))
+                }),
+            ))
+            .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri("/api/things/1")
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value = actix_test::read_body_json(resp).await;
+        assert_eq!(body, serde_json::json!({ "error": "Thing not found" }));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_missing_repository_renders_styled_404(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(error_handlers())
+                .app_data(web::Data::new(pool))
+                .route("/repositories/{id}", web::get().to(repository_detail)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri("/repositories/999999")
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let body = actix_test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("<title>Not Found - Cross Bow</title>"));
+        assert!(body.contains("Repository not found"));
+        assert!(body.contains("Back to Dashboard"));
+    }
+}
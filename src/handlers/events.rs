@@ -1,25 +1,102 @@
+use std::collections::HashMap;
+
 use actix_web::{web, HttpResponse, Result};
 use chrono::{DateTime, Utc};
 use maud::{html, PreEscaped, DOCTYPE};
 use serde::Deserialize;
 use sqlx::PgPool;
 
-use crate::models::Event;
+use crate::config::Config;
+use crate::db::EventStore;
+use crate::handlers::errors::AppError;
+use crate::handlers::layout::Theme;
+use crate::models::{
+    CreateEventNote, Event, EventFilterBuilder, EventNote, EventTag, FilterTable, NO_ACTION,
+};
+use crate::services::EntityKey;
+use crate::utils::{diff_json, DiffKind};
+
+/// Largest page the events page renders; the JSON API has its own, configurable cap
+pub const UI_MAX_PER_PAGE: i64 = 300;
+
+/// Upper bound on page buttons rendered, however many pages the filters match
+const MAX_PAGE_BUTTONS: i64 = 50;
+
+/// Most recent events the grouped view clusters
+const GROUPED_EVENTS_LIMIT: i64 = 500;
+
+/// Events listed per section of an event's related events
+const RELATED_EVENTS_LIMIT: i64 = 10;
+
+/// How far either side of an event its repository neighbors are looked for
+const RELATED_EVENTS_WINDOW_MINUTES: i64 = 60;
 
 #[derive(Debug, Deserialize, Default)]
 #[serde(default)]
 pub struct EventFilters {
     #[serde(deserialize_with = "deserialize_optional_i64")]
     pub page: Option<i64>,
+    #[serde(deserialize_with = "deserialize_optional_i64")]
+    pub per_page: Option<i64>,
+    pub tenant: Option<String>,
     pub source: Option<String>,
     pub event_type: Option<String>,
     pub action: Option<String>,
     pub actor_name: Option<String>,
+    #[serde(deserialize_with = "deserialize_optional_i64")]
+    pub repository_id: Option<i64>,
     pub processed: Option<bool>,
     pub search: Option<String>,
+    pub tag: Option<String>,
 }
 
-fn deserialize_optional_i64<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+impl EventFilters {
+    /// The page's filters as a query over the `events` table
+    pub fn to_filter_builder(&self) -> EventFilterBuilder {
+        EventFilterBuilder::new(FilterTable::Events)
+            .tenant(self.tenant.as_deref())
+            .source(self.source.as_deref())
+            .event_type(self.event_type.as_deref())
+            .action(self.action.as_deref())
+            .actor_name(self.actor_name.as_deref())
+            .repository_id(self.repository_id)
+            .processed(self.processed)
+            .search(self.search.as_deref())
+            .tag(self.tag.as_deref())
+    }
+
+    /// Requested page size, falling back to `default` and clamped to `1..=max`
+    pub fn per_page(&self, default: i64, max: i64) -> i64 {
+        self.per_page.unwrap_or(default).clamp(1, max)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub a: i64,
+    pub b: i64,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct BreakdownQuery {
+    pub source: Option<String>,
+    pub event_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagForm {
+    pub tag: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NoteForm {
+    pub note: String,
+    #[serde(default)]
+    pub author: String,
+}
+
+pub(crate) fn deserialize_optional_i64<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
@@ -33,54 +110,50 @@ where
 
 pub async fn list_events(
     pool: web::Data<PgPool>,
+    store: web::Data<dyn EventStore>,
+    config: web::Data<Config>,
+    theme: Theme,
     query: web::Query<EventFilters>,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let page = query.page.unwrap_or(1).max(1);
-    let per_page = 300;
+    let per_page = query.per_page(UI_MAX_PER_PAGE, UI_MAX_PER_PAGE);
     let offset = (page - 1) * per_page;
 
-    // Get filtered events
-    let events = Event::search_and_filter(
-        pool.get_ref(),
-        query.source.as_deref(),
-        query.event_type.as_deref(),
-        query.action.as_deref(),
-        query.actor_name.as_deref(),
-        query.processed,
-        query.search.as_deref(),
-        per_page,
-        offset,
-    )
-    .await
-    .unwrap_or_default();
-
-    let total_count = Event::count_filtered(
-        pool.get_ref(),
-        query.source.as_deref(),
-        query.event_type.as_deref(),
-        query.action.as_deref(),
-        query.actor_name.as_deref(),
-        query.processed,
-        query.search.as_deref(),
-    )
-    .await
-    .unwrap_or(0);
+    // Select and count share one filter set so the totals match the listed rows. A failed
+    // query is a 500, never an empty page that reads as "no events"
+    let filters = query.to_filter_builder();
+    let search = async {
+        let events = store.search_and_filter(&filters, per_page, offset).await?;
+        let total_count = store.count_filtered(&filters).await?;
+        Ok::<_, sqlx::Error>((events, total_count))
+    };
+    // A broad payload search can run for minutes; answer instead of tying up the worker
+    let searched = match config.search_timeout() {
+        Some(timeout) => tokio::time::timeout(timeout, search).await.ok(),
+        None => Some(search.await),
+    };
+    let (events, total_count) = match searched {
+        Some(Err(e)) if !is_query_canceled(&e) => return Err(e.into()),
+        Some(Ok(found)) => found,
+        _ => return Ok(search_timed_out(theme)),
+    };
 
     // Get unique event types, sources, actions, and actor names for filter dropdowns
-    let event_types = Event::get_event_types(pool.get_ref())
-        .await
-        .unwrap_or_default();
-    let sources = Event::get_sources(pool.get_ref()).await.unwrap_or_default();
-    let actions = Event::get_actions(pool.get_ref()).await.unwrap_or_default();
-    let actor_names = Event::get_actor_names(pool.get_ref())
-        .await
-        .unwrap_or_default();
+    let event_types = Event::get_event_types(pool.get_ref()).await?;
+    let tenants = Event::get_tenants(pool.get_ref()).await?;
+    let sources = Event::get_sources(pool.get_ref()).await?;
+    let actions = Event::get_actions(pool.get_ref()).await?;
+    let actor_names = Event::get_actor_names(pool.get_ref()).await?;
+    let repositories = Event::get_repositories(pool.get_ref()).await?;
+    let all_tags = EventTag::list_distinct(pool.get_ref()).await?;
 
-    let total_pages = (total_count as f64 / per_page as f64).ceil() as i64;
+    let event_ids: Vec<i64> = events.iter().map(|e| e.id).collect();
+    let event_tags = EventTag::list_for_events(pool.get_ref(), &event_ids).await?;
+    let event_notes = EventNote::list_for_events(pool.get_ref(), &event_ids).await?;
 
     let markup = html! {
         (DOCTYPE)
-        html lang="en" data-theme="dark" {
+        html lang="en" data-theme=[theme.data_theme()] {
             head {
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
@@ -95,7 +168,13 @@ pub async fn list_events(
                 (render_navbar())
 
                 div class="container mx-auto px-4 py-8" {
-                    h1 class="text-4xl font-bold mb-8" { "Webhook Events" }
+                    div class="flex justify-between items-center mb-8" {
+                        h1 class="text-4xl font-bold" { "Webhook Events" }
+                        div class="flex gap-2" {
+                            a class="btn btn-ghost btn-sm" href="/events/breakdown" { "By action" }
+                            a class="btn btn-ghost btn-sm" href="/events/grouped" { "Group by entity" }
+                        }
+                    }
 
                     // Filters section
                     div class="card bg-base-100 shadow-xl mb-6" {
@@ -125,7 +204,33 @@ pub async fn list_events(
                                         hx-target="body"
                                         hx-push-url="true"
                                         hx-trigger="input changed delay:500ms"
-                                        hx-include="[name='source'], [name='event_type'], [name='action'], [name='actor_name'], [name='processed']";
+                                        hx-include="[name='tag'], [name='tenant'], [name='source'], [name='event_type'], [name='action'], [name='actor_name'], [name='processed'], [name='repository_id']";
+                                }
+
+                                // Tenant filter
+                                @if !tenants.is_empty() {
+                                    div class="form-control" {
+                                        label class="label" {
+                                            span class="label-text" { "Tenant" }
+                                        }
+                                        select
+                                            name="tenant"
+                                            class="select select-bordered"
+                                            hx-get="/events"
+                                            hx-target="body"
+                                            hx-push-url="true"
+                                            hx-trigger="change"
+                                            hx-include="[name='tag'], [name='search'], [name='source'], [name='event_type'], [name='action'], [name='actor_name'], [name='processed'], [name='repository_id']"
+                                        {
+                                            option value="" selected[query.tenant.is_none()] { "All Tenants" }
+                                            @for tenant in &tenants {
+                                                option
+                                                    value=(tenant)
+                                                    selected[query.tenant.as_deref() == Some(tenant.as_str())]
+                                                { (tenant) }
+                                            }
+                                        }
+                                    }
                                 }
 
                                 // Source filter
@@ -140,9 +245,12 @@ pub async fn list_events(
                                         hx-target="body"
                                         hx-push-url="true"
                                         hx-trigger="change"
-                                        hx-include="[name='search'], [name='event_type'], [name='action'], [name='actor_name'], [name='processed']"
+                                        hx-include="[name='tag'], [name='tenant'], [name='search'], [name='event_type'], [name='action'], [name='actor_name'], [name='processed'], [name='repository_id']"
                                     {
                                         option value="" selected[query.source.is_none()] { "All Sources" }
+                                        @if let Some(list) = multi_value(query.source.as_deref()) {
+                                            option value=(list) selected { (list.replace(',', ", ")) }
+                                        }
                                         @for source in &sources {
                                             option
                                                 value=(source)
@@ -164,9 +272,12 @@ pub async fn list_events(
                                         hx-target="body"
                                         hx-push-url="true"
                                         hx-trigger="change"
-                                        hx-include="[name='search'], [name='source'], [name='action'], [name='actor_name'], [name='processed']"
+                                        hx-include="[name='tag'], [name='tenant'], [name='search'], [name='source'], [name='action'], [name='actor_name'], [name='processed'], [name='repository_id']"
                                     {
                                         option value="" selected[query.event_type.is_none()] { "All Types" }
+                                        @if let Some(list) = multi_value(query.event_type.as_deref()) {
+                                            option value=(list) selected { (list.replace(',', ", ")) }
+                                        }
                                         @for event_type in &event_types {
                                             option
                                                 value=(event_type)
@@ -188,9 +299,12 @@ pub async fn list_events(
                                         hx-target="body"
                                         hx-push-url="true"
                                         hx-trigger="change"
-                                        hx-include="[name='search'], [name='source'], [name='event_type'], [name='actor_name'], [name='processed']"
+                                        hx-include="[name='tag'], [name='tenant'], [name='search'], [name='source'], [name='event_type'], [name='actor_name'], [name='processed'], [name='repository_id']"
                                     {
                                         option value="" selected[query.action.is_none()] { "All Actions" }
+                                        @if let Some(list) = multi_value(query.action.as_deref()) {
+                                            option value=(list) selected { (list.replace(',', ", ")) }
+                                        }
                                         @for action in &actions {
                                             option
                                                 value=(action)
@@ -212,7 +326,7 @@ pub async fn list_events(
                                         hx-target="body"
                                         hx-push-url="true"
                                         hx-trigger="change"
-                                        hx-include="[name='search'], [name='source'], [name='event_type'], [name='action'], [name='processed']"
+                                        hx-include="[name='tag'], [name='tenant'], [name='search'], [name='source'], [name='event_type'], [name='action'], [name='processed'], [name='repository_id']"
                                     {
                                         option value="" selected[query.actor_name.is_none()] { "All Actors" }
                                         @for actor_name in &actor_names {
@@ -224,6 +338,32 @@ pub async fn list_events(
                                     }
                                 }
 
+                                // Repository filter
+                                @if !repositories.is_empty() {
+                                    div class="form-control" {
+                                        label class="label" {
+                                            span class="label-text" { "Repository" }
+                                        }
+                                        select
+                                            name="repository_id"
+                                            class="select select-bordered"
+                                            hx-get="/events"
+                                            hx-target="body"
+                                            hx-push-url="true"
+                                            hx-trigger="change"
+                                            hx-include="[name='tag'], [name='tenant'], [name='search'], [name='source'], [name='event_type'], [name='action'], [name='actor_name'], [name='processed']"
+                                        {
+                                            option value="" selected[query.repository_id.is_none()] { "All Repositories" }
+                                            @for (id, full_name) in &repositories {
+                                                option
+                                                    value=(id)
+                                                    selected[query.repository_id == Some(*id)]
+                                                { (full_name) }
+                                            }
+                                        }
+                                    }
+                                }
+
                                 // Processed status filter
                                 div class="form-control" {
                                     label class="label" {
@@ -236,7 +376,7 @@ pub async fn list_events(
                                         hx-target="body"
                                         hx-push-url="true"
                                         hx-trigger="change"
-                                        hx-include="[name='search'], [name='source'], [name='event_type'], [name='action'], [name='actor_name']"
+                                        hx-include="[name='tag'], [name='tenant'], [name='search'], [name='source'], [name='event_type'], [name='action'], [name='actor_name'], [name='repository_id']"
                                     {
                                         option value="" selected[query.processed.is_none()] { "All Status" }
                                         option value="true" selected[query.processed == Some(true)] { "Processed" }
@@ -244,6 +384,32 @@ pub async fn list_events(
                                     }
                                 }
 
+                                // Tag filter
+                                @if !all_tags.is_empty() {
+                                    div class="form-control" {
+                                        label class="label" {
+                                            span class="label-text" { "Tag" }
+                                        }
+                                        select
+                                            name="tag"
+                                            class="select select-bordered"
+                                            hx-get="/events"
+                                            hx-target="body"
+                                            hx-push-url="true"
+                                            hx-trigger="change"
+                                            hx-include="[name='tenant'], [name='search'], [name='source'], [name='event_type'], [name='action'], [name='actor_name'], [name='processed'], [name='repository_id']"
+                                        {
+                                            option value="" selected[query.tag.is_none()] { "All Tags" }
+                                            @for tag in &all_tags {
+                                                option
+                                                    value=(tag)
+                                                    selected[query.tag.as_deref() == Some(tag.as_str())]
+                                                { (tag) }
+                                            }
+                                        }
+                                    }
+                                }
+
                                 // Clear filters button
                                 div class="form-control flex items-end" {
                                     a href="/events" class="btn btn-ghost" { "Clear Filters" }
@@ -253,8 +419,19 @@ pub async fn list_events(
                     }
 
                     // Results summary
-                    div class="alert alert-info mb-6" {
+                    div class="alert alert-info mb-6 flex flex-wrap justify-between" {
                         span { "Showing " (events.len()) " of " (total_count) " events" }
+                        @if total_count > 0 {
+                            form
+                                class="join"
+                                hx-post=(bulk_tag_url(&query))
+                                hx-target="#bulk-tag-result"
+                            {
+                                input type="text" name="tag" required placeholder="triaged" class="input input-bordered input-sm join-item";
+                                button type="submit" class="btn btn-sm join-item" { "Tag all " (total_count) " matching" }
+                            }
+                        }
+                        span id="bulk-tag-result" {}
                     }
 
                     // Events table
@@ -290,6 +467,9 @@ pub async fn list_events(
                                                     }
                                                     td {
                                                         span class="badge badge-primary" { (event.event_type) }
+                                                        @if event.commits_truncated == Some(true) {
+                                                            span class="badge badge-warning badge-sm ml-1" title="GitHub listed only the first 20 commits of this push" { "truncated" }
+                                                        }
                                                     }
                                                     td {
                                                         @if let Some(action) = &event.action {
@@ -300,10 +480,13 @@ pub async fn list_events(
                                                     }
                                                     td {
                                                         @if let Some(actor_name) = &event.actor_name {
-                                                            div class="text-sm" {
-                                                                div { (actor_name) }
-                                                                @if let Some(actor_email) = &event.actor_email {
-                                                                    div class="text-xs text-base-content/60" { (actor_email) }
+                                                            div class="flex items-center gap-2 text-sm" {
+                                                                (render_actor_avatar(Some(actor_name), event.actor_avatar_url.as_deref(), "w-6"))
+                                                                div {
+                                                                    div { (actor_name) }
+                                                                    @if let Some(actor_email) = &event.actor_email {
+                                                                        div class="text-xs text-base-content/60" { (actor_email) }
+                                                                    }
                                                                 }
                                                             }
                                                         } @else {
@@ -316,9 +499,14 @@ pub async fn list_events(
                                                     td {
                                                         @if event.processed {
                                                             span class="badge badge-success" { "Processed" }
+                                                        } @else if event.processing_error.is_some() {
+                                                            span class="badge badge-error" { "Failed" }
                                                         } @else {
                                                             span class="badge badge-warning" { "Pending" }
                                                         }
+                                                        @for tag in event_tags.iter().filter(|t| t.event_id == event.id) {
+                                                            " " span class="badge badge-info badge-sm" { (tag.tag) }
+                                                        }
                                                     }
                                                     td {
                                                         button
@@ -343,12 +531,23 @@ pub async fn list_events(
                                                                     div { span class="font-medium" { "Source: " } (event.source) }
                                                                     div { span class="font-medium" { "Delivery ID: " } (event.delivery_id) }
                                                                     div { span class="font-medium" { "Received: " } (format_datetime(&event.received_at)) }
+                                                                    div { span class="font-medium" { "Stored: " } (format_datetime(&event.created_at)) }
+                                                                    @if let Some(latency) = event.delivery_latency() {
+                                                                        div { span class="font-medium" { "Delivery Latency: " } (format_latency(latency)) }
+                                                                    }
                                                                     div { span class="font-medium" { "Event Type: " } (event.event_type) }
+                                                                    @if let Some(raw_event_type) = event.raw_event_type.as_ref().filter(|t| **t != event.event_type) {
+                                                                        div { span class="font-medium" { "Sent As: " } (raw_event_type) }
+                                                                    }
                                                                     @if let Some(action) = &event.action {
                                                                         div { span class="font-medium" { "Action: " } (action) }
                                                                     }
                                                                     @if let Some(actor_name) = &event.actor_name {
-                                                                        div { span class="font-medium" { "Actor: " } (actor_name) }
+                                                                        div class="flex items-center gap-2" {
+                                                                            span class="font-medium" { "Actor: " }
+                                                                            (render_actor_avatar(Some(actor_name), event.actor_avatar_url.as_deref(), "w-8"))
+                                                                            (actor_name)
+                                                                        }
                                                                     }
                                                                     @if let Some(actor_email) = &event.actor_email {
                                                                         div { span class="font-medium" { "Actor Email: " } (actor_email) }
@@ -359,20 +558,48 @@ pub async fn list_events(
                                                                     div { span class="font-medium" { "Status: " }
                                                                         @if event.processed {
                                                                             span class="badge badge-success" { "Processed" }
+                                                                        } @else if event.processing_error.is_some() {
+                                                                            span class="badge badge-error" { "Failed" }
                                                                         } @else {
                                                                             span class="badge badge-warning" { "Pending" }
                                                                         }
                                                                     }
+                                                                    @if let Some(error) = &event.processing_error {
+                                                                        div class="col-span-2" { span class="font-medium" { "Error: " } (error) }
+                                                                    }
                                                                     @if let Some(processed_at) = event.processed_at {
                                                                         div { span class="font-medium" { "Processed At: " } (format_datetime(&processed_at)) }
                                                                     }
                                                                 }
                                                             }
+                                                            (render_annotations(event.id, &event_tags, &event_notes))
+                                                            div
+                                                                hx-get=(format!("/events/{}/related", event.id))
+                                                                hx-trigger="intersect once"
+                                                                hx-swap="outerHTML"
+                                                            {
+                                                                span class="loading loading-spinner loading-sm" {}
+                                                            }
+                                                            form method="get" action="/events/diff" class="flex gap-2 items-center" {
+                                                                input type="hidden" name="a" value=(event.id);
+                                                                span class="text-sm font-medium" { "Compare payload with event #" }
+                                                                input type="number" name="b" required class="input input-bordered input-sm w-28";
+                                                                button type="submit" class="btn btn-sm" { "Compare" }
+                                                            }
                                                             div {
                                                                 h4 class="font-semibold mb-2" { "Raw Event Payload" }
-                                                                pre class="bg-base-200 p-4 rounded-lg overflow-x-auto text-xs" {
-                                                                    code {
-                                                                        (PreEscaped(serde_json::to_string_pretty(&event.raw_event).unwrap_or_else(|_| "{}".to_string())))
+                                                                @if let Some(compacted_at) = event.raw_event_compacted_at {
+                                                                    p class="text-sm text-base-content/60" {
+                                                                        "Payload compacted at " (format_datetime(&compacted_at))
+                                                                        @if let Some(sha) = &event.payload_sha256 {
+                                                                            " (SHA-256 " code { (sha) } ")"
+                                                                        }
+                                                                    }
+                                                                } @else {
+                                                                    pre class="bg-base-200 p-4 rounded-lg overflow-x-auto text-xs" {
+                                                                        code {
+                                                                            (PreEscaped(serde_json::to_string_pretty(&event.raw_event).unwrap_or_else(|_| "{}".to_string())))
+                                                                        }
                                                                     }
                                                                 }
                                                             }
@@ -393,15 +620,286 @@ pub async fn list_events(
                     }
 
                     // Pagination
-                    @if total_pages > 1 {
-                        div class="flex justify-center" {
-                            div class="join" {
-                                @for p in 1..=total_pages {
-                                    a
-                                        href=(build_page_url(p, &query))
-                                        class=(format!("join-item btn {}", if p == page { "btn-active" } else { "" }))
-                                    {
-                                        (p)
+                    (render_pager(page, total_count, per_page, |p| build_page_url(p, &query)))
+                }
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+/// Whether PostgreSQL cancelled the query, e.g. for exceeding `statement_timeout`
+fn is_query_canceled(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(e) if e.code().as_deref() == Some("57014"))
+}
+
+fn search_timed_out(theme: Theme) -> HttpResponse {
+    let markup = crate::handlers::layout::page(
+        "Events",
+        theme,
+        html! {
+            h1 class="text-4xl font-bold mb-8" { "Events" }
+            div class="alert alert-warning mb-6" {
+                span { "Search timed out, narrow your filters and try again." }
+            }
+            a href="/events" class="btn btn-ghost" { "Clear filters" }
+        },
+    );
+
+    HttpResponse::ServiceUnavailable()
+        .content_type("text/html")
+        .body(markup.into_string())
+}
+
+/// Field-by-field comparison of two events' payloads
+pub async fn diff_events(
+    store: web::Data<dyn EventStore>,
+    theme: Theme,
+    query: web::Query<DiffQuery>,
+) -> Result<HttpResponse, AppError> {
+    let load = |id: i64| {
+        let store = store.clone();
+        async move {
+            store
+                .find_by_id(id)
+                .await?
+                .ok_or_else(|| AppError::not_found(format!("Event #{id} not found")))
+        }
+    };
+    let a = load(query.a).await?;
+    let b = load(query.b).await?;
+
+    let entries = diff_json(&a.raw_event, &b.raw_event);
+
+    let markup = crate::handlers::layout::page(
+        &format!("Event #{} vs #{}", a.id, b.id),
+        theme,
+        html! {
+            h1 class="text-4xl font-bold mb-8" { "Compare Events" }
+
+            div class="grid grid-cols-1 md:grid-cols-2 gap-4 mb-6" {
+                @for (label, event) in [("A", &a), ("B", &b)] {
+                    div class="card bg-base-100 shadow" {
+                        div class="card-body" {
+                            h2 class="card-title" { (label) ": Event #" (event.id) }
+                            div class="flex gap-2" {
+                                span class="badge badge-secondary" { (event.source) }
+                                span class="badge badge-primary" { (event.event_type) }
+                                @if let Some(action) = &event.action {
+                                    span class="badge badge-ghost" { (action) }
+                                }
+                            }
+                            p class="text-sm text-base-content/60" { (format_datetime(&event.received_at)) }
+                        }
+                    }
+                }
+            }
+
+            @if entries.is_empty() {
+                div class="alert alert-success" {
+                    span { "The payloads are identical." }
+                }
+            } @else {
+                div class="alert alert-info mb-6" {
+                    span { (entries.len()) " difference(s)" }
+                }
+                div class="card bg-base-100 shadow-xl" {
+                    div class="card-body p-0 overflow-x-auto" {
+                        table class="table font-mono text-xs" {
+                            thead {
+                                tr {
+                                    th {}
+                                    th { "Path" }
+                                    th { "A" }
+                                    th { "B" }
+                                }
+                            }
+                            tbody {
+                                @for entry in &entries {
+                                    @let (marker, class) = match entry.kind {
+                                        DiffKind::Added => ("+", "text-success"),
+                                        DiffKind::Removed => ("-", "text-error"),
+                                        DiffKind::Changed => ("~", "text-warning"),
+                                    };
+                                    tr {
+                                        td class=(class) { (marker) }
+                                        td { (entry.path) }
+                                        td class="text-error whitespace-pre-wrap break-all" {
+                                            @if let Some(old) = &entry.old { (old) }
+                                        }
+                                        td class="text-success whitespace-pre-wrap break-all" {
+                                            @if let Some(new) = &entry.new { (new) }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+/// Events that touched one entity (a pull request, issue, branch, ...), oldest first
+#[derive(Debug)]
+pub struct EntityGroup {
+    pub key: EntityKey,
+    pub events: Vec<Event>,
+}
+
+impl EntityGroup {
+    /// Heading for the group, taken from its most recent event's payload
+    pub fn label(&self) -> String {
+        let Some(event) = self.events.last() else {
+            return self.key.entity_id.clone();
+        };
+        let payload = &event.raw_event;
+
+        let entity = match event.event_type.as_str() {
+            "pull_request" => format!(
+                "PR #{} {}",
+                payload["pull_request"]["number"],
+                payload["pull_request"]["title"]
+                    .as_str()
+                    .unwrap_or_default()
+            ),
+            "issues" => format!(
+                "Issue #{} {}",
+                payload["issue"]["number"],
+                payload["issue"]["title"].as_str().unwrap_or_default()
+            ),
+            "push" => self
+                .key
+                .entity_id
+                .strip_prefix("refs/heads/")
+                .map(|branch| format!("Branch {branch}"))
+                .unwrap_or_else(|| self.key.entity_id.clone()),
+            "star" | "watch" => "Stars".to_string(),
+            "deployment" | "deployment_status" => format!(
+                "Deployment to {}",
+                payload["deployment"]["environment"]
+                    .as_str()
+                    .unwrap_or_default()
+            ),
+            _ => self.key.entity_id.clone(),
+        };
+
+        match payload["repository"]["full_name"].as_str() {
+            Some(repository) => format!("{repository} · {}", entity.trim_end()),
+            None => entity.trim_end().to_string(),
+        }
+    }
+}
+
+/// Cluster newest-first `events` by the entity they touch, most recently active entity
+/// first. Events whose type has no entity key are left out.
+pub fn group_by_entity(events: Vec<Event>) -> Vec<EntityGroup> {
+    let mut groups: Vec<EntityGroup> = Vec::new();
+    let mut index: HashMap<EntityKey, usize> = HashMap::new();
+
+    for event in events {
+        let Some(key) = EntityKey::from_payload(&event.source, &event.event_type, &event.raw_event)
+        else {
+            continue;
+        };
+
+        match index.get(&key) {
+            Some(&i) => groups[i].events.push(event),
+            None => {
+                index.insert(key.clone(), groups.len());
+                groups.push(EntityGroup {
+                    key,
+                    events: vec![event],
+                });
+            }
+        }
+    }
+
+    for group in &mut groups {
+        group.events.reverse();
+    }
+
+    groups
+}
+
+/// Recent events clustered into a collapsible timeline per pull request, issue or branch
+pub async fn grouped_events(
+    store: web::Data<dyn EventStore>,
+    theme: Theme,
+    query: web::Query<EventFilters>,
+) -> Result<HttpResponse, AppError> {
+    let filters = query.to_filter_builder();
+    let events = store
+        .search_and_filter(&filters, GROUPED_EVENTS_LIMIT, 0)
+        .await?;
+    let scanned = events.len();
+    let groups = group_by_entity(events);
+
+    let markup = crate::handlers::layout::page(
+        "Grouped Events",
+        theme,
+        html! {
+            div class="flex justify-between items-center mb-2" {
+                h1 class="text-4xl font-bold" { "Events by Entity" }
+                a class="btn btn-ghost btn-sm" href="/events" { "Back to Events" }
+            }
+            p class="text-base-content/60 mb-8" {
+                (groups.len()) " entities across the " (scanned) " most recent events. "
+                "Events that do not touch a pull request, issue, branch or star count are not shown."
+            }
+
+            @if groups.is_empty() {
+                div class="alert alert-info" {
+                    span { "No events with an entity to group by." }
+                }
+            }
+
+            div class="space-y-4" {
+                @for group in &groups {
+                    details class="collapse collapse-arrow bg-base-100 shadow" {
+                        summary class="collapse-title flex items-center gap-2" {
+                            span class="badge badge-secondary" { (group.key.source) }
+                            span class="font-semibold" { (group.label()) }
+                            span class="badge badge-ghost" { (group.events.len()) " events" }
+                        }
+                        div class="collapse-content" {
+                            ul class="timeline timeline-vertical timeline-compact" {
+                                @for event in &group.events {
+                                    li {
+                                        hr;
+                                        div class="timeline-start text-xs text-base-content/60" {
+                                            (format_datetime(&event.received_at))
+                                        }
+                                        div class="timeline-middle" {
+                                            span class="badge badge-xs badge-primary" {}
+                                        }
+                                        div class="timeline-end timeline-box flex gap-2 items-center" {
+                                            span class="badge badge-primary" { (event.event_type) }
+                                            @if let Some(action) = &event.action {
+                                                span class="badge badge-ghost" { (action) }
+                                            }
+                                            @if event.commits_truncated == Some(true) {
+                                                span class="badge badge-warning badge-sm" { "truncated" }
+                                            }
+                                            @if let Some(actor) = &event.actor_name {
+                                                span class="text-sm" { (actor) }
+                                            }
+                                            span class="text-xs text-base-content/60" { "#" (event.id) }
+                                            @if event.id != group.events[0].id {
+                                                a class="link link-hover text-xs" href=(format!("/events/diff?a={}&b={}", group.events[0].id, event.id)) {
+                                                    "diff from first"
+                                                }
+                                            }
+                                        }
+                                        hr;
                                     }
                                 }
                             }
@@ -409,6 +907,254 @@ pub async fn list_events(
                     }
                 }
             }
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+/// Events of one source and type broken down by action, e.g. how many pull request
+/// events were opened, closed or synchronize
+pub async fn action_breakdown(
+    pool: web::Data<PgPool>,
+    theme: Theme,
+    query: web::Query<BreakdownQuery>,
+) -> Result<HttpResponse, AppError> {
+    let source = query.source.as_deref().filter(|s| !s.is_empty());
+    let event_type = query.event_type.as_deref().filter(|t| !t.is_empty());
+    let breakdown = match (source, event_type) {
+        (Some(source), Some(event_type)) => {
+            Some(Event::action_breakdown(pool.get_ref(), source, event_type).await?)
+        }
+        _ => None,
+    };
+    let sources = Event::get_sources(pool.get_ref()).await?;
+    let event_types = Event::get_event_types(pool.get_ref()).await?;
+
+    let markup = crate::handlers::layout::page(
+        "Action Breakdown",
+        theme,
+        html! {
+            div class="flex justify-between items-center mb-2" {
+                h1 class="text-4xl font-bold" { "Events by Action" }
+                a class="btn btn-ghost btn-sm" href="/events" { "Back to Events" }
+            }
+            p class="text-base-content/60 mb-8" {
+                "How the events of one source and type split across their actions."
+            }
+
+            div class="card bg-base-100 shadow-xl mb-6" {
+                div class="card-body" {
+                    form method="get" action="/events/breakdown" class="grid grid-cols-1 md:grid-cols-3 gap-4" {
+                        div class="form-control" {
+                            label class="label" { span class="label-text" { "Source" } }
+                            select name="source" class="select select-bordered" {
+                                option value="" selected[source.is_none()] { "Choose a source" }
+                                @for s in &sources {
+                                    option value=(s) selected[source == Some(s.as_str())] { (s) }
+                                }
+                            }
+                        }
+                        div class="form-control" {
+                            label class="label" { span class="label-text" { "Event Type" } }
+                            select name="event_type" class="select select-bordered" {
+                                option value="" selected[event_type.is_none()] { "Choose a type" }
+                                @for t in &event_types {
+                                    option value=(t) selected[event_type == Some(t.as_str())] { (t) }
+                                }
+                            }
+                        }
+                        div class="form-control flex flex-row items-end" {
+                            button type="submit" class="btn btn-primary" { "Show" }
+                        }
+                    }
+                }
+            }
+
+            @match (&breakdown, source, event_type) {
+                (Some(breakdown), Some(source), Some(event_type)) => {
+                    @let total: i64 = breakdown.iter().map(|(_, count)| count).sum();
+                    @if breakdown.is_empty() {
+                        div class="alert alert-info" {
+                            span { "No " (source) " " (event_type) " events." }
+                        }
+                    } @else {
+                        div class="card bg-base-100 shadow-xl" {
+                            div class="card-body p-0" {
+                                table class="table" {
+                                    thead {
+                                        tr {
+                                            th { "Action" }
+                                            th { "Events" }
+                                            th { "Share" }
+                                        }
+                                    }
+                                    tbody {
+                                        @for (action, count) in breakdown {
+                                            tr {
+                                                td {
+                                                    a class="link link-hover" href=(breakdown_events_url(source, event_type, action)) {
+                                                        @if action == NO_ACTION {
+                                                            span class="text-base-content/60" { (action) }
+                                                        } @else {
+                                                            span class="badge badge-ghost" { (action) }
+                                                        }
+                                                    }
+                                                }
+                                                td { (count) }
+                                                td {
+                                                    div class="flex items-center gap-2" {
+                                                        progress class="progress progress-primary w-48" value=(count) max=(total) {}
+                                                        span class="text-sm text-base-content/60" {
+                                                            (format!("{:.1}%", *count as f64 * 100.0 / total as f64))
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    tfoot {
+                                        tr {
+                                            th { "Total" }
+                                            th { (total) }
+                                            th {}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    div class="alert alert-info" {
+                        span { "Choose a source and event type to see its actions." }
+                    }
+                }
+            }
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+pub async fn add_event_tag(
+    pool: web::Data<PgPool>,
+    path: web::Path<i64>,
+    form: web::Form<TagForm>,
+) -> Result<HttpResponse, AppError> {
+    let event_id = find_event_id(&pool, path.into_inner()).await?;
+    let tag = crate::models::event_tag::normalize_tag(&form.tag)
+        .ok_or_else(|| AppError::bad_request("Invalid tag"))?;
+
+    EventTag::add(pool.get_ref(), event_id, &tag).await?;
+
+    annotations_response(&pool, event_id).await
+}
+
+/// Tag every event matching the filters in the query string, reporting how many
+/// were newly tagged
+pub async fn bulk_tag_events(
+    pool: web::Data<PgPool>,
+    query: web::Query<EventFilters>,
+    form: web::Form<TagForm>,
+) -> Result<HttpResponse, AppError> {
+    let tag = crate::models::event_tag::normalize_tag(&form.tag)
+        .ok_or_else(|| AppError::bad_request("Invalid tag"))?;
+
+    let tagged = EventTag::add_matching(pool.get_ref(), &query.to_filter_builder(), &tag).await?;
+    log::info!("Tagged {tagged} matching event(s) with {tag:?}");
+
+    let markup = html! {
+        span { "Tagged " (tagged) " events with \"" (tag) "\"" }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+pub async fn remove_event_tag(
+    pool: web::Data<PgPool>,
+    path: web::Path<i64>,
+    form: web::Form<TagForm>,
+) -> Result<HttpResponse, AppError> {
+    let event_id = find_event_id(&pool, path.into_inner()).await?;
+
+    EventTag::remove(pool.get_ref(), event_id, &form.tag).await?;
+
+    annotations_response(&pool, event_id).await
+}
+
+pub async fn add_event_note(
+    pool: web::Data<PgPool>,
+    path: web::Path<i64>,
+    form: web::Form<NoteForm>,
+) -> Result<HttpResponse, AppError> {
+    let event_id = find_event_id(&pool, path.into_inner()).await?;
+    let form = form.into_inner();
+
+    let note = form.note.trim();
+    if note.is_empty() {
+        return Err(AppError::bad_request("Note is required"));
+    }
+    let author = Some(form.author.trim())
+        .filter(|a| !a.is_empty())
+        .unwrap_or("anonymous");
+
+    EventNote::create(
+        pool.get_ref(),
+        CreateEventNote {
+            event_id,
+            note: note.to_string(),
+            author: author.to_string(),
+        },
+    )
+    .await?;
+
+    annotations_response(&pool, event_id).await
+}
+
+/// Events by the same actor and events on the same repository around the same time,
+/// loaded into an event's details when they are opened
+pub async fn related_events(
+    pool: web::Data<PgPool>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let event = Event::find_by_id(pool.get_ref(), path.into_inner())
+        .await?
+        .ok_or_else(|| AppError::not_found("Event not found"))?;
+
+    let by_actor = Event::list_by_actor(pool.get_ref(), &event, RELATED_EVENTS_LIMIT).await?;
+    let neighbors = Event::list_by_delivery_neighbors(
+        pool.get_ref(),
+        &event,
+        chrono::Duration::minutes(RELATED_EVENTS_WINDOW_MINUTES),
+        RELATED_EVENTS_LIMIT,
+    )
+    .await?;
+
+    let markup = html! {
+        div {
+            h4 class="font-semibold mb-2" { "Related Events" }
+            @if by_actor.is_empty() && neighbors.is_empty() {
+                p class="text-sm text-base-content/60" { "No related events" }
+            }
+            @if !by_actor.is_empty() {
+                p class="text-sm font-medium mb-1" {
+                    "By " (event.actor_name.as_deref().or(event.actor_id.as_deref()).unwrap_or_default())
+                }
+                (render_related_list(event.id, &by_actor))
+            }
+            @if !neighbors.is_empty() {
+                p class="text-sm font-medium mb-1 mt-2" {
+                    "Same repository within " (RELATED_EVENTS_WINDOW_MINUTES) " minutes"
+                }
+                (render_related_list(event.id, &neighbors))
+            }
         }
     };
 
@@ -417,6 +1163,94 @@ pub async fn list_events(
         .body(markup.into_string()))
 }
 
+/// One line per related event, linking to a payload comparison with `event_id`
+fn render_related_list(event_id: i64, events: &[Event]) -> maud::Markup {
+    html! {
+        ul class="text-sm space-y-1" {
+            @for event in events {
+                li class="flex gap-2 items-center" {
+                    span class="text-xs text-base-content/60 font-mono" { (format_datetime(&event.received_at)) }
+                    span class="badge badge-primary badge-sm" { (event.event_type) }
+                    @if let Some(action) = &event.action {
+                        span class="badge badge-ghost badge-sm" { (action) }
+                    }
+                    a class="link link-hover text-xs" href=(format!("/events/diff?a={event_id}&b={}", event.id)) { "#" (event.id) }
+                }
+            }
+        }
+    }
+}
+
+async fn find_event_id(pool: &PgPool, event_id: i64) -> Result<i64, AppError> {
+    Event::find_by_id(pool, event_id)
+        .await?
+        .map(|event| event.id)
+        .ok_or_else(|| AppError::not_found("Event not found"))
+}
+
+/// Re-render the annotations block of an event after a change
+async fn annotations_response(pool: &PgPool, event_id: i64) -> Result<HttpResponse, AppError> {
+    let tags = EventTag::list_for_events(pool, &[event_id]).await?;
+    let notes = EventNote::list_for_events(pool, &[event_id]).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(render_annotations(event_id, &tags, &notes).into_string()))
+}
+
+/// Tags and notes for an event, editable in place via htmx
+fn render_annotations(event_id: i64, tags: &[EventTag], notes: &[EventNote]) -> maud::Markup {
+    let target = format!("#event-annotations-{event_id}");
+
+    html! {
+        div id=(format!("event-annotations-{event_id}")) {
+            h4 class="font-semibold mb-2" { "Triage" }
+            div class="flex flex-wrap items-center gap-2 mb-2" {
+                @for tag in tags.iter().filter(|t| t.event_id == event_id) {
+                    span class="badge badge-info gap-1" {
+                        (tag.tag)
+                        button
+                            class="btn btn-ghost btn-xs px-1"
+                            title="Remove tag"
+                            hx-post=(format!("/events/{event_id}/tags/delete"))
+                            hx-vals=(serde_json::json!({ "tag": tag.tag }).to_string())
+                            hx-target=(target)
+                            hx-swap="outerHTML"
+                        { "✕" }
+                    }
+                }
+                form
+                    class="join"
+                    hx-post=(format!("/events/{event_id}/tags"))
+                    hx-target=(target)
+                    hx-swap="outerHTML"
+                {
+                    input type="text" name="tag" required placeholder="investigated" class="input input-bordered input-sm join-item";
+                    button type="submit" class="btn btn-sm join-item" { "Add Tag" }
+                }
+            }
+            @for note in notes.iter().filter(|n| n.event_id == event_id) {
+                div class="bg-base-200 rounded-lg p-2 mb-2 text-sm" {
+                    p { (note.note) }
+                    p class="text-xs text-base-content/60 mt-1" {
+                        (note.author) " - " (format_datetime(&note.created_at))
+                    }
+                }
+            }
+            form
+                class="flex gap-2"
+                hx-post=(format!("/events/{event_id}/notes"))
+                hx-target=(target)
+                hx-swap="outerHTML"
+            {
+                input type="text" name="author" placeholder="Your name" class="input input-bordered input-sm w-32";
+                input type="text" name="note" required placeholder="Add a note..." class="input input-bordered input-sm flex-1";
+                button type="submit" class="btn btn-sm" { "Add Note" }
+            }
+        }
+    }
+}
+
 fn render_navbar() -> maud::Markup {
     html! {
         div class="navbar bg-base-100 shadow-lg" {
@@ -425,13 +1259,13 @@ fn render_navbar() -> maud::Markup {
             }
             div class="flex-none gap-2" {
                 ul class="menu menu-horizontal px-1" {
-                    li { a href="/" { "Dashboard" } }
+                    li { a href="/dashboard" { "Dashboard" } }
                     li { a href="/events" class="active" { "Events" } }
                 }
                 button
                     class="btn btn-ghost btn-circle"
                     onclick="toggleTheme()"
-                    title="Toggle theme"
+                    title="Toggle theme (light, dark, auto)"
                 {
                     // Sun icon for light mode
                     svg
@@ -453,31 +1287,678 @@ fn render_navbar() -> maud::Markup {
     }
 }
 
-fn format_datetime(dt: &DateTime<Utc>) -> String {
+/// Small round avatar, falling back to the actor's initials when no image is available
+fn render_actor_avatar(name: Option<&str>, avatar_url: Option<&str>, size: &str) -> maud::Markup {
+    html! {
+        @if let Some(url) = avatar_url {
+            div class="avatar" {
+                div class=(format!("{size} rounded-full")) {
+                    img src=(url) alt=(name.unwrap_or("actor")) loading="lazy";
+                }
+            }
+        } @else {
+            div class="avatar placeholder" {
+                div class=(format!("bg-neutral text-neutral-content {size} rounded-full")) {
+                    span class="text-xs" { (actor_initials(name.unwrap_or(""))) }
+                }
+            }
+        }
+    }
+}
+
+/// Up to two uppercase initials from a display name or username
+fn actor_initials(name: &str) -> String {
+    let initials: String = name
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_' || c == '.')
+        .filter_map(|part| part.chars().next())
+        .take(2)
+        .flat_map(|c| c.to_uppercase())
+        .collect();
+
+    if initials.is_empty() {
+        "?".to_string()
+    } else {
+        initials
+    }
+}
+
+/// Latency in seconds with millisecond precision, e.g. `2.500s`
+fn format_latency(latency: chrono::Duration) -> String {
+    format!("{:.3}s", latency.num_milliseconds() as f64 / 1000.0)
+}
+
+pub(crate) fn format_datetime(dt: &DateTime<Utc>) -> String {
     dt.format("%Y-%m-%d %H:%M:%S UTC").to_string()
 }
 
+/// Page buttons for an events list, capped at `MAX_PAGE_BUTTONS` (plus the current
+/// page when it lies beyond the cap) so huge result sets cannot bloat the markup
+pub(crate) fn render_pager(
+    page: i64,
+    total_count: i64,
+    per_page: i64,
+    page_url: impl Fn(i64) -> String,
+) -> maud::Markup {
+    let total_pages = (total_count as f64 / per_page as f64).ceil() as i64;
+    let shown = total_pages.min(MAX_PAGE_BUTTONS);
+    if total_pages > shown {
+        log::warn!("Pager capped at {shown} of {total_pages} pages ({total_count} rows)");
+    }
+
+    html! {
+        @if total_pages > 1 {
+            div class="flex justify-center" {
+                div class="join" {
+                    @for p in 1..=shown {
+                        a
+                            href=(page_url(p))
+                            class=(format!("join-item btn {}", if p == page { "btn-active" } else { "" }))
+                        {
+                            (p)
+                        }
+                    }
+                    @if total_pages > shown {
+                        @if page > shown {
+                            button class="join-item btn btn-disabled" { "…" }
+                            a href=(page_url(page)) class="join-item btn btn-active" {
+                                (page)
+                            }
+                        }
+                        button class="join-item btn btn-disabled" title=(format!("{total_pages} pages")) {
+                            "…"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A filter value naming several comma-separated entries, which no single option matches
+fn multi_value(value: Option<&str>) -> Option<&str> {
+    value.filter(|v| v.contains(','))
+}
+
 fn build_page_url(page: i64, query: &web::Query<EventFilters>) -> String {
-    let mut params = vec![format!("page={}", page)];
+    let mut params = vec![query_param("page", page)];
+
+    if let Some(per_page) = query.per_page {
+        params.push(query_param("per_page", per_page));
+    }
+    params.extend(filter_params(query));
+
+    format!("/events?{}", params.join("&"))
+}
+
+/// Bulk tag endpoint for the events matching the page's filters
+fn bulk_tag_url(query: &EventFilters) -> String {
+    format!("/events/tag?{}", filter_params(query).join("&"))
+}
+
+/// Query parameters for the filters, without paging
+fn filter_params(query: &EventFilters) -> Vec<String> {
+    let mut params = Vec::new();
 
+    if let Some(tenant) = &query.tenant {
+        params.push(query_param("tenant", tenant));
+    }
     if let Some(source) = &query.source {
-        params.push(format!("source={source}"));
+        params.push(query_param("source", source));
     }
     if let Some(event_type) = &query.event_type {
-        params.push(format!("event_type={event_type}"));
+        params.push(query_param("event_type", event_type));
     }
     if let Some(action) = &query.action {
-        params.push(format!("action={action}"));
+        params.push(query_param("action", action));
     }
     if let Some(actor_name) = &query.actor_name {
-        params.push(format!("actor_name={actor_name}"));
+        params.push(query_param("actor_name", actor_name));
+    }
+    if let Some(repository_id) = query.repository_id {
+        params.push(query_param("repository_id", repository_id));
     }
     if let Some(processed) = query.processed {
-        params.push(format!("processed={processed}"));
+        params.push(query_param("processed", processed));
     }
     if let Some(search) = &query.search {
-        params.push(format!("search={search}"));
+        params.push(query_param("search", search));
+    }
+    if let Some(tag) = &query.tag {
+        params.push(query_param("tag", tag));
+    }
+
+    params
+}
+
+/// Events list for one row of the action breakdown; the row for events without an
+/// action lists the whole type
+fn breakdown_events_url(source: &str, event_type: &str, action: &str) -> String {
+    let mut params = vec![
+        query_param("source", source),
+        query_param("event_type", event_type),
+    ];
+    if action != NO_ACTION {
+        params.push(query_param("action", action));
     }
 
     format!("/events?{}", params.join("&"))
 }
+
+/// `key=value` with the value form-encoded
+fn query_param(key: &str, value: impl ToString) -> String {
+    serde_urlencoded::to_string([(key, value.to_string())]).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Actor, CreateEvent};
+    use actix_web::{test as actix_test, App};
+    use futures_util::future::BoxFuture;
+
+    async fn create_event(pool: &PgPool) -> Event {
+        create_event_of_type(pool, "deploy").await
+    }
+
+    async fn create_event_of_type(pool: &PgPool, event_type: &str) -> Event {
+        Event::create(pool, CreateEvent::for_test("custom", event_type))
+            .await
+            .unwrap()
+    }
+
+    async fn create_event_by(pool: &PgPool, actor_id: &str, event_type: &str) -> Event {
+        Event::create(
+            pool,
+            CreateEvent {
+                actor: Actor {
+                    name: Some(format!("user-{actor_id}")),
+                    id: Some(actor_id.to_string()),
+                    ..Actor::default()
+                },
+                ..CreateEvent::for_test("custom", event_type)
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_related_events_surface_same_actor(pool: PgPool) {
+        let opened = create_event_by(&pool, "42", "deploy_started").await;
+        let finished = create_event_by(&pool, "42", "deploy_finished").await;
+        let other = create_event_by(&pool, "7", "rollback").await;
+        let anonymous = create_event(&pool).await;
+
+        let related = Event::list_by_actor(&pool, &opened, 10).await.unwrap();
+        assert_eq!(
+            related.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![finished.id]
+        );
+        assert!(Event::list_by_actor(&pool, &anonymous, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/events/{id}/related", web::get().to(related_events)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/events/{}/related", opened.id))
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("By user-42"));
+        assert!(body.contains("deploy_finished"));
+        assert!(body.contains(&format!(
+            "/events/diff?a={}&amp;b={}",
+            opened.id, finished.id
+        )));
+        assert!(!body.contains(&other.event_type));
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/events/{}/related", anonymous.id))
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        assert!(String::from_utf8(body.to_vec())
+            .unwrap()
+            .contains("No related events"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_add_tag_and_filter_by_it(pool: PgPool) {
+        let tagged = create_event(&pool).await;
+        create_event(&pool).await;
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/events/{id}/tags", web::post().to(add_event_tag)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri(&format!("/events/{}/tags", tagged.id))
+            .set_form([("tag", " false alarm ")])
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(&format!(r#"id="event-annotations-{}""#, tagged.id)));
+        assert!(body.contains("false alarm"));
+
+        let by_tag = EventFilterBuilder::new(FilterTable::Events).tag(Some("false alarm"));
+        let filtered = Event::search_and_filter(&pool, &by_tag, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].id, tagged.id);
+        assert_eq!(
+            Event::count_filtered(&pool, &EventFilterBuilder::new(FilterTable::Events))
+                .await
+                .unwrap(),
+            2
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_bulk_tag_only_tags_matching_events(pool: PgPool) {
+        let deploys = [
+            create_event_of_type(&pool, "deploy").await,
+            create_event_of_type(&pool, "deploy").await,
+        ];
+        let build = create_event_of_type(&pool, "build").await;
+        // Already tagged events are not counted again
+        EventTag::add(&pool, deploys[0].id, "triaged")
+            .await
+            .unwrap();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/events/tag", web::post().to(bulk_tag_events)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/events/tag?source=custom&event_type=deploy")
+            .set_form([("tag", "triaged")])
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("Tagged 1 events"), "{body}");
+
+        let ids = [deploys[0].id, deploys[1].id, build.id];
+        let tags = EventTag::list_for_events(&pool, &ids).await.unwrap();
+        let mut tagged: Vec<i64> = tags.iter().map(|t| t.event_id).collect();
+        tagged.sort();
+        assert_eq!(tagged, vec![deploys[0].id, deploys[1].id]);
+
+        let req = actix_test::TestRequest::post()
+            .uri("/events/tag?event_type=deploy")
+            .set_form([("tag", "  ")])
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_filter_events_by_repository(pool: PgPool) {
+        use crate::db::{EventStore, PgEventStore};
+        use crate::models::github::{CreateRepository, Repository};
+        use std::sync::Arc;
+
+        let mut events = Vec::new();
+        for (github_id, name) in [(1, "api"), (2, "web")] {
+            let repo = Repository::create(
+                &pool,
+                CreateRepository {
+                    github_id,
+                    name: name.to_string(),
+                    full_name: format!("acme/{name}"),
+                    owner: "acme".to_string(),
+                    description: None,
+                    url: format!("https://github.com/acme/{name}"),
+                    is_private: false,
+                    language: None,
+                    topics: Vec::new(),
+                },
+            )
+            .await
+            .unwrap();
+            let event = Event::create(
+                &pool,
+                CreateEvent {
+                    repository_id: Some(repo.id),
+                    ..CreateEvent::for_test("github", "push")
+                },
+            )
+            .await
+            .unwrap();
+            events.push((repo, event));
+        }
+        let (api, api_push) = &events[0];
+        let (_, web_push) = &events[1];
+
+        let store: web::Data<dyn EventStore> =
+            web::Data::from(Arc::new(PgEventStore::new(pool.clone())) as Arc<dyn EventStore>);
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(store)
+                .route("/events", web::get().to(list_events)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/events?repository_id={}", api.id))
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("Showing 1 of 1 events"), "{body}");
+        assert!(body.contains(&api_push.delivery_id.to_string()));
+        assert!(!body.contains(&web_push.delivery_id.to_string()));
+        assert!(body.contains(&format!(
+            r#"<option value="{}" selected>acme/api</option>"#,
+            api.id
+        )));
+        assert!(body.contains("acme/web"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_tagging_unknown_event_is_not_found(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/events/{id}/tags", web::post().to(add_event_tag)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/events/424242/tags")
+            .set_form([("tag", "investigated")])
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_events_for_one_pull_request_are_grouped(pool: PgPool) {
+        let store: std::sync::Arc<dyn EventStore> =
+            std::sync::Arc::new(crate::db::PgEventStore::new(pool));
+        let repository = serde_json::json!({ "id": 1296269, "full_name": "octocat/Hello-World" });
+        let pr_event = |action: &str, number: i64| CreateEvent {
+            action: Some(action.to_string()),
+            raw_event: serde_json::json!({
+                "action": action,
+                "pull_request": { "id": 7000 + number, "number": number, "title": "Add feature" },
+                "repository": repository,
+            }),
+            ..CreateEvent::for_test("github", "pull_request")
+        };
+        for action in ["opened", "synchronize", "closed"] {
+            store.create(pr_event(action, 7)).await.unwrap();
+        }
+        store.create(pr_event("opened", 8)).await.unwrap();
+        let mut deploy = pr_event("opened", 9);
+        deploy.source = "custom".to_string();
+        deploy.event_type = "deploy".to_string();
+        store.create(deploy).await.unwrap();
+
+        let filters = EventFilterBuilder::new(FilterTable::Events);
+        let events = store.search_and_filter(&filters, 10, 0).await.unwrap();
+        let groups = group_by_entity(events);
+        assert_eq!(groups.len(), 2);
+        let pr7 = groups
+            .iter()
+            .find(|g| g.key.entity_id == "7007")
+            .expect("PR #7 is grouped");
+        assert_eq!(pr7.events.len(), 3);
+        assert_eq!(pr7.label(), "octocat/Hello-World · PR #7 Add feature");
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::from(store))
+                .route("/events/grouped", web::get().to(grouped_events)),
+        )
+        .await;
+        let req = actix_test::TestRequest::get()
+            .uri("/events/grouped?source=github")
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("2 entities across the 4 most recent events"));
+        assert!(body.contains("PR #7 Add feature"));
+        assert!(body.contains("3 events"));
+    }
+
+    #[actix_web::test]
+    async fn test_database_failure_is_an_error_not_an_empty_page() {
+        use crate::db::{EventStore, PgEventStore};
+        use crate::handlers::error_handlers;
+        use sqlx::postgres::PgPoolOptions;
+        use std::sync::Arc;
+
+        // Nothing listens on port 1, so every query fails to connect
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(200))
+            .connect_lazy("postgres://127.0.0.1:1/cross_bow_test")
+            .unwrap();
+        let store: web::Data<dyn EventStore> =
+            web::Data::from(Arc::new(PgEventStore::new(pool.clone())) as Arc<dyn EventStore>);
+
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(error_handlers())
+                .app_data(web::Data::new(pool))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(store)
+                .route("/events", web::get().to(list_events)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get().uri("/events").to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(
+            resp.status(),
+            actix_web::http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let body = actix_test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("Something went wrong"));
+        assert!(!body.contains("No events found"));
+    }
+
+    /// An event store whose searches take `delay` longer than the wrapped store's
+    struct SlowStore {
+        inner: std::sync::Arc<dyn EventStore>,
+        delay: std::time::Duration,
+    }
+
+    impl EventStore for SlowStore {
+        fn create(&self, data: CreateEvent) -> BoxFuture<'_, Result<Event, sqlx::Error>> {
+            self.inner.create(data)
+        }
+
+        fn find_by_id(&self, id: i64) -> BoxFuture<'_, Result<Option<Event>, sqlx::Error>> {
+            self.inner.find_by_id(id)
+        }
+
+        fn search_and_filter<'a>(
+            &'a self,
+            filters: &'a EventFilterBuilder,
+            limit: i64,
+            offset: i64,
+        ) -> BoxFuture<'a, Result<Vec<Event>, sqlx::Error>> {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                self.inner.search_and_filter(filters, limit, offset).await
+            })
+        }
+
+        fn count_filtered<'a>(
+            &'a self,
+            filters: &'a EventFilterBuilder,
+        ) -> BoxFuture<'a, Result<i64, sqlx::Error>> {
+            self.inner.count_filtered(filters)
+        }
+
+        fn mark_processed(&self, id: i64) -> BoxFuture<'_, Result<(), sqlx::Error>> {
+            self.inner.mark_processed(id)
+        }
+
+        fn mark_failed<'a>(
+            &'a self,
+            id: i64,
+            error: &'a str,
+        ) -> BoxFuture<'a, Result<(), sqlx::Error>> {
+            self.inner.mark_failed(id, error)
+        }
+
+        fn count(&self) -> BoxFuture<'_, Result<i64, sqlx::Error>> {
+            self.inner.count()
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_slow_search_times_out_with_a_message() {
+        use sqlx::postgres::PgPoolOptions;
+
+        // The page gives up before the store or the filter dropdowns touch the database
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/cross_bow_test")
+            .unwrap();
+        let store = SlowStore {
+            inner: std::sync::Arc::new(crate::db::PgEventStore::new(pool.clone())),
+            delay: std::time::Duration::from_secs(5),
+        };
+        let store: web::Data<dyn EventStore> =
+            web::Data::from(std::sync::Arc::new(store) as std::sync::Arc<dyn EventStore>);
+        let mut config = Config::test_default();
+        config.search_timeout_ms = 50;
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .app_data(web::Data::new(config))
+                .app_data(store)
+                .route("/events", web::get().to(list_events)),
+        )
+        .await;
+
+        let started = std::time::Instant::now();
+        let req = actix_test::TestRequest::get()
+            .uri("/events?search=needle")
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(
+            resp.status(),
+            actix_web::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        let body = actix_test::read_body(resp).await;
+        assert!(String::from_utf8(body.to_vec())
+            .unwrap()
+            .contains("Search timed out, narrow your filters"));
+    }
+
+    #[test]
+    fn test_ui_per_page_is_clamped() {
+        let filters = |query: &str| web::Query::<EventFilters>::from_query(query).unwrap();
+
+        assert_eq!(filters("").per_page(UI_MAX_PER_PAGE, UI_MAX_PER_PAGE), 300);
+        assert_eq!(
+            filters("per_page=50").per_page(UI_MAX_PER_PAGE, UI_MAX_PER_PAGE),
+            50
+        );
+        assert_eq!(
+            filters("per_page=1000").per_page(UI_MAX_PER_PAGE, UI_MAX_PER_PAGE),
+            UI_MAX_PER_PAGE
+        );
+        assert_eq!(
+            filters("per_page=0").per_page(UI_MAX_PER_PAGE, UI_MAX_PER_PAGE),
+            1
+        );
+    }
+
+    #[test]
+    fn test_page_url_keeps_repository_filter() {
+        let query =
+            web::Query::<EventFilters>::from_query("repository_id=7&source=github").unwrap();
+        assert_eq!(query.repository_id, Some(7));
+        assert_eq!(
+            build_page_url(2, &query),
+            "/events?page=2&source=github&repository_id=7"
+        );
+
+        // An empty selection means all repositories
+        let query = web::Query::<EventFilters>::from_query("repository_id=").unwrap();
+        assert_eq!(query.repository_id, None);
+
+        let query =
+            web::Query::<EventFilters>::from_query("search=a%26b+c%23d&source=github%2Cgitlab")
+                .unwrap();
+        assert_eq!(
+            build_page_url(1, &query),
+            "/events?page=1&source=github%2Cgitlab&search=a%26b+c%23d"
+        );
+    }
+
+    #[test]
+    fn test_breakdown_links_encode_values() {
+        assert_eq!(
+            breakdown_events_url("ci", "build", "retry & skip"),
+            "/events?source=ci&event_type=build&action=retry+%26+skip"
+        );
+        assert_eq!(
+            breakdown_events_url("ci", "build", NO_ACTION),
+            "/events?source=ci&event_type=build"
+        );
+    }
+
+    #[test]
+    fn test_pager_is_bounded_for_huge_results() {
+        let query = web::Query::<EventFilters>::from_query("source=github").unwrap();
+
+        let small = render_pager(2, 90, 30, |p| build_page_url(p, &query)).into_string();
+        assert_eq!(small.matches("href=").count(), 3);
+
+        let huge = render_pager(1, 50_000_000, 20, |p| build_page_url(p, &query)).into_string();
+        assert_eq!(huge.matches("href=").count(), MAX_PAGE_BUTTONS as usize);
+        assert!(huge.len() < 10_000, "pager rendered {} bytes", huge.len());
+
+        // A page beyond the cap is still reachable from the pager
+        let deep = render_pager(1_000, 50_000_000, 20, |p| build_page_url(p, &query)).into_string();
+        assert_eq!(deep.matches("href=").count(), MAX_PAGE_BUTTONS as usize + 1);
+        assert!(deep.contains("page=1000&amp;source=github"));
+    }
+
+    #[test]
+    fn test_actor_initials() {
+        assert_eq!(actor_initials("Jane Smith"), "JS");
+        assert_eq!(actor_initials("octocat"), "O");
+        assert_eq!(actor_initials("dependabot-preview"), "DP");
+        assert_eq!(actor_initials(""), "?");
+    }
+
+    #[test]
+    fn test_avatar_falls_back_to_initials() {
+        let markup = render_actor_avatar(Some("Jane Smith"), None, "w-6").into_string();
+        assert!(markup.contains("JS"));
+        assert!(!markup.contains("<img"));
+
+        let markup = render_actor_avatar(Some("octocat"), Some("https://avatars.example/1"), "w-6")
+            .into_string();
+        assert!(markup.contains(r#"src="https://avatars.example/1""#));
+    }
+}
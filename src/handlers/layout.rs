@@ -0,0 +1,187 @@
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use maud::{html, Markup, DOCTYPE};
+
+use crate::config::Config;
+
+/// Cookie written by `theme-switcher.js` when the visitor picks a theme
+pub const THEME_COOKIE: &str = "theme";
+
+/// Client hint carrying the OS color scheme, requested via `Accept-CH`
+pub const PREFERS_COLOR_SCHEME_HINT: &str = "Sec-CH-Prefers-Color-Scheme";
+
+/// Page theme rendered into `data-theme`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+    /// Follow the OS setting through `prefers-color-scheme`
+    Auto,
+}
+
+impl Theme {
+    /// Accepts both config names (`light`/`dark`/`auto`) and daisyUI theme names
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "light" | "swiss" => Some(Theme::Light),
+            "dark" | "swiss-dark" => Some(Theme::Dark),
+            "auto" => Some(Theme::Auto),
+            _ => None,
+        }
+    }
+
+    /// The `data-theme` value; `None` leaves the choice to the stylesheet
+    pub fn data_theme(self) -> Option<&'static str> {
+        match self {
+            Theme::Light => Some("swiss"),
+            Theme::Dark => Some("swiss-dark"),
+            Theme::Auto => None,
+        }
+    }
+
+    /// Theme for a request: a saved cookie wins, then the route's configured
+    /// default, with `auto` resolved by the color scheme client hint when sent
+    pub fn for_request(req: &HttpRequest) -> Self {
+        let saved = req
+            .cookie(THEME_COOKIE)
+            .and_then(|cookie| Theme::parse(cookie.value()))
+            .filter(|theme| *theme != Theme::Auto);
+        if let Some(theme) = saved {
+            return theme;
+        }
+
+        let default = req
+            .app_data::<web::Data<Config>>()
+            .map(|config| config.theme_for(req.path()))
+            .unwrap_or(Theme::Dark);
+        if default != Theme::Auto {
+            return default;
+        }
+
+        req.headers()
+            .get(PREFERS_COLOR_SCHEME_HINT)
+            .and_then(|hint| hint.to_str().ok())
+            .and_then(|hint| Theme::parse(hint.trim_matches('"')))
+            .unwrap_or(Theme::Auto)
+    }
+}
+
+impl FromRequest for Theme {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(Theme::for_request(req)))
+    }
+}
+
+/// Full page shell shared by the web interface: head assets, navbar and content container
+pub fn page(title: &str, theme: Theme, content: Markup) -> Markup {
+    html! {
+        (DOCTYPE)
+        html lang="en" data-theme=[theme.data_theme()] {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { (title) " - Cross Bow" }
+                link rel="stylesheet" href="/assets/daisy.css";
+                link rel="stylesheet" href="/assets/themes.css";
+                script src="/assets/htmx.js" {}
+                script src="/assets/tw.js" {}
+                script src="/assets/theme-switcher.js" {}
+            }
+            body {
+                div class="navbar bg-base-100 shadow-lg" {
+                    div class="flex-1" {
+                        a class="btn btn-ghost text-xl" href="/" { "Cross Bow" }
+                    }
+                    div class="flex-none gap-2" {
+                        ul class="menu menu-horizontal px-1" {
+                            li { a href="/dashboard" { "Dashboard" } }
+                            li { a href="/repositories" { "Repositories" } }
+                            li { a href="/events" { "Events" } }
+                        }
+                        button
+                            class="btn btn-ghost btn-circle"
+                            onclick="toggleTheme()"
+                            title="Toggle theme (light, dark, auto)"
+                        {
+                            // Sun icon for light mode
+                            svg
+                                xmlns="http://www.w3.org/2000/svg"
+                                class="h-5 w-5"
+                                fill="none"
+                                viewBox="0 0 24 24"
+                                stroke="currentColor"
+                            {
+                                path
+                                    stroke-linecap="round"
+                                    stroke-linejoin="round"
+                                    stroke-width="2"
+                                    d="M12 3v1m0 16v1m9-9h-1M4 12H3m15.364 6.364l-.707-.707M6.343 6.343l-.707-.707m12.728 0l-.707.707M6.343 17.657l-.707.707M16 12a4 4 0 11-8 0 4 4 0 018 0z";
+                            }
+                        }
+                    }
+                }
+
+                div class="container mx-auto px-4 py-8" {
+                    (content)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::cookie::Cookie;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_auto_theme_omits_data_theme_attribute() {
+        let auto = page("Events", Theme::Auto, html! {}).into_string();
+        assert!(auto.contains(r#"<html lang="en">"#));
+        assert!(!auto.contains("data-theme"));
+
+        let dark = page("Events", Theme::Dark, html! {}).into_string();
+        assert!(dark.contains(r#"<html lang="en" data-theme="swiss-dark">"#));
+    }
+
+    #[test]
+    fn test_theme_for_request() {
+        let mut config = Config::test_default();
+        config.default_theme = Theme::Auto;
+        config.route_themes = vec![("/admin".to_string(), Theme::Light)];
+        let config = web::Data::new(config);
+
+        let request = |path: &str| TestRequest::get().uri(path).app_data(config.clone());
+
+        assert_eq!(
+            Theme::for_request(&request("/events").to_http_request()),
+            Theme::Auto
+        );
+        assert_eq!(
+            Theme::for_request(
+                &request("/events")
+                    .insert_header((PREFERS_COLOR_SCHEME_HINT, "\"dark\""))
+                    .to_http_request()
+            ),
+            Theme::Dark
+        );
+        assert_eq!(
+            Theme::for_request(&request("/admin/sources").to_http_request()),
+            Theme::Light
+        );
+        assert_eq!(
+            Theme::for_request(
+                &request("/admin/sources")
+                    .cookie(Cookie::new(THEME_COOKIE, "swiss-dark"))
+                    .to_http_request()
+            ),
+            Theme::Dark
+        );
+    }
+}
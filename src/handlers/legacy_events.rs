@@ -0,0 +1,264 @@
+use actix_web::{web, HttpResponse, Result};
+use maud::html;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::handlers::errors::AppError;
+use crate::handlers::events::{deserialize_optional_i64, format_datetime, render_pager};
+use crate::handlers::layout::{self, Theme};
+use crate::models::{EventFilterBuilder, FilterTable, WebhookEvent};
+
+/// Rows per page of the legacy events viewer
+const LEGACY_EVENTS_PER_PAGE: i64 = 50;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct LegacyEventFilters {
+    #[serde(deserialize_with = "deserialize_optional_i64")]
+    pub page: Option<i64>,
+    pub event_type: Option<String>,
+    pub action: Option<String>,
+    pub processed: Option<bool>,
+    pub search: Option<String>,
+}
+
+impl LegacyEventFilters {
+    /// The page's filters as a query over the `webhook_events` table
+    pub fn to_filter_builder(&self) -> EventFilterBuilder {
+        EventFilterBuilder::new(FilterTable::WebhookEvents)
+            .event_type(self.event_type.as_deref())
+            .action(self.action.as_deref())
+            .processed(self.processed)
+            .search(self.search.as_deref())
+    }
+
+    fn page_url(&self, page: i64) -> String {
+        let mut params = vec![format!("page={page}")];
+
+        if let Some(event_type) = &self.event_type {
+            params.push(format!("event_type={event_type}"));
+        }
+        if let Some(action) = &self.action {
+            params.push(format!("action={action}"));
+        }
+        if let Some(processed) = self.processed {
+            params.push(format!("processed={processed}"));
+        }
+        if let Some(search) = &self.search {
+            params.push(format!("search={search}"));
+        }
+
+        format!("/legacy-events?{}", params.join("&"))
+    }
+}
+
+/// Read-only view of the pre-migration `webhook_events` table
+pub async fn list_legacy_events(
+    pool: web::Data<PgPool>,
+    theme: Theme,
+    query: web::Query<LegacyEventFilters>,
+) -> Result<HttpResponse, AppError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let offset = (page - 1) * LEGACY_EVENTS_PER_PAGE;
+
+    let filters = query.to_filter_builder();
+    let events =
+        WebhookEvent::search_and_filter(pool.get_ref(), &filters, LEGACY_EVENTS_PER_PAGE, offset)
+            .await?;
+    let total_count = WebhookEvent::count_filtered(pool.get_ref(), &filters).await?;
+    let event_types = WebhookEvent::get_event_types(pool.get_ref()).await?;
+
+    let markup = layout::page(
+        "Legacy Events",
+        theme,
+        html! {
+            h1 class="text-4xl font-bold mb-2" { "Legacy Webhook Events" }
+            p class="text-base-content/60 mb-8" {
+                "GitHub deliveries stored in " code { "webhook_events" } " before the move to the events table."
+            }
+
+            div class="card bg-base-100 shadow-xl mb-6" {
+                div class="card-body" {
+                    form method="get" action="/legacy-events" class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-5 gap-4" {
+                        div class="form-control" {
+                            label class="label" { span class="label-text" { "Search" } }
+                            input
+                                type="text"
+                                name="search"
+                                placeholder="Search in payload..."
+                                class="input input-bordered"
+                                value=(query.search.as_deref().unwrap_or(""));
+                        }
+                        div class="form-control" {
+                            label class="label" { span class="label-text" { "Event Type" } }
+                            select name="event_type" class="select select-bordered" {
+                                option value="" selected[query.event_type.is_none()] { "All Types" }
+                                @for event_type in &event_types {
+                                    option
+                                        value=(event_type)
+                                        selected[query.event_type.as_deref() == Some(event_type.as_str())]
+                                    { (event_type) }
+                                }
+                            }
+                        }
+                        div class="form-control" {
+                            label class="label" { span class="label-text" { "Action" } }
+                            input
+                                type="text"
+                                name="action"
+                                class="input input-bordered"
+                                value=(query.action.as_deref().unwrap_or(""));
+                        }
+                        div class="form-control" {
+                            label class="label" { span class="label-text" { "Status" } }
+                            select name="processed" class="select select-bordered" {
+                                option value="" selected[query.processed.is_none()] { "All Status" }
+                                option value="true" selected[query.processed == Some(true)] { "Processed" }
+                                option value="false" selected[query.processed == Some(false)] { "Pending" }
+                            }
+                        }
+                        div class="form-control flex flex-row items-end gap-2" {
+                            button type="submit" class="btn btn-primary" { "Filter" }
+                            a href="/legacy-events" class="btn btn-ghost" { "Clear" }
+                        }
+                    }
+                }
+            }
+
+            div class="alert alert-info mb-6" {
+                span { "Showing " (events.len()) " of " (total_count) " legacy events" }
+            }
+
+            div class="card bg-base-100 shadow-xl mb-6" {
+                div class="card-body p-0" {
+                    div class="overflow-x-auto" {
+                        table class="table table-zebra" {
+                            thead {
+                                tr {
+                                    th { "ID" }
+                                    th { "Event Type" }
+                                    th { "Action" }
+                                    th { "Delivery" }
+                                    th { "Received" }
+                                    th { "Status" }
+                                    th { "Payload" }
+                                }
+                            }
+                            tbody {
+                                @if events.is_empty() {
+                                    tr {
+                                        td colspan="7" class="text-center text-base-content/60 py-8" {
+                                            "No legacy events found matching the filters"
+                                        }
+                                    }
+                                }
+                                @for event in &events {
+                                    tr {
+                                        td { (event.id) }
+                                        td { span class="badge badge-primary" { (event.event_type) } }
+                                        td {
+                                            @if let Some(action) = &event.event_action {
+                                                span class="badge badge-ghost" { (action) }
+                                            } @else {
+                                                span class="text-base-content/60" { "-" }
+                                            }
+                                        }
+                                        td class="font-mono text-xs" { (event.delivery_id) }
+                                        td class="text-sm" { (format_datetime(&event.received_at)) }
+                                        td {
+                                            @if event.processed {
+                                                span class="badge badge-success" { "Processed" }
+                                            } @else {
+                                                span class="badge badge-warning" { "Pending" }
+                                            }
+                                        }
+                                        td {
+                                            details {
+                                                summary class="cursor-pointer text-sm" { "View" }
+                                                pre class="bg-base-200 p-4 rounded-lg overflow-x-auto text-xs mt-2 max-w-xl" {
+                                                    code {
+                                                        (serde_json::to_string_pretty(&event.payload).unwrap_or_else(|_| "{}".to_string()))
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            (render_pager(page, total_count, LEGACY_EVENTS_PER_PAGE, |p| query.page_url(p)))
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateWebhookEvent;
+    use actix_web::{test as actix_test, App};
+    use uuid::Uuid;
+
+    async fn create_legacy_event(pool: &PgPool, event_type: &str, action: Option<&str>) {
+        WebhookEvent::create(
+            pool,
+            CreateWebhookEvent {
+                repository_id: None,
+                event_type: event_type.to_string(),
+                event_action: action.map(str::to_string),
+                delivery_id: Uuid::new_v4(),
+                payload: serde_json::json!({ "ref": "refs/heads/main" }),
+                signature: "sha256=legacy".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_page_url_keeps_filters() {
+        let query = web::Query::<LegacyEventFilters>::from_query("event_type=push&processed=false")
+            .unwrap();
+        assert_eq!(
+            query.page_url(3),
+            "/legacy-events?page=3&event_type=push&processed=false"
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_legacy_events_page_lists_filtered_rows(pool: PgPool) {
+        create_legacy_event(&pool, "push", None).await;
+        create_legacy_event(&pool, "issues", Some("opened")).await;
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/legacy-events", web::get().to(list_legacy_events)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri("/legacy-events")
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("Showing 2 of 2 legacy events"));
+        assert!(body.contains("refs/heads/main"));
+
+        let req = actix_test::TestRequest::get()
+            .uri("/legacy-events?event_type=issues")
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("Showing 1 of 1 legacy events"));
+        assert!(body.contains("opened"));
+    }
+}
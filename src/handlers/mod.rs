@@ -1,9 +1,35 @@
+pub mod admin;
+pub mod api;
+pub mod api_auth;
+pub mod body_timeout;
 pub mod dashboard;
+pub mod errors;
 pub mod events;
+pub mod layout;
+pub mod legacy_events;
+pub mod rate_limit;
 pub mod repositories;
+pub mod routes;
 pub mod webhook;
 
-pub use dashboard::dashboard;
-pub use events::list_events;
-pub use repositories::{list_repositories, repository_detail};
-pub use webhook::{generic_webhook, github_webhook};
+pub use admin::{
+    create_replay, list_memberships, list_processors, list_rejections, list_replays, list_sources,
+    save_source, save_watched_paths,
+};
+pub use api::{
+    event_msgpack, export_events_ndjson, healthz, import_events_ndjson, list_events_json,
+    list_repositories_json, metrics, readyz, repository_json, reprocess_events,
+    sources_health_json,
+};
+pub use api_auth::AdminApiKey;
+pub use body_timeout::BodyReadTimeout;
+pub use dashboard::{dashboard, home};
+pub use errors::{error_handlers, AppError};
+pub use events::{
+    action_breakdown, add_event_note, add_event_tag, bulk_tag_events, diff_events, grouped_events,
+    list_events, related_events, remove_event_tag,
+};
+pub use legacy_events::list_legacy_events;
+pub use rate_limit::WebhookRateLimit;
+pub use repositories::{commit_detail, list_repositories, repository_detail};
+pub use webhook::{generic_webhook, github_webhook, tenant_webhook};
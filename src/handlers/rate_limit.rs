@@ -0,0 +1,238 @@
+use std::future::{ready, Ready};
+use std::net::IpAddr;
+
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use actix_web::{Error, HttpResponse};
+use chrono::Utc;
+use futures_util::future::LocalBoxFuture;
+
+use crate::services::rate_limit::{RateLimitState, RateLimiter};
+
+/// Limits webhook deliveries per caller IP and reports the caller's bucket in
+/// `X-RateLimit-*` headers on every webhook response, accepted or rejected.
+///
+/// Only `/webhook...` paths are counted; other routes pass through untouched. The caller
+/// is the connection's peer, unless that peer is one of `trusted_proxies`.
+#[derive(Debug, Clone)]
+pub struct WebhookRateLimit {
+    limiter: RateLimiter,
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl WebhookRateLimit {
+    pub fn new(limiter: RateLimiter, trusted_proxies: Vec<IpAddr>) -> Self {
+        WebhookRateLimit {
+            limiter,
+            trusted_proxies,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for WebhookRateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = WebhookRateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(WebhookRateLimitMiddleware {
+            service,
+            limiter: self.limiter.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+        }))
+    }
+}
+
+pub struct WebhookRateLimitMiddleware<S> {
+    service: S,
+    limiter: RateLimiter,
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl<S, B> Service<ServiceRequest> for WebhookRateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if !req.path().starts_with("/webhook") {
+            let fut = self.service.call(req);
+            return Box::pin(async move { Ok(fut.await?.map_into_left_body()) });
+        }
+
+        let caller = caller_ip(&req, &self.trusted_proxies)
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let state = self.limiter.hit(&caller, Utc::now());
+
+        if !state.allowed {
+            let retry_after = (state.reset_at - Utc::now()).num_seconds().max(1);
+            let mut response = HttpResponse::TooManyRequests()
+                .insert_header((header::RETRY_AFTER, retry_after))
+                .json(serde_json::json!({ "error": "Rate limit exceeded" }));
+            insert_headers(response.headers_mut(), &state);
+
+            return Box::pin(ready(Ok(req.into_response(response).map_into_right_body())));
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let mut res = fut.await?;
+            insert_headers(res.headers_mut(), &state);
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+/// The peer's IP, or for a trusted proxy the nearest `X-Forwarded-For` address that is
+/// not itself a trusted proxy. Entries left of that one are client-supplied and ignored.
+fn caller_ip(req: &ServiceRequest, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer = req.peer_addr()?.ip();
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer);
+    }
+
+    let forwarded: Vec<IpAddr> = req
+        .headers()
+        .get_all("x-forwarded-for")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|addr| addr.trim().parse().ok())
+        .collect();
+    let client = forwarded
+        .into_iter()
+        .rev()
+        .find(|ip| !trusted_proxies.contains(ip));
+    Some(client.unwrap_or(peer))
+}
+
+/// `X-RateLimit-Reset` is the end of the window as Unix seconds, like GitHub's API
+fn insert_headers(headers: &mut HeaderMap, state: &RateLimitState) {
+    headers.insert(
+        HeaderName::from_static("x-ratelimit-limit"),
+        HeaderValue::from(state.limit),
+    );
+    headers.insert(
+        HeaderName::from_static("x-ratelimit-remaining"),
+        HeaderValue::from(state.remaining),
+    );
+    headers.insert(
+        HeaderName::from_static("x-ratelimit-reset"),
+        HeaderValue::from(state.reset_at.timestamp()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test as actix_test, web, App};
+
+    fn header<B>(resp: &ServiceResponse<B>, name: &str) -> String {
+        resp.headers()
+            .get(name)
+            .unwrap_or_else(|| panic!("missing {name}"))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[actix_web::test]
+    async fn test_headers_count_down_across_webhook_requests() {
+        let limiter = RateLimiter::new(2, chrono::Duration::seconds(60));
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(WebhookRateLimit::new(limiter, Vec::new()))
+                .route("/webhook/{source}", web::post().to(HttpResponse::Ok))
+                .route(
+                    "/webhooks/github",
+                    web::post().to(HttpResponse::Unauthorized),
+                )
+                .route("/events", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let caller: std::net::SocketAddr = "203.0.113.7:40000".parse().unwrap();
+        let post = |uri: &str| {
+            actix_test::TestRequest::post()
+                .uri(uri)
+                .peer_addr(caller)
+                .to_request()
+        };
+
+        let first = actix_test::call_service(&app, post("/webhook/ci")).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(header(&first, "x-ratelimit-limit"), "2");
+        assert_eq!(header(&first, "x-ratelimit-remaining"), "1");
+        let reset = header(&first, "x-ratelimit-reset");
+
+        // Rejected deliveries carry the headers too
+        let second = actix_test::call_service(&app, post("/webhooks/github")).await;
+        assert_eq!(second.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(header(&second, "x-ratelimit-remaining"), "0");
+        assert_eq!(header(&second, "x-ratelimit-reset"), reset);
+
+        let third = actix_test::call_service(&app, post("/webhook/ci")).await;
+        assert_eq!(third.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(header(&third, "x-ratelimit-remaining"), "0");
+        assert!(third.headers().contains_key(header::RETRY_AFTER));
+
+        // Other routes are neither counted nor annotated
+        let req = actix_test::TestRequest::get()
+            .uri("/events")
+            .peer_addr(caller)
+            .to_request();
+        let page = actix_test::call_service(&app, req).await;
+        assert_eq!(page.status(), StatusCode::OK);
+        assert!(!page.headers().contains_key("x-ratelimit-limit"));
+    }
+
+    #[actix_web::test]
+    async fn test_forwarded_for_is_only_trusted_from_proxies() {
+        let proxy: std::net::SocketAddr = "10.0.0.2:50000".parse().unwrap();
+        let limiter = RateLimiter::new(1, chrono::Duration::seconds(60));
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(WebhookRateLimit::new(limiter, vec![proxy.ip()]))
+                .route("/webhook/{source}", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+        let post = |peer: std::net::SocketAddr, forwarded_for: &str| {
+            actix_test::TestRequest::post()
+                .uri("/webhook/ci")
+                .peer_addr(peer)
+                .insert_header(("X-Forwarded-For", forwarded_for))
+                .to_request()
+        };
+
+        // A direct caller cannot escape its bucket by rotating the header
+        let direct: std::net::SocketAddr = "203.0.113.7:40000".parse().unwrap();
+        let first = actix_test::call_service(&app, post(direct, "198.51.100.1")).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        let second = actix_test::call_service(&app, post(direct, "198.51.100.2")).await;
+        assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Through the proxy, clients are told apart by the address the proxy appended,
+        // whatever they claimed to the left of it
+        let first = actix_test::call_service(&app, post(proxy, "1.1.1.1, 198.51.100.9")).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        let spoofed = actix_test::call_service(&app, post(proxy, "2.2.2.2, 198.51.100.9")).await;
+        assert_eq!(spoofed.status(), StatusCode::TOO_MANY_REQUESTS);
+        let other = actix_test::call_service(&app, post(proxy, "198.51.100.10")).await;
+        assert_eq!(other.status(), StatusCode::OK);
+    }
+}
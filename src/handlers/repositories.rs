@@ -1,28 +1,157 @@
+use std::collections::HashMap;
+
 use actix_web::{web, HttpResponse, Result};
-use maud::{html, DOCTYPE};
+use chrono::{DateTime, Duration, Utc};
+use maud::{html, Markup, DOCTYPE};
+use serde::Deserialize;
 use sqlx::PgPool;
 
-use crate::utils::PaginationParams;
+use crate::handlers::errors::AppError;
+use crate::handlers::events::{deserialize_optional_i64, render_pager};
+use crate::handlers::layout::Theme;
+use crate::utils::datetime::deserialize_lenient;
+use crate::utils::{PaginationParams, ParsedDateTime};
+
+/// Days of activity shown in the repositories list sparkline
+const ACTIVITY_DAYS: i32 = 14;
+
+/// Pull requests and issues per page on the repository detail
+const DETAIL_SECTION_PER_PAGE: i64 = 10;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct RepositoryFilters {
+    pub topic: Option<String>,
+}
+
+/// Independent page numbers for the detail page's pull request and issue sections
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct DetailPages {
+    #[serde(deserialize_with = "deserialize_optional_i64")]
+    pub pr_page: Option<i64>,
+    #[serde(deserialize_with = "deserialize_optional_i64")]
+    pub issue_page: Option<i64>,
+}
+
+impl DetailPages {
+    fn pr_page(&self) -> i64 {
+        self.pr_page.unwrap_or(1).max(1)
+    }
+
+    fn issue_page(&self) -> i64 {
+        self.issue_page.unwrap_or(1).max(1)
+    }
+}
+
+/// Optional bounds on the detail page's commits section; a bare `until` date includes
+/// that whole day. Malformed bounds are ignored.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct CommitWindow {
+    #[serde(deserialize_with = "deserialize_lenient")]
+    pub since: Option<ParsedDateTime>,
+    #[serde(deserialize_with = "deserialize_lenient")]
+    pub until: Option<ParsedDateTime>,
+    /// Only list commits touching the repository's watched paths
+    pub watched_only: bool,
+}
+
+impl CommitWindow {
+    fn from(&self) -> Option<DateTime<Utc>> {
+        self.since.map(ParsedDateTime::into_inner)
+    }
+
+    /// End of the window; for a bare date the start of the next day, so commits on
+    /// `until` itself are included
+    fn to(&self) -> Option<DateTime<Utc>> {
+        self.until.and_then(|until| {
+            if until.is_midnight() {
+                until.into_inner().checked_add_signed(Duration::days(1))
+            } else {
+                Some(until.into_inner())
+            }
+        })
+    }
+
+    fn is_bounded(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    fn is_filtered(&self) -> bool {
+        self.is_bounded() || self.watched_only
+    }
+
+    /// Query parameters that keep the window on links to other pages
+    fn query(&self) -> String {
+        let mut query = String::new();
+        if let Some(since) = self.since {
+            query.push_str(&format!("&since={since}"));
+        }
+        if let Some(until) = self.until {
+            query.push_str(&format!("&until={until}"));
+        }
+        if self.watched_only {
+            query.push_str("&watched_only=true");
+        }
+        query
+    }
+}
+
+/// Detail URL for a pair of section pages, anchored on the section being paged
+fn detail_url(
+    repo_id: i64,
+    pr_page: i64,
+    issue_page: i64,
+    window: &CommitWindow,
+    anchor: &str,
+) -> String {
+    format!(
+        "/repositories/{repo_id}?pr_page={pr_page}&issue_page={issue_page}{}#{anchor}",
+        window.query()
+    )
+}
 
 pub async fn list_repositories(
     pool: web::Data<PgPool>,
+    theme: Theme,
     query: web::Query<PaginationParams>,
-) -> Result<HttpResponse> {
+    filters: web::Query<RepositoryFilters>,
+) -> Result<HttpResponse, AppError> {
     let params = query.into_inner();
     let limit = params.limit();
     let offset = params.offset();
+    let topic = filters.topic.as_deref().filter(|t| !t.is_empty());
 
-    let repositories = crate::models::Repository::list_all(pool.get_ref(), limit, offset)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let (repositories, total) = match topic {
+        Some(topic) => (
+            crate::models::Repository::list_by_topic(pool.get_ref(), topic, limit, offset).await,
+            crate::models::Repository::count_by_topic(pool.get_ref(), topic).await,
+        ),
+        None => (
+            crate::models::Repository::list_all(pool.get_ref(), limit, offset).await,
+            crate::models::Repository::count(pool.get_ref()).await,
+        ),
+    };
+    let repositories = repositories?;
+    let total = total?;
 
-    let total = crate::models::Repository::count(pool.get_ref())
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let topics = crate::models::Repository::list_topics(pool.get_ref()).await?;
+
+    let mut activity = Vec::with_capacity(repositories.len());
+    for repo in &repositories {
+        let counts: Vec<i64> =
+            crate::models::Repository::activity_buckets(pool.get_ref(), repo.id, ACTIVITY_DAYS)
+                .await?
+                .into_iter()
+                .map(|b| b.count)
+                .collect();
+        activity.push(counts);
+    }
 
     let markup = html! {
         (DOCTYPE)
-        html lang="en" data-theme="dark" {
+        html lang="en" data-theme=[theme.data_theme()] {
             head {
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
@@ -40,13 +169,13 @@ pub async fn list_repositories(
                     }
                     div class="flex-none gap-2" {
                         ul class="menu menu-horizontal px-1" {
-                            li { a href="/" { "Dashboard" } }
+                            li { a href="/dashboard" { "Dashboard" } }
                             li { a href="/events" { "Events" } }
                         }
                         button
                             class="btn btn-ghost btn-circle"
                             onclick="toggleTheme()"
-                            title="Toggle theme"
+                            title="Toggle theme (light, dark, auto)"
                         {
                             // Sun icon for light mode
                             svg
@@ -68,7 +197,28 @@ pub async fn list_repositories(
 
                 div class="container mx-auto px-4 py-8" {
                     h1 class="text-4xl font-bold mb-8" { "Repositories" }
-                    p class="mb-4" { "Total: " (total) " repositories" }
+                    p class="mb-4" {
+                        "Total: " (total) " repositories"
+                        @if let Some(topic) = topic {
+                            " tagged " span class="badge badge-primary" { (topic) }
+                        }
+                    }
+
+                    @if !topics.is_empty() {
+                        div class="flex flex-wrap gap-2 mb-6" {
+                            a class=(if topic.is_none() { "badge badge-primary" } else { "badge badge-outline" }) href="/repositories" {
+                                "All topics"
+                            }
+                            @for t in &topics {
+                                a
+                                    class=(if topic == Some(t.as_str()) { "badge badge-primary" } else { "badge badge-outline" })
+                                    href=(topic_url(t))
+                                {
+                                    (t)
+                                }
+                            }
+                        }
+                    }
 
                     @if repositories.is_empty() {
                         div class="alert alert-info" {
@@ -82,17 +232,19 @@ pub async fn list_repositories(
                                         th { "Name" }
                                         th { "Owner" }
                                         th { "Description" }
+                                        th { "Activity" }
                                         th { "Private" }
                                         th { "Actions" }
                                     }
                                 }
                                 tbody {
-                                    @for repo in repositories {
+                                    @for (repo, counts) in repositories.iter().zip(&activity) {
                                         tr {
                                             td {
                                                 a class="link link-primary" href=(format!("/repositories/{}", repo.id)) {
                                                     (repo.full_name)
                                                 }
+                                                (render_language_and_topics(repo))
                                             }
                                             td { (repo.owner) }
                                             td {
@@ -102,6 +254,9 @@ pub async fn list_repositories(
                                                     span class="text-gray-500" { "No description" }
                                                 }
                                             }
+                                            td title=(format!("{} events in the last {ACTIVITY_DAYS} days", counts.iter().sum::<i64>())) {
+                                                (render_sparkline(counts))
+                                            }
                                             td {
                                                 @if repo.is_private {
                                                     span class="badge badge-warning" { "Private" }
@@ -132,34 +287,82 @@ pub async fn list_repositories(
 
 pub async fn repository_detail(
     pool: web::Data<PgPool>,
+    theme: Theme,
     path: web::Path<i64>,
-) -> Result<HttpResponse> {
+    pages: web::Query<DetailPages>,
+    window: web::Query<CommitWindow>,
+) -> Result<HttpResponse, AppError> {
     let repo_id = path.into_inner();
+    let pr_page = pages.pr_page();
+    let issue_page = pages.issue_page();
 
     let repository = crate::models::Repository::find_by_id(pool.get_ref(), repo_id)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?
-        .ok_or_else(|| actix_web::error::ErrorNotFound("Repository not found"))?;
+        .await?
+        .ok_or_else(|| AppError::not_found("Repository not found"))?;
 
-    let commits = crate::models::Commit::list_by_repository(pool.get_ref(), repo_id, 10, 0)
-        .await
-        .unwrap_or_default();
+    let renames =
+        crate::models::github::RepositoryRename::list_by_repository(pool.get_ref(), repo_id)
+            .await?;
 
-    let prs = crate::models::PullRequest::list_by_repository(pool.get_ref(), repo_id, 10, 0)
-        .await
-        .unwrap_or_default();
+    let commits = crate::models::Commit::list_by_repository_between(
+        pool.get_ref(),
+        repo_id,
+        window.from(),
+        window.to(),
+        window.watched_only,
+        10,
+        0,
+    )
+    .await?;
 
-    let issues = crate::models::Issue::list_by_repository(pool.get_ref(), repo_id, 10, 0)
-        .await
-        .unwrap_or_default();
+    let commit_pr_ids: Vec<i64> = commits.iter().filter_map(|c| c.pull_request_id).collect();
+    let commit_prs: HashMap<i64, crate::models::PullRequest> =
+        crate::models::PullRequest::list_by_ids(pool.get_ref(), &commit_pr_ids)
+            .await?
+            .into_iter()
+            .map(|pr| (pr.id, pr))
+            .collect();
 
-    let commit_count = crate::models::Commit::count_by_repository(pool.get_ref(), repo_id)
-        .await
-        .unwrap_or(0);
+    let prs = crate::models::PullRequest::list_by_repository(
+        pool.get_ref(),
+        repo_id,
+        DETAIL_SECTION_PER_PAGE,
+        (pr_page - 1) * DETAIL_SECTION_PER_PAGE,
+    )
+    .await?;
+
+    let issues = crate::models::Issue::list_by_repository(
+        pool.get_ref(),
+        repo_id,
+        DETAIL_SECTION_PER_PAGE,
+        (issue_page - 1) * DETAIL_SECTION_PER_PAGE,
+    )
+    .await?;
+
+    let commit_count = crate::models::Commit::count_by_repository(pool.get_ref(), repo_id).await?;
+
+    let pr_count = crate::models::PullRequest::count_by_repository(pool.get_ref(), repo_id).await?;
+
+    let issue_count = crate::models::Issue::count_by_repository(pool.get_ref(), repo_id).await?;
+
+    let stargazers =
+        crate::models::RepositoryStar::list_recent_stargazers(pool.get_ref(), repo_id, 10).await?;
+
+    let deployments =
+        crate::models::Deployment::list_by_repository(pool.get_ref(), repo_id, 10).await?;
+
+    let pr_ids: Vec<i64> = prs.iter().map(|pr| pr.id).collect();
+    let pr_changes =
+        crate::models::EntityChange::list_for_entities(pool.get_ref(), "pull_request", &pr_ids)
+            .await?;
+
+    let issue_ids: Vec<i64> = issues.iter().map(|issue| issue.id).collect();
+    let issue_changes =
+        crate::models::EntityChange::list_for_entities(pool.get_ref(), "issue", &issue_ids).await?;
 
     let markup = html! {
         (DOCTYPE)
-        html lang="en" data-theme="dark" {
+        html lang="en" data-theme=[theme.data_theme()] {
             head {
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
@@ -177,13 +380,13 @@ pub async fn repository_detail(
                     }
                     div class="flex-none gap-2" {
                         ul class="menu menu-horizontal px-1" {
-                            li { a href="/" { "Dashboard" } }
+                            li { a href="/dashboard" { "Dashboard" } }
                             li { a href="/events" { "Events" } }
                         }
                         button
                             class="btn btn-ghost btn-circle"
                             onclick="toggleTheme()"
-                            title="Toggle theme"
+                            title="Toggle theme (light, dark, auto)"
                         {
                             // Sun icon for light mode
                             svg
@@ -216,6 +419,15 @@ pub async fn repository_detail(
                             @if let Some(desc) = &repository.description {
                                 p class="text-gray-600" { (desc) }
                             }
+                            @if !renames.is_empty() {
+                                p class="text-sm text-base-content/60" {
+                                    "Formerly "
+                                    @for (i, rename) in renames.iter().enumerate() {
+                                        @if i > 0 { ", " }
+                                        span class="font-mono" { (rename.old_full_name) }
+                                    }
+                                }
+                            }
                             div class="flex gap-2 mt-4" {
                                 @if repository.is_private {
                                     span class="badge badge-warning" { "Private" }
@@ -224,6 +436,8 @@ pub async fn repository_detail(
                                 }
                                 span class="badge badge-outline" { "Owner: " (repository.owner) }
                             }
+                            (render_language_and_topics(&repository))
+                            (render_watched_paths(&repository))
                             div class="card-actions justify-end mt-4" {
                                 a class="btn btn-primary" href=(repository.url) target="_blank" {
                                     "View on GitHub"
@@ -239,18 +453,101 @@ pub async fn repository_detail(
                         }
                         div class="stat" {
                             div class="stat-title" { "Pull Requests" }
-                            div class="stat-value" { (prs.len()) }
+                            div class="stat-value" { (pr_count) }
                         }
                         div class="stat" {
                             div class="stat-title" { "Issues" }
-                            div class="stat-value" { (issues.len()) }
+                            div class="stat-value" { (issue_count) }
+                        }
+                        div class="stat" {
+                            div class="stat-title" { "Stars" }
+                            div class="stat-value" { (repository.stars) }
                         }
                     }
 
-                    h2 class="text-2xl font-bold mb-4" { "Recent Commits" }
+                    @if !stargazers.is_empty() {
+                        h2 class="text-2xl font-bold mb-4" { "Recent Stargazers" }
+                        div class="flex flex-wrap gap-2 mb-8" {
+                            @for star in &stargazers {
+                                span class="badge badge-outline" title=(star.at.format("%Y-%m-%d %H:%M")) {
+                                    "★ " (star.actor)
+                                }
+                            }
+                        }
+                    }
+
+                    @if !deployments.is_empty() {
+                        h2 id="deployments" class="text-2xl font-bold mb-4" { "Recent Deployments" }
+                        div class="overflow-x-auto mb-8" {
+                            table class="table table-sm" {
+                                thead {
+                                    tr {
+                                        th { "Environment" }
+                                        th { "Ref" }
+                                        th { "Commit" }
+                                        th { "By" }
+                                        th { "Deployed" }
+                                        th { "Status" }
+                                    }
+                                }
+                                tbody {
+                                    @for deployment in &deployments {
+                                        tr {
+                                            td class="font-bold" { (deployment.environment) }
+                                            td { (deployment.git_ref) }
+                                            td class="font-mono text-sm" {
+                                                a class="link link-hover" href=(format!("/repositories/{}/commits/{}", repository.id, deployment.sha)) {
+                                                    (deployment.sha[..7].to_string())
+                                                }
+                                            }
+                                            td { (deployment.creator) }
+                                            td { (deployment.deployed_at.format("%Y-%m-%d %H:%M")) }
+                                            td {
+                                                span class=(format!("badge {}", deployment_state_badge(deployment.state.as_deref()))) {
+                                                    (deployment.state.as_deref().unwrap_or("no status"))
+                                                }
+                                                @if let Some(url) = &deployment.url {
+                                                    " "
+                                                    a class="link link-hover text-sm" href=(url) target="_blank" { "Open" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    h2 id="commits" class="text-2xl font-bold mb-4" { "Recent Commits" }
+                    form method="get" action=(format!("/repositories/{repo_id}#commits")) class="flex flex-wrap items-end gap-2 mb-4" {
+                        label class="form-control" {
+                            span class="label-text" { "Since" }
+                            input type="date" name="since" class="input input-bordered input-sm" value=[window.since.map(|since| since.into_inner().date_naive())];
+                        }
+                        label class="form-control" {
+                            span class="label-text" { "Until" }
+                            input type="date" name="until" class="input input-bordered input-sm" value=[window.until.map(|until| until.into_inner().date_naive())];
+                        }
+                        @if !repository.watched_paths.is_empty() {
+                            label class="label cursor-pointer gap-2" {
+                                input type="checkbox" name="watched_only" value="true" class="checkbox checkbox-sm" checked[window.watched_only];
+                                span class="label-text" { "Watched only" }
+                            }
+                        }
+                        button type="submit" class="btn btn-sm btn-primary" { "Filter" }
+                        @if window.is_filtered() {
+                            a class="btn btn-sm btn-ghost" href=(format!("/repositories/{repo_id}#commits")) { "Clear" }
+                        }
+                    }
                     @if commits.is_empty() {
                         div class="alert alert-info mb-8" {
-                            span { "No commits tracked yet." }
+                            @if window.watched_only {
+                                span { "No commits touching the watched paths in this range." }
+                            } @else if window.is_bounded() {
+                                span { "No commits in this date range." }
+                            } @else {
+                                span { "No commits tracked yet." }
+                            }
                         }
                     } @else {
                         div class="space-y-4 mb-8" {
@@ -259,12 +556,21 @@ pub async fn repository_detail(
                                     div class="card-body" {
                                         div class="flex justify-between items-start" {
                                             div {
-                                                p class="font-mono text-sm text-primary" {
+                                                a class="font-mono text-sm text-primary link link-hover" href=(format!("/repositories/{}/commits/{}", repository.id, commit.sha)) {
                                                     (commit.sha[..7].to_string())
                                                 }
-                                                p class="mt-2" { (commit.message) }
+                                                @if commit.touches_watched == Some(true) {
+                                                    span class="badge badge-accent badge-sm ml-2" { "watched" }
+                                                }
+                                                (render_commit_message(&commit.message))
                                                 p class="text-sm text-gray-500 mt-1" {
                                                     "by " (commit.author_name) " at " (commit.committed_at.format("%Y-%m-%d %H:%M"))
+                                                    @if let Some(pr) = commit.pull_request_id.and_then(|id| commit_prs.get(&id)) {
+                                                        " · "
+                                                        a class="link link-hover" href=(pr.url) target="_blank" {
+                                                            "part of PR #" (pr.number)
+                                                        }
+                                                    }
                                                 }
                                             }
                                             a class="btn btn-sm btn-ghost" href=(commit.url) target="_blank" {
@@ -277,7 +583,7 @@ pub async fn repository_detail(
                         }
                     }
 
-                    h2 class="text-2xl font-bold mb-4" { "Recent Pull Requests" }
+                    h2 id="pull-requests" class="text-2xl font-bold mb-4" { "Recent Pull Requests" }
                     @if prs.is_empty() {
                         div class="alert alert-info mb-8" {
                             span { "No pull requests tracked yet." }
@@ -302,6 +608,7 @@ pub async fn repository_detail(
                                                         span class="badge badge-error" { "Closed" }
                                                     }
                                                 }
+                                                (render_change_history(&pr_changes, pr.id))
                                             }
                                             a class="btn btn-sm btn-ghost" href=(pr.url) target="_blank" {
                                                 "View"
@@ -312,6 +619,50 @@ pub async fn repository_detail(
                             }
                         }
                     }
+
+                    div class="mb-8" {
+                        (render_pager(pr_page, pr_count, DETAIL_SECTION_PER_PAGE, |p| detail_url(repo_id, p, issue_page, &window, "pull-requests")))
+                    }
+
+                    h2 id="issues" class="text-2xl font-bold mb-4" { "Recent Issues" }
+                    @if issues.is_empty() {
+                        div class="alert alert-info mb-8" {
+                            span { "No issues tracked yet." }
+                        }
+                    } @else {
+                        div class="space-y-4 mb-8" {
+                            @for issue in issues {
+                                div class="card bg-base-200 shadow" {
+                                    div class="card-body" {
+                                        div class="flex justify-between items-start" {
+                                            div {
+                                                p class="font-bold" { "#" (issue.number) " " (issue.title) }
+                                                p class="text-sm text-gray-500 mt-1" {
+                                                    "by " (issue.author) " at " (issue.opened_at.format("%Y-%m-%d %H:%M"))
+                                                }
+                                                div class="mt-2 flex gap-1" {
+                                                    @if issue.state == "open" {
+                                                        span class="badge badge-success" { "Open" }
+                                                    } @else {
+                                                        span class="badge badge-error" { "Closed" }
+                                                    }
+                                                    @for label in &issue.labels {
+                                                        span class="badge badge-outline" { (label) }
+                                                    }
+                                                }
+                                                (render_change_history(&issue_changes, issue.id))
+                                            }
+                                            a class="btn btn-sm btn-ghost" href=(issue.url) target="_blank" {
+                                                "View"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    (render_pager(issue_page, issue_count, DETAIL_SECTION_PER_PAGE, |p| detail_url(repo_id, pr_page, p, &window, "issues")))
                 }
             }
         }
@@ -321,3 +672,608 @@ pub async fn repository_detail(
         .content_type("text/html")
         .body(markup.into_string()))
 }
+
+pub async fn commit_detail(
+    pool: web::Data<PgPool>,
+    theme: Theme,
+    path: web::Path<(i64, String)>,
+) -> Result<HttpResponse, AppError> {
+    let (repo_id, sha) = path.into_inner();
+
+    let repository = crate::models::Repository::find_by_id(pool.get_ref(), repo_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Repository not found"))?;
+
+    let commit = crate::models::Commit::find_by_sha(pool.get_ref(), repo_id, &sha)
+        .await?
+        .ok_or_else(|| AppError::not_found("Commit not found"))?;
+
+    let webhook_event =
+        crate::models::WebhookEvent::find_by_id(pool.get_ref(), commit.webhook_event_id).await?;
+
+    let file_changes = webhook_event.as_ref().and_then(|event| {
+        crate::models::github::FileChangeCounts::from_push_payload(&event.payload, &commit.sha)
+    });
+
+    let short_sha = &commit.sha[..7];
+
+    let markup = html! {
+        (DOCTYPE)
+        html lang="en" data-theme=[theme.data_theme()] {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { (short_sha) " - " (repository.full_name) " - Cross Bow" }
+                link rel="stylesheet" href="/assets/daisy.css";
+                link rel="stylesheet" href="/assets/themes.css";
+                script src="/assets/htmx.js" {}
+                script src="/assets/tw.js" {}
+                script src="/assets/theme-switcher.js" {}
+            }
+            body {
+                div class="navbar bg-base-100 shadow-lg" {
+                    div class="flex-1" {
+                        a class="btn btn-ghost text-xl" href="/" { "Cross Bow" }
+                    }
+                    div class="flex-none gap-2" {
+                        ul class="menu menu-horizontal px-1" {
+                            li { a href="/dashboard" { "Dashboard" } }
+                            li { a href="/events" { "Events" } }
+                        }
+                        button
+                            class="btn btn-ghost btn-circle"
+                            onclick="toggleTheme()"
+                            title="Toggle theme (light, dark, auto)"
+                        {
+                            // Sun icon for light mode
+                            svg
+                                xmlns="http://www.w3.org/2000/svg"
+                                class="h-5 w-5"
+                                fill="none"
+                                viewBox="0 0 24 24"
+                                stroke="currentColor"
+                            {
+                                path
+                                    stroke-linecap="round"
+                                    stroke-linejoin="round"
+                                    stroke-width="2"
+                                    d="M12 3v1m0 16v1m9-9h-1M4 12H3m15.364 6.364l-.707-.707M6.343 6.343l-.707-.707m12.728 0l-.707.707M6.343 17.657l-.707.707M16 12a4 4 0 11-8 0 4 4 0 018 0z";
+                            }
+                        }
+                    }
+                }
+
+                div class="container mx-auto px-4 py-8" {
+                    div class="breadcrumbs text-sm mb-4" {
+                        ul {
+                            li { a href=(format!("/repositories/{}", repository.id)) { (repository.full_name) } }
+                            li { (short_sha) }
+                        }
+                    }
+
+                    div class="card bg-base-100 shadow-xl mb-8" {
+                        div class="card-body" {
+                            p class="font-mono text-sm text-primary" { (commit.sha) }
+                            pre class="whitespace-pre-wrap font-sans text-lg mt-2" { (commit.message) }
+                            div class="card-actions justify-end mt-4" {
+                                a class="btn btn-primary" href=(commit.url) target="_blank" {
+                                    "View on GitHub"
+                                }
+                            }
+                        }
+                    }
+
+                    div class="stats shadow mb-8 w-full" {
+                        div class="stat" {
+                            div class="stat-title" { "Added" }
+                            div class="stat-value text-success" {
+                                (file_changes.map_or("-".to_string(), |c| c.added.to_string()))
+                            }
+                        }
+                        div class="stat" {
+                            div class="stat-title" { "Modified" }
+                            div class="stat-value text-warning" {
+                                (file_changes.map_or("-".to_string(), |c| c.modified.to_string()))
+                            }
+                        }
+                        div class="stat" {
+                            div class="stat-title" { "Removed" }
+                            div class="stat-value text-error" {
+                                (file_changes.map_or("-".to_string(), |c| c.removed.to_string()))
+                            }
+                        }
+                    }
+
+                    div class="card bg-base-200 shadow mb-8" {
+                        div class="card-body" {
+                            table class="table" {
+                                tbody {
+                                    tr {
+                                        th { "Author" }
+                                        td { (commit.author_name) " <" (commit.author_email) ">" }
+                                    }
+                                    tr {
+                                        th { "Committer" }
+                                        td { (commit.committer_name) " <" (commit.committer_email) ">" }
+                                    }
+                                    tr {
+                                        th { "Committed" }
+                                        td { (commit.committed_at.format("%Y-%m-%d %H:%M:%S UTC")) }
+                                    }
+                                    tr {
+                                        th { "Event" }
+                                        td {
+                                            @if let Some(event) = &webhook_event {
+                                                span class="badge badge-outline" { (event.event_type) }
+                                                " #" (event.id) " - delivery "
+                                                span class="font-mono" { (event.delivery_id) }
+                                                " received " (event.received_at.format("%Y-%m-%d %H:%M"))
+                                            } @else {
+                                                span class="text-gray-500" { "Not available" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html")
+        .body(markup.into_string()))
+}
+
+/// A commit message split at its first line break into the summary and the rest, with any
+/// blank lines between them dropped
+fn split_commit_message(message: &str) -> (&str, Option<&str>) {
+    match message.split_once('\n') {
+        Some((summary, body)) => {
+            let body = body.trim_matches(|c| c == '\n' || c == '\r');
+            (
+                summary.trim_end(),
+                (!body.trim().is_empty()).then_some(body),
+            )
+        }
+        None => (message, None),
+    }
+}
+
+/// The commit's summary line, with the rest of a multi-line message behind "show more"
+fn render_commit_message(message: &str) -> Markup {
+    let (summary, body) = split_commit_message(message);
+    html! {
+        p class="mt-2" { (summary) }
+        @if let Some(body) = body {
+            details class="mt-1" {
+                summary class="cursor-pointer text-sm text-base-content/60" { "show more" }
+                pre class="whitespace-pre-wrap font-sans text-sm mt-2" { (body) }
+            }
+        }
+    }
+}
+
+/// Badge class for a deployment's latest status state
+fn deployment_state_badge(state: Option<&str>) -> &'static str {
+    match state {
+        Some("success") => "badge-success",
+        Some("failure" | "error") => "badge-error",
+        Some("pending" | "queued" | "in_progress") => "badge-warning",
+        Some(_) => "badge-info",
+        None => "badge-ghost",
+    }
+}
+
+/// Watched path globs and the admin form that edits them
+fn render_watched_paths(repository: &crate::models::Repository) -> Markup {
+    html! {
+        details class="mt-4" {
+            summary class="cursor-pointer text-sm" {
+                "Watched paths"
+                @if repository.watched_paths.is_empty() {
+                    span class="text-base-content/60" { " (none)" }
+                }
+                @for glob in &repository.watched_paths {
+                    span class="badge badge-accent badge-outline ml-1 font-mono" { (glob) }
+                }
+            }
+            form method="post" action=(format!("/admin/repositories/{}/watched-paths", repository.id)) class="mt-2 space-y-2" {
+                textarea name="watched_paths" rows="3" class="textarea textarea-bordered w-full font-mono text-sm" placeholder="services/api/**" {
+                    (repository.watched_paths.join("\n"))
+                }
+                label class="label cursor-pointer justify-start gap-2" {
+                    input type="checkbox" name="skip_unwatched_commits" value="true" class="checkbox checkbox-sm" checked[repository.skip_unwatched_commits];
+                    span class="label-text" { "Skip pushed commits that touch no watched path" }
+                }
+                button type="submit" class="btn btn-sm btn-primary" { "Save" }
+            }
+        }
+    }
+}
+
+/// Language and topic badges; topics link to the filtered repositories list
+fn render_language_and_topics(repo: &crate::models::Repository) -> Markup {
+    html! {
+        @if repo.language.is_some() || !repo.topics.is_empty() {
+            div class="flex flex-wrap gap-1 mt-1" {
+                @if let Some(language) = &repo.language {
+                    span class="badge badge-secondary badge-sm" { (language) }
+                }
+                @for topic in &repo.topics {
+                    a class="badge badge-ghost badge-sm" href=(topic_url(topic)) { (topic) }
+                }
+            }
+        }
+    }
+}
+
+/// GitHub topics are lowercase letters, digits and hyphens, so they need no escaping
+fn topic_url(topic: &str) -> String {
+    format!("/repositories?topic={topic}")
+}
+
+/// Tiny inline SVG line chart of daily event counts
+fn render_sparkline(counts: &[i64]) -> Markup {
+    const WIDTH: f64 = 84.0;
+    const HEIGHT: f64 = 20.0;
+
+    let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let step = WIDTH / (counts.len().max(2) - 1) as f64;
+    let points: Vec<String> = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let x = i as f64 * step;
+            // Leave a pixel of headroom so the stroke isn't clipped
+            let y = HEIGHT - 1.0 - (count as f64 / max) * (HEIGHT - 2.0);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+
+    html! {
+        svg
+            xmlns="http://www.w3.org/2000/svg"
+            width=(WIDTH)
+            height=(HEIGHT)
+            viewBox=(format!("0 0 {WIDTH} {HEIGHT}"))
+            class="text-primary"
+        {
+            polyline
+                points=(points.join(" "))
+                fill="none"
+                stroke="currentColor"
+                stroke-width="1.5";
+        }
+    }
+}
+
+/// Collapsible "edited" history for a pull request or issue
+fn render_change_history(changes: &[crate::models::EntityChange], entity_id: i64) -> Markup {
+    let changes: Vec<_> = changes
+        .iter()
+        .filter(|c| c.entity_id == entity_id)
+        .collect();
+
+    html! {
+        @if !changes.is_empty() {
+            details class="mt-2" {
+                summary class="cursor-pointer text-sm" {
+                    span class="badge badge-info badge-sm" { "Edited" }
+                    " " (changes.len()) " change(s)"
+                }
+                ul class="text-sm text-gray-500 mt-2 space-y-1" {
+                    @for change in changes {
+                        li {
+                            span class="font-medium" { (change.field) } ": "
+                            (change.old_value.as_deref().unwrap_or("-"))
+                            " → "
+                            (change.new_value.as_deref().unwrap_or("-"))
+                            " (" (change.changed_at.format("%Y-%m-%d %H:%M")) ")"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_scales_to_busiest_day() {
+        let markup = render_sparkline(&[0, 5, 10]).into_string();
+
+        assert!(markup.contains(r#"points="0.0,19.0 42.0,10.0 84.0,1.0""#));
+    }
+
+    #[test]
+    fn test_multi_line_commit_message_shows_only_the_summary() {
+        let markup =
+            render_commit_message("Fix crash on start\n\nThe config was read before\nlogging.")
+                .into_string();
+
+        assert!(
+            markup.starts_with(r#"<p class="mt-2">Fix crash on start</p><details class="mt-1">"#)
+        );
+        assert!(markup.contains("The config was read before\nlogging.</pre></details>"));
+
+        let single = render_commit_message("Bump version").into_string();
+        assert_eq!(single, r#"<p class="mt-2">Bump version</p>"#);
+        assert_eq!(split_commit_message("Summary\r\n\r\n"), ("Summary", None));
+    }
+
+    #[test]
+    fn test_commit_window_covers_whole_days() {
+        let window = web::Query::<CommitWindow>::from_query("since=2024-03-04&until=2024-03-10")
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            window.from().unwrap().to_rfc3339(),
+            "2024-03-04T00:00:00+00:00"
+        );
+        assert_eq!(
+            window.to().unwrap().to_rfc3339(),
+            "2024-03-11T00:00:00+00:00"
+        );
+        assert_eq!(
+            detail_url(7, 1, 2, &window, "issues"),
+            "/repositories/7?pr_page=1&issue_page=2&since=2024-03-04&until=2024-03-10#issues"
+        );
+
+        let open = web::Query::<CommitWindow>::from_query("since=&until=")
+            .unwrap()
+            .into_inner();
+        assert!(!open.is_bounded());
+        assert_eq!(open.from(), None);
+        assert_eq!(open.to(), None);
+
+        let malformed = web::Query::<CommitWindow>::from_query("since=last-week")
+            .unwrap()
+            .into_inner();
+        assert!(!malformed.is_bounded());
+
+        let relative =
+            web::Query::<CommitWindow>::from_query("since=-7d&until=2024-03-10T12:00:00Z")
+                .unwrap()
+                .into_inner();
+        assert!(
+            (Utc::now() - Duration::days(7) - relative.from().unwrap())
+                .num_seconds()
+                .abs()
+                < 5
+        );
+        assert_eq!(
+            relative.to().unwrap().to_rfc3339(),
+            "2024-03-10T12:00:00+00:00"
+        );
+
+        let watched = web::Query::<CommitWindow>::from_query("watched_only=true")
+            .unwrap()
+            .into_inner();
+        assert!(!watched.is_bounded());
+        assert!(watched.is_filtered());
+        assert_eq!(
+            detail_url(7, 2, 1, &watched, "pull-requests"),
+            "/repositories/7?pr_page=2&issue_page=1&watched_only=true#pull-requests"
+        );
+    }
+    use crate::models::github::{CreateIssue, CreatePullRequest, CreateRepository};
+    use crate::models::{CreateWebhookEvent, Issue, PullRequest, Repository, WebhookEvent};
+    use actix_web::{test as actix_test, App};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_detail_issue_stat_counts_beyond_display_limit(pool: PgPool) {
+        let repo = Repository::create(
+            &pool,
+            CreateRepository {
+                github_id: 1296269,
+                name: "Hello-World".to_string(),
+                full_name: "octocat/Hello-World".to_string(),
+                owner: "octocat".to_string(),
+                description: None,
+                url: "https://github.com/octocat/Hello-World".to_string(),
+                is_private: false,
+                language: None,
+                topics: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let webhook_event = WebhookEvent::create(
+            &pool,
+            CreateWebhookEvent {
+                repository_id: Some(repo.id),
+                event_type: "issues".to_string(),
+                event_action: Some("opened".to_string()),
+                delivery_id: Uuid::new_v4(),
+                payload: serde_json::json!({}),
+                signature: "sha256=abc".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        for number in 1..=12 {
+            Issue::create(
+                &pool,
+                CreateIssue {
+                    repository_id: repo.id,
+                    webhook_event_id: webhook_event.id,
+                    github_id: 1000 + number,
+                    number: number as i32,
+                    title: format!("Issue {number}"),
+                    state: "open".to_string(),
+                    author: "octocat".to_string(),
+                    labels: vec![],
+                    url: format!("https://github.com/octocat/Hello-World/issues/{number}"),
+                    opened_at: Utc::now(),
+                    closed_at: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            Issue::count_by_repository(&pool, repo.id).await.unwrap(),
+            12
+        );
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/repositories/{id}", web::get().to(repository_detail)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/repositories/{}", repo.id))
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body
+            .contains(r#"<div class="stat-title">Issues</div><div class="stat-value">12</div>"#));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_list_filters_by_topic(pool: PgPool) {
+        for (github_id, name, topics) in [
+            (1, "hooks", vec!["webhooks", "rust"]),
+            (2, "site", vec!["rust"]),
+        ] {
+            Repository::create(
+                &pool,
+                CreateRepository {
+                    github_id,
+                    name: name.to_string(),
+                    full_name: format!("octocat/{name}"),
+                    owner: "octocat".to_string(),
+                    description: None,
+                    url: format!("https://github.com/octocat/{name}"),
+                    is_private: false,
+                    language: Some("Rust".to_string()),
+                    topics: topics.into_iter().map(String::from).collect(),
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            Repository::list_topics(&pool).await.unwrap(),
+            vec!["rust", "webhooks"]
+        );
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/repositories", web::get().to(list_repositories)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri("/repositories?topic=webhooks")
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("octocat/hooks"));
+        assert!(!body.contains("octocat/site"));
+        assert!(body.contains(r#"<span class="badge badge-secondary badge-sm">Rust</span>"#));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_detail_pages_pull_requests_independently(pool: PgPool) {
+        let repo = Repository::create(
+            &pool,
+            CreateRepository {
+                github_id: 1296269,
+                name: "Hello-World".to_string(),
+                full_name: "octocat/Hello-World".to_string(),
+                owner: "octocat".to_string(),
+                description: None,
+                url: "https://github.com/octocat/Hello-World".to_string(),
+                is_private: false,
+                language: None,
+                topics: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let webhook_event = WebhookEvent::create(
+            &pool,
+            CreateWebhookEvent {
+                repository_id: Some(repo.id),
+                event_type: "pull_request".to_string(),
+                event_action: Some("opened".to_string()),
+                delivery_id: Uuid::new_v4(),
+                payload: serde_json::json!({}),
+                signature: "sha256=abc".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        // Newest first: PR #1 was opened most recently, PR #12 longest ago
+        for number in 1..=12 {
+            PullRequest::create(
+                &pool,
+                CreatePullRequest {
+                    repository_id: repo.id,
+                    webhook_event_id: webhook_event.id,
+                    github_id: 2000 + number,
+                    number: number as i32,
+                    title: format!("PR {number}"),
+                    state: "open".to_string(),
+                    author: "octocat".to_string(),
+                    base_branch: "main".to_string(),
+                    head_branch: format!("feature-{number}"),
+                    url: format!("https://github.com/octocat/Hello-World/pull/{number}"),
+                    opened_at: Utc::now() - chrono::Duration::minutes(number),
+                    closed_at: None,
+                    merged_at: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .route("/repositories/{id}", web::get().to(repository_detail)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::get()
+            .uri(&format!("/repositories/{}?pr_page=2&issue_page=3", repo.id))
+            .to_request();
+        let body = actix_test::call_and_read_body(&app, req).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains(">#11 PR 11</p>"));
+        assert!(body.contains(">#12 PR 12</p>"));
+        assert!(!body.contains(">#10 PR 10</p>"));
+        assert!(body.contains(
+            r#"<div class="stat-title">Pull Requests</div><div class="stat-value">12</div>"#
+        ));
+        // Paging pull requests keeps the issue section's page
+        assert!(body.contains(&format!(
+            "/repositories/{}?pr_page=1&amp;issue_page=3#pull-requests",
+            repo.id
+        )));
+    }
+}
@@ -0,0 +1,129 @@
+use actix_files as fs;
+use actix_web::web;
+
+use super::*;
+
+/// Every route the server mounts; shared state and middleware are added by the caller
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    // API routes
+    cfg.route("/webhooks/github", web::post().to(github_webhook))
+        .route("/webhook/{source}", web::post().to(generic_webhook))
+        .route("/webhook/{tenant}/{source}", web::post().to(tenant_webhook))
+        .route("/metrics", web::get().to(metrics))
+        .route("/healthz", web::get().to(healthz))
+        .route("/readyz", web::get().to(readyz))
+        // JSON API routes; `AdminApiKey` requires the admin key on the POST ones
+        .route("/api/repositories", web::get().to(list_repositories_json))
+        .route("/api/repositories/{id}", web::get().to(repository_json))
+        .route("/api/events", web::get().to(list_events_json))
+        .route("/api/events/{id}.msgpack", web::get().to(event_msgpack))
+        .route("/api/sources/health", web::get().to(sources_health_json))
+        .route(
+            "/api/events/export.ndjson",
+            web::get().to(export_events_ndjson),
+        )
+        .route("/api/events/import", web::post().to(import_events_ndjson))
+        .route("/api/events/reprocess", web::post().to(reprocess_events))
+        // Web interface routes
+        .route("/", web::get().to(home))
+        .route("/dashboard", web::get().to(dashboard))
+        .route("/repositories", web::get().to(list_repositories))
+        .route("/repositories/{id}", web::get().to(repository_detail))
+        .route(
+            "/repositories/{id}/commits/{sha}",
+            web::get().to(commit_detail),
+        )
+        .route("/events", web::get().to(list_events))
+        .route("/events/diff", web::get().to(diff_events))
+        .route("/events/grouped", web::get().to(grouped_events))
+        .route("/events/breakdown", web::get().to(action_breakdown))
+        .route("/legacy-events", web::get().to(list_legacy_events))
+        .route("/events/tag", web::post().to(bulk_tag_events))
+        .route("/events/{id}/tags", web::post().to(add_event_tag))
+        .route("/events/{id}/tags/delete", web::post().to(remove_event_tag))
+        .route("/events/{id}/notes", web::post().to(add_event_note))
+        .route("/events/{id}/related", web::get().to(related_events))
+        // Admin routes
+        .route("/admin/sources", web::get().to(list_sources))
+        .route("/admin/sources", web::post().to(save_source))
+        .route("/admin/rejections", web::get().to(list_rejections))
+        .route("/admin/processors", web::get().to(list_processors))
+        .route("/admin/replays", web::get().to(list_replays))
+        .route("/admin/memberships", web::get().to(list_memberships))
+        .route("/admin/replays", web::post().to(create_replay))
+        .route(
+            "/admin/repositories/{id}/watched-paths",
+            web::post().to(save_watched_paths),
+        )
+        // Static file serving
+        .service(fs::Files::new("/assets", "./assets").show_files_listing());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::db::{EventStore, PgEventStore};
+    use crate::services::{Metrics, OrderedDispatcher, RepositoryCache, SchemaRegistry};
+    use actix_web::http::header;
+    use actix_web::{middleware, test as actix_test, App};
+    use sqlx::PgPool;
+    use std::io::Read;
+    use std::sync::Arc;
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_pages_and_webhook_responses_are_compressed(pool: PgPool) {
+        let store: web::Data<dyn EventStore> =
+            web::Data::from(Arc::new(PgEventStore::new(pool.clone())) as Arc<dyn EventStore>);
+        let app = actix_test::init_service(
+            App::new()
+                .wrap(middleware::Compress::default())
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .app_data(store)
+                .configure(configure),
+        )
+        .await;
+        let gunzip = |body: &[u8]| {
+            let mut decoded = String::new();
+            flate2::read::GzDecoder::new(body)
+                .read_to_string(&mut decoded)
+                .unwrap();
+            decoded
+        };
+
+        let req = actix_test::TestRequest::get()
+            .uri("/events")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "gzip"
+        );
+        let body = actix_test::read_body(resp).await;
+        assert!(gunzip(&body).contains("Webhook Events"));
+
+        // Webhook senders that accept gzip still get a readable JSON acknowledgement
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/ci")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .set_json(serde_json::json!({ "type": "build", "status": "passed" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "gzip"
+        );
+        let body = actix_test::read_body(resp).await;
+        let ack: serde_json::Value = serde_json::from_str(&gunzip(&body)).unwrap();
+        assert!(ack.is_object());
+    }
+}
@@ -1,73 +1,250 @@
 use crate::config::Config;
-use crate::models::{CreateEvent, CreateWebhookEvent, Event, WebhookEvent};
-use crate::services::{convert_github_webhook_to_event, process_github_event};
-use crate::utils::verify_github_signature;
+use crate::models::rejected_webhook::body_sha256;
+use crate::models::{
+    Actor, CreateEvent, CreateRejectedWebhook, CreateWebhookEvent, Event, RejectedWebhook,
+    SourceConfig, WebhookEvent,
+};
+use crate::services::{
+    convert_github_webhook_to_event, process_github_event, EntityKey, Metrics, OrderedDispatcher,
+    ProcessingResult, RepositoryCache, SchemaRegistry,
+};
+use crate::utils::{
+    github_signature, json_depth, json_i64, verify_gitea_signature, verify_github,
+    verify_github_signature, verify_token, GithubSignatureError, GithubSignatureScheme,
+    PayloadTransform,
+};
 use actix_web::{web, HttpRequest, HttpResponse, Result};
+use secrecy::{ExposeSecret, SecretString};
 use serde_json::Value as JsonValue;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 /// Generic webhook handler that accepts webhooks from any source
+#[allow(clippy::too_many_arguments)]
 pub async fn generic_webhook(
     req: HttpRequest,
     body: web::Bytes,
     pool: web::Data<PgPool>,
     path: web::Path<String>,
     config: web::Data<Config>,
+    schemas: web::Data<SchemaRegistry>,
+    dispatcher: web::Data<OrderedDispatcher>,
+    repos: web::Data<RepositoryCache>,
+    metrics: web::Data<Metrics>,
 ) -> Result<HttpResponse> {
     let source = path.into_inner();
 
-    log::info!("Received webhook from source: {source}");
+    receive_webhook(
+        req, body, pool, config, schemas, dispatcher, repos, metrics, None, source,
+    )
+    .await
+}
+
+/// Tenant-scoped variant of the generic webhook handler (`/webhook/{tenant}/{source}`)
+#[allow(clippy::too_many_arguments)]
+pub async fn tenant_webhook(
+    req: HttpRequest,
+    body: web::Bytes,
+    pool: web::Data<PgPool>,
+    path: web::Path<(String, String)>,
+    config: web::Data<Config>,
+    schemas: web::Data<SchemaRegistry>,
+    dispatcher: web::Data<OrderedDispatcher>,
+    repos: web::Data<RepositoryCache>,
+    metrics: web::Data<Metrics>,
+) -> Result<HttpResponse> {
+    let (tenant, source) = path.into_inner();
+
+    receive_webhook(
+        req,
+        body,
+        pool,
+        config,
+        schemas,
+        dispatcher,
+        repos,
+        metrics,
+        Some(tenant),
+        source,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn receive_webhook(
+    req: HttpRequest,
+    body: web::Bytes,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    schemas: web::Data<SchemaRegistry>,
+    dispatcher: web::Data<OrderedDispatcher>,
+    repos: web::Data<RepositoryCache>,
+    metrics: web::Data<Metrics>,
+    tenant: Option<String>,
+    raw_source: String,
+) -> Result<HttpResponse> {
+    // Resolve aliases first so verification, processing and filters see one source
+    let source = config.canonical_source(&raw_source).to_string();
+
+    // Reject unknown sources before they reach metrics or the events table
+    if !config.source_allowed(&source) {
+        log::warn!("Rejected webhook for unknown source: {raw_source}");
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Unknown webhook source '{raw_source}'"),
+            "allowed_sources": config.allowed_sources
+        })));
+    }
+    metrics.observe_webhook(&source, body.len());
+
+    if let Some(response) =
+        reject_content_encoding(pool.get_ref(), &req, &source, tenant.as_deref(), &body).await
+    {
+        return Ok(response);
+    }
+
+    match &tenant {
+        Some(tenant) => log::info!("Received webhook from source: {source} (tenant: {tenant})"),
+        None => log::info!("Received webhook from source: {source}"),
+    }
 
     // Generate a delivery ID if not provided
     let delivery_id = extract_delivery_id(&req, &source).unwrap_or_else(Uuid::new_v4);
 
+    // The stored source config drives content type, signature and transform checks
+    let source_config = SourceConfig::find_by_source(pool.get_ref(), &source)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load source config for {source}: {e}");
+            actix_web::error::ErrorInternalServerError("Failed to load source configuration")
+        })?;
+
+    // Before parsing, so a sender misconfigured with another content type hears that
+    // rather than "invalid JSON"
+    if let Some(source_config) = &source_config {
+        if let Some(response) = reject_content_type(
+            pool.get_ref(),
+            &req,
+            &source,
+            tenant.as_deref(),
+            source_config,
+            &body,
+        )
+        .await
+        {
+            return Ok(response);
+        }
+    }
+
     // Parse payload
-    let payload: JsonValue = serde_json::from_slice(&body).map_err(|e| {
-        log::error!("Failed to parse webhook payload from {source}: {e}");
-        actix_web::error::ErrorBadRequest("Invalid JSON payload")
-    })?;
+    let payload: JsonValue = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            log::error!("Failed to parse webhook payload from {source}: {e}");
+            record_rejection(
+                pool.get_ref(),
+                &req,
+                &source,
+                tenant.as_deref(),
+                format!("Invalid JSON payload: {e}"),
+                &body,
+            )
+            .await;
+            return Err(actix_web::error::ErrorBadRequest("Invalid JSON payload"));
+        }
+    };
+
+    if let Err(reason) = check_payload_depth(&payload, config.max_json_depth) {
+        log::warn!("Rejected {source} webhook for delivery {delivery_id}: {reason}");
+        record_rejection(
+            pool.get_ref(),
+            &req,
+            &source,
+            tenant.as_deref(),
+            reason,
+            &body,
+        )
+        .await;
+        return Err(actix_web::error::ErrorBadRequest(
+            "JSON payload is nested too deeply",
+        ));
+    }
 
-    // Extract basic event information
-    let event_type = extract_event_type(&source, &payload, &req);
-    let action = extract_action(&source, &payload);
     let signature = extract_signature(&source, &req);
 
-    // For GitHub, verify signature if present
-    if source == "github" {
-        if let Some(sig) = &signature {
-            if !verify_github_signature(&config.github_webhook_secret, &body, sig) {
-                log::warn!("Invalid GitHub webhook signature for delivery {delivery_id}");
-                return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                    "error": "Invalid signature"
-                })));
-            }
-        } else {
-            log::warn!("Missing GitHub signature for delivery {delivery_id}");
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": "Missing signature"
-            })));
-        }
+    // Verify the signature using the stored source config, falling back to env
+    let (signature_mode, secret) = signature_policy(&source, source_config.as_ref(), &config);
+
+    let verified = verify_source_signature(signature_mode, secret, signature.as_deref(), &body);
+    if let Err(error) = enforce_signature(&config, &source, delivery_id, verified) {
+        log::warn!("Rejected {source} webhook for delivery {delivery_id}: {error}");
+        record_rejection(
+            pool.get_ref(),
+            &req,
+            &source,
+            tenant.as_deref(),
+            error.to_string(),
+            &body,
+        )
+        .await;
+        return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": error
+        })));
+    }
+
+    // Answer provider verification handshakes (e.g. Slack) without storing an event; they are
+    // signed like any delivery, so only a verified handshake is echoed
+    if let Some(challenge) = extract_url_verification_challenge(&payload) {
+        log::info!("Answering url_verification handshake from source: {source}");
+        return Ok(HttpResponse::Ok().json(serde_json::json!({
+            "challenge": challenge
+        })));
+    }
+
+    // Reshape the payload before extraction so downstream code sees one canonical form
+    let (payload, raw_original) = apply_source_transform(source_config.as_ref(), &source, payload);
+
+    // Extract basic event information, storing the type under its canonical name
+    let raw_event_type = extract_event_type(&source, &payload, &req);
+    let event_type = config
+        .canonical_event_type(&source, &raw_event_type)
+        .to_string();
+    let action = extract_action(&source, &payload);
+
+    if let Some(response) = validate_payload(&schemas, &source, &event_type, &payload) {
+        return Ok(response);
     }
 
-    // Extract actor information (source-specific)
-    let (actor_name, actor_email, actor_id) = extract_actor_info(&source, &payload);
+    let payload_sha256 = body_sha256(&body);
+    if let Some(existing) =
+        find_duplicate_payload(pool.get_ref(), &config, &source, &payload_sha256).await
+    {
+        log::info!(
+            "Skipping {source} delivery {delivery_id}: same payload as event #{}",
+            existing.id
+        );
+        return Ok(duplicate_response(&existing));
+    }
 
     // Create generic event
     let create_event = CreateEvent {
         source: source.clone(),
         event_type: event_type.clone(),
         action: action.clone(),
-        actor_name,
-        actor_email,
-        actor_id,
-        raw_event: payload.clone(),
+        actor: Actor::from_payload(&source, &payload),
+        raw_event: config.payload_for_storage(&payload),
         delivery_id,
         signature: signature.clone(),
         repository_id: None, // Will be set by source-specific processors
+        tenant,
+        raw_source: Some(raw_source),
+        raw_event_type: Some(raw_event_type),
+        raw_original,
+        payload_sha256: Some(payload_sha256),
+        // From the whole payload, so SEARCH_FIELDS outside the pruned fields still count
+        searchable_text: config.searchable_text(&payload),
     };
 
-    let event = Event::create(pool.get_ref(), create_event)
+    let event = store_event(pool.get_ref(), &config, create_event)
         .await
         .map_err(|e| {
             log::error!("Failed to store generic event from {source}: {e}");
@@ -81,44 +258,195 @@ pub async fn generic_webhook(
         event_type,
         delivery_id
     );
+    if let Some(latency) = event.delivery_latency() {
+        metrics.observe_delivery_latency(&source, latency);
+    }
 
-    // Process event asynchronously based on source
-    let pool_clone = pool.get_ref().clone();
-    let event_clone = event.clone();
-    let source_clone = source.clone();
-
-    tokio::spawn(async move {
-        if let Err(e) = process_event_by_source(&pool_clone, &event_clone, &source_clone).await {
-            log::error!(
-                "Failed to process {} event {}: {}",
-                source_clone,
-                event_clone.id,
-                e
-            );
-        } else {
-            log::info!(
-                "Successfully processed {} event {}",
-                source_clone,
-                event_clone.id
-            );
-        }
-    });
+    let processing = if config.processes_synchronously(&source) {
+        // Inline processing skips the dispatcher, so it is not ordered against queued events
+        let result = process_and_record(
+            pool.get_ref(),
+            repos.get_ref(),
+            &event,
+            &source,
+            config.strict_processing,
+        )
+        .await;
+        Some(match result {
+            Ok(result) => serde_json::json!({
+                "status": "processed",
+                "result": result,
+                "summary": result.to_string()
+            }),
+            Err(e) => serde_json::json!({ "status": "failed", "error": e }),
+        })
+    } else {
+        // Process event asynchronously based on source, after earlier events for the same entity
+        let entity_key = EntityKey::from_payload(&source, &event_type, &payload);
+        let pool_clone = pool.get_ref().clone();
+        let event_clone = event.clone();
+        let source_clone = source.clone();
+        let repos = repos.get_ref().clone();
+        let strict = config.strict_processing;
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({
+        dispatcher.dispatch(entity_key.as_ref(), async move {
+            let _ =
+                process_and_record(&pool_clone, &repos, &event_clone, &source_clone, strict).await;
+        });
+        None
+    };
+
+    if let Some(template) = config.ack_templates.get(&source) {
+        return Ok(HttpResponse::Ok().json(render_ack_template(
+            template,
+            &source,
+            event.id,
+            &event_type,
+        )));
+    }
+
+    let mut response = serde_json::json!({
         "status": "received",
         "source": source,
         "event_id": event.id,
         "event_type": event_type
+    });
+    if let Some(processing) = processing {
+        response["processing"] = processing;
+    }
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Refuse payloads nested deeper than `max_depth`, which the extractors, pretty-printed
+/// payload views and JSON diffs would all have to recurse through
+fn check_payload_depth(payload: &JsonValue, max_depth: usize) -> Result<(), String> {
+    let depth = json_depth(payload);
+    if depth > max_depth {
+        return Err(format!(
+            "JSON payload nested {depth} levels deep exceeds MAX_JSON_DEPTH ({max_depth})"
+        ));
+    }
+    Ok(())
+}
+
+/// A 415 telling the sender which content type the source expects, when the delivery
+/// carries another one; the rejection is recorded for the audit page
+async fn reject_content_type(
+    pool: &PgPool,
+    req: &HttpRequest,
+    source: &str,
+    tenant: Option<&str>,
+    source_config: &SourceConfig,
+    body: &[u8],
+) -> Option<HttpResponse> {
+    let content_type = req
+        .headers()
+        .get(actix_web::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    if source_config.accepts_content_type(content_type) {
+        return None;
+    }
+
+    let expected = source_config.expected_content_type.as_deref().unwrap_or("");
+    let received = content_type.unwrap_or("none");
+    let reason = format!("Content-Type {received} does not match the expected {expected}");
+    log::warn!("Rejected {source} webhook: {reason}");
+    record_rejection(pool, req, source, tenant, reason, body).await;
+
+    Some(HttpResponse::UnsupportedMediaType().json(serde_json::json!({
+        "error": format!("Unsupported content type '{received}' for source '{source}'"),
+        "expected_content_type": expected,
+        "hint": format!("Configure the sender to deliver payloads with Content-Type: {expected}")
     })))
 }
 
+/// `Content-Encoding` values the `Bytes` extractor decodes
+const SUPPORTED_CONTENT_ENCODINGS: &[&str] = &["gzip", "deflate", "br", "zstd", "identity"];
+
+/// A 415 for a body in an encoding the server cannot decode, recorded for the audit page.
+///
+/// The `Bytes` extractor decompresses `gzip`, `deflate`, `br` and `zstd` bodies per
+/// `Content-Encoding` before a handler runs, so signatures are verified over, and payloads
+/// parsed from, the decompressed JSON. Any other encoding would arrive still encoded and
+/// fail as invalid JSON.
+async fn reject_content_encoding(
+    pool: &PgPool,
+    req: &HttpRequest,
+    source: &str,
+    tenant: Option<&str>,
+    body: &[u8],
+) -> Option<HttpResponse> {
+    let encoding = req
+        .headers()
+        .get(actix_web::http::header::CONTENT_ENCODING)?
+        .to_str()
+        .unwrap_or_default();
+    if encoding
+        .parse::<actix_web::http::header::ContentEncoding>()
+        .is_ok()
+    {
+        return None;
+    }
+
+    let reason = format!("Unsupported Content-Encoding '{encoding}'");
+    log::warn!("Rejected {source} webhook: {reason}");
+    record_rejection(pool, req, source, tenant, reason.clone(), body).await;
+
+    Some(
+        HttpResponse::UnsupportedMediaType().json(serde_json::json!({
+            "error": reason,
+            "supported_encodings": SUPPORTED_CONTENT_ENCODINGS
+        })),
+    )
+}
+
+/// Apply the source's configured transform, returning the payload to store and, when
+/// it was reshaped, the original
+fn apply_source_transform(
+    source_config: Option<&SourceConfig>,
+    source: &str,
+    payload: JsonValue,
+) -> (JsonValue, Option<JsonValue>) {
+    let Some(spec) = source_config.and_then(|c| c.transform.as_deref()) else {
+        return (payload, None);
+    };
+    let Some(transform) = PayloadTransform::parse(spec) else {
+        log::warn!("Ignoring unsupported transform '{spec}' for source {source}");
+        return (payload, None);
+    };
+
+    match transform.apply(&payload) {
+        Some(transformed) => (transformed, Some(payload)),
+        None => {
+            log::debug!(
+                "Transform '{spec}' does not match this {source} payload, storing it as is"
+            );
+            (payload, None)
+        }
+    }
+}
+
 /// Backward compatibility: GitHub-specific webhook endpoint
+#[allow(clippy::too_many_arguments)]
 pub async fn github_webhook(
     req: HttpRequest,
     body: web::Bytes,
     pool: web::Data<PgPool>,
     config: web::Data<Config>,
+    schemas: web::Data<SchemaRegistry>,
+    dispatcher: web::Data<OrderedDispatcher>,
+    repos: web::Data<RepositoryCache>,
+    metrics: web::Data<Metrics>,
 ) -> Result<HttpResponse> {
+    metrics.observe_webhook("github", body.len());
+
+    if let Some(response) =
+        reject_content_encoding(pool.get_ref(), &req, "github", None, &body).await
+    {
+        return Ok(response);
+    }
+
     // Extract headers
     let event_type = req
         .headers()
@@ -127,6 +455,15 @@ pub async fn github_webhook(
         .ok_or_else(|| actix_web::error::ErrorBadRequest("Missing X-GitHub-Event header"))?
         .to_string();
 
+    // Drop event types outside GITHUB_ALLOWED_EVENTS before any database work
+    if !config.github_event_allowed(&event_type) {
+        log::debug!("Ignoring GitHub {event_type} event outside GITHUB_ALLOWED_EVENTS");
+        return Ok(HttpResponse::Accepted().json(serde_json::json!({
+            "status": "ignored",
+            "event_type": event_type
+        })));
+    }
+
     let delivery_id = req
         .headers()
         .get("X-GitHub-Delivery")
@@ -134,34 +471,106 @@ pub async fn github_webhook(
         .and_then(|s| Uuid::parse_str(s).ok())
         .ok_or_else(|| actix_web::error::ErrorBadRequest("Invalid X-GitHub-Delivery header"))?;
 
-    let signature = req
-        .headers()
-        .get("X-Hub-Signature-256")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| actix_web::error::ErrorBadRequest("Missing X-Hub-Signature-256 header"))?;
+    // Verify signature, falling back to the SHA-1 header older GitHub Enterprise versions send
+    let secret = github_secret(pool.get_ref(), &config).await;
+    let signature = match verify_github(&secret, &body, req.headers()) {
+        Ok(signature) => {
+            if signature.scheme == GithubSignatureScheme::Sha1 {
+                log::debug!("GitHub delivery {delivery_id} verified with X-Hub-Signature (SHA-1)");
+            }
+            Some(signature.value)
+        }
+        Err(error) if !config.verify_signatures => {
+            warn_unverified("github", delivery_id, &error);
+            github_signature(req.headers()).map(|signature| signature.value)
+        }
+        Err(GithubSignatureError::Missing) => {
+            record_rejection(
+                pool.get_ref(),
+                &req,
+                "github",
+                None,
+                GithubSignatureError::Missing.to_string(),
+                &body,
+            )
+            .await;
+            return Err(actix_web::error::ErrorBadRequest(
+                GithubSignatureError::Missing.to_string(),
+            ));
+        }
+        Err(GithubSignatureError::Invalid) => {
+            log::warn!("Invalid webhook signature for delivery {delivery_id}");
+            // Compare with the sender's delivery log: a different digest means the body was altered
+            log::debug!(
+                "Rejected GitHub delivery {delivery_id}: {} body bytes, sha256 {}",
+                body.len(),
+                body_sha256(&body)
+            );
+            record_rejection(
+                pool.get_ref(),
+                &req,
+                "github",
+                None,
+                "Invalid signature".to_string(),
+                &body,
+            )
+            .await;
+            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Invalid signature"
+            })));
+        }
+    };
 
-    // Verify signature
-    if !verify_github_signature(&config.github_webhook_secret, &body, signature) {
-        log::warn!("Invalid webhook signature for delivery {delivery_id}");
-        return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-            "error": "Invalid signature"
-        })));
+    // Parse payload
+    let payload: JsonValue = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            log::error!("Failed to parse webhook payload: {e}");
+            record_rejection(
+                pool.get_ref(),
+                &req,
+                "github",
+                None,
+                format!("Invalid JSON payload: {e}"),
+                &body,
+            )
+            .await;
+            return Err(actix_web::error::ErrorBadRequest("Invalid JSON payload"));
+        }
+    };
+
+    if let Err(reason) = check_payload_depth(&payload, config.max_json_depth) {
+        log::warn!("Rejected GitHub delivery {delivery_id}: {reason}");
+        record_rejection(pool.get_ref(), &req, "github", None, reason, &body).await;
+        return Err(actix_web::error::ErrorBadRequest(
+            "JSON payload is nested too deeply",
+        ));
     }
 
-    // Parse payload
-    let payload: JsonValue = serde_json::from_slice(&body).map_err(|e| {
-        log::error!("Failed to parse webhook payload: {e}");
-        actix_web::error::ErrorBadRequest("Invalid JSON payload")
-    })?;
+    if let Some(response) = validate_payload(&schemas, "github", &event_type, &payload) {
+        return Ok(response);
+    }
+
+    let payload_sha256 = body_sha256(&body);
+    if let Some(existing) =
+        find_duplicate_payload(pool.get_ref(), &config, "github", &payload_sha256).await
+    {
+        log::info!(
+            "Skipping GitHub delivery {delivery_id}: same payload as event #{}",
+            existing.id
+        );
+        return Ok(duplicate_response(&existing));
+    }
 
     let event_action = payload["action"].as_str().map(|s| s.to_string());
 
     // Extract repository ID if present
     let repository_id = if let Some(repo) = payload["repository"].as_object() {
-        if let Some(_id) = repo["id"].as_i64() {
-            // Try to find or create repository
-            match crate::models::Repository::find_by_full_name(
+        if let Some(github_id) = json_i64(&repo["id"]) {
+            // Match by GitHub id first so renamed repositories are still found
+            match crate::models::Repository::find_by_github_id_or_full_name(
                 pool.get_ref(),
+                github_id,
                 repo["full_name"].as_str().unwrap_or(""),
             )
             .await
@@ -180,14 +589,17 @@ pub async fn github_webhook(
         None
     };
 
+    // The legacy copy is pruned like the event, so STORE_FULL_PAYLOAD=false saves space in both
+    let stored_payload = config.payload_for_storage(&payload);
+
     // Store legacy webhook event for backward compatibility
     let webhook_event = CreateWebhookEvent {
         repository_id,
         event_type: event_type.clone(),
         event_action: event_action.clone(),
         delivery_id,
-        payload: payload.clone(),
-        signature: signature.to_string(),
+        payload: stored_payload.clone(),
+        signature: signature.unwrap_or_default().to_string(),
     };
 
     let _legacy_event = WebhookEvent::create(pool.get_ref(), webhook_event)
@@ -198,16 +610,18 @@ pub async fn github_webhook(
         })?;
 
     // Convert to generic event
-    let create_event = convert_github_webhook_to_event(
+    let mut create_event = convert_github_webhook_to_event(
         event_type.clone(),
         event_action,
-        payload,
+        stored_payload,
         delivery_id,
-        Some(signature.to_string()),
+        signature.map(str::to_string),
         repository_id,
     );
+    create_event.payload_sha256 = Some(payload_sha256);
+    create_event.searchable_text = config.searchable_text(&payload);
 
-    let event = Event::create(pool.get_ref(), create_event)
+    let event = store_event(pool.get_ref(), &config, create_event)
         .await
         .map_err(|e| {
             log::error!("Failed to store generic event: {e}");
@@ -215,15 +629,25 @@ pub async fn github_webhook(
         })?;
 
     log::info!("Received GitHub webhook event: {event_type} (delivery: {delivery_id})");
+    if let Some(latency) = event.delivery_latency() {
+        metrics.observe_delivery_latency("github", latency);
+    }
 
-    // Process event asynchronously
+    // Process event asynchronously, after earlier events for the same entity
+    let entity_key = EntityKey::from_payload("github", &event_type, &payload);
     let pool_clone = pool.get_ref().clone();
     let event_clone = event.clone();
-    tokio::spawn(async move {
-        if let Err(e) = process_github_event(&pool_clone, &event_clone).await {
-            log::error!("Failed to process GitHub event {}: {}", event_clone.id, e);
-        } else {
-            log::info!("Successfully processed GitHub event {}", event_clone.id);
+    let repos = repos.get_ref().clone();
+    let strict = config.strict_processing;
+    dispatcher.dispatch(entity_key.as_ref(), async move {
+        match process_github_event(&pool_clone, &repos, &event_clone, strict).await {
+            Ok(result) => {
+                log::info!("Processed GitHub event {}: {result}", event_clone.id);
+            }
+            Err(e) => {
+                log::error!("Failed to process GitHub event {}: {}", event_clone.id, e);
+                record_processing_failure(&pool_clone, event_clone.id, &e.to_string()).await;
+            }
         }
     });
 
@@ -233,9 +657,175 @@ pub async fn github_webhook(
     })))
 }
 
+/// An event from `source` with the same body received within the dedup window, when
+/// `DEDUP_BY_PAYLOAD` is on. A failed lookup stores the delivery rather than dropping it.
+async fn find_duplicate_payload(
+    pool: &PgPool,
+    config: &Config,
+    source: &str,
+    payload_sha256: &str,
+) -> Option<Event> {
+    if !config.dedup_by_payload {
+        return None;
+    }
+
+    let since = chrono::Utc::now() - config.dedup_window();
+    match Event::find_recent_by_payload_hash(pool, source, payload_sha256, since).await {
+        Ok(existing) => existing,
+        Err(e) => {
+            log::error!("Failed to check {source} payload for duplicates: {e}");
+            None
+        }
+    }
+}
+
+fn duplicate_response(existing: &Event) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "status": "duplicate",
+        "event_id": existing.id
+    }))
+}
+
+/// Resolve the GitHub secret from the stored source config, falling back to env
+async fn github_secret(pool: &PgPool, config: &Config) -> String {
+    match SourceConfig::find_by_source(pool, "github").await {
+        Ok(Some(source_config)) if source_config.has_secret() => {
+            source_config.secret.unwrap_or_default()
+        }
+        Ok(_) => config.github_webhook_secret.expose_secret().clone(),
+        Err(e) => {
+            log::error!("Failed to load GitHub source config, using env secret: {e}");
+            config.github_webhook_secret.expose_secret().clone()
+        }
+    }
+}
+
+/// Signature mode and secret for a source: its stored config, else the env defaults
+fn signature_policy<'a>(
+    source: &str,
+    source_config: Option<&'a SourceConfig>,
+    config: &'a Config,
+) -> (&'a str, Option<&'a str>) {
+    match source_config {
+        Some(c) => (c.signature_mode.as_str(), c.secret.as_deref()),
+        None if source == "github" => (
+            "hmac_sha256",
+            Some(config.github_webhook_secret.expose_secret().as_str()),
+        ),
+        None if is_gitea(source) => ("hmac_sha256_hex", exposed(&config.gitea_webhook_secret)),
+        // Auth0 log streams are only verified once a secret is configured
+        None if source == "auth0" && config.auth0_webhook_secret.is_some() => {
+            ("hmac_sha256_hex", exposed(&config.auth0_webhook_secret))
+        }
+        None => ("none", None),
+    }
+}
+
+/// An optional env secret as a plain `&str` for the duration of a verification
+fn exposed(secret: &Option<SecretString>) -> Option<&str> {
+    secret.as_ref().map(|s| s.expose_secret().as_str())
+}
+
+/// Let a failed signature check through with a warning when `VERIFY_SIGNATURES` is off
+fn enforce_signature(
+    config: &Config,
+    source: &str,
+    delivery_id: Uuid,
+    verified: Result<(), &'static str>,
+) -> Result<(), &'static str> {
+    match verified {
+        Err(error) if !config.verify_signatures => {
+            warn_unverified(source, delivery_id, &error);
+            Ok(())
+        }
+        verified => verified,
+    }
+}
+
+fn warn_unverified(source: &str, delivery_id: Uuid, error: &dyn std::fmt::Display) {
+    log::warn!(
+        "Accepting {source} delivery {delivery_id} despite failed signature check ({error}): VERIFY_SIGNATURES is off"
+    );
+}
+
+/// Verify a webhook signature according to the source's signature mode
+fn verify_source_signature(
+    mode: &str,
+    secret: Option<&str>,
+    signature: Option<&str>,
+    body: &[u8],
+) -> Result<(), &'static str> {
+    if mode == "none" {
+        return Ok(());
+    }
+
+    let secret = secret
+        .filter(|s| !s.is_empty())
+        .ok_or("Source secret not configured")?;
+    let signature = signature.ok_or("Missing signature")?;
+
+    let valid = match mode {
+        "hmac_sha256" => verify_github_signature(secret, body, signature),
+        "hmac_sha256_hex" => verify_gitea_signature(secret, body, signature),
+        "token" => verify_token(secret, signature),
+        _ => return Err("Unsupported signature mode"),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err("Invalid signature")
+    }
+}
+
+/// Validate a payload against its configured schema, building a 422 response on failure
+fn validate_payload(
+    schemas: &SchemaRegistry,
+    source: &str,
+    event_type: &str,
+    payload: &JsonValue,
+) -> Option<HttpResponse> {
+    let errors = schemas.validate(source, event_type, payload).err()?;
+
+    log::warn!("Rejected {source} {event_type} payload failing schema validation: {errors:?}");
+
+    Some(HttpResponse::UnprocessableEntity().json(serde_json::json!({
+        "error": "Payload failed schema validation",
+        "details": errors
+    })))
+}
+
+/// Apply the configured payload storage mode
+async fn store_event(
+    pool: &PgPool,
+    config: &Config,
+    data: CreateEvent,
+) -> Result<Event, sqlx::Error> {
+    if config.compress_payloads {
+        Event::create_compressed(pool, data).await
+    } else {
+        Event::create(pool, data).await
+    }
+}
+
+/// Gitea and its ancestor Gogs send GitHub-shaped payloads under their own headers
+fn is_gitea(source: &str) -> bool {
+    matches!(source, "gitea" | "gogs")
+}
+
+/// First present header among Gitea's and Gogs' spellings of the same header
+fn gitea_header<'a>(req: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    ["Gitea", "Gogs"].iter().find_map(|vendor| {
+        req.headers()
+            .get(format!("X-{vendor}-{name}"))
+            .and_then(|h| h.to_str().ok())
+    })
+}
+
 /// Extract delivery ID from headers based on source
 fn extract_delivery_id(req: &HttpRequest, source: &str) -> Option<Uuid> {
     match source {
+        "gitea" | "gogs" => gitea_header(req, "Delivery").and_then(|s| Uuid::parse_str(s).ok()),
         "github" => req
             .headers()
             .get("X-GitHub-Delivery")
@@ -253,48 +843,98 @@ fn extract_delivery_id(req: &HttpRequest, source: &str) -> Option<Uuid> {
 /// Extract event type from payload or headers based on source
 fn extract_event_type(source: &str, payload: &JsonValue, req: &HttpRequest) -> String {
     match source {
+        "gitea" | "gogs" => gitea_header(req, "Event")
+            .filter(|s| !s.is_empty())
+            .unwrap_or("unknown")
+            .to_string(),
         "github" => req
             .headers()
             .get("X-GitHub-Event")
             .and_then(|h| h.to_str().ok())
+            .filter(|s| !s.is_empty())
             .map(|s| s.to_string())
             .unwrap_or_else(|| "unknown".to_string()),
         "gitlab" => req
             .headers()
             .get("X-Gitlab-Event")
             .and_then(|h| h.to_str().ok())
+            .filter(|s| !s.is_empty())
             .map(|s| s.to_string())
             .unwrap_or_else(|| {
-                payload["object_kind"]
-                    .as_str()
+                non_empty_str(&payload["object_kind"])
                     .unwrap_or("unknown")
                     .to_string()
             }),
-        "auth0" => payload["type"]
-            .as_str()
-            .or_else(|| payload["event"].as_str())
+        "auth0" => non_empty_str(&payload["type"])
+            .or_else(|| non_empty_str(&payload["event"]))
             .unwrap_or("unknown")
             .to_string(),
-        _ => payload["type"]
-            .as_str()
-            .or_else(|| payload["event"].as_str())
-            .or_else(|| payload["event_type"].as_str())
+        _ => non_empty_str(&payload["type"])
+            .or_else(|| non_empty_str(&payload["event"]))
+            .or_else(|| non_empty_str(&payload["event_type"]))
             .unwrap_or("webhook")
             .to_string(),
     }
 }
 
+/// A payload string field, treating `""` like a missing field
+fn non_empty_str(value: &JsonValue) -> Option<&str> {
+    value.as_str().filter(|s| !s.is_empty())
+}
+
+/// Extract the challenge from a URL verification handshake payload
+fn extract_url_verification_challenge(payload: &JsonValue) -> Option<&str> {
+    if payload["type"].as_str() != Some("url_verification") {
+        return None;
+    }
+
+    payload["challenge"].as_str()
+}
+
+/// Fill the `{source}`, `{event_id}` and `{event_type}` placeholders in an ack template
+fn render_ack_template(
+    template: &JsonValue,
+    source: &str,
+    event_id: i64,
+    event_type: &str,
+) -> JsonValue {
+    match template {
+        JsonValue::String(s) => JsonValue::String(
+            s.replace("{source}", source)
+                .replace("{event_id}", &event_id.to_string())
+                .replace("{event_type}", event_type),
+        ),
+        JsonValue::Array(items) => JsonValue::Array(
+            items
+                .iter()
+                .map(|item| render_ack_template(item, source, event_id, event_type))
+                .collect(),
+        ),
+        JsonValue::Object(map) => JsonValue::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    (
+                        k.clone(),
+                        render_ack_template(v, source, event_id, event_type),
+                    )
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
 /// Extract action from payload
 fn extract_action(_source: &str, payload: &JsonValue) -> Option<String> {
-    payload["action"]
-        .as_str()
-        .or_else(|| payload["event_action"].as_str())
+    non_empty_str(&payload["action"])
+        .or_else(|| non_empty_str(&payload["event_action"]))
         .map(|s| s.to_string())
 }
 
 /// Extract signature from headers based on source
 fn extract_signature(source: &str, req: &HttpRequest) -> Option<String> {
     match source {
+        "gitea" | "gogs" => gitea_header(req, "Signature").map(|s| s.to_string()),
         "github" => req
             .headers()
             .get("X-Hub-Signature-256")
@@ -305,96 +945,121 @@ fn extract_signature(source: &str, req: &HttpRequest) -> Option<String> {
             .get("X-Gitlab-Token")
             .and_then(|h| h.to_str().ok())
             .map(|s| s.to_string()),
-        _ => None,
+        "auth0" => req
+            .headers()
+            .get("X-Auth0-Signature")
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string()),
+        _ => req
+            .headers()
+            .get("X-Hub-Signature-256")
+            .or_else(|| req.headers().get("X-Webhook-Token"))
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string()),
     }
 }
 
-/// Extract actor information based on source
-fn extract_actor_info(
+/// Headers kept with a rejected webhook; signature and token headers are never stored
+const REJECTION_HEADERS: &[&str] = &[
+    "content-type",
+    "content-encoding",
+    "content-length",
+    "user-agent",
+    "x-github-event",
+    "x-github-delivery",
+    "x-gitlab-event",
+    "x-gitea-event",
+    "x-gitea-delivery",
+    "x-gogs-event",
+    "x-gogs-delivery",
+    "x-request-id",
+];
+
+/// Persist a rejected webhook for the admin audit page; failures are only logged
+async fn record_rejection(
+    pool: &PgPool,
+    req: &HttpRequest,
     source: &str,
-    payload: &JsonValue,
-) -> (Option<String>, Option<String>, Option<String>) {
-    match source {
-        "github" => {
-            let name = payload["sender"]["login"]
-                .as_str()
-                .or_else(|| payload["pusher"]["name"].as_str())
-                .map(|s| s.to_string());
-
-            let email = payload["sender"]["email"]
-                .as_str()
-                .or_else(|| payload["pusher"]["email"].as_str())
-                .map(|s| s.to_string());
-
-            let id = payload["sender"]["login"]
-                .as_str()
-                .map(|s| s.to_string())
-                .or_else(|| payload["sender"]["id"].as_i64().map(|i| i.to_string()));
-
-            (name, email, id)
-        }
-        "gitlab" => {
-            let name = payload["user_username"]
-                .as_str()
-                .or_else(|| payload["user"]["username"].as_str())
-                .map(|s| s.to_string());
-
-            let email = payload["user_email"]
-                .as_str()
-                .or_else(|| payload["user"]["email"].as_str())
-                .map(|s| s.to_string());
-
-            let id = payload["user_id"]
-                .as_i64()
-                .map(|i| i.to_string())
-                .or_else(|| payload["user"]["id"].as_i64().map(|i| i.to_string()));
-
-            (name, email, id)
-        }
-        "auth0" => {
-            let name = payload["user"]["name"]
-                .as_str()
-                .or_else(|| payload["user"]["username"].as_str())
-                .map(|s| s.to_string());
+    tenant: Option<&str>,
+    reason: String,
+    body: &[u8],
+) {
+    let headers: serde_json::Map<String, JsonValue> = REJECTION_HEADERS
+        .iter()
+        .filter_map(|name| {
+            let value = req.headers().get(*name)?.to_str().ok()?;
+            Some((name.to_string(), JsonValue::String(value.to_string())))
+        })
+        .collect();
 
-            let email = payload["user"]["email"].as_str().map(|s| s.to_string());
+    let rejection = CreateRejectedWebhook {
+        source: source.to_string(),
+        tenant: tenant.map(|t| t.to_string()),
+        reason,
+        client_ip: req
+            .connection_info()
+            .realip_remote_addr()
+            .map(|ip| ip.to_string()),
+        headers: JsonValue::Object(headers),
+        body: body.to_vec(),
+    };
 
-            let id = payload["user"]["user_id"]
-                .as_str()
-                .or_else(|| payload["user"]["id"].as_str())
-                .map(|s| s.to_string());
+    if let Err(e) = RejectedWebhook::create(pool, rejection).await {
+        log::error!("Failed to record rejected {source} webhook: {e}");
+    }
+}
 
-            (name, email, id)
-        }
-        _ => {
-            // Generic extraction
-            let name = payload["actor"]
-                .as_str()
-                .or_else(|| payload["user"].as_str())
-                .or_else(|| payload["username"].as_str())
-                .map(|s| s.to_string());
+/// Store the processing error on the event so it shows up as failed
+pub(crate) async fn record_processing_failure(pool: &PgPool, event_id: i64, error: &str) {
+    if let Err(e) = Event::mark_failed(pool, event_id, error).await {
+        log::error!("Failed to record processing error for event {event_id}: {e}");
+    }
+}
 
-            let email = payload["email"].as_str().map(|s| s.to_string());
+/// Sources the webhook endpoints know how to authenticate and parse
+pub(crate) const KNOWN_SOURCES: &[&str] = &["github", "gitea", "gogs", "gitlab", "auth0"];
 
-            let id = payload["actor_id"]
-                .as_str()
-                .or_else(|| payload["user_id"].as_str())
-                .map(|s| s.to_string());
+/// Whether `process_event_by_source` applies the source's events to the domain tables
+/// rather than just marking them processed
+pub(crate) fn has_processor(source: &str) -> bool {
+    matches!(source, "github" | "gitea" | "gogs")
+}
 
-            (name, email, id)
+/// Process a stored event, recording the error on the event when processing fails
+async fn process_and_record(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    source: &str,
+    strict: bool,
+) -> Result<ProcessingResult, String> {
+    let result = process_event_by_source(pool, repos, event, source, strict)
+        .await
+        .map_err(|e| e.to_string());
+
+    match &result {
+        Ok(processed) => log::info!("Processed {} event {}: {processed}", source, event.id),
+        Err(e) => {
+            log::error!("Failed to process {} event {}: {}", source, event.id, e);
+            record_processing_failure(pool, event.id, e).await;
         }
     }
+
+    result
 }
 
 /// Route event to source-specific processor
-async fn process_event_by_source(
+pub(crate) async fn process_event_by_source(
     pool: &PgPool,
+    repos: &RepositoryCache,
     event: &Event,
     source: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    strict: bool,
+) -> Result<ProcessingResult, Box<dyn std::error::Error>> {
     match source {
-        "github" => {
-            process_github_event(pool, event).await?;
+        // Push, pull request and issue payloads match GitHub's shape
+        "github" | "gitea" | "gogs" => {
+            return Ok(process_github_event(pool, repos, event, strict).await?);
         }
         "gitlab" => {
             log::info!(
@@ -420,5 +1085,1405 @@ async fn process_event_by_source(
         }
     }
 
-    Ok(())
+    Ok(ProcessingResult::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test as actix_test, App};
+    use sqlx::postgres::PgPoolOptions;
+
+    fn lazy_pool() -> PgPool {
+        PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/cross_bow_test")
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_webhook_payload_size_is_observed() {
+        // Nothing listens on port 1, so the delivery fails once it reaches the database
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(200))
+            .connect_lazy("postgres://127.0.0.1:1/cross_bow_test")
+            .unwrap();
+        let metrics = web::Data::new(Metrics::new());
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(metrics.clone())
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        // The size is observed before any database access
+        let body = r#"{"type":"url_verification","challenge":"abc"}"#;
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/slack")
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(body)
+            .to_request();
+        actix_test::call_service(&app, req).await;
+
+        let histogram = metrics.webhook_payload_bytes.with_label_values(&["slack"]);
+        assert_eq!(histogram.get_sample_count(), 1);
+        assert_eq!(histogram.get_sample_sum(), body.len() as f64);
+        assert!(metrics
+            .render()
+            .contains(r#"webhook_payload_bytes_bucket{source="slack",le="256"} 1"#));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_slack_url_verification_handshake(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+        let handshake = || {
+            actix_test::TestRequest::post()
+                .uri("/webhook/slack")
+                .set_json(serde_json::json!({
+                    "token": "Jhj5dZrVaK7ZwHHjRyZWjbDl",
+                    "challenge": "3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P",
+                    "type": "url_verification"
+                }))
+        };
+
+        let resp = actix_test::call_service(&app, handshake().to_request()).await;
+        assert!(resp.status().is_success());
+
+        let body: JsonValue = actix_test::read_body_json(resp).await;
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "challenge": "3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P"
+            })
+        );
+
+        // Once the source requires a signature, an unsigned handshake is not echoed
+        crate::models::SourceConfig::upsert(
+            &pool,
+            crate::models::UpsertSourceConfig {
+                source: "slack".to_string(),
+                signature_mode: "token".to_string(),
+                secret: Some("slack-token".to_string()),
+                transform: None,
+                expected_content_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let resp = actix_test::call_service(&app, handshake().to_request()).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let signed = handshake()
+            .insert_header(("X-Webhook-Token", "slack-token"))
+            .to_request();
+        let body: JsonValue = actix_test::call_and_read_body_json(&app, signed).await;
+        assert_eq!(
+            body["challenge"],
+            "3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P"
+        );
+        assert_eq!(Event::count(&pool).await.unwrap(), 0);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_generic_webhook_uses_stored_source_config(pool: PgPool) {
+        crate::models::SourceConfig::upsert(
+            &pool,
+            crate::models::UpsertSourceConfig {
+                source: "custom".to_string(),
+                signature_mode: "token".to_string(),
+                secret: Some("stored-token".to_string()),
+                transform: None,
+                expected_content_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let rejected = actix_test::TestRequest::post()
+            .uri("/webhook/custom")
+            .insert_header(("X-Webhook-Token", "wrong-token"))
+            .set_json(serde_json::json!({ "type": "ping" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, rejected).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let accepted = actix_test::TestRequest::post()
+            .uri("/webhook/custom")
+            .insert_header(("X-Webhook-Token", "stored-token"))
+            .set_json(serde_json::json!({ "type": "ping" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, accepted).await;
+        assert!(resp.status().is_success());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_generic_webhook_enforces_expected_content_type(pool: PgPool) {
+        crate::models::SourceConfig::upsert(
+            &pool,
+            crate::models::UpsertSourceConfig {
+                source: "billing".to_string(),
+                signature_mode: "none".to_string(),
+                secret: None,
+                transform: None,
+                expected_content_type: Some("application/json".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+        let post = |content_type: &str, body: &'static str| {
+            actix_test::TestRequest::post()
+                .uri("/webhook/billing")
+                .insert_header(("Content-Type", content_type))
+                .set_payload(body)
+                .to_request()
+        };
+
+        // A form body is reported as the wrong content type, not as invalid JSON
+        let resp = actix_test::call_service(
+            &app,
+            post("application/x-www-form-urlencoded", "type=invoice.paid"),
+        )
+        .await;
+        assert_eq!(
+            resp.status(),
+            actix_web::http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        let body: JsonValue = actix_test::read_body_json(resp).await;
+        assert_eq!(body["expected_content_type"], "application/json");
+
+        // Valid JSON under the wrong header is refused too
+        let resp =
+            actix_test::call_service(&app, post("text/plain", r#"{"type":"invoice.paid"}"#)).await;
+        assert_eq!(
+            resp.status(),
+            actix_web::http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+
+        let resp = actix_test::call_service(
+            &app,
+            post(
+                "application/json; charset=utf-8",
+                r#"{"type":"invoice.paid"}"#,
+            ),
+        )
+        .await;
+        assert!(resp.status().is_success());
+
+        let events = Event::list_by_source(&pool, "billing", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_gzip_encoded_body_is_decompressed_before_parsing(pool: PgPool) {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(br#"{"type":"invoice.paid","amount":42}"#)
+            .unwrap();
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/billing")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Content-Encoding", "gzip"))
+            .set_payload(encoder.finish().unwrap())
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let events = Event::list_by_source(&pool, "billing", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].raw_event["amount"], 42);
+
+        // An encoding the server cannot decode is refused rather than parsed as JSON
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/billing")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Content-Encoding", "compress"))
+            .set_payload(vec![0x1f, 0x9d, 0x90])
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(
+            resp.status(),
+            actix_web::http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        let body: JsonValue = actix_test::read_body_json(resp).await;
+        assert_eq!(body["supported_encodings"][0], "gzip");
+
+        let rejections = RejectedWebhook::list_recent(&pool, 10).await.unwrap();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].headers["content-encoding"], "compress");
+        assert_eq!(Event::count(&pool).await.unwrap(), 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_generic_webhook_applies_source_transform(pool: PgPool) {
+        crate::models::SourceConfig::upsert(
+            &pool,
+            crate::models::UpsertSourceConfig {
+                source: "billing".to_string(),
+                signature_mode: "none".to_string(),
+                secret: None,
+                transform: Some("unwrap:data".to_string()),
+                expected_content_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let envelope = serde_json::json!({
+            "id": "evt_1",
+            "data": { "type": "invoice.paid", "amount": 42 }
+        });
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/billing")
+            .set_json(&envelope)
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let events = Event::list_all(&pool, 1, 0).await.unwrap();
+        assert_eq!(events[0].event_type, "invoice.paid");
+        assert_eq!(
+            events[0].raw_event,
+            serde_json::json!({ "id": "evt_1", "type": "invoice.paid", "amount": 42 })
+        );
+        assert_eq!(events[0].raw_original, Some(envelope));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_generic_webhook_rejects_schema_violations(pool: PgPool) {
+        let mut schemas = SchemaRegistry::default();
+        schemas.insert(
+            "billing",
+            None,
+            jsonschema::validator_for(&serde_json::json!({ "required": ["customer_id"] })).unwrap(),
+        );
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(schemas))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/billing")
+            .set_json(serde_json::json!({ "type": "invoice.paid" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(
+            resp.status(),
+            actix_web::http::StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        let body: JsonValue = actix_test::read_body_json(resp).await;
+        assert!(body["details"][0].as_str().unwrap().contains("customer_id"));
+        assert_eq!(Event::count(&pool).await.unwrap(), 0);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_generic_webhook_route_shapes_store_tenant(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook))
+                .route("/webhook/{tenant}/{source}", web::post().to(tenant_webhook)),
+        )
+        .await;
+
+        for uri in ["/webhook/custom", "/webhook/acme/custom"] {
+            let req = actix_test::TestRequest::post()
+                .uri(uri)
+                .set_json(serde_json::json!({ "type": "ping" }))
+                .to_request();
+            let resp = actix_test::call_service(&app, req).await;
+            assert!(resp.status().is_success(), "{uri} was rejected");
+        }
+
+        let events = Event::list_all(&pool, 10, 0).await.unwrap();
+        let mut tenants: Vec<_> = events.iter().map(|e| e.tenant.clone()).collect();
+        tenants.sort();
+        assert_eq!(tenants, vec![None, Some("acme".to_string())]);
+        assert!(events.iter().all(|e| e.source == "custom"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_signature_failure_is_recorded(pool: PgPool) {
+        crate::models::SourceConfig::upsert(
+            &pool,
+            crate::models::UpsertSourceConfig {
+                source: "custom".to_string(),
+                signature_mode: "token".to_string(),
+                secret: Some("stored-token".to_string()),
+                transform: None,
+                expected_content_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/custom")
+            .insert_header(("X-Webhook-Token", "wrong-token"))
+            .insert_header(("User-Agent", "custom-sender/1.0"))
+            .set_json(serde_json::json!({ "type": "ping" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let rejections = RejectedWebhook::list_recent(&pool, 10).await.unwrap();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].source, "custom");
+        assert_eq!(rejections[0].reason, "Invalid signature");
+        assert_eq!(rejections[0].body, r#"{"type":"ping"}"#);
+        assert_eq!(rejections[0].headers["user-agent"], "custom-sender/1.0");
+        // The presented token must never be persisted
+        assert!(rejections[0].headers.get("x-webhook-token").is_none());
+        assert_eq!(Event::count(&pool).await.unwrap(), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_github_event_outside_allowlist_is_ignored() {
+        let mut config = Config::test_default();
+        config.github_allowed_events = vec!["push".to_string(), "pull_request".to_string()];
+
+        // The lazy pool never connects, so an ignored event must not touch the database
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(lazy_pool()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhooks/github", web::post().to(github_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhooks/github")
+            .insert_header(("X-GitHub-Event", "watch"))
+            .insert_header(("X-GitHub-Delivery", Uuid::new_v4().to_string()))
+            .set_payload(r#"{"action":"started"}"#)
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::ACCEPTED);
+
+        let body: JsonValue = actix_test::read_body_json(resp).await;
+        assert_eq!(
+            body,
+            serde_json::json!({ "status": "ignored", "event_type": "watch" })
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_unknown_source_is_rejected() {
+        let mut config = Config::test_default();
+        config.allowed_sources = vec!["ci".to_string(), "stripe".to_string()];
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(lazy_pool()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/strpie")
+            .set_json(serde_json::json!({ "type": "invoice.paid" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+
+        let body: JsonValue = actix_test::read_body_json(resp).await;
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": "Unknown webhook source 'strpie'",
+                "allowed_sources": ["ci", "stripe"]
+            })
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_allowed_source_is_stored(pool: PgPool) {
+        let mut config = Config::test_default();
+        config.allowed_sources = vec!["ci".to_string()];
+        config.source_aliases = [("ci-legacy".to_string(), "ci".to_string())].into();
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        // Aliases are checked by the canonical source they resolve to
+        for path in ["/webhook/ci", "/webhook/ci-legacy"] {
+            let req = actix_test::TestRequest::post()
+                .uri(path)
+                .set_json(serde_json::json!({ "type": "build" }))
+                .to_request();
+            let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+            assert_eq!(body["status"], "received");
+        }
+
+        assert_eq!(Event::get_sources(&pool).await.unwrap(), vec!["ci"]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_github_enterprise_sha1_delivery_is_stored(pool: PgPool) {
+        use hmac::{Hmac, Mac};
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhooks/github", web::post().to(github_webhook)),
+        )
+        .await;
+
+        let body = r#"{"zen":"Keep it logically awesome."}"#;
+        let mut mac = Hmac::<sha1::Sha1>::new_from_slice(b"test_secret").unwrap();
+        mac.update(body.as_bytes());
+        let signature = format!("sha1={}", hex::encode(mac.finalize().into_bytes()));
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhooks/github")
+            .insert_header(("x-github-event", "ping"))
+            .insert_header(("x-github-delivery", Uuid::new_v4().to_string()))
+            .insert_header(("x-hub-signature", signature.as_str()))
+            .set_payload(body)
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let events = Event::list_all(&pool, 10, 0).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].signature.as_deref(), Some(signature.as_str()));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_github_event_in_allowlist_is_stored(pool: PgPool) {
+        use hmac::{Hmac, Mac};
+
+        let mut config = Config::test_default();
+        config.github_allowed_events = vec!["push".to_string()];
+
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhooks/github", web::post().to(github_webhook)),
+        )
+        .await;
+
+        let body = r#"{"ref":"refs/heads/main"}"#;
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"test_secret").unwrap();
+        mac.update(body.as_bytes());
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhooks/github")
+            .insert_header(("X-GitHub-Event", "push"))
+            .insert_header(("X-GitHub-Delivery", Uuid::new_v4().to_string()))
+            .insert_header(("X-Hub-Signature-256", signature))
+            .set_payload(body)
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert_eq!(Event::count(&pool).await.unwrap(), 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_github_signature_failure_records_body_digest(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhooks/github", web::post().to(github_webhook)),
+        )
+        .await;
+
+        let body = r#"{"zen":"Keep it logically awesome."}"#;
+        let req = actix_test::TestRequest::post()
+            .uri("/webhooks/github")
+            .insert_header(("X-GitHub-Event", "ping"))
+            .insert_header(("X-GitHub-Delivery", Uuid::new_v4().to_string()))
+            .insert_header(("X-Hub-Signature-256", "sha256=00"))
+            .set_payload(body)
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let rejections = RejectedWebhook::list_recent(&pool, 10).await.unwrap();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].body_bytes, Some(body.len() as i64));
+        assert_eq!(
+            rejections[0].body_sha256.as_deref(),
+            Some(body_sha256(body.as_bytes()).as_str())
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_bad_json_is_recorded(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{tenant}/{source}", web::post().to(tenant_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/acme/custom")
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload("{not json")
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+        let rejections = RejectedWebhook::list_recent(&pool, 10).await.unwrap();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].tenant.as_deref(), Some("acme"));
+        assert!(rejections[0].reason.starts_with("Invalid JSON payload"));
+        assert_eq!(rejections[0].body, "{not json");
+        assert!(!rejections[0].body_truncated);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_aliased_source_is_stored_canonically(pool: PgPool) {
+        let mut config = Config::test_default();
+        config.source_aliases = [("ci-legacy".to_string(), "ci".to_string())].into();
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        for path in ["/webhook/ci-legacy", "/webhook/ci"] {
+            let req = actix_test::TestRequest::post()
+                .uri(path)
+                .set_json(serde_json::json!({ "type": "build" }))
+                .to_request();
+            let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+            assert_eq!(body["source"], "ci");
+        }
+
+        let events = Event::list_all(&pool, 10, 0).await.unwrap();
+        let mut raw_sources: Vec<_> = events
+            .iter()
+            .map(|e| (e.source.as_str(), e.raw_source.as_deref()))
+            .collect();
+        raw_sources.sort();
+        assert_eq!(
+            raw_sources,
+            vec![("ci", Some("ci")), ("ci", Some("ci-legacy"))]
+        );
+        assert_eq!(Event::get_sources(&pool).await.unwrap(), vec!["ci"]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_gitlab_merge_request_is_stored_as_pull_request(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        // Once named by the header, once only by object_kind
+        let with_header = actix_test::TestRequest::post()
+            .uri("/webhook/gitlab")
+            .insert_header(("X-Gitlab-Event", "Merge Request Hook"))
+            .set_json(serde_json::json!({ "object_kind": "merge_request", "iid": 1 }))
+            .to_request();
+        let without_header = actix_test::TestRequest::post()
+            .uri("/webhook/gitlab")
+            .set_json(serde_json::json!({ "object_kind": "merge_request", "iid": 2 }))
+            .to_request();
+        for req in [with_header, without_header] {
+            let resp = actix_test::call_service(&app, req).await;
+            assert!(resp.status().is_success());
+        }
+
+        let events = Event::list_all(&pool, 10, 0).await.unwrap();
+        assert!(events.iter().all(|e| e.event_type == "pull_request"));
+        let mut raw_types: Vec<_> = events.iter().map(|e| e.raw_event_type.as_deref()).collect();
+        raw_types.sort();
+        assert_eq!(
+            raw_types,
+            vec![Some("Merge Request Hook"), Some("merge_request")]
+        );
+        assert_eq!(
+            Event::get_event_types(&pool).await.unwrap(),
+            vec!["pull_request"]
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_identical_payload_under_new_delivery_id_is_skipped(pool: PgPool) {
+        let mut config = Config::test_default();
+        config.dedup_by_payload = true;
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let post = |payload: JsonValue| {
+            actix_test::TestRequest::post()
+                .uri("/webhook/ci")
+                .set_json(payload)
+                .to_request()
+        };
+
+        // Without a delivery header every request gets a fresh delivery id
+        let build = serde_json::json!({ "type": "build", "id": 41 });
+        let first: JsonValue = actix_test::call_and_read_body_json(&app, post(build.clone())).await;
+        assert_eq!(first["status"], "received");
+
+        let retry: JsonValue = actix_test::call_and_read_body_json(&app, post(build)).await;
+        assert_eq!(retry["status"], "duplicate");
+        assert_eq!(retry["event_id"], first["event_id"]);
+
+        let other = serde_json::json!({ "type": "build", "id": 42 });
+        let other: JsonValue = actix_test::call_and_read_body_json(&app, post(other)).await;
+        assert_eq!(other["status"], "received");
+
+        let events = Event::list_all(&pool, 10, 0).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.payload_sha256.is_some()));
+        assert_ne!(events[0].payload_sha256, events[1].payload_sha256);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_identical_payloads_are_stored_without_dedup(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        for _ in 0..2 {
+            let req = actix_test::TestRequest::post()
+                .uri("/webhook/ci")
+                .set_json(serde_json::json!({ "type": "build", "id": 41 }))
+                .to_request();
+            let body: JsonValue = actix_test::call_and_read_body_json(&app, req).await;
+            assert_eq!(body["status"], "received");
+        }
+
+        let events = Event::list_all(&pool, 10, 0).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].payload_sha256, events[1].payload_sha256);
+    }
+
+    #[test]
+    fn test_unenforced_signature_failure_is_accepted_and_logged() {
+        testing_logger::setup();
+        let delivery_id = Uuid::new_v4();
+        let mut config = Config::test_default();
+        assert_eq!(
+            enforce_signature(&config, "ci", delivery_id, Err("Invalid signature")),
+            Err("Invalid signature")
+        );
+
+        config.verify_signatures = false;
+        assert_eq!(
+            enforce_signature(&config, "ci", delivery_id, Err("Invalid signature")),
+            Ok(())
+        );
+        testing_logger::validate(|logs| {
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].level, log::Level::Warn);
+            assert!(logs[0].body.contains(&delivery_id.to_string()));
+            assert!(logs[0].body.contains("VERIFY_SIGNATURES is off"));
+        });
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_invalid_signatures_are_stored_when_verification_is_off(pool: PgPool) {
+        crate::models::SourceConfig::upsert(
+            &pool,
+            crate::models::UpsertSourceConfig {
+                source: "custom".to_string(),
+                signature_mode: "token".to_string(),
+                secret: Some("stored-token".to_string()),
+                transform: None,
+                expected_content_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut config = Config::test_default();
+        config.verify_signatures = false;
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook))
+                .route("/webhooks/github", web::post().to(github_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/custom")
+            .insert_header(("X-Webhook-Token", "wrong-token"))
+            .set_json(serde_json::json!({ "type": "build" }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhooks/github")
+            .insert_header(("x-github-event", "ping"))
+            .insert_header(("x-github-delivery", Uuid::new_v4().to_string()))
+            .insert_header(("x-hub-signature-256", "sha256=00"))
+            .set_payload(r#"{"zen":"Captured in production."}"#)
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let mut signatures: Vec<_> = Event::list_all(&pool, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.signature)
+            .collect();
+        signatures.sort();
+        assert_eq!(
+            signatures,
+            vec![
+                Some("sha256=00".to_string()),
+                Some("wrong-token".to_string())
+            ]
+        );
+        assert!(RejectedWebhook::list_recent(&pool, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_verify_source_signature_modes() {
+        assert!(verify_source_signature("none", None, None, b"{}").is_ok());
+        assert_eq!(
+            verify_source_signature("token", None, Some("abc"), b"{}"),
+            Err("Source secret not configured")
+        );
+        assert_eq!(
+            verify_source_signature("token", Some("abc"), None, b"{}"),
+            Err("Missing signature")
+        );
+        assert!(verify_source_signature("token", Some("abc"), Some("abc"), b"{}").is_ok());
+        assert_eq!(
+            verify_source_signature("hmac_sha256", Some("abc"), Some("sha256=00"), b"{}"),
+            Err("Invalid signature")
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_generic_webhook_stores_actor_avatar(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/custom")
+            .set_json(serde_json::json!({
+                "type": "deploy",
+                "actor": "deploy-bot",
+                "avatar_url": "https://avatars.example/deploy-bot"
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let events = Event::list_all(&pool, 1, 0).await.unwrap();
+        assert_eq!(
+            events[0].actor_avatar_url.as_deref(),
+            Some("https://avatars.example/deploy-bot")
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_search_fields_outside_the_pruned_payload_are_indexed(pool: PgPool) {
+        let config = Config {
+            store_full_payload: false,
+            search_fields: vec!["deployment_note".to_string()],
+            ..Config::test_default()
+        };
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/custom")
+            .set_json(serde_json::json!({
+                "type": "deploy",
+                "deployment_note": "rolled back canary"
+            }))
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let event = &Event::list_all(&pool, 1, 0).await.unwrap()[0];
+        assert!(event.raw_event.get("deployment_note").is_none());
+        let searchable_text: Option<String> =
+            sqlx::query_scalar("SELECT searchable_text FROM events WHERE id = $1")
+                .bind(event.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(searchable_text.as_deref(), Some("rolled back canary"));
+    }
+
+    #[test]
+    fn test_url_verification_requires_type() {
+        let payload = serde_json::json!({ "challenge": "abc", "type": "event_callback" });
+        assert_eq!(extract_url_verification_challenge(&payload), None);
+    }
+
+    #[test]
+    fn test_render_ack_template() {
+        let template = serde_json::json!({
+            "ok": true,
+            "ref": "{source}-{event_id}",
+            "tags": ["{event_type}"]
+        });
+
+        let rendered = render_ack_template(&template, "stripe", 42, "charge.succeeded");
+
+        assert_eq!(
+            rendered,
+            serde_json::json!({
+                "ok": true,
+                "ref": "stripe-42",
+                "tags": ["charge.succeeded"]
+            })
+        );
+    }
+
+    fn gitea_push_payload() -> JsonValue {
+        serde_json::json!({
+            "ref": "refs/heads/main",
+            "before": "0000000000000000000000000000000000000000",
+            "after": "bffeb74224043ba2feb48d137756c8a9331c449a",
+            "commits": [{
+                "id": "bffeb74224043ba2feb48d137756c8a9331c449a",
+                "message": "Add README\n",
+                "url": "https://gitea.example/gitea/webhooks/commit/bffeb74224043ba2feb48d137756c8a9331c449a",
+                "author": { "name": "Gitea", "email": "someone@gitea.io", "username": "gitea" },
+                "committer": { "name": "Gitea", "email": "someone@gitea.io", "username": "gitea" },
+                "timestamp": "2017-03-13T13:52:11+08:00"
+            }],
+            "repository": {
+                "id": 140,
+                "name": "webhooks",
+                "full_name": "gitea/webhooks",
+                "owner": { "id": 1, "login": "gitea", "username": "gitea" },
+                "html_url": "https://gitea.example/gitea/webhooks",
+                "description": "",
+                "private": false
+            },
+            "pusher": { "id": 1, "login": "gitea", "username": "gitea", "email": "someone@gitea.io" },
+            "sender": {
+                "id": 1,
+                "login": "gitea",
+                "username": "gitea",
+                "email": "someone@gitea.io",
+                "avatar_url": "https://gitea.example/avatars/1"
+            }
+        })
+    }
+
+    fn gitea_signature(body: &[u8]) -> String {
+        use hmac::{Hmac, Mac};
+
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"gitea_secret").unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn test_gitea_and_gogs_headers() {
+        let delivery = Uuid::new_v4();
+        let payload = gitea_push_payload();
+
+        let gitea = actix_test::TestRequest::post()
+            .insert_header(("X-Gitea-Event", "push"))
+            .insert_header(("X-Gitea-Delivery", delivery.to_string()))
+            .insert_header(("X-Gitea-Signature", "abc123"))
+            .to_http_request();
+        assert_eq!(extract_event_type("gitea", &payload, &gitea), "push");
+        assert_eq!(extract_delivery_id(&gitea, "gitea"), Some(delivery));
+        assert_eq!(
+            extract_signature("gitea", &gitea).as_deref(),
+            Some("abc123")
+        );
+
+        let gogs = actix_test::TestRequest::post()
+            .insert_header(("X-Gogs-Event", "issues"))
+            .to_http_request();
+        assert_eq!(extract_event_type("gogs", &payload, &gogs), "issues");
+
+        let actor = Actor::from_payload("gitea", &payload);
+        assert_eq!(actor.name.as_deref(), Some("gitea"));
+        assert_eq!(actor.email.as_deref(), Some("someone@gitea.io"));
+        assert_eq!(actor.id.as_deref(), Some("1"));
+        assert_eq!(
+            actor.avatar_url.as_deref(),
+            Some("https://gitea.example/avatars/1")
+        );
+    }
+
+    #[test]
+    fn test_verify_gitea_signature_mode() {
+        let body = br#"{"ref":"refs/heads/main"}"#;
+        let signature = gitea_signature(body);
+
+        assert!(verify_source_signature(
+            "hmac_sha256_hex",
+            Some("gitea_secret"),
+            Some(&signature),
+            body
+        )
+        .is_ok());
+        assert_eq!(
+            verify_source_signature("hmac_sha256_hex", None, Some(&signature), body),
+            Err("Source secret not configured")
+        );
+    }
+
+    #[test]
+    fn test_auth0_signature_policy() {
+        use hmac::{Hmac, Mac};
+
+        let body = br#"{"type":"s","user_id":"auth0|123"}"#;
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"auth0_secret").unwrap();
+        mac.update(body);
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let verify = |config: &Config, signature: Option<&str>| {
+            let (mode, secret) = signature_policy("auth0", None, config);
+            verify_source_signature(mode, secret, signature, body)
+        };
+
+        // Unconfigured: accepted without a signature, as before
+        let unconfigured = Config::test_default();
+        assert_eq!(verify(&unconfigured, None), Ok(()));
+
+        let mut configured = Config::test_default();
+        configured.auth0_webhook_secret = Some(SecretString::new("auth0_secret".to_string()));
+        assert_eq!(verify(&configured, Some(&signature)), Ok(()));
+        assert_eq!(verify(&configured, Some("00")), Err("Invalid signature"));
+        assert_eq!(verify(&configured, None), Err("Missing signature"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_gitea_push_flows_into_commits(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let body = serde_json::to_vec(&gitea_push_payload()).unwrap();
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/gitea")
+            .insert_header(("X-Gitea-Event", "push"))
+            .insert_header(("X-Gitea-Delivery", Uuid::new_v4().to_string()))
+            .insert_header(("X-Gitea-Signature", gitea_signature(&body)))
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(body)
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        // Processing runs on the dispatcher; wait for the commit to land
+        let mut commit = None;
+        for _ in 0..50 {
+            if let Some(repo) =
+                crate::models::Repository::find_by_full_name(&pool, "gitea/webhooks")
+                    .await
+                    .unwrap()
+            {
+                commit = crate::models::Commit::find_by_sha(
+                    &pool,
+                    repo.id,
+                    "bffeb74224043ba2feb48d137756c8a9331c449a",
+                )
+                .await
+                .unwrap();
+            }
+            if commit.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let commit = commit.expect("Gitea push was not processed into a commit");
+        assert_eq!(commit.author_name, "Gitea");
+        assert_eq!(commit.message, "Add README\n");
+
+        let events = Event::list_all(&pool, 1, 0).await.unwrap();
+        assert_eq!(events[0].source, "gitea");
+        assert!(events[0].processed);
+    }
+
+    fn nested_payload(depth: usize) -> String {
+        format!("{}1{}", r#"{"a":"#.repeat(depth), "}".repeat(depth))
+    }
+
+    #[test]
+    fn test_check_payload_depth() {
+        let shallow: JsonValue = serde_json::from_str(&nested_payload(64)).unwrap();
+        assert_eq!(check_payload_depth(&shallow, 64), Ok(()));
+
+        let deep: JsonValue = serde_json::from_str(&nested_payload(65)).unwrap();
+        assert_eq!(
+            check_payload_depth(&deep, 64),
+            Err("JSON payload nested 65 levels deep exceeds MAX_JSON_DEPTH (64)".to_string())
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_deeply_nested_payloads_are_rejected(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        // Past MAX_JSON_DEPTH, and far past serde_json's own recursion limit
+        for depth in [100, 100_000] {
+            let req = actix_test::TestRequest::post()
+                .uri("/webhook/custom")
+                .insert_header(("Content-Type", "application/json"))
+                .set_payload(nested_payload(depth))
+                .to_request();
+            let resp = actix_test::call_service(&app, req).await;
+            assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+        }
+
+        assert_eq!(Event::count(&pool).await.unwrap(), 0);
+        let rejections = RejectedWebhook::list_recent(&pool, 10).await.unwrap();
+        assert_eq!(rejections.len(), 2);
+        assert!(
+            rejections
+                .iter()
+                .any(|r| r.reason
+                    == "JSON payload nested 100 levels deep exceeds MAX_JSON_DEPTH (64)")
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_sync_processing_returns_the_outcome(pool: PgPool) {
+        let mut config = Config::test_default();
+        config.sync_processing_sources = vec!["gitea".to_string()];
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+        let push = |payload: JsonValue| {
+            let body = serde_json::to_vec(&payload).unwrap();
+            actix_test::TestRequest::post()
+                .uri("/webhook/gitea")
+                .insert_header(("X-Gitea-Event", "push"))
+                .insert_header(("X-Gitea-Delivery", Uuid::new_v4().to_string()))
+                .insert_header(("X-Gitea-Signature", gitea_signature(&body)))
+                .insert_header(("Content-Type", "application/json"))
+                .set_payload(body)
+                .to_request()
+        };
+
+        let resp: JsonValue =
+            actix_test::call_and_read_body_json(&app, push(gitea_push_payload())).await;
+        assert_eq!(resp["processing"]["status"], "processed");
+        // No waiting on the dispatcher: the commit is there when the response is
+        let repo = crate::models::Repository::find_by_full_name(&pool, "gitea/webhooks")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(crate::models::Commit::find_by_sha(
+            &pool,
+            repo.id,
+            "bffeb74224043ba2feb48d137756c8a9331c449a"
+        )
+        .await
+        .unwrap()
+        .is_some());
+
+        let mut broken = gitea_push_payload();
+        broken["commits"][0]["id"] = serde_json::json!("not-a-sha");
+        let resp: JsonValue = actix_test::call_and_read_body_json(&app, push(broken)).await;
+        assert_eq!(resp["processing"]["status"], "failed");
+        assert_eq!(
+            resp["processing"]["error"],
+            "Invalid payload: Invalid commit sha"
+        );
+        let event = Event::find_by_id(&pool, resp["event_id"].as_i64().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            event.processing_error.as_deref(),
+            Some("Invalid payload: Invalid commit sha")
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_gitea_rejects_bad_signature(pool: PgPool) {
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::test_default()))
+                .app_data(web::Data::new(OrderedDispatcher::new(1)))
+                .app_data(web::Data::new(RepositoryCache::default()))
+                .app_data(web::Data::new(Metrics::new()))
+                .app_data(web::Data::new(SchemaRegistry::default()))
+                .route("/webhook/{source}", web::post().to(generic_webhook)),
+        )
+        .await;
+
+        let req = actix_test::TestRequest::post()
+            .uri("/webhook/gogs")
+            .insert_header(("X-Gogs-Event", "push"))
+            .insert_header(("X-Gogs-Signature", "00"))
+            .set_json(gitea_push_payload())
+            .to_request();
+        let resp = actix_test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+    }
+
+    mod fuzz {
+        use super::*;
+        use crate::utils::fuzz::arb_payload;
+        use proptest::prelude::*;
+
+        const SOURCES: &[&str] = &["github", "gitea", "gogs", "gitlab", "auth0", "ci"];
+
+        proptest! {
+            /// Extraction never panics and never yields empty event types, actions or actor fields
+            #[test]
+            fn test_header_less_extraction_never_panics(
+                payload in arb_payload(),
+                source in prop::sample::select(SOURCES),
+            ) {
+                let req = actix_test::TestRequest::post().to_http_request();
+
+                prop_assert!(!extract_event_type(source, &payload, &req).is_empty());
+                prop_assert!(extract_action(source, &payload).is_none_or(|a| !a.is_empty()));
+
+                let actor = Actor::from_payload(source, &payload);
+                for field in [&actor.name, &actor.email, &actor.id, &actor.avatar_url] {
+                    prop_assert!(field.as_deref().is_none_or(|v| !v.is_empty()));
+                }
+            }
+        }
+    }
 }
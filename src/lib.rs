@@ -0,0 +1,9 @@
+//! Cross Bow webhook receiver: models, handlers and services shared by the
+//! server binary and the benchmarks in `benches/`.
+
+pub mod config;
+pub mod db;
+pub mod handlers;
+pub mod models;
+pub mod services;
+pub mod utils;
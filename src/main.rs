@@ -1,13 +1,9 @@
-mod config;
-mod db;
-mod handlers;
-mod models;
-mod services;
-mod utils;
-
-use actix_files as fs;
+use std::sync::Arc;
+
 use actix_web::{middleware, web, App, HttpServer};
-use config::Config;
+use cross_bow::config::Config;
+use cross_bow::{db, handlers, services};
+use secrecy::ExposeSecret;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -20,44 +16,231 @@ async fn main() -> std::io::Result<()> {
 
     log::info!("Starting Cross Bow server...");
     log::info!("Configuration loaded successfully");
+    if !config.verify_signatures {
+        log::warn!(
+            "VERIFY_SIGNATURES=false: webhook signatures are NOT enforced and anyone can post events. Never run production like this."
+        );
+    }
 
     // Create database pool
-    let pool = db::create_pool(&config.database_url, config.max_connections)
+    let pool = db::create_pool(config.database_url.expose_secret(), config.max_connections)
         .await
         .expect("Failed to create database pool");
 
     log::info!("Database connection established");
     log::info!("Running database migrations...");
 
+    let args: Vec<String> = std::env::args().collect();
+
+    match args.get(1).map(|s| s.as_str()) {
+        // `cross_bow backfill` copies legacy webhook_events into events and exits
+        Some("backfill") => {
+            log::info!("Backfilling legacy webhook events...");
+            let stats = services::backfill_legacy_events(&pool, 500)
+                .await
+                .map_err(std::io::Error::other)?;
+            log::info!(
+                "Backfill complete: {} inserted, {} skipped",
+                stats.inserted,
+                stats.skipped
+            );
+            pool.close().await;
+            return Ok(());
+        }
+        // `cross_bow tail [--source <source>]` prints new events to stdout
+        Some("tail") => {
+            let source = flag_value(&args, "--source");
+            services::tail_events(
+                &pool,
+                source,
+                // Inserts wake the tail; this only bounds a missed notification
+                std::time::Duration::from_secs(30),
+                &mut std::io::stdout(),
+            )
+            .await
+            .map_err(std::io::Error::other)?;
+            pool.close().await;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Load payload schemas
+    let schemas = match &config.schema_dir {
+        Some(dir) => {
+            let registry = services::SchemaRegistry::load_from_dir(std::path::Path::new(dir))
+                .expect("Failed to load payload schemas");
+            if registry.is_empty() {
+                log::warn!("No payload schemas found in {dir}");
+            } else {
+                log::info!("Loaded {} payload schemas from {dir}", registry.len());
+            }
+            registry
+        }
+        None => services::SchemaRegistry::default(),
+    };
+    let schemas = web::Data::new(schemas);
+
+    // Apply per-source retention in the background
+    if !config.retention_days.is_empty() {
+        tokio::spawn(services::retention::run_pruning(
+            pool.clone(),
+            config.retention_days.clone(),
+            services::retention::PRUNE_INTERVAL,
+        ));
+    }
+
+    // Drop payloads of old processed events in the background
+    if let Some(after_days) = config.compact_payloads_after_days {
+        tokio::spawn(services::retention::run_compaction(
+            pool.clone(),
+            after_days,
+            services::retention::PRUNE_INTERVAL,
+        ));
+    }
+
+    // Export old events to the archive bucket in the background
+    match services::archive::s3_store(&config) {
+        Some(Ok(store)) => {
+            tokio::spawn(services::archive::run_archiving(
+                pool.clone(),
+                store,
+                config.archive_s3_prefix.clone(),
+                config.archive_after_days,
+                config.archive_prune,
+                services::archive::ARCHIVE_INTERVAL,
+            ));
+        }
+        Some(Err(e)) => log::error!("Event archiving disabled, invalid archive bucket config: {e}"),
+        None => {}
+    }
+
+    // Replay queued batches of events to the forward targets at each job's rate
+    if !config.forward_urls.is_empty() {
+        tokio::spawn(services::replay::run_replays(
+            pool.clone(),
+            reqwest::Client::new(),
+            config.forward_urls.clone(),
+            services::replay::REPLAY_POLL_INTERVAL,
+        ));
+    }
+
+    // Alert when the processing backlog grows too large or too old
+    if let Some(url) = config.alert_webhook_url.clone() {
+        tokio::spawn(services::alerts::run_lag_monitor(
+            pool.clone(),
+            reqwest::Client::new(),
+            url,
+            services::alerts::LagMonitor::from_config(&config),
+            services::alerts::LAG_CHECK_INTERVAL,
+        ));
+    }
+
+    // Process events for the same entity sequentially to preserve causality
+    let dispatcher = web::Data::new(services::OrderedDispatcher::new(config.processing_shards));
+    // Shared by processing tasks to skip upserting recently seen, unchanged repositories
+    let metrics = web::Data::new(services::Metrics::new());
+    let repository_cache = web::Data::new(
+        services::RepositoryCache::default().with_metrics(metrics.get_ref().clone()),
+    );
+    if config.pool_metrics_interval_secs > 0 {
+        tokio::spawn(services::metrics::run_pool_metrics(
+            pool.clone(),
+            metrics.get_ref().clone(),
+            std::time::Duration::from_secs(config.pool_metrics_interval_secs),
+        ));
+    }
+
+    // The server needs PostgreSQL for its domain tables, so events use the same pool
+    let event_store: web::Data<dyn db::EventStore> = web::Data::from(Arc::new(
+        db::PgEventStore::new(pool.clone()).with_search_statement_timeout(config.search_timeout()),
+    )
+        as Arc<dyn db::EventStore>);
+
+    let rate_limiter = services::RateLimiter::new(
+        config.webhook_rate_limit,
+        config.webhook_rate_limit_window(),
+    );
+
+    let client_request_timeout = config.client_request_timeout();
+    let http2 = config.http2;
+
     log::info!("Server starting on http://{server_address}");
     log::info!("🌐 Click here to open: http://localhost:{}", config.port);
 
-    // Start HTTP server
-    HttpServer::new(move || {
+    // Start HTTP server; the original pool is kept to close it on shutdown
+    let app_pool = pool.clone();
+    let server = HttpServer::new(move || {
         App::new()
+            // Render styled HTML (or JSON for /api) bodies for 404 and 500 responses
+            .wrap(handlers::error_handlers())
+            // Count webhook deliveries per caller and report X-RateLimit-* headers
+            .wrap(middleware::Condition::new(
+                config.webhook_rate_limit > 0,
+                handlers::WebhookRateLimit::new(
+                    rate_limiter.clone(),
+                    config.trusted_proxies.clone(),
+                ),
+            ))
+            // Require ADMIN_API_KEY as a bearer token on mutating /api routes
+            .wrap(handlers::AdminApiKey::new(config.admin_api_key.clone()))
+            // Drop webhook deliveries whose body trickles in slower than BODY_READ_TIMEOUT_MS
+            .wrap(handlers::BodyReadTimeout::new(config.body_read_timeout()))
             // Add logger middleware
             .wrap(middleware::Logger::default())
+            // Compress responses based on Accept-Encoding
+            .wrap(middleware::Compress::default())
+            // Ask browsers for the OS color scheme so "auto" themes render without a flash
+            .wrap(
+                middleware::DefaultHeaders::new()
+                    .add(("Accept-CH", handlers::layout::PREFERS_COLOR_SCHEME_HINT)),
+            )
             // Add shared state
-            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(app_pool.clone()))
             .app_data(web::Data::new(config.clone()))
-            // API routes
-            .route("/webhooks/github", web::post().to(handlers::github_webhook))
-            .route(
-                "/webhook/{source}",
-                web::post().to(handlers::generic_webhook),
-            )
-            // Web interface routes
-            .route("/", web::get().to(handlers::dashboard))
-            .route("/repositories", web::get().to(handlers::list_repositories))
-            .route(
-                "/repositories/{id}",
-                web::get().to(handlers::repository_detail),
-            )
-            .route("/events", web::get().to(handlers::list_events))
-            // Static file serving
-            .service(fs::Files::new("/assets", "./assets").show_files_listing())
+            .app_data(schemas.clone())
+            .app_data(dispatcher.clone())
+            .app_data(repository_cache.clone())
+            .app_data(metrics.clone())
+            .app_data(event_store.clone())
+            .configure(handlers::routes::configure)
     })
-    .bind(&server_address)?
-    .run()
-    .await
+    .client_request_timeout(client_request_timeout);
+
+    // h2c is negotiated per connection, so HTTP/1.1 senders keep working on the same port
+    let server = if http2 {
+        server.bind_auto_h2c(&server_address)?
+    } else {
+        server.bind(&server_address)?
+    };
+    let result = server.run().await;
+
+    // In-flight requests have finished; hand the connections back to Postgres cleanly
+    log::info!("Server stopped, closing database connections");
+    pool.close().await;
+    result
+}
+
+/// Value following a `--flag` in the command line arguments
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flag_value;
+
+    #[test]
+    fn test_flag_value() {
+        let args: Vec<String> = ["cross_bow", "tail", "--source", "github"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(flag_value(&args, "--source"), Some("github"));
+        assert_eq!(flag_value(&args, "--tenant"), None);
+    }
 }
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// Who triggered an event, flattened into the `actor_*` columns when the event is stored
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actor {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub id: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+impl Actor {
+    /// Extract the actor from a webhook payload using the source's payload shape
+    pub fn from_payload(source: &str, payload: &JsonValue) -> Self {
+        match source {
+            "github" => Self::github(payload),
+            "gitea" | "gogs" => Self::gitea(payload),
+            "gitlab" => Self::gitlab(payload),
+            "auth0" => Self::auth0(payload),
+            _ => Self::generic(payload),
+        }
+    }
+
+    fn github(payload: &JsonValue) -> Self {
+        let first_author = &payload["commits"][0]["author"];
+
+        Actor {
+            name: str_field(&[
+                &payload["sender"]["login"],
+                &payload["pusher"]["name"],
+                &first_author["name"],
+            ]),
+            email: str_field(&[
+                &payload["sender"]["email"],
+                &payload["pusher"]["email"],
+                &first_author["email"],
+            ]),
+            id: str_field(&[&payload["sender"]["login"]])
+                .or_else(|| int_field(&[&payload["sender"]["id"]]))
+                .or_else(|| str_field(&[&payload["pusher"]["name"]])),
+            avatar_url: str_field(&[&payload["sender"]["avatar_url"]]),
+        }
+    }
+
+    fn gitea(payload: &JsonValue) -> Self {
+        Actor {
+            name: str_field(&[
+                &payload["sender"]["login"],
+                &payload["sender"]["username"],
+                &payload["pusher"]["login"],
+                &payload["pusher"]["username"],
+            ]),
+            email: str_field(&[&payload["sender"]["email"], &payload["pusher"]["email"]]),
+            id: int_field(&[&payload["sender"]["id"], &payload["pusher"]["id"]]),
+            avatar_url: str_field(&[&payload["sender"]["avatar_url"]]),
+        }
+    }
+
+    fn gitlab(payload: &JsonValue) -> Self {
+        Actor {
+            name: str_field(&[&payload["user_username"], &payload["user"]["username"]]),
+            email: str_field(&[&payload["user_email"], &payload["user"]["email"]]),
+            id: int_field(&[&payload["user_id"], &payload["user"]["id"]]),
+            avatar_url: str_field(&[&payload["user_avatar"], &payload["user"]["avatar_url"]]),
+        }
+    }
+
+    fn auth0(payload: &JsonValue) -> Self {
+        let user = &payload["user"];
+
+        Actor {
+            name: str_field(&[&user["name"], &user["username"]]),
+            email: str_field(&[&user["email"]]),
+            id: str_field(&[&user["user_id"], &user["id"]]),
+            avatar_url: str_field(&[&user["picture"]]),
+        }
+    }
+
+    fn generic(payload: &JsonValue) -> Self {
+        Actor {
+            name: str_field(&[&payload["actor"], &payload["user"], &payload["username"]]),
+            email: str_field(&[&payload["email"]]),
+            id: str_field(&[&payload["actor_id"], &payload["user_id"]]),
+            avatar_url: str_field(&[&payload["avatar_url"]]),
+        }
+    }
+}
+
+/// The first candidate that is a non-empty JSON string
+fn str_field(candidates: &[&JsonValue]) -> Option<String> {
+    candidates
+        .iter()
+        .find_map(|v| v.as_str().filter(|s| !s.is_empty()))
+        .map(|s| s.to_string())
+}
+
+/// The first candidate that is a JSON integer, as a string
+fn int_field(candidates: &[&JsonValue]) -> Option<String> {
+    candidates
+        .iter()
+        .find_map(|v| v.as_i64())
+        .map(|i| i.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_actor() {
+        let sender = serde_json::json!({
+            "sender": {
+                "login": "octocat",
+                "id": 1,
+                "avatar_url": "https://avatars.example/octocat"
+            }
+        });
+        assert_eq!(
+            Actor::from_payload("github", &sender),
+            Actor {
+                name: Some("octocat".to_string()),
+                email: None,
+                id: Some("octocat".to_string()),
+                avatar_url: Some("https://avatars.example/octocat".to_string()),
+            }
+        );
+
+        // Pushes without a sender fall back to the pusher, then the first commit author
+        let push = serde_json::json!({
+            "pusher": { "name": "monalisa" },
+            "commits": [{ "author": { "name": "Mona", "email": "mona@example.com" } }]
+        });
+        let actor = Actor::from_payload("github", &push);
+        assert_eq!(actor.name.as_deref(), Some("monalisa"));
+        assert_eq!(actor.email.as_deref(), Some("mona@example.com"));
+        assert_eq!(actor.id.as_deref(), Some("monalisa"));
+        assert_eq!(actor.avatar_url, None);
+    }
+
+    #[test]
+    fn test_gitea_and_gogs_actor() {
+        let payload = serde_json::json!({
+            "pusher": { "id": 7, "username": "gitea", "email": "someone@gitea.io" }
+        });
+        let expected = Actor {
+            name: Some("gitea".to_string()),
+            email: Some("someone@gitea.io".to_string()),
+            id: Some("7".to_string()),
+            avatar_url: None,
+        };
+
+        assert_eq!(Actor::from_payload("gitea", &payload), expected);
+        assert_eq!(Actor::from_payload("gogs", &payload), expected);
+    }
+
+    #[test]
+    fn test_gitlab_actor() {
+        let flat = serde_json::json!({
+            "user_id": 4,
+            "user_username": "jsmith",
+            "user_email": "john@example.com",
+            "user_avatar": "https://gitlab.example/avatar.png"
+        });
+        let nested = serde_json::json!({
+            "user": {
+                "id": 4,
+                "username": "jsmith",
+                "email": "john@example.com",
+                "avatar_url": "https://gitlab.example/avatar.png"
+            }
+        });
+
+        assert_eq!(
+            Actor::from_payload("gitlab", &flat),
+            Actor::from_payload("gitlab", &nested)
+        );
+        assert_eq!(
+            Actor::from_payload("gitlab", &flat).id.as_deref(),
+            Some("4")
+        );
+    }
+
+    #[test]
+    fn test_auth0_actor() {
+        let payload = serde_json::json!({
+            "user": {
+                "user_id": "auth0|123",
+                "name": "Jane Smith",
+                "email": "jane@example.com",
+                "picture": "https://cdn.auth0.example/jane.png"
+            }
+        });
+
+        assert_eq!(
+            Actor::from_payload("auth0", &payload),
+            Actor {
+                name: Some("Jane Smith".to_string()),
+                email: Some("jane@example.com".to_string()),
+                id: Some("auth0|123".to_string()),
+                avatar_url: Some("https://cdn.auth0.example/jane.png".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_generic_actor() {
+        let payload = serde_json::json!({
+            "actor": "deploy-bot",
+            "actor_id": "bot-1",
+            "avatar_url": "https://avatars.example/deploy-bot"
+        });
+        let actor = Actor::from_payload("custom", &payload);
+        assert_eq!(actor.name.as_deref(), Some("deploy-bot"));
+        assert_eq!(actor.id.as_deref(), Some("bot-1"));
+        assert_eq!(
+            actor.avatar_url.as_deref(),
+            Some("https://avatars.example/deploy-bot")
+        );
+
+        assert_eq!(
+            Actor::from_payload("custom", &serde_json::json!({})),
+            Actor::default()
+        );
+    }
+}
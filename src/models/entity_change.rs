@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EntityChange {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub event_id: Option<i64>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A single changed field detected between a stored row and an incoming payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl FieldChange {
+    /// Build a change if the two values differ
+    pub fn compare(
+        field: &str,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Option<Self> {
+        if old_value == new_value {
+            return None;
+        }
+
+        Some(FieldChange {
+            field: field.to_string(),
+            old_value,
+            new_value,
+        })
+    }
+}
+
+impl EntityChange {
+    /// Record `changes` made by `event_id`; changes an event already recorded are skipped,
+    /// so reprocessing it adds no history
+    pub async fn record(
+        pool: &sqlx::PgPool,
+        entity_type: &str,
+        entity_id: i64,
+        event_id: Option<i64>,
+        changes: &[FieldChange],
+    ) -> Result<(), sqlx::Error> {
+        for change in changes {
+            sqlx::query(
+                r#"
+                INSERT INTO entity_changes (entity_type, entity_id, field, old_value, new_value, event_id)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (event_id, entity_type, entity_id, field) DO NOTHING
+                "#,
+            )
+            .bind(entity_type)
+            .bind(entity_id)
+            .bind(&change.field)
+            .bind(&change.old_value)
+            .bind(&change.new_value)
+            .bind(event_id)
+            .execute(pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// List changes for several entities of one type, newest first
+    pub async fn list_for_entities(
+        pool: &sqlx::PgPool,
+        entity_type: &str,
+        entity_ids: &[i64],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let changes = sqlx::query_as::<_, EntityChange>(
+            "SELECT * FROM entity_changes WHERE entity_type = $1 AND entity_id = ANY($2) ORDER BY changed_at DESC, id DESC",
+        )
+        .bind(entity_type)
+        .bind(entity_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_compare_ignores_equal_values() {
+        assert_eq!(
+            FieldChange::compare("state", Some("open".into()), Some("open".into())),
+            None
+        );
+        assert!(
+            FieldChange::compare("state", Some("open".into()), Some("closed".into())).is_some()
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_record_and_list_changes(pool: PgPool) {
+        let changes = vec![FieldChange::compare(
+            "title",
+            Some("Old title".to_string()),
+            Some("New title".to_string()),
+        )
+        .unwrap()];
+
+        EntityChange::record(&pool, "issue", 7, None, &changes)
+            .await
+            .unwrap();
+
+        let stored = EntityChange::list_for_entities(&pool, "issue", &[7, 8])
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].field, "title");
+        assert_eq!(stored[0].new_value.as_deref(), Some("New title"));
+
+        let other = EntityChange::list_for_entities(&pool, "pull_request", &[7])
+            .await
+            .unwrap();
+        assert!(other.is_empty());
+    }
+}
@@ -1,10 +1,14 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use sqlx::FromRow;
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+use super::{Actor, EventFilterBuilder};
+use crate::utils::{compress_payload, decompress_payload};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
     pub id: i64,
     pub source: String,
@@ -13,13 +17,94 @@ pub struct Event {
     pub actor_name: Option<String>,
     pub actor_email: Option<String>,
     pub actor_id: Option<String>,
+    pub actor_avatar_url: Option<String>,
+    /// Always the full payload; compressed rows are decompressed when read and
+    /// compacted rows read as `null`
     pub raw_event: JsonValue,
     pub delivery_id: Uuid,
     pub signature: Option<String>,
     pub received_at: DateTime<Utc>,
+    /// When the row was inserted; later than `received_at` when the write was queued,
+    /// imported or backfilled
+    pub created_at: DateTime<Utc>,
     pub processed: bool,
     pub processed_at: Option<DateTime<Utc>>,
     pub repository_id: Option<i64>,
+    pub tenant: Option<String>,
+    /// Set when processing failed; the event stays unprocessed
+    pub processing_error: Option<String>,
+    /// The webhook path's `{source}` before alias resolution
+    pub raw_source: Option<String>,
+    /// The event type as the sender named it, before EVENT_TYPE_ALIASES normalization
+    pub raw_event_type: Option<String>,
+    /// The payload as received when a source transform reshaped `raw_event`
+    pub raw_original: Option<JsonValue>,
+    /// SHA-256 of the request body, used to spot the same payload under a new delivery id
+    pub payload_sha256: Option<String>,
+    /// When the payload was dropped by compaction; such events cannot be processed again
+    pub raw_event_compacted_at: Option<DateTime<Utc>>,
+    /// Whether a GitHub push listed fewer commits than it contained; `None` for other events
+    pub commits_truncated: Option<bool>,
+}
+
+/// Payload fields carrying the sender's own timestamp, in order of preference
+const SENDER_TIMESTAMP_POINTERS: &[&str] = &["/head_commit/timestamp", "/timestamp"];
+
+/// The sender's RFC 3339 timestamp from a payload, if it carries one
+pub fn sender_timestamp(payload: &JsonValue) -> Option<DateTime<Utc>> {
+    SENDER_TIMESTAMP_POINTERS.iter().find_map(|pointer| {
+        let raw = payload.pointer(pointer)?.as_str()?;
+        DateTime::parse_from_rfc3339(raw)
+            .ok()
+            .map(|ts| ts.with_timezone(&Utc))
+    })
+}
+
+/// Most recent event received from a source
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SourceLastSeen {
+    pub source: String,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl SourceLastSeen {
+    /// Whether the source has been silent for longer than `threshold`
+    pub fn is_stale(&self, now: DateTime<Utc>, threshold: chrono::Duration) -> bool {
+        now - self.last_seen > threshold
+    }
+}
+
+/// Events still waiting to be processed; events whose processing failed are not counted
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct PendingSummary {
+    pub pending: i64,
+    pub oldest_received_at: Option<DateTime<Utc>>,
+}
+
+impl PendingSummary {
+    /// How long the oldest pending event has been waiting
+    pub fn oldest_age(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        self.oldest_received_at.map(|received_at| now - received_at)
+    }
+}
+
+/// Total and processed event counts for one source
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SourceProcessingCounts {
+    pub source: String,
+    pub total: i64,
+    pub processed: i64,
+}
+
+/// Stands in for a missing action in [`Event::action_breakdown`]
+pub const NO_ACTION: &str = "(none)";
+
+/// How many of a source's events of one type are still unprocessed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UnprocessedTypeCount {
+    pub source: String,
+    pub event_type: String,
+    pub count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,43 +112,331 @@ pub struct CreateEvent {
     pub source: String,
     pub event_type: String,
     pub action: Option<String>,
-    pub actor_name: Option<String>,
-    pub actor_email: Option<String>,
-    pub actor_id: Option<String>,
+    /// Stored in the `actor_*` columns
+    pub actor: Actor,
     pub raw_event: JsonValue,
     pub delivery_id: Uuid,
     pub signature: Option<String>,
     pub repository_id: Option<i64>,
+    pub tenant: Option<String>,
+    pub raw_source: Option<String>,
+    pub raw_event_type: Option<String>,
+    pub raw_original: Option<JsonValue>,
+    pub payload_sha256: Option<String>,
+    /// Values of the `SEARCH_FIELDS` paths, searched instead of the whole payload
+    pub searchable_text: Option<String>,
+}
+
+#[cfg(test)]
+impl CreateEvent {
+    /// An unsigned event with an empty payload and a fresh delivery id; tests override
+    /// the fields they care about with struct update syntax
+    pub fn for_test(source: &str, event_type: &str) -> Self {
+        CreateEvent {
+            source: source.to_string(),
+            event_type: event_type.to_string(),
+            action: None,
+            actor: Actor::default(),
+            raw_event: serde_json::json!({}),
+            delivery_id: Uuid::new_v4(),
+            signature: None,
+            repository_id: None,
+            tenant: None,
+            raw_source: None,
+            raw_event_type: None,
+            raw_original: None,
+            payload_sha256: None,
+            searchable_text: None,
+        }
+    }
+}
+
+/// An event brought over from another tool, keeping the time it was first received
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedEvent {
+    pub event: CreateEvent,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Rows per `INSERT` in [`Event::create_many`], well under the bind parameter limit
+const CREATE_MANY_CHUNK: usize = 1000;
+
+impl<'r> FromRow<'r, PgRow> for Event {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let raw_event_compacted_at: Option<DateTime<Utc>> =
+            row.try_get("raw_event_compacted_at")?;
+        let raw_event = if raw_event_compacted_at.is_some() {
+            JsonValue::Null
+        } else if row.try_get("raw_event_compressed")? {
+            let compressed: Vec<u8> = row.try_get("raw_event_gz")?;
+            decompress_payload(&compressed).map_err(|e| sqlx::Error::ColumnDecode {
+                index: "raw_event_gz".to_string(),
+                source: Box::new(e),
+            })?
+        } else {
+            row.try_get("raw_event")?
+        };
+
+        Ok(Event {
+            id: row.try_get("id")?,
+            source: row.try_get("source")?,
+            event_type: row.try_get("event_type")?,
+            action: row.try_get("action")?,
+            actor_name: row.try_get("actor_name")?,
+            actor_email: row.try_get("actor_email")?,
+            actor_id: row.try_get("actor_id")?,
+            actor_avatar_url: row.try_get("actor_avatar_url")?,
+            raw_event,
+            delivery_id: row.try_get("delivery_id")?,
+            signature: row.try_get("signature")?,
+            received_at: row.try_get("received_at")?,
+            created_at: row.try_get("created_at")?,
+            processed: row.try_get("processed")?,
+            processed_at: row.try_get("processed_at")?,
+            repository_id: row.try_get("repository_id")?,
+            tenant: row.try_get("tenant")?,
+            processing_error: row.try_get("processing_error")?,
+            raw_source: row.try_get("raw_source")?,
+            raw_event_type: row.try_get("raw_event_type")?,
+            raw_original: row.try_get("raw_original")?,
+            payload_sha256: row.try_get("payload_sha256")?,
+            raw_event_compacted_at,
+            commits_truncated: row.try_get("commits_truncated")?,
+        })
+    }
 }
 
 impl Event {
+    /// The event as MessagePack, keyed by field name like the JSON API. Human-readable mode
+    /// keeps UUIDs and timestamps as the same strings the JSON API returns
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        let mut buf = Vec::new();
+        self.serialize(
+            &mut rmp_serde::Serializer::new(&mut buf)
+                .with_struct_map()
+                .with_human_readable(),
+        )?;
+        Ok(buf)
+    }
+
+    /// Inverse of `to_msgpack`
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
+        Event::deserialize(&mut rmp_serde::Deserializer::new(bytes).with_human_readable())
+    }
+
+    /// Time from the sender's payload timestamp to receipt; `None` when the payload
+    /// has no timestamp or the sender's clock is ahead of ours
+    pub fn delivery_latency(&self) -> Option<chrono::Duration> {
+        let latency = self.received_at - sender_timestamp(&self.raw_event)?;
+        (latency >= chrono::Duration::zero()).then_some(latency)
+    }
+
     pub async fn create(pool: &sqlx::PgPool, data: CreateEvent) -> Result<Self, sqlx::Error> {
         let event = sqlx::query_as::<_, Event>(
             r#"
-            INSERT INTO events (source, event_type, action, actor_name, actor_email, actor_id, raw_event, delivery_id, signature, repository_id)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO events (source, event_type, action, actor_name, actor_email, actor_id, actor_avatar_url, raw_event, delivery_id, signature, repository_id, tenant, raw_source, raw_event_type, raw_original, payload_sha256, searchable_text)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            RETURNING *
+            "#,
+        )
+        .bind(data.source)
+        .bind(data.event_type)
+        .bind(data.action)
+        .bind(data.actor.name)
+        .bind(data.actor.email)
+        .bind(data.actor.id)
+        .bind(data.actor.avatar_url)
+        .bind(data.raw_event)
+        .bind(data.delivery_id)
+        .bind(data.signature)
+        .bind(data.repository_id)
+        .bind(data.tenant)
+        .bind(data.raw_source)
+        .bind(data.raw_event_type)
+        .bind(data.raw_original)
+        .bind(data.payload_sha256)
+        .bind(data.searchable_text)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Insert events in one transaction, skipping any whose delivery id is already
+    /// stored (or repeated earlier in `events`). With `compress` payloads are stored
+    /// as in [`Event::create_compressed`]. Returns how many were inserted.
+    pub async fn create_many(
+        pool: &sqlx::PgPool,
+        events: Vec<ImportedEvent>,
+        compress: bool,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let mut inserted = 0;
+
+        for chunk in events.chunks(CREATE_MANY_CHUNK) {
+            let compressed = chunk
+                .iter()
+                .map(|imported| {
+                    compress
+                        .then(|| compress_payload(&imported.event.raw_event))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(sqlx::Error::Io)?;
+
+            let mut query = QueryBuilder::<Postgres>::new(
+                "INSERT INTO events (source, event_type, action, actor_name, actor_email, actor_id, actor_avatar_url, raw_event, raw_event_gz, raw_event_compressed, raw_event_search, delivery_id, signature, repository_id, tenant, raw_source, raw_event_type, raw_original, payload_sha256, searchable_text, received_at) ",
+            );
+            query.push_values(chunk.iter().zip(compressed), |mut row, (imported, gz)| {
+                let data = imported.event.clone();
+                row.push_bind(data.source)
+                    .push_bind(data.event_type)
+                    .push_bind(data.action)
+                    .push_bind(data.actor.name)
+                    .push_bind(data.actor.email)
+                    .push_bind(data.actor.id)
+                    .push_bind(data.actor.avatar_url);
+                if let Some(gz) = gz {
+                    row.push("NULL")
+                        .push_bind(gz)
+                        .push("TRUE")
+                        .push("jsonb_to_tsvector('simple', ")
+                        .push_bind_unseparated(data.raw_event)
+                        .push_unseparated(", '[\"string\", \"numeric\"]')");
+                } else {
+                    row.push_bind(data.raw_event)
+                        .push("NULL")
+                        .push("FALSE")
+                        .push("NULL");
+                }
+                row.push_bind(data.delivery_id)
+                    .push_bind(data.signature)
+                    .push_bind(data.repository_id)
+                    .push_bind(data.tenant)
+                    .push_bind(data.raw_source)
+                    .push_bind(data.raw_event_type)
+                    .push_bind(data.raw_original)
+                    .push_bind(data.payload_sha256)
+                    .push_bind(data.searchable_text)
+                    .push_bind(imported.received_at);
+            });
+            query.push(" ON CONFLICT (delivery_id) DO NOTHING");
+
+            inserted += query.build().execute(&mut *tx).await?.rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    /// Insert an event with its payload gzip-compressed, indexing its values for search
+    pub async fn create_compressed(
+        pool: &sqlx::PgPool,
+        data: CreateEvent,
+    ) -> Result<Self, sqlx::Error> {
+        let compressed = compress_payload(&data.raw_event).map_err(sqlx::Error::Io)?;
+
+        let event = sqlx::query_as::<_, Event>(
+            r#"
+            INSERT INTO events (source, event_type, action, actor_name, actor_email, actor_id, actor_avatar_url, raw_event_gz, raw_event_compressed, raw_event_search, delivery_id, signature, repository_id, tenant, raw_source, raw_event_type, raw_original, payload_sha256, searchable_text)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, TRUE, jsonb_to_tsvector('simple', $9, '["string", "numeric"]'), $10, $11, $12, $13, $14, $15, $16, $17)
+            RETURNING *
+            "#,
+        )
+        .bind(data.source)
+        .bind(data.event_type)
+        .bind(data.action)
+        .bind(data.actor.name)
+        .bind(data.actor.email)
+        .bind(data.actor.id)
+        .bind(data.actor.avatar_url)
+        .bind(compressed)
+        .bind(data.raw_event)
+        .bind(data.delivery_id)
+        .bind(data.signature)
+        .bind(data.repository_id)
+        .bind(data.tenant)
+        .bind(data.raw_source)
+        .bind(data.raw_event_type)
+        .bind(data.raw_original)
+        .bind(data.payload_sha256)
+        .bind(data.searchable_text)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Insert a historical event, keeping its original receive and processing times
+    pub async fn create_backfilled(
+        pool: &sqlx::PgPool,
+        data: CreateEvent,
+        received_at: DateTime<Utc>,
+        processed_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let event = sqlx::query_as::<_, Event>(
+            r#"
+            INSERT INTO events (source, event_type, action, actor_name, actor_email, actor_id, actor_avatar_url, raw_event, delivery_id, signature, repository_id, tenant, raw_source, raw_event_type, raw_original, payload_sha256, searchable_text, received_at, processed, processed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
             RETURNING *
             "#,
         )
         .bind(data.source)
         .bind(data.event_type)
         .bind(data.action)
-        .bind(data.actor_name)
-        .bind(data.actor_email)
-        .bind(data.actor_id)
+        .bind(data.actor.name)
+        .bind(data.actor.email)
+        .bind(data.actor.id)
+        .bind(data.actor.avatar_url)
         .bind(data.raw_event)
         .bind(data.delivery_id)
         .bind(data.signature)
         .bind(data.repository_id)
+        .bind(data.tenant)
+        .bind(data.raw_source)
+        .bind(data.raw_event_type)
+        .bind(data.raw_original)
+        .bind(data.payload_sha256)
+        .bind(data.searchable_text)
+        .bind(received_at)
+        .bind(processed_at.is_some())
+        .bind(processed_at)
         .fetch_one(pool)
         .await?;
 
         Ok(event)
     }
 
+    pub async fn set_commits_truncated(
+        pool: &sqlx::PgPool,
+        id: i64,
+        truncated: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE events SET commits_truncated = $2 WHERE id = $1")
+            .bind(id)
+            .bind(truncated)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn mark_processed(pool: &sqlx::PgPool, id: i64) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE events SET processed = true, processed_at = NOW() WHERE id = $1")
+        sqlx::query(
+            "UPDATE events SET processed = true, processed_at = NOW(), processing_error = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record why processing failed, leaving the event unprocessed
+    pub async fn mark_failed(pool: &sqlx::PgPool, id: i64, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE events SET processing_error = $2 WHERE id = $1")
             .bind(id)
+            .bind(error)
             .execute(pool)
             .await?;
 
@@ -80,6 +453,69 @@ impl Event {
         Ok(event)
     }
 
+    pub async fn find_by_delivery_id(
+        pool: &sqlx::PgPool,
+        delivery_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let event = sqlx::query_as::<_, Event>("SELECT * FROM events WHERE delivery_id = $1")
+            .bind(delivery_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(event)
+    }
+
+    /// Most recent event from `source` with the same body hash received since `since`
+    pub async fn find_recent_by_payload_hash(
+        pool: &sqlx::PgPool,
+        source: &str,
+        payload_sha256: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let event = sqlx::query_as::<_, Event>(
+            r#"
+            SELECT * FROM events
+            WHERE payload_sha256 = $1 AND source = $2 AND received_at >= $3
+            ORDER BY received_at DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(payload_sha256)
+        .bind(source)
+        .bind(since)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// List events with an id greater than `after_id`, oldest first, optionally for one source
+    pub async fn list_after_id(
+        pool: &sqlx::PgPool,
+        after_id: i64,
+        source: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let events = sqlx::query_as::<_, Event>(
+            "SELECT * FROM events WHERE id > $1 AND ($2::text IS NULL OR source = $2) ORDER BY id ASC LIMIT $3",
+        )
+        .bind(after_id)
+        .bind(source)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    pub async fn max_id(pool: &sqlx::PgPool) -> Result<i64, sqlx::Error> {
+        let max: (Option<i64>,) = sqlx::query_as("SELECT MAX(id) FROM events")
+            .fetch_one(pool)
+            .await?;
+
+        Ok(max.0.unwrap_or(0))
+    }
+
     #[allow(dead_code)]
     pub async fn list_by_repository(
         pool: &sqlx::PgPool,
@@ -88,7 +524,7 @@ impl Event {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let events = sqlx::query_as::<_, Event>(
-            "SELECT * FROM events WHERE repository_id = $1 ORDER BY received_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM events WHERE repository_id = $1 ORDER BY received_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(repository_id)
         .bind(limit)
@@ -99,6 +535,70 @@ impl Event {
         Ok(events)
     }
 
+    /// Other events from the same source by the same actor, newest first. Actors are
+    /// matched by `actor_id` when the event has one, otherwise by name.
+    pub async fn list_by_actor(
+        pool: &sqlx::PgPool,
+        event: &Event,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        if event.actor_id.is_none() && event.actor_name.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let events = sqlx::query_as::<_, Event>(
+            r#"
+            SELECT * FROM events
+            WHERE id <> $1 AND source = $2
+              AND CASE WHEN $3::text IS NOT NULL THEN actor_id = $3 ELSE actor_name = $4 END
+            ORDER BY received_at DESC, id DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(event.id)
+        .bind(&event.source)
+        .bind(&event.actor_id)
+        .bind(&event.actor_name)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Other events for the same repository received within `window` of `event`, oldest first
+    pub async fn list_by_delivery_neighbors(
+        pool: &sqlx::PgPool,
+        event: &Event,
+        window: chrono::Duration,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let Some(repository_id) = event.repository_id else {
+            return Ok(Vec::new());
+        };
+
+        let mut events = sqlx::query_as::<_, Event>(
+            r#"
+            SELECT * FROM events
+            WHERE id <> $1 AND repository_id = $2 AND received_at BETWEEN $3 AND $4
+            ORDER BY abs(extract(epoch FROM received_at - $5)), received_at, id
+            LIMIT $6
+            "#,
+        )
+        .bind(event.id)
+        .bind(repository_id)
+        .bind(event.received_at - window)
+        .bind(event.received_at + window)
+        .bind(event.received_at)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        // Closest first keeps the nearest neighbors under the limit; show them in order
+        events.sort_by_key(|e| e.received_at);
+        Ok(events)
+    }
+
     #[allow(dead_code)]
     pub async fn list_all(
         pool: &sqlx::PgPool,
@@ -106,7 +606,7 @@ impl Event {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let events = sqlx::query_as::<_, Event>(
-            "SELECT * FROM events ORDER BY received_at DESC LIMIT $1 OFFSET $2",
+            "SELECT * FROM events ORDER BY received_at DESC, id DESC LIMIT $1 OFFSET $2",
         )
         .bind(limit)
         .bind(offset)
@@ -124,7 +624,7 @@ impl Event {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let events = sqlx::query_as::<_, Event>(
-            "SELECT * FROM events WHERE event_type = $1 ORDER BY received_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM events WHERE event_type = $1 ORDER BY received_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(event_type)
         .bind(limit)
@@ -135,6 +635,27 @@ impl Event {
         Ok(events)
     }
 
+    /// Unprocessed events of one source and type, oldest first, for reprocessing
+    pub async fn list_by_type_unprocessed(
+        pool: &sqlx::PgPool,
+        source: &str,
+        event_type: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let events = sqlx::query_as::<_, Event>(
+            "SELECT * FROM events WHERE source = $1 AND event_type = $2 AND processed = false ORDER BY id ASC LIMIT $3 OFFSET $4",
+        )
+        .bind(source)
+        .bind(event_type)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
     #[allow(dead_code)]
     pub async fn list_by_source(
         pool: &sqlx::PgPool,
@@ -143,7 +664,7 @@ impl Event {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let events = sqlx::query_as::<_, Event>(
-            "SELECT * FROM events WHERE source = $1 ORDER BY received_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM events WHERE source = $1 ORDER BY received_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(source)
         .bind(limit)
@@ -154,6 +675,58 @@ impl Event {
         Ok(events)
     }
 
+    /// Delete a source's events received before `cutoff`, returning how many were removed
+    pub async fn delete_older_than_for_source(
+        pool: &sqlx::PgPool,
+        source: &str,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM events WHERE source = $1 AND received_at < $2")
+            .bind(source)
+            .bind(cutoff)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete the events with ids in `first..=last`, returning how many were removed
+    pub async fn delete_id_range(
+        pool: &sqlx::PgPool,
+        first: i64,
+        last: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM events WHERE id BETWEEN $1 AND $2")
+            .bind(first)
+            .bind(last)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Drop the payload of processed events received before `cutoff`, returning how many
+    /// were compacted. `payload_sha256` and the structured rows built from them are kept.
+    pub async fn compact_processed_before(
+        pool: &sqlx::PgPool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE events
+            SET raw_event = NULL, raw_event_gz = NULL, raw_event_compressed = FALSE,
+                raw_event_search = NULL, searchable_text = NULL, raw_original = NULL,
+                raw_event_compacted_at = NOW()
+            WHERE processed = TRUE AND received_at < $1 AND raw_event_compacted_at IS NULL
+            "#,
+        )
+        .bind(cutoff)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn count(pool: &sqlx::PgPool) -> Result<i64, sqlx::Error> {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events")
             .fetch_one(pool)
@@ -162,146 +735,140 @@ impl Event {
         Ok(count.0)
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub async fn search_and_filter(
-        pool: &sqlx::PgPool,
-        source: Option<&str>,
-        event_type: Option<&str>,
-        action: Option<&str>,
-        actor_name: Option<&str>,
-        processed: Option<bool>,
-        search: Option<&str>,
+    pub async fn search_and_filter<'e, E: sqlx::PgExecutor<'e>>(
+        executor: E,
+        filters: &EventFilterBuilder,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        let mut query = String::from("SELECT * FROM events WHERE 1=1");
-        let mut bindings = Vec::new();
-        let mut param_count = 1;
-
-        if let Some(src) = source {
-            query.push_str(&format!(" AND source = ${param_count}"));
-            bindings.push(src.to_string());
-            param_count += 1;
-        }
+        let mut query = filters.select_query();
 
-        if let Some(et) = event_type {
-            query.push_str(&format!(" AND event_type = ${param_count}"));
-            bindings.push(et.to_string());
-            param_count += 1;
-        }
+        query
+            .push(" ORDER BY received_at DESC, id DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
 
-        if let Some(act) = action {
-            query.push_str(&format!(" AND action = ${param_count}"));
-            bindings.push(act.to_string());
-            param_count += 1;
-        }
+        let events = query.build_query_as::<Event>().fetch_all(executor).await?;
 
-        if let Some(actor) = actor_name {
-            query.push_str(&format!(" AND actor_name = ${param_count}"));
-            bindings.push(actor.to_string());
-            param_count += 1;
-        }
+        Ok(events)
+    }
 
-        if let Some(proc) = processed {
-            query.push_str(&format!(" AND processed = ${param_count}"));
-            bindings.push(proc.to_string());
-            param_count += 1;
-        }
+    pub async fn count_filtered<'e, E: sqlx::PgExecutor<'e>>(
+        executor: E,
+        filters: &EventFilterBuilder,
+    ) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = filters
+            .count_query()
+            .build_query_as()
+            .fetch_one(executor)
+            .await?;
 
-        if let Some(s) = search {
-            if !s.is_empty() {
-                query.push_str(&format!(" AND raw_event::text ILIKE ${param_count}"));
-                bindings.push(format!("%{s}%"));
-                param_count += 1;
-            }
-        }
+        Ok(count.0)
+    }
 
-        query.push_str(&format!(
-            " ORDER BY received_at DESC LIMIT ${} OFFSET ${}",
-            param_count,
-            param_count + 1
-        ));
-        bindings.push(limit.to_string());
-        bindings.push(offset.to_string());
+    pub async fn get_event_types(pool: &sqlx::PgPool) -> Result<Vec<String>, sqlx::Error> {
+        let types: Vec<(String,)> =
+            sqlx::query_as("SELECT DISTINCT event_type FROM events ORDER BY event_type")
+                .fetch_all(pool)
+                .await?;
 
-        let mut query_builder = sqlx::query_as::<_, Event>(&query);
-        for binding in bindings {
-            query_builder = query_builder.bind(binding);
-        }
+        Ok(types.into_iter().map(|(t,)| t).collect())
+    }
 
-        let events = query_builder.fetch_all(pool).await?;
+    pub async fn get_tenants(pool: &sqlx::PgPool) -> Result<Vec<String>, sqlx::Error> {
+        let tenants: Vec<(String,)> = sqlx::query_as(
+            "SELECT DISTINCT tenant FROM events WHERE tenant IS NOT NULL ORDER BY tenant",
+        )
+        .fetch_all(pool)
+        .await?;
 
-        Ok(events)
+        Ok(tenants.into_iter().map(|(t,)| t).collect())
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub async fn count_filtered(
+    pub async fn last_seen_per_source(
         pool: &sqlx::PgPool,
-        source: Option<&str>,
-        event_type: Option<&str>,
-        action: Option<&str>,
-        actor_name: Option<&str>,
-        processed: Option<bool>,
-        search: Option<&str>,
-    ) -> Result<i64, sqlx::Error> {
-        let mut query = String::from("SELECT COUNT(*) FROM events WHERE 1=1");
-        let mut bindings = Vec::new();
-        let mut param_count = 1;
-
-        if let Some(src) = source {
-            query.push_str(&format!(" AND source = ${param_count}"));
-            bindings.push(src.to_string());
-            param_count += 1;
-        }
-
-        if let Some(et) = event_type {
-            query.push_str(&format!(" AND event_type = ${param_count}"));
-            bindings.push(et.to_string());
-            param_count += 1;
-        }
-
-        if let Some(act) = action {
-            query.push_str(&format!(" AND action = ${param_count}"));
-            bindings.push(act.to_string());
-            param_count += 1;
-        }
+    ) -> Result<Vec<SourceLastSeen>, sqlx::Error> {
+        let sources = sqlx::query_as::<_, SourceLastSeen>(
+            "SELECT source, MAX(received_at) AS last_seen FROM events GROUP BY source ORDER BY source",
+        )
+        .fetch_all(pool)
+        .await?;
 
-        if let Some(actor) = actor_name {
-            query.push_str(&format!(" AND actor_name = ${param_count}"));
-            bindings.push(actor.to_string());
-            param_count += 1;
-        }
+        Ok(sources)
+    }
 
-        if let Some(proc) = processed {
-            query.push_str(&format!(" AND processed = ${param_count}"));
-            bindings.push(proc.to_string());
-            param_count += 1;
-        }
+    pub async fn processing_counts_per_source(
+        pool: &sqlx::PgPool,
+    ) -> Result<Vec<SourceProcessingCounts>, sqlx::Error> {
+        let counts = sqlx::query_as::<_, SourceProcessingCounts>(
+            r#"
+            SELECT source, COUNT(*) AS total, COUNT(*) FILTER (WHERE processed) AS processed
+            FROM events
+            GROUP BY source
+            ORDER BY source
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
 
-        if let Some(s) = search {
-            if !s.is_empty() {
-                query.push_str(&format!(" AND raw_event::text ILIKE ${param_count}"));
-                bindings.push(format!("%{s}%"));
-            }
-        }
+        Ok(counts)
+    }
 
-        let mut query_builder = sqlx::query_as::<_, (i64,)>(&query);
-        for binding in bindings {
-            query_builder = query_builder.bind(binding);
-        }
+    /// Unprocessed events per source and event type, most frequent first
+    pub async fn unprocessed_types_per_source(
+        pool: &sqlx::PgPool,
+    ) -> Result<Vec<UnprocessedTypeCount>, sqlx::Error> {
+        let counts = sqlx::query_as::<_, UnprocessedTypeCount>(
+            r#"
+            SELECT source, event_type, COUNT(*) AS count
+            FROM events
+            WHERE processed = FALSE
+            GROUP BY source, event_type
+            ORDER BY source, count DESC, event_type
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
 
-        let count = query_builder.fetch_one(pool).await?;
+        Ok(counts)
+    }
 
-        Ok(count.0)
+    /// Events of one source and type counted per action, most frequent first; events
+    /// without an action are counted under [`NO_ACTION`]
+    pub async fn action_breakdown(
+        pool: &sqlx::PgPool,
+        source: &str,
+        event_type: &str,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT COALESCE(action, $3) AS action, COUNT(*) AS count
+            FROM events
+            WHERE source = $1 AND event_type = $2
+            GROUP BY action
+            ORDER BY count DESC, action
+            "#,
+        )
+        .bind(source)
+        .bind(event_type)
+        .bind(NO_ACTION)
+        .fetch_all(pool)
+        .await
     }
 
-    pub async fn get_event_types(pool: &sqlx::PgPool) -> Result<Vec<String>, sqlx::Error> {
-        let types: Vec<(String,)> =
-            sqlx::query_as("SELECT DISTINCT event_type FROM events ORDER BY event_type")
-                .fetch_all(pool)
-                .await?;
+    pub async fn pending_summary(pool: &sqlx::PgPool) -> Result<PendingSummary, sqlx::Error> {
+        let summary = sqlx::query_as::<_, PendingSummary>(
+            r#"
+            SELECT COUNT(*) AS pending, MIN(received_at) AS oldest_received_at
+            FROM events
+            WHERE processed = FALSE AND processing_error IS NULL
+            "#,
+        )
+        .fetch_one(pool)
+        .await?;
 
-        Ok(types.into_iter().map(|(t,)| t).collect())
+        Ok(summary)
     }
 
     pub async fn get_sources(pool: &sqlx::PgPool) -> Result<Vec<String>, sqlx::Error> {
@@ -332,4 +899,394 @@ impl Event {
 
         Ok(actor_names.into_iter().map(|(a,)| a).collect())
     }
+
+    /// `(id, full_name)` of the repositories events were recorded for, by name
+    pub async fn get_repositories(pool: &sqlx::PgPool) -> Result<Vec<(i64, String)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT r.id, r.full_name
+            FROM repositories r
+            WHERE EXISTS (SELECT 1 FROM events e WHERE e.repository_id = r.id)
+            ORDER BY r.full_name
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FilterTable;
+
+    fn push_event() -> CreateEvent {
+        CreateEvent {
+            actor: Actor {
+                name: Some("octocat".to_string()),
+                ..Actor::default()
+            },
+            raw_event: serde_json::json!({
+                "ref": "refs/heads/main",
+                "head_commit": { "message": "Fix flaky deploy" }
+            }),
+            ..CreateEvent::for_test("github", "push")
+        }
+    }
+
+    #[test]
+    fn test_source_is_stale_after_threshold() {
+        let now = Utc::now();
+        let threshold = chrono::Duration::hours(24);
+        let seen = |hours_ago| SourceLastSeen {
+            source: "github".to_string(),
+            last_seen: now - chrono::Duration::hours(hours_ago),
+        };
+
+        assert!(!seen(1).is_stale(now, threshold));
+        assert!(!seen(24).is_stale(now, threshold));
+        assert!(seen(25).is_stale(now, threshold));
+    }
+
+    #[test]
+    fn test_msgpack_round_trip() {
+        let event = Event {
+            id: 42,
+            source: "github".to_string(),
+            event_type: "push".to_string(),
+            action: Some("created".to_string()),
+            actor_name: Some("octocat".to_string()),
+            actor_email: None,
+            actor_id: Some("1".to_string()),
+            actor_avatar_url: None,
+            raw_event: serde_json::json!({
+                "ref": "refs/heads/main",
+                "commits": [{ "id": "59b20b8d", "distinct": true, "size": 1.5 }],
+                "forced": null
+            }),
+            delivery_id: Uuid::new_v4(),
+            signature: Some("sha256=abc".to_string()),
+            received_at: Utc::now(),
+            created_at: Utc::now(),
+            processed: true,
+            processed_at: Some(Utc::now()),
+            repository_id: Some(7),
+            tenant: None,
+            processing_error: None,
+            raw_source: Some("gh".to_string()),
+            raw_event_type: None,
+            raw_original: None,
+            payload_sha256: None,
+            raw_event_compacted_at: None,
+            commits_truncated: None,
+        };
+
+        let encoded = event.to_msgpack().unwrap();
+        let decoded = Event::from_msgpack(&encoded).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&event).unwrap()
+        );
+        // Field names are kept, so consumers can read it as a map
+        let generic: JsonValue = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(generic["delivery_id"], event.delivery_id.to_string());
+        assert_eq!(generic["raw_event"]["commits"][0]["size"], 1.5);
+    }
+
+    #[test]
+    fn test_delivery_latency_from_sender_timestamp() {
+        let received_at = DateTime::parse_from_rfc3339("2024-01-01T12:00:02.5Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let event = |raw_event: JsonValue| Event {
+            id: 1,
+            source: "github".to_string(),
+            event_type: "push".to_string(),
+            action: None,
+            actor_name: None,
+            actor_email: None,
+            actor_id: None,
+            actor_avatar_url: None,
+            raw_event,
+            delivery_id: Uuid::new_v4(),
+            signature: None,
+            received_at,
+            created_at: received_at,
+            processed: false,
+            processed_at: None,
+            repository_id: None,
+            tenant: None,
+            processing_error: None,
+            raw_source: None,
+            raw_event_type: None,
+            raw_original: None,
+            payload_sha256: None,
+            raw_event_compacted_at: None,
+            commits_truncated: None,
+        };
+
+        let push = event(serde_json::json!({
+            "head_commit": { "timestamp": "2024-01-01T13:00:00+01:00" }
+        }));
+        assert_eq!(
+            push.delivery_latency(),
+            Some(chrono::Duration::milliseconds(2500))
+        );
+
+        let generic = event(serde_json::json!({ "timestamp": "2024-01-01T11:59:02.5Z" }));
+        assert_eq!(
+            generic.delivery_latency(),
+            Some(chrono::Duration::minutes(1))
+        );
+
+        // Missing, unparseable and future timestamps have no latency
+        assert_eq!(event(serde_json::json!({})).delivery_latency(), None);
+        assert_eq!(
+            event(serde_json::json!({ "timestamp": 1704110400 })).delivery_latency(),
+            None
+        );
+        assert_eq!(
+            event(serde_json::json!({ "timestamp": "2024-01-01T12:05:00Z" })).delivery_latency(),
+            None
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_pending_summary_skips_processed_and_failed(pool: sqlx::PgPool) {
+        assert_eq!(Event::pending_summary(&pool).await.unwrap().pending, 0);
+
+        let processed = Event::create(&pool, push_event()).await.unwrap();
+        Event::mark_processed(&pool, processed.id).await.unwrap();
+        let failed = Event::create(&pool, push_event()).await.unwrap();
+        Event::mark_failed(&pool, failed.id, "boom").await.unwrap();
+        let pending = Event::create(&pool, push_event()).await.unwrap();
+
+        let summary = Event::pending_summary(&pool).await.unwrap();
+        assert_eq!(summary.pending, 1);
+        assert_eq!(summary.oldest_received_at, Some(pending.received_at));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_action_breakdown_sums_to_type_total(pool: sqlx::PgPool) {
+        let actions = [
+            Some("opened"),
+            Some("closed"),
+            Some("opened"),
+            None,
+            Some("closed"),
+            Some("opened"),
+        ];
+        for action in actions {
+            let mut data = push_event();
+            data.event_type = "pull_request".to_string();
+            data.action = action.map(str::to_string);
+            Event::create(&pool, data).await.unwrap();
+        }
+        // Other types and sources are not counted
+        Event::create(&pool, push_event()).await.unwrap();
+        let mut gitlab = push_event();
+        gitlab.source = "gitlab".to_string();
+        gitlab.event_type = "pull_request".to_string();
+        gitlab.action = Some("opened".to_string());
+        Event::create(&pool, gitlab).await.unwrap();
+
+        let breakdown = Event::action_breakdown(&pool, "github", "pull_request")
+            .await
+            .unwrap();
+        assert_eq!(
+            breakdown,
+            vec![
+                ("opened".to_string(), 3),
+                ("closed".to_string(), 2),
+                (NO_ACTION.to_string(), 1),
+            ]
+        );
+
+        let filters = EventFilterBuilder::new(FilterTable::Events)
+            .source(Some("github"))
+            .event_type(Some("pull_request"));
+        let total = Event::count_filtered(&pool, &filters).await.unwrap();
+        assert_eq!(breakdown.iter().map(|(_, count)| count).sum::<i64>(), total);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_pages_are_stable_when_timestamps_tie(pool: sqlx::PgPool) {
+        let mut ids = Vec::new();
+        for _ in 0..5 {
+            ids.push(Event::create(&pool, push_event()).await.unwrap().id);
+        }
+        // As from a bulk insert: every row shares one timestamp
+        sqlx::query("UPDATE events SET received_at = '2026-10-01T12:00:00Z'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let filters = EventFilterBuilder::new(FilterTable::Events);
+        let mut paged = Vec::new();
+        for offset in (0..5).step_by(2) {
+            let page = Event::search_and_filter(&pool, &filters, 2, offset)
+                .await
+                .unwrap();
+            paged.extend(page.iter().map(|e| e.id));
+        }
+
+        ids.reverse();
+        assert_eq!(paged, ids);
+        let listed: Vec<i64> = Event::list_all(&pool, 5, 0)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(listed, ids);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_compressed_payload_round_trip(pool: sqlx::PgPool) {
+        let data = push_event();
+        let payload = data.raw_event.clone();
+
+        let created = Event::create_compressed(&pool, data).await.unwrap();
+        assert_eq!(created.raw_event, payload);
+
+        let (stored_json, compressed): (Option<JsonValue>, bool) =
+            sqlx::query_as("SELECT raw_event, raw_event_compressed FROM events WHERE id = $1")
+                .bind(created.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(compressed);
+        assert_eq!(stored_json, None);
+
+        let found = Event::find_by_id(&pool, created.id).await.unwrap().unwrap();
+        assert_eq!(found.raw_event, payload);
+        assert_eq!(serde_json::to_value(&found).unwrap()["raw_event"], payload);
+
+        let filters = EventFilterBuilder::new(FilterTable::Events).search(Some("flaky"));
+        let matches = Event::search_and_filter(&pool, &filters, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_created_at_is_set_on_insert(pool: sqlx::PgPool) {
+        let before = Utc::now() - chrono::Duration::seconds(5);
+
+        let live = Event::create(&pool, push_event()).await.unwrap();
+        assert!(live.created_at >= before);
+
+        // A backfilled event keeps its original receipt time but is stamped when written
+        let received_at = Utc::now() - chrono::Duration::days(30);
+        let backfilled = Event::create_backfilled(&pool, push_event(), received_at, None)
+            .await
+            .unwrap();
+        assert!(backfilled.created_at >= before);
+        assert!(backfilled.created_at > backfilled.received_at);
+
+        let found = Event::find_by_id(&pool, backfilled.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.created_at, backfilled.created_at);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_search_uses_searchable_text_when_stored(pool: sqlx::PgPool) {
+        let fields = vec!["head_commit.message".to_string()];
+        let mut indexed = push_event();
+        indexed.searchable_text = Some(crate::utils::searchable_text(&indexed.raw_event, &fields));
+        let indexed = Event::create(&pool, indexed).await.unwrap();
+        let mut compressed = push_event();
+        compressed.searchable_text = Some(crate::utils::searchable_text(
+            &compressed.raw_event,
+            &fields,
+        ));
+        let compressed = Event::create_compressed(&pool, compressed).await.unwrap();
+        let unindexed = Event::create(&pool, push_event()).await.unwrap();
+
+        let ids = |term: &'static str| {
+            let pool = pool.clone();
+            async move {
+                let filters = EventFilterBuilder::new(FilterTable::Events).search(Some(term));
+                let mut ids: Vec<i64> = Event::search_and_filter(&pool, &filters, 10, 0)
+                    .await
+                    .unwrap()
+                    .iter()
+                    .map(|e| e.id)
+                    .collect();
+                ids.sort();
+                ids
+            }
+        };
+
+        // Indexed fields match, case-insensitively and by substring
+        assert_eq!(
+            ids("FLAKY DEP").await,
+            vec![indexed.id, compressed.id, unindexed.id]
+        );
+        // The rest of an indexed payload is noise; events stored without search fields
+        // are still searched in full
+        assert_eq!(ids("refs/heads").await, vec![unindexed.id]);
+        assert_eq!(ids("main").await, vec![unindexed.id]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_search_can_use_the_trigram_index(pool: sqlx::PgPool) {
+        let filters = EventFilterBuilder::new(FilterTable::Events).search(Some("flaky"));
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("SET LOCAL enable_seqscan = off")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        let plan: Vec<String> =
+            sqlx::query_scalar(&format!("EXPLAIN {}", filters.select_query().sql()))
+                .bind("%flaky%")
+                .bind("flaky")
+                .fetch_all(&mut *tx)
+                .await
+                .unwrap();
+
+        assert!(
+            plan.iter()
+                .any(|line| line.contains("idx_events_search_text_trgm")),
+            "{plan:#?}"
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_select_and_count_agree_for_same_filters(pool: sqlx::PgPool) {
+        for actor in ["octocat", "octocat", "hubot"] {
+            let mut data = push_event();
+            data.actor.name = Some(actor.to_string());
+            Event::create(&pool, data).await.unwrap();
+        }
+
+        let cases = [
+            EventFilterBuilder::new(FilterTable::Events),
+            EventFilterBuilder::new(FilterTable::Events).actor_name(Some("octocat")),
+            EventFilterBuilder::new(FilterTable::Events)
+                .source(Some("github"))
+                .search(Some("deploy")),
+            EventFilterBuilder::new(FilterTable::Events).processed(Some(true)),
+        ];
+
+        for filters in cases {
+            let events = Event::search_and_filter(&pool, &filters, 100, 0)
+                .await
+                .unwrap();
+            let count = Event::count_filtered(&pool, &filters).await.unwrap();
+            assert_eq!(events.len() as i64, count);
+        }
+    }
 }
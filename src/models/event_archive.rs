@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A contiguous range of events written to one archive object
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventArchive {
+    pub id: i64,
+    pub object_key: String,
+    pub first_event_id: i64,
+    pub last_event_id: i64,
+    pub event_count: i64,
+    pub cutoff: DateTime<Utc>,
+    /// Whether the range was deleted from the events table after upload
+    pub pruned: bool,
+    pub archived_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateEventArchive {
+    pub object_key: String,
+    pub first_event_id: i64,
+    pub last_event_id: i64,
+    pub event_count: i64,
+    pub cutoff: DateTime<Utc>,
+}
+
+impl EventArchive {
+    pub async fn record(
+        pool: &sqlx::PgPool,
+        data: CreateEventArchive,
+    ) -> Result<Self, sqlx::Error> {
+        let archive = sqlx::query_as::<_, EventArchive>(
+            r#"
+            INSERT INTO event_archives (object_key, first_event_id, last_event_id, event_count, cutoff)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(data.object_key)
+        .bind(data.first_event_id)
+        .bind(data.last_event_id)
+        .bind(data.event_count)
+        .bind(data.cutoff)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(archive)
+    }
+
+    /// Highest event id already archived, or 0 before the first archive
+    pub async fn last_archived_event_id(pool: &sqlx::PgPool) -> Result<i64, sqlx::Error> {
+        let last: (Option<i64>,) = sqlx::query_as("SELECT MAX(last_event_id) FROM event_archives")
+            .fetch_one(pool)
+            .await?;
+
+        Ok(last.0.unwrap_or(0))
+    }
+
+    pub async fn mark_pruned(pool: &sqlx::PgPool, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE event_archives SET pruned = TRUE WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_recent(pool: &sqlx::PgPool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        let archives = sqlx::query_as::<_, EventArchive>(
+            "SELECT * FROM event_archives ORDER BY last_event_id DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(archives)
+    }
+}
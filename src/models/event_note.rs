@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventNote {
+    pub id: i64,
+    pub event_id: i64,
+    pub note: String,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateEventNote {
+    pub event_id: i64,
+    pub note: String,
+    pub author: String,
+}
+
+impl EventNote {
+    pub async fn create(pool: &sqlx::PgPool, data: CreateEventNote) -> Result<Self, sqlx::Error> {
+        let note = sqlx::query_as::<_, EventNote>(
+            "INSERT INTO event_notes (event_id, note, author) VALUES ($1, $2, $3) RETURNING *",
+        )
+        .bind(data.event_id)
+        .bind(data.note)
+        .bind(data.author)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Notes for several events, oldest first so they read as a conversation
+    pub async fn list_for_events(
+        pool: &sqlx::PgPool,
+        event_ids: &[i64],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let notes = sqlx::query_as::<_, EventNote>(
+            "SELECT * FROM event_notes WHERE event_id = ANY($1) ORDER BY created_at, id",
+        )
+        .bind(event_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(notes)
+    }
+}
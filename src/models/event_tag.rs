@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::models::EventFilterBuilder;
+
+/// Longest tag accepted, matching the column width
+pub const MAX_TAG_LENGTH: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EventTag {
+    pub event_id: i64,
+    pub tag: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Trim a user-supplied tag, rejecting empty or overlong values
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+        return None;
+    }
+
+    Some(tag.to_string())
+}
+
+impl EventTag {
+    /// Tag an event; tagging it again with the same tag is a no-op
+    pub async fn add(pool: &sqlx::PgPool, event_id: i64, tag: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO event_tags (event_id, tag) VALUES ($1, $2) ON CONFLICT (event_id, tag) DO NOTHING",
+        )
+        .bind(event_id)
+        .bind(tag)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Tag every event matching `filters` in one statement, returning how many events
+    /// were newly tagged; events that already carry the tag are not counted
+    pub async fn add_matching(
+        pool: &sqlx::PgPool,
+        filters: &EventFilterBuilder,
+        tag: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = filters.tag_query(tag).build().execute(pool).await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn remove(pool: &sqlx::PgPool, event_id: i64, tag: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM event_tags WHERE event_id = $1 AND tag = $2")
+            .bind(event_id)
+            .bind(tag)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_for_events(
+        pool: &sqlx::PgPool,
+        event_ids: &[i64],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let tags = sqlx::query_as::<_, EventTag>(
+            "SELECT * FROM event_tags WHERE event_id = ANY($1) ORDER BY tag",
+        )
+        .bind(event_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// Every tag in use, for the events page filter
+    pub async fn list_distinct(pool: &sqlx::PgPool) -> Result<Vec<String>, sqlx::Error> {
+        let tags: Vec<(String,)> =
+            sqlx::query_as("SELECT DISTINCT tag FROM event_tags ORDER BY tag")
+                .fetch_all(pool)
+                .await?;
+
+        Ok(tags.into_iter().map(|(t,)| t).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(
+            normalize_tag("  false alarm "),
+            Some("false alarm".to_string())
+        );
+        assert_eq!(normalize_tag("   "), None);
+        assert_eq!(normalize_tag(&"x".repeat(MAX_TAG_LENGTH + 1)), None);
+    }
+}
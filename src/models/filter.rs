@@ -0,0 +1,295 @@
+use sqlx::{Postgres, QueryBuilder};
+
+/// Table an `EventFilterBuilder` queries, which decides how payloads are searched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterTable {
+    Events,
+    WebhookEvents,
+}
+
+impl FilterTable {
+    fn name(self) -> &'static str {
+        match self {
+            FilterTable::Events => "events",
+            FilterTable::WebhookEvents => "webhook_events",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Clause {
+    Text(&'static str, String),
+    AnyText(&'static str, Vec<String>),
+    Int(&'static str, i64),
+    Bool(&'static str, bool),
+    Search(String),
+    Tag(String),
+}
+
+/// Collects event filters once and renders the same WHERE clause for both the
+/// select and the count query, so paging totals always match the listed rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventFilterBuilder {
+    table: FilterTable,
+    clauses: Vec<Clause>,
+}
+
+impl EventFilterBuilder {
+    pub fn new(table: FilterTable) -> Self {
+        EventFilterBuilder {
+            table,
+            clauses: Vec::new(),
+        }
+    }
+
+    fn text(mut self, column: &'static str, value: Option<&str>) -> Self {
+        if let Some(value) = value {
+            self.clauses.push(Clause::Text(column, value.to_string()));
+        }
+        self
+    }
+
+    /// Match any value of a comma-separated list; blank entries are ignored
+    fn text_list(mut self, column: &'static str, list: Option<&str>) -> Self {
+        let mut values: Vec<String> = list
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        match values.len() {
+            0 => {}
+            1 => self.clauses.push(Clause::Text(column, values.remove(0))),
+            _ => self.clauses.push(Clause::AnyText(column, values)),
+        }
+        self
+    }
+
+    pub fn tenant(self, tenant: Option<&str>) -> Self {
+        self.text("tenant", tenant)
+    }
+
+    /// One source or a comma-separated list such as `github,gitlab`
+    pub fn source(self, source: Option<&str>) -> Self {
+        self.text_list("source", source)
+    }
+
+    /// One event type or a comma-separated list
+    pub fn event_type(self, event_type: Option<&str>) -> Self {
+        self.text_list("event_type", event_type)
+    }
+
+    /// Filter on the event action (`events.action` / `webhook_events.event_action`),
+    /// one action or a comma-separated list
+    pub fn action(self, action: Option<&str>) -> Self {
+        let column = match self.table {
+            FilterTable::Events => "action",
+            FilterTable::WebhookEvents => "event_action",
+        };
+        self.text_list(column, action)
+    }
+
+    pub fn actor_name(self, actor_name: Option<&str>) -> Self {
+        self.text("actor_name", actor_name)
+    }
+
+    pub fn repository_id(mut self, repository_id: Option<i64>) -> Self {
+        if let Some(id) = repository_id {
+            self.clauses.push(Clause::Int("repository_id", id));
+        }
+        self
+    }
+
+    pub fn processed(mut self, processed: Option<bool>) -> Self {
+        if let Some(processed) = processed {
+            self.clauses.push(Clause::Bool("processed", processed));
+        }
+        self
+    }
+
+    /// Substring search over the payload, or over its `searchable_text` when the event
+    /// was stored with one; empty terms are ignored
+    pub fn search(mut self, search: Option<&str>) -> Self {
+        if let Some(term) = search.filter(|s| !s.is_empty()) {
+            self.clauses.push(Clause::Search(term.to_string()));
+        }
+        self
+    }
+
+    /// Only events carrying this tag; tags exist for the `events` table only
+    pub fn tag(mut self, tag: Option<&str>) -> Self {
+        if let Some(tag) = tag {
+            self.clauses.push(Clause::Tag(tag.to_string()));
+        }
+        self
+    }
+
+    /// `SELECT * FROM <table> WHERE ...`, ready for ordering and paging
+    pub fn select_query(&self) -> QueryBuilder<'static, Postgres> {
+        self.query("SELECT *")
+    }
+
+    /// `SELECT COUNT(*) FROM <table> WHERE ...`
+    pub fn count_query(&self) -> QueryBuilder<'static, Postgres> {
+        self.query("SELECT COUNT(*)")
+    }
+
+    /// `INSERT INTO event_tags` of `tag` for every matching event, skipping events that
+    /// already carry it; tags exist for the `events` table only
+    pub fn tag_query(&self, tag: &str) -> QueryBuilder<'static, Postgres> {
+        let mut query = QueryBuilder::new("INSERT INTO event_tags (event_id, tag) SELECT id, ");
+        query.push_bind(tag.to_string()).push(" FROM events");
+        self.push_where(&mut query);
+        query.push(" ON CONFLICT (event_id, tag) DO NOTHING");
+        query
+    }
+
+    fn query(&self, select: &str) -> QueryBuilder<'static, Postgres> {
+        let mut query = QueryBuilder::new(format!("{select} FROM {}", self.table.name()));
+        self.push_where(&mut query);
+        query
+    }
+
+    fn push_where(&self, query: &mut QueryBuilder<'static, Postgres>) {
+        query.push(" WHERE 1=1");
+
+        for clause in &self.clauses {
+            match clause {
+                Clause::Text(column, value) => {
+                    query
+                        .push(format!(" AND {column} = "))
+                        .push_bind(value.clone());
+                }
+                Clause::AnyText(column, values) => {
+                    query
+                        .push(format!(" AND {column} = ANY("))
+                        .push_bind(values.clone())
+                        .push(")");
+                }
+                Clause::Int(column, value) => {
+                    query.push(format!(" AND {column} = ")).push_bind(*value);
+                }
+                Clause::Bool(column, value) => {
+                    query.push(format!(" AND {column} = ")).push_bind(*value);
+                }
+                Clause::Search(term) => match self.table {
+                    // Rows stored with `SEARCH_FIELDS` match on those values alone, without
+                    // serializing the payload; compressed rows without them are only
+                    // searchable by whole words via their tsvector
+                    FilterTable::Events => {
+                        query
+                            .push(" AND (COALESCE(searchable_text, raw_event::text) ILIKE ")
+                            .push_bind(format!("%{term}%"))
+                            .push(" OR (searchable_text IS NULL AND raw_event_search @@ plainto_tsquery('simple', ")
+                            .push_bind(term.clone())
+                            .push(")))");
+                    }
+                    FilterTable::WebhookEvents => {
+                        query
+                            .push(" AND payload::text ILIKE ")
+                            .push_bind(format!("%{term}%"));
+                    }
+                },
+                Clause::Tag(tag) => {
+                    query
+                        .push(" AND EXISTS (SELECT 1 FROM event_tags WHERE event_tags.event_id = events.id AND event_tags.tag = ")
+                        .push_bind(tag.clone())
+                        .push(")");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn where_clause(query: &QueryBuilder<'static, Postgres>) -> String {
+        let sql = query.sql();
+        sql[sql.find(" WHERE ").unwrap()..].to_string()
+    }
+
+    #[test]
+    fn test_select_and_count_share_where_clause() {
+        let filters = EventFilterBuilder::new(FilterTable::Events)
+            .tenant(Some("acme"))
+            .source(Some("github"))
+            .processed(Some(false))
+            .search(Some("main"))
+            .tag(Some("flaky"));
+
+        let select = filters.select_query();
+        let count = filters.count_query();
+
+        assert!(select.sql().starts_with("SELECT * FROM events WHERE"));
+        assert!(count.sql().starts_with("SELECT COUNT(*) FROM events WHERE"));
+        assert_eq!(where_clause(&select), where_clause(&count));
+        assert!(where_clause(&select).contains("event_tags.tag = $6"));
+    }
+
+    #[test]
+    fn test_tag_query_binds_tag_before_filters() {
+        let filters = EventFilterBuilder::new(FilterTable::Events)
+            .source(Some("github"))
+            .processed(Some(false));
+
+        assert_eq!(
+            filters.tag_query("triaged").sql(),
+            "INSERT INTO event_tags (event_id, tag) SELECT id, $1 FROM events WHERE 1=1 AND source = $2 AND processed = $3 ON CONFLICT (event_id, tag) DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn test_unset_and_empty_filters_add_no_clauses() {
+        let filters = EventFilterBuilder::new(FilterTable::WebhookEvents)
+            .event_type(None)
+            .repository_id(None)
+            .search(Some(""));
+
+        assert_eq!(
+            filters.select_query().sql(),
+            "SELECT * FROM webhook_events WHERE 1=1"
+        );
+    }
+
+    #[test]
+    fn test_columns_follow_table() {
+        let filters = EventFilterBuilder::new(FilterTable::WebhookEvents)
+            .action(Some("opened"))
+            .search(Some("bug"));
+
+        assert_eq!(
+            filters.count_query().sql(),
+            "SELECT COUNT(*) FROM webhook_events WHERE 1=1 AND event_action = $1 AND payload::text ILIKE $2"
+        );
+    }
+
+    #[test]
+    fn test_single_value_lists_compare_with_equals() {
+        let filters = EventFilterBuilder::new(FilterTable::Events)
+            .source(Some("github"))
+            .event_type(Some(" push ,"))
+            .action(Some(""));
+
+        assert_eq!(
+            filters.count_query().sql(),
+            "SELECT COUNT(*) FROM events WHERE 1=1 AND source = $1 AND event_type = $2"
+        );
+    }
+
+    #[test]
+    fn test_multiple_values_bind_one_array() {
+        let filters = EventFilterBuilder::new(FilterTable::Events)
+            .source(Some("github, gitlab"))
+            .event_type(Some("push,pull_request,issues"))
+            .processed(Some(true));
+
+        assert_eq!(
+            filters.count_query().sql(),
+            "SELECT COUNT(*) FROM events WHERE 1=1 AND source = ANY($1) AND event_type = ANY($2) AND processed = $3"
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use sqlx::FromRow;
 
+/// Shortest abbreviated SHA accepted by `Commit::find_by_sha`
+pub const MIN_SHA_PREFIX: usize = 4;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Commit {
     pub id: i64,
@@ -16,6 +20,37 @@ pub struct Commit {
     pub committed_at: DateTime<Utc>,
     pub url: String,
     pub created_at: DateTime<Utc>,
+    /// Branch the commit was first pushed to
+    pub branch: Option<String>,
+    /// Pull request opened from `branch`
+    pub pull_request_id: Option<i64>,
+    /// Whether the commit changed one of the repository's watched paths
+    pub touches_watched: Option<bool>,
+}
+
+/// Number of files touched by a commit, as reported in the push payload
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FileChangeCounts {
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+}
+
+impl FileChangeCounts {
+    /// Counts for `sha` from the `commits` array of a GitHub push payload
+    pub fn from_push_payload(payload: &JsonValue, sha: &str) -> Option<Self> {
+        let commit = payload["commits"]
+            .as_array()?
+            .iter()
+            .find(|c| c["id"].as_str() == Some(sha))?;
+        let count = |key: &str| commit[key].as_array().map_or(0, |files| files.len());
+
+        Some(FileChangeCounts {
+            added: count("added"),
+            removed: count("removed"),
+            modified: count("modified"),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,14 +65,16 @@ pub struct CreateCommit {
     pub committer_email: String,
     pub committed_at: DateTime<Utc>,
     pub url: String,
+    pub branch: Option<String>,
+    pub touches_watched: Option<bool>,
 }
 
 impl Commit {
     pub async fn create(pool: &sqlx::PgPool, data: CreateCommit) -> Result<Self, sqlx::Error> {
         let commit = sqlx::query_as::<_, Commit>(
             r#"
-            INSERT INTO commits (repository_id, webhook_event_id, sha, message, author_name, author_email, committer_name, committer_email, committed_at, url)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO commits (repository_id, webhook_event_id, sha, message, author_name, author_email, committer_name, committer_email, committed_at, url, branch, touches_watched)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             ON CONFLICT (sha, repository_id) DO UPDATE
             SET message = EXCLUDED.message,
                 author_name = EXCLUDED.author_name,
@@ -45,7 +82,10 @@ impl Commit {
                 committer_name = EXCLUDED.committer_name,
                 committer_email = EXCLUDED.committer_email,
                 committed_at = EXCLUDED.committed_at,
-                url = EXCLUDED.url
+                url = EXCLUDED.url,
+                -- A merge pushes the same commit to the base branch; keep the branch it was made on
+                branch = COALESCE(commits.branch, EXCLUDED.branch),
+                touches_watched = COALESCE(EXCLUDED.touches_watched, commits.touches_watched)
             RETURNING *
             "#,
         )
@@ -59,6 +99,8 @@ impl Commit {
         .bind(data.committer_email)
         .bind(data.committed_at)
         .bind(data.url)
+        .bind(data.branch)
+        .bind(data.touches_watched)
         .fetch_one(pool)
         .await?;
 
@@ -72,9 +114,44 @@ impl Commit {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let commits = sqlx::query_as::<_, Commit>(
-            "SELECT * FROM commits WHERE repository_id = $1 ORDER BY committed_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM commits WHERE repository_id = $1 ORDER BY committed_at DESC, id DESC LIMIT $2 OFFSET $3",
+        )
+        .bind(repository_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(commits)
+    }
+
+    /// Commits committed in `[from, to)`, newest first; a missing bound is unbounded.
+    ///
+    /// With `watched_only`, only commits known to touch a watched path are listed.
+    pub async fn list_by_repository_between(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        watched_only: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let commits = sqlx::query_as::<_, Commit>(
+            r#"
+            SELECT * FROM commits
+            WHERE repository_id = $1
+              AND ($2::timestamptz IS NULL OR committed_at >= $2)
+              AND ($3::timestamptz IS NULL OR committed_at < $3)
+              AND (NOT $4 OR touches_watched IS TRUE)
+            ORDER BY committed_at DESC, id DESC
+            LIMIT $5 OFFSET $6
+            "#,
         )
         .bind(repository_id)
+        .bind(from)
+        .bind(to)
+        .bind(watched_only)
         .bind(limit)
         .bind(offset)
         .fetch_all(pool)
@@ -90,7 +167,7 @@ impl Commit {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let commits = sqlx::query_as::<_, Commit>(
-            "SELECT * FROM commits ORDER BY committed_at DESC LIMIT $1 OFFSET $2",
+            "SELECT * FROM commits ORDER BY committed_at DESC, id DESC LIMIT $1 OFFSET $2",
         )
         .bind(limit)
         .bind(offset)
@@ -108,7 +185,7 @@ impl Commit {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let commits = sqlx::query_as::<_, Commit>(
-            "SELECT * FROM commits WHERE author_email = $1 ORDER BY committed_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM commits WHERE author_email = $1 ORDER BY committed_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(author_email)
         .bind(limit)
@@ -138,4 +215,231 @@ impl Commit {
 
         Ok(count.0)
     }
+
+    /// Attach the repository's unassociated commits on `branch` to a pull request,
+    /// returning how many were attached
+    pub async fn associate_branch_with_pull_request(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+        branch: &str,
+        pull_request_id: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE commits SET pull_request_id = $3 WHERE repository_id = $1 AND branch = $2 AND pull_request_id IS NULL",
+        )
+        .bind(repository_id)
+        .bind(branch)
+        .bind(pull_request_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Look up a commit by full SHA, or by an abbreviated SHA that matches exactly one commit
+    pub async fn find_by_sha(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+        sha: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let sha = sha.to_ascii_lowercase();
+        if sha.len() < MIN_SHA_PREFIX || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(None);
+        }
+
+        // Hex-only input cannot contain LIKE wildcards; an exact match sorts first
+        let matches = sqlx::query_as::<_, Commit>(
+            "SELECT * FROM commits WHERE repository_id = $1 AND sha LIKE $2 || '%' ORDER BY sha = $2 DESC LIMIT 2",
+        )
+        .bind(repository_id)
+        .bind(&sha)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(match matches.as_slice() {
+            [commit] => Some(commit.clone()),
+            [first, _] if first.sha == sha => Some(first.clone()),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::github::CreateRepository;
+    use crate::models::{CreateWebhookEvent, Repository, WebhookEvent};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_file_change_counts_from_push_payload() {
+        let payload = json!({
+            "commits": [
+                {"id": "aaa", "added": ["a.rs"], "removed": [], "modified": ["b.rs", "c.rs"]},
+                {"id": "bbb", "added": [], "removed": ["d.rs"], "modified": []}
+            ]
+        });
+
+        assert_eq!(
+            FileChangeCounts::from_push_payload(&payload, "aaa"),
+            Some(FileChangeCounts {
+                added: 1,
+                removed: 0,
+                modified: 2,
+            })
+        );
+        assert_eq!(FileChangeCounts::from_push_payload(&payload, "ccc"), None);
+    }
+
+    /// A repository and the push event its test commits point at
+    async fn seed_repository(pool: &sqlx::PgPool) -> (Repository, WebhookEvent) {
+        let repo = Repository::create(
+            pool,
+            CreateRepository {
+                github_id: 1296269,
+                name: "Hello-World".to_string(),
+                full_name: "octocat/Hello-World".to_string(),
+                owner: "octocat".to_string(),
+                description: None,
+                url: "https://github.com/octocat/Hello-World".to_string(),
+                is_private: false,
+                language: None,
+                topics: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let webhook_event = WebhookEvent::create(
+            pool,
+            CreateWebhookEvent {
+                repository_id: Some(repo.id),
+                event_type: "push".to_string(),
+                event_action: None,
+                delivery_id: Uuid::new_v4(),
+                payload: json!({}),
+                signature: "sha256=abc".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        (repo, webhook_event)
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_find_by_sha_exact_and_prefix(pool: sqlx::PgPool) {
+        let (repo, webhook_event) = seed_repository(&pool).await;
+
+        for sha in [
+            "abc1234def5678900000000000000000000000aa",
+            "abc1234def5678900000000000000000000000bb",
+            "fed9876000000000000000000000000000000000",
+        ] {
+            Commit::create(
+                &pool,
+                CreateCommit {
+                    repository_id: repo.id,
+                    webhook_event_id: webhook_event.id,
+                    sha: sha.to_string(),
+                    message: "Update README".to_string(),
+                    author_name: "Monalisa Octocat".to_string(),
+                    author_email: "mona@github.com".to_string(),
+                    committer_name: "Monalisa Octocat".to_string(),
+                    committer_email: "mona@github.com".to_string(),
+                    committed_at: Utc::now(),
+                    url: format!("https://github.com/octocat/Hello-World/commit/{sha}"),
+                    branch: None,
+                    touches_watched: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let find = |sha: &'static str| Commit::find_by_sha(&pool, repo.id, sha);
+
+        let exact = find("abc1234def5678900000000000000000000000bb")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(exact.sha, "abc1234def5678900000000000000000000000bb");
+
+        let prefix = find("FED9876").await.unwrap().unwrap();
+        assert_eq!(prefix.sha, "fed9876000000000000000000000000000000000");
+
+        // Ambiguous, too short, and non-hex prefixes resolve to nothing
+        assert!(find("abc1234").await.unwrap().is_none());
+        assert!(find("fed").await.unwrap().is_none());
+        assert!(find("fed9%").await.unwrap().is_none());
+        assert!(Commit::find_by_sha(&pool, repo.id + 1, "fed9876")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_list_by_repository_between(pool: sqlx::PgPool) {
+        let (repo, webhook_event) = seed_repository(&pool).await;
+        let day = |d: u32| {
+            DateTime::parse_from_rfc3339(&format!("2024-03-{d:02}T12:00:00Z"))
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        for d in [1, 5, 9] {
+            Commit::create(
+                &pool,
+                CreateCommit {
+                    repository_id: repo.id,
+                    webhook_event_id: webhook_event.id,
+                    sha: format!("{d:040x}"),
+                    message: format!("Commit on day {d}"),
+                    author_name: "Monalisa Octocat".to_string(),
+                    author_email: "mona@github.com".to_string(),
+                    committer_name: "Monalisa Octocat".to_string(),
+                    committer_email: "mona@github.com".to_string(),
+                    committed_at: day(d),
+                    url: format!("https://github.com/octocat/Hello-World/commit/{d}"),
+                    branch: None,
+                    // Day 1 predates the watched paths, day 5 touches them and day 9 does not
+                    touches_watched: (d != 1).then_some(d == 5),
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let between =
+            |from, to| Commit::list_by_repository_between(&pool, repo.id, from, to, false, 10, 0);
+        let messages = |commits: Vec<Commit>| -> Vec<String> {
+            commits.into_iter().map(|c| c.message).collect()
+        };
+
+        assert_eq!(
+            messages(between(None, None).await.unwrap()),
+            vec!["Commit on day 9", "Commit on day 5", "Commit on day 1"]
+        );
+        // The lower bound is inclusive and the upper bound exclusive
+        assert_eq!(
+            messages(between(Some(day(5)), Some(day(9))).await.unwrap()),
+            vec!["Commit on day 5"]
+        );
+        assert_eq!(
+            messages(between(Some(day(2)), None).await.unwrap()),
+            vec!["Commit on day 9", "Commit on day 5"]
+        );
+        assert_eq!(
+            messages(between(None, Some(day(5))).await.unwrap()),
+            vec!["Commit on day 1"]
+        );
+
+        let watched = Commit::list_by_repository_between(&pool, repo.id, None, None, true, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(messages(watched), vec!["Commit on day 5"]);
+    }
 }
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A deployment of a ref to an environment, with the latest status reported for it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Deployment {
+    pub id: i64,
+    pub repository_id: i64,
+    pub event_id: Option<i64>,
+    pub github_id: i64,
+    pub environment: String,
+    pub git_ref: String,
+    pub sha: String,
+    pub creator: String,
+    /// Latest `deployment_status` state (`pending`, `in_progress`, `success`, `failure`, ...);
+    /// `None` until the first status arrives
+    pub state: Option<String>,
+    /// Environment or log URL from the latest status
+    pub url: Option<String>,
+    pub deployed_at: DateTime<Utc>,
+    pub status_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDeployment {
+    pub repository_id: i64,
+    pub event_id: i64,
+    pub github_id: i64,
+    pub environment: String,
+    pub git_ref: String,
+    pub sha: String,
+    pub creator: String,
+    pub deployed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentStatus {
+    pub state: String,
+    pub url: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl Deployment {
+    /// Insert a deployment, or refresh its details when `github_id` is already known.
+    /// The latest status is left as it is.
+    pub async fn create(pool: &sqlx::PgPool, data: CreateDeployment) -> Result<Self, sqlx::Error> {
+        let deployment = sqlx::query_as::<_, Deployment>(
+            r#"
+            INSERT INTO deployments (repository_id, event_id, github_id, environment, git_ref, sha, creator, deployed_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (github_id) DO UPDATE
+            SET environment = EXCLUDED.environment,
+                git_ref = EXCLUDED.git_ref,
+                sha = EXCLUDED.sha,
+                creator = EXCLUDED.creator,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(data.repository_id)
+        .bind(data.event_id)
+        .bind(data.github_id)
+        .bind(data.environment)
+        .bind(data.git_ref)
+        .bind(data.sha)
+        .bind(data.creator)
+        .bind(data.deployed_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(deployment)
+    }
+
+    /// Apply a status to the deployment with `github_id`. Statuses older than the one
+    /// already applied are ignored, so late redeliveries don't roll the state back;
+    /// returns `None` when the status was ignored.
+    pub async fn record_status(
+        pool: &sqlx::PgPool,
+        github_id: i64,
+        status: DeploymentStatus,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let deployment = sqlx::query_as::<_, Deployment>(
+            r#"
+            UPDATE deployments
+            SET state = $2, url = COALESCE($3, url), status_at = $4, updated_at = NOW()
+            WHERE github_id = $1 AND (status_at IS NULL OR status_at <= $4)
+            RETURNING *
+            "#,
+        )
+        .bind(github_id)
+        .bind(status.state)
+        .bind(status.url)
+        .bind(status.at)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(deployment)
+    }
+
+    pub async fn find_by_github_id(
+        pool: &sqlx::PgPool,
+        github_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let deployment =
+            sqlx::query_as::<_, Deployment>("SELECT * FROM deployments WHERE github_id = $1")
+                .bind(github_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(deployment)
+    }
+
+    /// Most recent deployments of a repository, newest first
+    pub async fn list_by_repository(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let deployments = sqlx::query_as::<_, Deployment>(
+            "SELECT * FROM deployments WHERE repository_id = $1 ORDER BY deployed_at DESC, id DESC LIMIT $2",
+        )
+        .bind(repository_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(deployments)
+    }
+}
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::models::FieldChange;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Issue {
     pub id: i64,
@@ -76,7 +78,7 @@ impl Issue {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let issues = sqlx::query_as::<_, Issue>(
-            "SELECT * FROM issues WHERE repository_id = $1 ORDER BY opened_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM issues WHERE repository_id = $1 ORDER BY opened_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(repository_id)
         .bind(limit)
@@ -94,7 +96,7 @@ impl Issue {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let issues = sqlx::query_as::<_, Issue>(
-            "SELECT * FROM issues ORDER BY opened_at DESC LIMIT $1 OFFSET $2",
+            "SELECT * FROM issues ORDER BY opened_at DESC, id DESC LIMIT $1 OFFSET $2",
         )
         .bind(limit)
         .bind(offset)
@@ -112,7 +114,7 @@ impl Issue {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let issues = sqlx::query_as::<_, Issue>(
-            "SELECT * FROM issues WHERE state = $1 ORDER BY opened_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM issues WHERE state = $1 ORDER BY opened_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(state)
         .bind(limit)
@@ -139,4 +141,104 @@ impl Issue {
 
         Ok(count.0)
     }
+
+    pub async fn count_by_repository(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+    ) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM issues WHERE repository_id = $1")
+            .bind(repository_id)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(count.0)
+    }
+
+    pub async fn find_by_github_id(
+        pool: &sqlx::PgPool,
+        github_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as::<_, Issue>("SELECT * FROM issues WHERE github_id = $1")
+            .bind(github_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    /// Fields that differ between the stored row and an incoming update
+    pub fn diff(&self, incoming: &CreateIssue) -> Vec<FieldChange> {
+        [
+            FieldChange::compare(
+                "title",
+                Some(self.title.clone()),
+                Some(incoming.title.clone()),
+            ),
+            FieldChange::compare(
+                "state",
+                Some(self.state.clone()),
+                Some(incoming.state.clone()),
+            ),
+            FieldChange::compare(
+                "labels",
+                Some(self.labels.join(", ")),
+                Some(incoming.labels.join(", ")),
+            ),
+            FieldChange::compare(
+                "closed_at",
+                self.closed_at.map(|t| t.to_rfc3339()),
+                incoming.closed_at.map(|t| t.to_rfc3339()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_transition_is_detected() {
+        let now = Utc::now();
+        let issue = Issue {
+            id: 1,
+            repository_id: 1,
+            webhook_event_id: 1,
+            github_id: 42,
+            number: 3,
+            title: "Crash on startup".to_string(),
+            state: "open".to_string(),
+            author: "octocat".to_string(),
+            labels: vec!["bug".to_string()],
+            url: "https://github.com/octocat/Hello-World/issues/3".to_string(),
+            opened_at: now,
+            closed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let update = CreateIssue {
+            repository_id: 1,
+            webhook_event_id: 2,
+            github_id: 42,
+            number: 3,
+            title: "Crash on startup".to_string(),
+            state: "closed".to_string(),
+            author: "octocat".to_string(),
+            labels: vec!["bug".to_string()],
+            url: issue.url.clone(),
+            opened_at: now,
+            closed_at: Some(now),
+        };
+
+        let changes = issue.diff(&update);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].field, "state");
+        assert_eq!(changes[0].old_value.as_deref(), Some("open"));
+        assert_eq!(changes[0].new_value.as_deref(), Some("closed"));
+        assert_eq!(changes[1].field, "closed_at");
+    }
 }
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One change to who belongs to a repository, team or organization. Rows are only
+/// ever inserted, and are kept when their event is pruned.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MembershipEvent {
+    pub id: i64,
+    pub event_id: Option<i64>,
+    /// GitHub's action, e.g. `added`, `removed` or `member_invited`
+    pub action: String,
+    /// Login (or invited email) of the member whose access changed
+    pub member: String,
+    /// `repository`, `team` or `organization`
+    pub scope_type: String,
+    /// Repository full name, team slug or organization login
+    pub scope: String,
+    pub organization: Option<String>,
+    /// Who made the change
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMembershipEvent {
+    pub event_id: i64,
+    pub action: String,
+    pub member: String,
+    pub scope_type: String,
+    pub scope: String,
+    pub organization: Option<String>,
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+impl MembershipEvent {
+    /// Append a membership change; returns `None` when the event was already recorded
+    pub async fn record(
+        pool: &sqlx::PgPool,
+        data: CreateMembershipEvent,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let membership = sqlx::query_as::<_, MembershipEvent>(
+            r#"
+            INSERT INTO membership_events (event_id, action, member, scope_type, scope, organization, actor, at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (event_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(data.event_id)
+        .bind(data.action)
+        .bind(data.member)
+        .bind(data.scope_type)
+        .bind(data.scope)
+        .bind(data.organization)
+        .bind(data.actor)
+        .bind(data.at)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(membership)
+    }
+
+    /// Most recent changes, newest first, optionally only those of one member
+    pub async fn list_recent(
+        pool: &sqlx::PgPool,
+        member: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let memberships = sqlx::query_as::<_, MembershipEvent>(
+            r#"
+            SELECT * FROM membership_events
+            WHERE $1::TEXT IS NULL OR member = $1
+            ORDER BY at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(member)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(memberships)
+    }
+}
@@ -1,9 +1,17 @@
 pub mod commit;
+pub mod deployment;
 pub mod issue;
+pub mod membership_event;
 pub mod pull_request;
 pub mod repository;
+pub mod repository_rename;
+pub mod repository_star;
 
-pub use commit::{Commit, CreateCommit};
+pub use commit::{Commit, CreateCommit, FileChangeCounts};
+pub use deployment::{CreateDeployment, Deployment, DeploymentStatus};
 pub use issue::{CreateIssue, Issue};
+pub use membership_event::{CreateMembershipEvent, MembershipEvent};
 pub use pull_request::{CreatePullRequest, PullRequest};
 pub use repository::{CreateRepository, Repository};
+pub use repository_rename::{CreateRepositoryRename, RepositoryRename};
+pub use repository_star::{CreateRepositoryStar, RepositoryStar};
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::models::FieldChange;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct PullRequest {
     pub id: i64,
@@ -84,7 +86,7 @@ impl PullRequest {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let prs = sqlx::query_as::<_, PullRequest>(
-            "SELECT * FROM pull_requests WHERE repository_id = $1 ORDER BY opened_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM pull_requests WHERE repository_id = $1 ORDER BY opened_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(repository_id)
         .bind(limit)
@@ -102,7 +104,7 @@ impl PullRequest {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let prs = sqlx::query_as::<_, PullRequest>(
-            "SELECT * FROM pull_requests ORDER BY opened_at DESC LIMIT $1 OFFSET $2",
+            "SELECT * FROM pull_requests ORDER BY opened_at DESC, id DESC LIMIT $1 OFFSET $2",
         )
         .bind(limit)
         .bind(offset)
@@ -120,7 +122,7 @@ impl PullRequest {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let prs = sqlx::query_as::<_, PullRequest>(
-            "SELECT * FROM pull_requests WHERE state = $1 ORDER BY opened_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM pull_requests WHERE state = $1 ORDER BY opened_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(state)
         .bind(limit)
@@ -147,4 +149,154 @@ impl PullRequest {
 
         Ok(count.0)
     }
+
+    pub async fn count_by_repository(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+    ) -> Result<i64, sqlx::Error> {
+        let count: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM pull_requests WHERE repository_id = $1")
+                .bind(repository_id)
+                .fetch_one(pool)
+                .await?;
+
+        Ok(count.0)
+    }
+
+    pub async fn list_by_ids(pool: &sqlx::PgPool, ids: &[i64]) -> Result<Vec<Self>, sqlx::Error> {
+        let prs =
+            sqlx::query_as::<_, PullRequest>("SELECT * FROM pull_requests WHERE id = ANY($1)")
+                .bind(ids)
+                .fetch_all(pool)
+                .await?;
+
+        Ok(prs)
+    }
+
+    pub async fn find_by_github_id(
+        pool: &sqlx::PgPool,
+        github_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row =
+            sqlx::query_as::<_, PullRequest>("SELECT * FROM pull_requests WHERE github_id = $1")
+                .bind(github_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(row)
+    }
+
+    /// Fields that differ between the stored row and an incoming update
+    pub fn diff(&self, incoming: &CreatePullRequest) -> Vec<FieldChange> {
+        [
+            FieldChange::compare(
+                "title",
+                Some(self.title.clone()),
+                Some(incoming.title.clone()),
+            ),
+            FieldChange::compare(
+                "state",
+                Some(self.state.clone()),
+                Some(incoming.state.clone()),
+            ),
+            FieldChange::compare(
+                "base_branch",
+                Some(self.base_branch.clone()),
+                Some(incoming.base_branch.clone()),
+            ),
+            FieldChange::compare(
+                "closed_at",
+                self.closed_at.map(|t| t.to_rfc3339()),
+                incoming.closed_at.map(|t| t.to_rfc3339()),
+            ),
+            FieldChange::compare(
+                "merged_at",
+                self.merged_at.map(|t| t.to_rfc3339()),
+                incoming.merged_at.map(|t| t.to_rfc3339()),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored() -> PullRequest {
+        let now = Utc::now();
+        PullRequest {
+            id: 1,
+            repository_id: 1,
+            webhook_event_id: 1,
+            github_id: 42,
+            number: 7,
+            title: "Add feature".to_string(),
+            state: "open".to_string(),
+            author: "octocat".to_string(),
+            base_branch: "main".to_string(),
+            head_branch: "feature".to_string(),
+            url: "https://github.com/octocat/Hello-World/pull/7".to_string(),
+            opened_at: now,
+            closed_at: None,
+            merged_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn incoming(pr: &PullRequest) -> CreatePullRequest {
+        CreatePullRequest {
+            repository_id: pr.repository_id,
+            webhook_event_id: 2,
+            github_id: pr.github_id,
+            number: pr.number,
+            title: pr.title.clone(),
+            state: pr.state.clone(),
+            author: pr.author.clone(),
+            base_branch: pr.base_branch.clone(),
+            head_branch: pr.head_branch.clone(),
+            url: pr.url.clone(),
+            opened_at: pr.opened_at,
+            closed_at: pr.closed_at,
+            merged_at: pr.merged_at,
+        }
+    }
+
+    #[test]
+    fn test_redelivery_has_no_changes() {
+        let pr = stored();
+        assert!(pr.diff(&incoming(&pr)).is_empty());
+    }
+
+    #[test]
+    fn test_title_edit_is_detected() {
+        let pr = stored();
+        let mut update = incoming(&pr);
+        update.title = "Add feature flag".to_string();
+
+        assert_eq!(
+            pr.diff(&update),
+            vec![FieldChange {
+                field: "title".to_string(),
+                old_value: Some("Add feature".to_string()),
+                new_value: Some("Add feature flag".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_records_state_transition() {
+        let pr = stored();
+        let mut update = incoming(&pr);
+        let merged_at = Utc::now();
+        update.state = "closed".to_string();
+        update.closed_at = Some(merged_at);
+        update.merged_at = Some(merged_at);
+
+        let fields: Vec<_> = pr.diff(&update).into_iter().map(|c| c.field).collect();
+        assert_eq!(fields, vec!["state", "closed_at", "merged_at"]);
+    }
 }
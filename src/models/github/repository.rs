@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, Row};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Repository {
@@ -12,11 +12,25 @@ pub struct Repository {
     pub description: Option<String>,
     pub url: String,
     pub is_private: bool,
+    pub stars: i32,
+    pub language: Option<String>,
+    pub topics: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Path globs marking the commits worth following
+    pub watched_paths: Vec<String>,
+    /// Drop pushed commits that touch no watched path instead of storing them
+    pub skip_unwatched_commits: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Number of events received for a repository on one day
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ActivityBucket {
+    pub day: DateTime<Utc>,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateRepository {
     pub github_id: i64,
     pub name: String,
@@ -25,14 +39,29 @@ pub struct CreateRepository {
     pub description: Option<String>,
     pub url: String,
     pub is_private: bool,
+    pub language: Option<String>,
+    pub topics: Vec<String>,
 }
 
 impl Repository {
     pub async fn create(pool: &sqlx::PgPool, data: CreateRepository) -> Result<Self, sqlx::Error> {
-        let repo = sqlx::query_as::<_, Repository>(
+        let (repo, _) = Self::upsert_returning_created(pool, data).await?;
+
+        Ok(repo)
+    }
+
+    /// Insert or update the repository, reporting `true` when the row was newly inserted.
+    ///
+    /// A row written by this statement's insert has `xmax = 0`; an updated row carries the
+    /// updating transaction's id.
+    pub async fn upsert_returning_created(
+        pool: &sqlx::PgPool,
+        data: CreateRepository,
+    ) -> Result<(Self, bool), sqlx::Error> {
+        let row = sqlx::query(
             r#"
-            INSERT INTO repositories (github_id, name, full_name, owner, description, url, is_private)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO repositories (github_id, name, full_name, owner, description, url, is_private, language, topics)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             ON CONFLICT (github_id) DO UPDATE
             SET name = EXCLUDED.name,
                 full_name = EXCLUDED.full_name,
@@ -40,8 +69,10 @@ impl Repository {
                 description = EXCLUDED.description,
                 url = EXCLUDED.url,
                 is_private = EXCLUDED.is_private,
+                language = EXCLUDED.language,
+                topics = EXCLUDED.topics,
                 updated_at = NOW()
-            RETURNING *
+            RETURNING *, (xmax = 0) AS inserted
             "#,
         )
         .bind(data.github_id)
@@ -51,10 +82,12 @@ impl Repository {
         .bind(data.description)
         .bind(data.url)
         .bind(data.is_private)
+        .bind(data.language)
+        .bind(data.topics)
         .fetch_one(pool)
         .await?;
 
-        Ok(repo)
+        Ok((Repository::from_row(&row)?, row.try_get("inserted")?))
     }
 
     pub async fn find_by_id(pool: &sqlx::PgPool, id: i64) -> Result<Option<Self>, sqlx::Error> {
@@ -66,6 +99,30 @@ impl Repository {
         Ok(repo)
     }
 
+    /// Replace the watched path globs, returning the updated repository if it exists
+    pub async fn set_watched_paths(
+        pool: &sqlx::PgPool,
+        id: i64,
+        watched_paths: &[String],
+        skip_unwatched_commits: bool,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let repo = sqlx::query_as::<_, Repository>(
+            r#"
+            UPDATE repositories
+            SET watched_paths = $2, skip_unwatched_commits = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(watched_paths)
+        .bind(skip_unwatched_commits)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(repo)
+    }
+
     pub async fn find_by_full_name(
         pool: &sqlx::PgPool,
         full_name: &str,
@@ -79,13 +136,40 @@ impl Repository {
         Ok(repo)
     }
 
+    /// Look up by GitHub's stable id, which survives renames
+    pub async fn find_by_github_id(
+        pool: &sqlx::PgPool,
+        github_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let repo =
+            sqlx::query_as::<_, Repository>("SELECT * FROM repositories WHERE github_id = $1")
+                .bind(github_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(repo)
+    }
+
+    /// The repository a payload refers to, by GitHub id so a rename whose new
+    /// `full_name` is not stored yet still matches, falling back to `full_name`
+    pub async fn find_by_github_id_or_full_name(
+        pool: &sqlx::PgPool,
+        github_id: i64,
+        full_name: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        match Self::find_by_github_id(pool, github_id).await? {
+            Some(repo) => Ok(Some(repo)),
+            None => Self::find_by_full_name(pool, full_name).await,
+        }
+    }
+
     pub async fn list_all(
         pool: &sqlx::PgPool,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let repos = sqlx::query_as::<_, Repository>(
-            "SELECT * FROM repositories ORDER BY updated_at DESC LIMIT $1 OFFSET $2",
+            "SELECT * FROM repositories ORDER BY updated_at DESC, id DESC LIMIT $1 OFFSET $2",
         )
         .bind(limit)
         .bind(offset)
@@ -95,6 +179,75 @@ impl Repository {
         Ok(repos)
     }
 
+    /// Repositories tagged with `topic`, most recently updated first
+    pub async fn list_by_topic(
+        pool: &sqlx::PgPool,
+        topic: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let repos = sqlx::query_as::<_, Repository>(
+            "SELECT * FROM repositories WHERE $1 = ANY(topics) ORDER BY updated_at DESC, id DESC LIMIT $2 OFFSET $3",
+        )
+        .bind(topic)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(repos)
+    }
+
+    pub async fn count_by_topic(pool: &sqlx::PgPool, topic: &str) -> Result<i64, sqlx::Error> {
+        let count: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM repositories WHERE $1 = ANY(topics)")
+                .bind(topic)
+                .fetch_one(pool)
+                .await?;
+
+        Ok(count.0)
+    }
+
+    /// Every topic used by at least one repository, for the topic filter
+    pub async fn list_topics(pool: &sqlx::PgPool) -> Result<Vec<String>, sqlx::Error> {
+        let topics: Vec<(String,)> = sqlx::query_as(
+            "SELECT DISTINCT topic FROM repositories, unnest(topics) AS topic ORDER BY topic",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(topics.into_iter().map(|t| t.0).collect())
+    }
+
+    /// Daily event counts for the last `days` days (oldest first, today last), zero-filled
+    pub async fn activity_buckets(
+        pool: &sqlx::PgPool,
+        id: i64,
+        days: i32,
+    ) -> Result<Vec<ActivityBucket>, sqlx::Error> {
+        let buckets = sqlx::query_as::<_, ActivityBucket>(
+            r#"
+            SELECT d.day, COUNT(e.id) AS count
+            FROM generate_series(
+                date_trunc('day', NOW()) - ($2 - 1) * INTERVAL '1 day',
+                date_trunc('day', NOW()),
+                INTERVAL '1 day'
+            ) AS d(day)
+            LEFT JOIN events e
+                ON e.repository_id = $1
+                AND date_trunc('day', e.received_at) = d.day
+            GROUP BY d.day
+            ORDER BY d.day
+            "#,
+        )
+        .bind(id)
+        .bind(days)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(buckets)
+    }
+
     pub async fn count(pool: &sqlx::PgPool) -> Result<i64, sqlx::Error> {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM repositories")
             .fetch_one(pool)
@@ -103,3 +256,87 @@ impl Repository {
         Ok(count.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateEvent, Event};
+    use chrono::Duration;
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_activity_buckets_count_events_per_day(pool: sqlx::PgPool) {
+        let repo = Repository::create(
+            &pool,
+            CreateRepository {
+                github_id: 1296269,
+                name: "Hello-World".to_string(),
+                full_name: "octocat/Hello-World".to_string(),
+                owner: "octocat".to_string(),
+                description: None,
+                url: "https://github.com/octocat/Hello-World".to_string(),
+                is_private: false,
+                language: None,
+                topics: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        // Two events today, one three days ago, one outside the window, one for no repository
+        let now = Utc::now();
+        for (days_ago, repository_id) in [
+            (0, Some(repo.id)),
+            (0, Some(repo.id)),
+            (3, Some(repo.id)),
+            (10, Some(repo.id)),
+            (0, None),
+        ] {
+            Event::create_backfilled(
+                &pool,
+                CreateEvent {
+                    repository_id,
+                    ..CreateEvent::for_test("github", "push")
+                },
+                now - Duration::days(days_ago),
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let buckets = Repository::activity_buckets(&pool, repo.id, 7)
+            .await
+            .unwrap();
+        let counts: Vec<i64> = buckets.iter().map(|b| b.count).collect();
+
+        assert_eq!(counts, vec![0, 0, 0, 1, 0, 0, 2]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_upsert_reports_created_then_updated(pool: sqlx::PgPool) {
+        let data = CreateRepository {
+            github_id: 1296269,
+            name: "Hello-World".to_string(),
+            full_name: "octocat/Hello-World".to_string(),
+            owner: "octocat".to_string(),
+            description: None,
+            url: "https://github.com/octocat/Hello-World".to_string(),
+            is_private: false,
+            language: None,
+            topics: Vec::new(),
+        };
+
+        let (first, created) = Repository::upsert_returning_created(&pool, data.clone())
+            .await
+            .unwrap();
+        assert!(created);
+
+        let (second, created) = Repository::upsert_returning_created(&pool, data)
+            .await
+            .unwrap();
+        assert!(!created);
+        assert_eq!(second.id, first.id);
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A repository's previous full name, recorded from a `repository` `renamed` event
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RepositoryRename {
+    pub id: i64,
+    pub repository_id: i64,
+    pub event_id: i64,
+    pub old_full_name: String,
+    pub new_full_name: String,
+    pub renamed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreateRepositoryRename {
+    pub repository_id: i64,
+    pub event_id: i64,
+    pub old_full_name: String,
+    pub new_full_name: String,
+    pub renamed_at: DateTime<Utc>,
+}
+
+impl RepositoryRename {
+    /// Record a rename; returns `None` when the event was already recorded
+    pub async fn record(
+        pool: &sqlx::PgPool,
+        data: CreateRepositoryRename,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let rename = sqlx::query_as::<_, RepositoryRename>(
+            r#"
+            INSERT INTO repository_renames (repository_id, event_id, old_full_name, new_full_name, renamed_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (event_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(data.repository_id)
+        .bind(data.event_id)
+        .bind(data.old_full_name)
+        .bind(data.new_full_name)
+        .bind(data.renamed_at)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(rename)
+    }
+
+    /// Renames of a repository, newest first
+    pub async fn list_by_repository(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let renames = sqlx::query_as::<_, RepositoryRename>(
+            "SELECT * FROM repository_renames WHERE repository_id = $1 ORDER BY renamed_at DESC, id DESC",
+        )
+        .bind(repository_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(renames)
+    }
+}
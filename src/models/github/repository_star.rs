@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One star, unstar or watch of a repository
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RepositoryStar {
+    pub id: i64,
+    pub repository_id: i64,
+    pub event_id: i64,
+    pub action: String,
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRepositoryStar {
+    pub repository_id: i64,
+    pub event_id: i64,
+    pub action: String,
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Change in star count for a `star` action. GitHub sends `watch` (`started`)
+/// alongside every `star` (`created`), so watches never move the count.
+pub fn star_delta(action: &str) -> i32 {
+    match action {
+        "created" => 1,
+        "deleted" => -1,
+        _ => 0,
+    }
+}
+
+impl RepositoryStar {
+    /// Record a star change and update the repository's running count.
+    ///
+    /// `stargazers_count` from the payload is authoritative when present;
+    /// otherwise the count moves by the action's delta. Returns `None` when the
+    /// event was already recorded, leaving the count untouched.
+    pub async fn record(
+        pool: &sqlx::PgPool,
+        data: CreateRepositoryStar,
+        stargazers_count: Option<i32>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let star = sqlx::query_as::<_, RepositoryStar>(
+            r#"
+            INSERT INTO repository_stars (repository_id, event_id, action, actor, at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (event_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(data.repository_id)
+        .bind(data.event_id)
+        .bind(&data.action)
+        .bind(data.actor)
+        .bind(data.at)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if star.is_some() {
+            sqlx::query(
+                "UPDATE repositories SET stars = COALESCE($2, GREATEST(stars + $3, 0)) WHERE id = $1",
+            )
+            .bind(data.repository_id)
+            .bind(stargazers_count)
+            .bind(star_delta(&data.action))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(star)
+    }
+
+    /// Most recent stargazers of a repository, newest first
+    pub async fn list_recent_stargazers(
+        pool: &sqlx::PgPool,
+        repository_id: i64,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let stars = sqlx::query_as::<_, RepositoryStar>(
+            r#"
+            SELECT * FROM repository_stars
+            WHERE repository_id = $1 AND action = 'created'
+            ORDER BY at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(repository_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(stars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_star_delta() {
+        assert_eq!(star_delta("created"), 1);
+        assert_eq!(star_delta("deleted"), -1);
+        assert_eq!(star_delta("started"), 0);
+    }
+}
@@ -1,7 +1,32 @@
+pub mod actor;
+pub mod entity_change;
 pub mod event;
+pub mod event_archive;
+pub mod event_note;
+pub mod event_tag;
+pub mod filter;
 pub mod github;
+pub mod rejected_webhook;
+pub mod replay_job;
+pub mod source_config;
+pub mod stats;
 pub mod webhook_event;
 
-pub use event::{CreateEvent, Event};
-pub use github::{Commit, Issue, PullRequest, Repository};
+pub use actor::Actor;
+pub use entity_change::{EntityChange, FieldChange};
+pub use event::{
+    CreateEvent, Event, ImportedEvent, PendingSummary, SourceProcessingCounts,
+    UnprocessedTypeCount, NO_ACTION,
+};
+pub use event_archive::{CreateEventArchive, EventArchive};
+pub use event_note::{CreateEventNote, EventNote};
+pub use event_tag::EventTag;
+pub use filter::{EventFilterBuilder, FilterTable};
+pub use github::{
+    Commit, Deployment, Issue, MembershipEvent, PullRequest, Repository, RepositoryStar,
+};
+pub use rejected_webhook::{CreateRejectedWebhook, RejectedWebhook};
+pub use replay_job::{CreateReplayJob, ReplayJob};
+pub use source_config::{SourceConfig, UpsertSourceConfig};
+pub use stats::{stats_overview, StatsOverview};
 pub use webhook_event::{CreateWebhookEvent, WebhookEvent};
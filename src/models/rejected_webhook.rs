@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+
+/// Largest request body kept for a rejected webhook
+pub const MAX_STORED_BODY_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RejectedWebhook {
+    pub id: i64,
+    pub source: String,
+    pub tenant: Option<String>,
+    pub reason: String,
+    pub client_ip: Option<String>,
+    pub headers: JsonValue,
+    pub body: String,
+    pub body_truncated: bool,
+    /// Length of the whole body, before truncation
+    pub body_bytes: Option<i64>,
+    /// Hex SHA-256 of the whole body, before truncation
+    pub body_sha256: Option<String>,
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRejectedWebhook {
+    pub source: String,
+    pub tenant: Option<String>,
+    pub reason: String,
+    pub client_ip: Option<String>,
+    pub headers: JsonValue,
+    pub body: Vec<u8>,
+}
+
+/// Lossily decode at most `MAX_STORED_BODY_BYTES` of a body, reporting whether it was cut
+pub fn truncate_body(body: &[u8]) -> (String, bool) {
+    if body.len() <= MAX_STORED_BODY_BYTES {
+        return (String::from_utf8_lossy(body).into_owned(), false);
+    }
+
+    let mut text = String::from_utf8_lossy(&body[..MAX_STORED_BODY_BYTES]).into_owned();
+    // A multi-byte character split at the limit decodes to a trailing replacement char
+    if text.ends_with(char::REPLACEMENT_CHARACTER) {
+        text.pop();
+    }
+
+    (text, true)
+}
+
+/// Hex-encoded SHA-256 of a request body
+pub fn body_sha256(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+impl RejectedWebhook {
+    pub async fn create(
+        pool: &sqlx::PgPool,
+        data: CreateRejectedWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let (body, body_truncated) = truncate_body(&data.body);
+        let body_bytes = data.body.len() as i64;
+        let digest = body_sha256(&data.body);
+
+        let rejected = sqlx::query_as::<_, RejectedWebhook>(
+            r#"
+            INSERT INTO rejected_webhooks (source, tenant, reason, client_ip, headers, body, body_truncated, body_bytes, body_sha256)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(data.source)
+        .bind(data.tenant)
+        .bind(data.reason)
+        .bind(data.client_ip)
+        .bind(data.headers)
+        .bind(body)
+        .bind(body_truncated)
+        .bind(body_bytes)
+        .bind(digest)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(rejected)
+    }
+
+    pub async fn list_recent(pool: &sqlx::PgPool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        let rejected = sqlx::query_as::<_, RejectedWebhook>(
+            "SELECT * FROM rejected_webhooks ORDER BY received_at DESC, id DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rejected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_sha256() {
+        assert_eq!(
+            body_sha256(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_small_body_is_kept_whole() {
+        assert_eq!(
+            truncate_body(b"{\"ok\":true}"),
+            ("{\"ok\":true}".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn test_large_body_is_capped_on_char_boundary() {
+        let mut body = vec![b'a'; MAX_STORED_BODY_BYTES - 1];
+        body.extend("é".repeat(10).as_bytes());
+
+        let (text, truncated) = truncate_body(&body);
+
+        assert!(truncated);
+        assert_eq!(text.len(), MAX_STORED_BODY_BYTES - 1);
+        assert!(text.chars().all(|c| c == 'a'));
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::models::{Event, EventFilterBuilder, FilterTable};
+
+/// A batch of stored events replayed to the forward targets at `rate_per_minute`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReplayJob {
+    pub id: i64,
+    pub source: Option<String>,
+    pub event_type: Option<String>,
+    pub action: Option<String>,
+    pub rate_per_minute: i32,
+    pub status: String,
+    /// Newest event the job covers, fixed when the job is created
+    pub max_event_id: i64,
+    pub total_events: i64,
+    /// Cursor: the last event replayed, successfully or not
+    pub last_event_id: i64,
+    pub replayed_events: i64,
+    pub failed_events: i64,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReplayJob {
+    pub source: Option<String>,
+    pub event_type: Option<String>,
+    pub action: Option<String>,
+    pub rate_per_minute: i32,
+}
+
+fn job_filters(
+    source: Option<&str>,
+    event_type: Option<&str>,
+    action: Option<&str>,
+) -> EventFilterBuilder {
+    EventFilterBuilder::new(FilterTable::Events)
+        .source(source)
+        .event_type(event_type)
+        .action(action)
+}
+
+impl ReplayJob {
+    /// The events filter the job replays, as on the events page
+    pub fn filters(&self) -> EventFilterBuilder {
+        job_filters(
+            self.source.as_deref(),
+            self.event_type.as_deref(),
+            self.action.as_deref(),
+        )
+    }
+
+    /// Time between two replayed events
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(60) / self.rate_per_minute.max(1) as u32
+    }
+
+    /// Events attempted so far, delivered or not
+    pub fn attempted_events(&self) -> i64 {
+        self.replayed_events + self.failed_events
+    }
+
+    /// Snapshot the matching events and queue the job
+    pub async fn create(pool: &sqlx::PgPool, data: CreateReplayJob) -> Result<Self, sqlx::Error> {
+        let max_event_id = Event::max_id(pool).await?;
+        let filters = job_filters(
+            data.source.as_deref(),
+            data.event_type.as_deref(),
+            data.action.as_deref(),
+        );
+
+        let mut count = filters.count_query();
+        count.push(" AND id <= ").push_bind(max_event_id);
+        let (total_events,): (i64,) = count.build_query_as().fetch_one(pool).await?;
+
+        let job = sqlx::query_as::<_, ReplayJob>(
+            r#"
+            INSERT INTO replay_jobs (source, event_type, action, rate_per_minute, max_event_id, total_events)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(data.source)
+        .bind(data.event_type)
+        .bind(data.action)
+        .bind(data.rate_per_minute)
+        .bind(max_event_id)
+        .bind(total_events)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn find_by_id(pool: &sqlx::PgPool, id: i64) -> Result<Option<Self>, sqlx::Error> {
+        let job = sqlx::query_as::<_, ReplayJob>("SELECT * FROM replay_jobs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(job)
+    }
+
+    pub async fn list_recent(pool: &sqlx::PgPool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        let jobs =
+            sqlx::query_as::<_, ReplayJob>("SELECT * FROM replay_jobs ORDER BY id DESC LIMIT $1")
+                .bind(limit)
+                .fetch_all(pool)
+                .await?;
+
+        Ok(jobs)
+    }
+
+    /// The oldest job that has not completed; jobs run one at a time, in creation order
+    pub async fn next_unfinished(pool: &sqlx::PgPool) -> Result<Option<Self>, sqlx::Error> {
+        let job = sqlx::query_as::<_, ReplayJob>(
+            "SELECT * FROM replay_jobs WHERE status IN ('pending', 'running') ORDER BY id LIMIT 1",
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn mark_running(pool: &sqlx::PgPool, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE replay_jobs SET status = 'running', started_at = COALESCE(started_at, NOW()) WHERE id = $1",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_completed(pool: &sqlx::PgPool, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE replay_jobs SET status = 'completed', finished_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The next event after the cursor, if the job has any left
+    pub async fn next_event(&self, pool: &sqlx::PgPool) -> Result<Option<Event>, sqlx::Error> {
+        let mut query = self.filters().select_query();
+        query
+            .push(" AND id > ")
+            .push_bind(self.last_event_id)
+            .push(" AND id <= ")
+            .push_bind(self.max_event_id)
+            .push(" ORDER BY id LIMIT 1");
+
+        query.build_query_as::<Event>().fetch_optional(pool).await
+    }
+
+    /// Advance the cursor past `event_id`, counting it as replayed or failed
+    pub async fn record_attempt(
+        &mut self,
+        pool: &sqlx::PgPool,
+        event_id: i64,
+        delivered: bool,
+    ) -> Result<(), sqlx::Error> {
+        let updated = sqlx::query_as::<_, ReplayJob>(
+            r#"
+            UPDATE replay_jobs
+            SET last_event_id = $2,
+                replayed_events = replayed_events + CASE WHEN $3 THEN 1 ELSE 0 END,
+                failed_events = failed_events + CASE WHEN $3 THEN 0 ELSE 1 END
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(self.id)
+        .bind(event_id)
+        .bind(delivered)
+        .fetch_one(pool)
+        .await?;
+
+        *self = updated;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(rate_per_minute: i32) -> ReplayJob {
+        ReplayJob {
+            id: 1,
+            source: Some("github,gitlab".to_string()),
+            event_type: None,
+            action: None,
+            rate_per_minute,
+            status: "pending".to_string(),
+            max_event_id: 10,
+            total_events: 4,
+            last_event_id: 0,
+            replayed_events: 0,
+            failed_events: 0,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    #[test]
+    fn test_interval_follows_rate() {
+        assert_eq!(job(60).interval(), Duration::from_secs(1));
+        assert_eq!(job(1200).interval(), Duration::from_millis(50));
+        assert_eq!(job(0).interval(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_filters_use_event_page_lists() {
+        assert!(job(60)
+            .filters()
+            .select_query()
+            .sql()
+            .ends_with("AND source = ANY($1)"));
+    }
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Supported webhook signature verification modes
+pub const SIGNATURE_MODES: &[&str] = &["none", "hmac_sha256", "hmac_sha256_hex", "token"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SourceConfig {
+    pub id: i64,
+    pub source: String,
+    pub signature_mode: String,
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+    /// Built-in payload transform such as `unwrap:data`, applied before storage
+    pub transform: Option<String>,
+    /// Deliveries with another `Content-Type` are rejected with 415 when set
+    pub expected_content_type: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertSourceConfig {
+    pub source: String,
+    pub signature_mode: String,
+    /// `None` keeps the currently stored secret
+    pub secret: Option<String>,
+    pub transform: Option<String>,
+    pub expected_content_type: Option<String>,
+}
+
+impl SourceConfig {
+    pub fn has_secret(&self) -> bool {
+        self.secret.as_deref().is_some_and(|s| !s.is_empty())
+    }
+
+    /// Whether a delivery's `Content-Type` header satisfies `expected_content_type`.
+    /// Parameters such as `charset` are ignored and the media type is compared case-insensitively.
+    pub fn accepts_content_type(&self, content_type: Option<&str>) -> bool {
+        let Some(expected) = self.expected_content_type.as_deref() else {
+            return true;
+        };
+        let essence = |value: &str| value.split(';').next().unwrap_or("").trim().to_lowercase();
+        content_type.is_some_and(|actual| essence(actual) == essence(expected))
+    }
+
+    pub async fn upsert(
+        pool: &sqlx::PgPool,
+        data: UpsertSourceConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let config = sqlx::query_as::<_, SourceConfig>(
+            r#"
+            INSERT INTO source_config (source, signature_mode, secret, transform, expected_content_type)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (source) DO UPDATE
+            SET signature_mode = EXCLUDED.signature_mode,
+                secret = COALESCE(EXCLUDED.secret, source_config.secret),
+                transform = EXCLUDED.transform,
+                expected_content_type = EXCLUDED.expected_content_type,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(data.source)
+        .bind(data.signature_mode)
+        .bind(data.secret)
+        .bind(data.transform)
+        .bind(data.expected_content_type)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(config)
+    }
+
+    pub async fn find_by_source(
+        pool: &sqlx::PgPool,
+        source: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let config =
+            sqlx::query_as::<_, SourceConfig>("SELECT * FROM source_config WHERE source = $1")
+                .bind(source)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(config)
+    }
+
+    pub async fn list_all(pool: &sqlx::PgPool) -> Result<Vec<Self>, sqlx::Error> {
+        let configs =
+            sqlx::query_as::<_, SourceConfig>("SELECT * FROM source_config ORDER BY source")
+                .fetch_all(pool)
+                .await?;
+
+        Ok(configs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    #[test]
+    fn test_accepts_content_type_ignores_parameters_and_case() {
+        let mut config = SourceConfig {
+            id: 1,
+            source: "billing".to_string(),
+            signature_mode: "none".to_string(),
+            secret: None,
+            transform: None,
+            expected_content_type: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert!(config.accepts_content_type(None));
+
+        config.expected_content_type = Some("application/json".to_string());
+        assert!(config.accepts_content_type(Some("application/json")));
+        assert!(config.accepts_content_type(Some("Application/JSON; charset=utf-8")));
+        assert!(!config.accepts_content_type(Some("application/x-www-form-urlencoded")));
+        assert!(!config.accepts_content_type(None));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_create_and_update_source_config(pool: PgPool) {
+        let created = SourceConfig::upsert(
+            &pool,
+            UpsertSourceConfig {
+                source: "gitlab".to_string(),
+                signature_mode: "token".to_string(),
+                secret: Some("first".to_string()),
+                transform: None,
+                expected_content_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(created.signature_mode, "token");
+        assert!(created.has_secret());
+
+        // Updating without a secret keeps the stored one
+        let updated = SourceConfig::upsert(
+            &pool,
+            UpsertSourceConfig {
+                source: "gitlab".to_string(),
+                signature_mode: "hmac_sha256".to_string(),
+                secret: None,
+                transform: Some("unwrap:data".to_string()),
+                expected_content_type: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(updated.id, created.id);
+        assert_eq!(updated.signature_mode, "hmac_sha256");
+        assert_eq!(updated.secret.as_deref(), Some("first"));
+        assert_eq!(updated.transform.as_deref(), Some("unwrap:data"));
+
+        let all = SourceConfig::list_all(&pool).await.unwrap();
+        assert_eq!(all.len(), 1);
+    }
+}
@@ -0,0 +1,93 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// Row counts shown on the dashboard
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, FromRow)]
+pub struct StatsOverview {
+    pub repositories: i64,
+    pub events: i64,
+    pub commits: i64,
+    pub pull_requests: i64,
+    pub issues: i64,
+    pub open_pull_requests: i64,
+    pub open_issues: i64,
+}
+
+/// All dashboard counts in a single round trip
+pub async fn stats_overview(pool: &sqlx::PgPool) -> Result<StatsOverview, sqlx::Error> {
+    sqlx::query_as::<_, StatsOverview>(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM repositories) AS repositories,
+            (SELECT COUNT(*) FROM events) AS events,
+            (SELECT COUNT(*) FROM commits) AS commits,
+            (SELECT COUNT(*) FROM pull_requests) AS pull_requests,
+            (SELECT COUNT(*) FROM issues) AS issues,
+            (SELECT COUNT(*) FROM pull_requests WHERE state = 'open') AS open_pull_requests,
+            (SELECT COUNT(*) FROM issues WHERE state = 'open') AS open_issues
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Actor, Commit, CreateEvent, Event, Issue, PullRequest, Repository};
+    use crate::services::{process_github_event, RepositoryCache};
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_overview_matches_individual_counts(pool: PgPool) {
+        let fixtures = [
+            (
+                "push",
+                include_str!("../../tests/fixtures/payloads/github_push.json"),
+            ),
+            (
+                "pull_request",
+                include_str!("../../tests/fixtures/payloads/github_pull_request.json"),
+            ),
+            (
+                "issues",
+                include_str!("../../tests/fixtures/payloads/github_issues.json"),
+            ),
+        ];
+        let repos = RepositoryCache::default();
+        for (event_type, raw) in fixtures {
+            let payload: serde_json::Value = serde_json::from_str(raw).unwrap();
+            let event = Event::create(
+                &pool,
+                CreateEvent {
+                    action: payload["action"].as_str().map(str::to_string),
+                    actor: Actor::from_payload("github", &payload),
+                    raw_event: payload,
+                    ..CreateEvent::for_test("github", event_type)
+                },
+            )
+            .await
+            .unwrap();
+            process_github_event(&pool, &repos, &event, false)
+                .await
+                .unwrap();
+        }
+
+        let overview = stats_overview(&pool).await.unwrap();
+        assert_eq!(
+            overview,
+            StatsOverview {
+                repositories: Repository::count(&pool).await.unwrap(),
+                events: Event::count(&pool).await.unwrap(),
+                commits: Commit::count(&pool).await.unwrap(),
+                pull_requests: PullRequest::count(&pool).await.unwrap(),
+                issues: Issue::count(&pool).await.unwrap(),
+                open_pull_requests: PullRequest::count_by_state(&pool, "open").await.unwrap(),
+                open_issues: Issue::count_by_state(&pool, "open").await.unwrap(),
+            }
+        );
+        assert_eq!(overview.events, 3);
+        assert!(overview.repositories > 0);
+    }
+}
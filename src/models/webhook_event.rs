@@ -4,6 +4,8 @@ use serde_json::Value as JsonValue;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::EventFilterBuilder;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct WebhookEvent {
     pub id: i64,
@@ -82,7 +84,7 @@ impl WebhookEvent {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let events = sqlx::query_as::<_, WebhookEvent>(
-            "SELECT * FROM webhook_events WHERE repository_id = $1 ORDER BY received_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM webhook_events WHERE repository_id = $1 ORDER BY received_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(repository_id)
         .bind(limit)
@@ -100,7 +102,7 @@ impl WebhookEvent {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let events = sqlx::query_as::<_, WebhookEvent>(
-            "SELECT * FROM webhook_events ORDER BY received_at DESC LIMIT $1 OFFSET $2",
+            "SELECT * FROM webhook_events ORDER BY received_at DESC, id DESC LIMIT $1 OFFSET $2",
         )
         .bind(limit)
         .bind(offset)
@@ -110,6 +112,23 @@ impl WebhookEvent {
         Ok(events)
     }
 
+    /// List events with an id greater than `after_id`, oldest first, for batch iteration
+    pub async fn list_after_id(
+        pool: &sqlx::PgPool,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let events = sqlx::query_as::<_, WebhookEvent>(
+            "SELECT * FROM webhook_events WHERE id > $1 ORDER BY id ASC LIMIT $2",
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(events)
+    }
+
     #[allow(dead_code)]
     pub async fn list_by_type(
         pool: &sqlx::PgPool,
@@ -118,7 +137,7 @@ impl WebhookEvent {
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
         let events = sqlx::query_as::<_, WebhookEvent>(
-            "SELECT * FROM webhook_events WHERE event_type = $1 ORDER BY received_at DESC LIMIT $2 OFFSET $3",
+            "SELECT * FROM webhook_events WHERE event_type = $1 ORDER BY received_at DESC, id DESC LIMIT $2 OFFSET $3",
         )
         .bind(event_type)
         .bind(limit)
@@ -138,112 +157,41 @@ impl WebhookEvent {
         Ok(count.0)
     }
 
-    #[allow(dead_code)]
     pub async fn search_and_filter(
         pool: &sqlx::PgPool,
-        event_type: Option<&str>,
-        repository_id: Option<i64>,
-        processed: Option<bool>,
-        search: Option<&str>,
+        filters: &EventFilterBuilder,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Self>, sqlx::Error> {
-        let mut query = String::from("SELECT * FROM webhook_events WHERE 1=1");
-        let mut bindings = Vec::new();
-        let mut param_count = 1;
-
-        if let Some(et) = event_type {
-            query.push_str(&format!(" AND event_type = ${param_count}"));
-            bindings.push(et.to_string());
-            param_count += 1;
-        }
-
-        if let Some(rid) = repository_id {
-            query.push_str(&format!(" AND repository_id = ${param_count}"));
-            bindings.push(rid.to_string());
-            param_count += 1;
-        }
-
-        if let Some(proc) = processed {
-            query.push_str(&format!(" AND processed = ${param_count}"));
-            bindings.push(proc.to_string());
-            param_count += 1;
-        }
-
-        if let Some(s) = search {
-            if !s.is_empty() {
-                query.push_str(&format!(" AND payload::text ILIKE ${param_count}"));
-                bindings.push(format!("%{s}%"));
-                param_count += 1;
-            }
-        }
-
-        query.push_str(&format!(
-            " ORDER BY received_at DESC LIMIT ${} OFFSET ${}",
-            param_count,
-            param_count + 1
-        ));
-        bindings.push(limit.to_string());
-        bindings.push(offset.to_string());
-
-        let mut query_builder = sqlx::query_as::<_, WebhookEvent>(&query);
-        for binding in bindings {
-            query_builder = query_builder.bind(binding);
-        }
-
-        let events = query_builder.fetch_all(pool).await?;
+        let mut query = filters.select_query();
+
+        query
+            .push(" ORDER BY received_at DESC, id DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let events = query
+            .build_query_as::<WebhookEvent>()
+            .fetch_all(pool)
+            .await?;
 
         Ok(events)
     }
 
-    #[allow(dead_code)]
     pub async fn count_filtered(
         pool: &sqlx::PgPool,
-        event_type: Option<&str>,
-        repository_id: Option<i64>,
-        processed: Option<bool>,
-        search: Option<&str>,
+        filters: &EventFilterBuilder,
     ) -> Result<i64, sqlx::Error> {
-        let mut query = String::from("SELECT COUNT(*) FROM webhook_events WHERE 1=1");
-        let mut bindings = Vec::new();
-        let mut param_count = 1;
-
-        if let Some(et) = event_type {
-            query.push_str(&format!(" AND event_type = ${param_count}"));
-            bindings.push(et.to_string());
-            param_count += 1;
-        }
-
-        if let Some(rid) = repository_id {
-            query.push_str(&format!(" AND repository_id = ${param_count}"));
-            bindings.push(rid.to_string());
-            param_count += 1;
-        }
-
-        if let Some(proc) = processed {
-            query.push_str(&format!(" AND processed = ${param_count}"));
-            bindings.push(proc.to_string());
-            param_count += 1;
-        }
-
-        if let Some(s) = search {
-            if !s.is_empty() {
-                query.push_str(&format!(" AND payload::text ILIKE ${param_count}"));
-                bindings.push(format!("%{s}%"));
-            }
-        }
-
-        let mut query_builder = sqlx::query_as::<_, (i64,)>(&query);
-        for binding in bindings {
-            query_builder = query_builder.bind(binding);
-        }
-
-        let count = query_builder.fetch_one(pool).await?;
+        let count: (i64,) = filters
+            .count_query()
+            .build_query_as()
+            .fetch_one(pool)
+            .await?;
 
         Ok(count.0)
     }
 
-    #[allow(dead_code)]
     pub async fn get_event_types(pool: &sqlx::PgPool) -> Result<Vec<String>, sqlx::Error> {
         let types: Vec<(String,)> =
             sqlx::query_as("SELECT DISTINCT event_type FROM webhook_events ORDER BY event_type")
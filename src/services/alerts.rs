@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use secrecy::{ExposeSecret, SecretString};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::models::{Event, PendingSummary};
+
+/// How often the monitor checks the pending backlog
+pub const LAG_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Decides when the pending backlog warrants an alert, at most once per cooldown
+#[derive(Debug, Clone)]
+pub struct LagMonitor {
+    max_pending: i64,
+    max_age: chrono::Duration,
+    cooldown: chrono::Duration,
+    last_alert: Option<DateTime<Utc>>,
+}
+
+impl LagMonitor {
+    pub fn new(max_pending: i64, max_age: chrono::Duration, cooldown: chrono::Duration) -> Self {
+        LagMonitor {
+            max_pending,
+            max_age,
+            cooldown,
+            last_alert: None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let seconds = |secs: u64| chrono::Duration::seconds(secs.min(i64::MAX as u64) as i64);
+
+        Self::new(
+            config.alert_max_pending,
+            seconds(config.alert_max_pending_age_secs),
+            seconds(config.alert_cooldown_secs),
+        )
+    }
+
+    /// The alert payload when the backlog crosses a threshold, unless an alert already
+    /// went out within the cooldown
+    pub fn check(&mut self, summary: &PendingSummary, now: DateTime<Utc>) -> Option<JsonValue> {
+        let oldest_age = summary
+            .oldest_age(now)
+            .unwrap_or_else(chrono::Duration::zero);
+        if summary.pending <= self.max_pending && oldest_age <= self.max_age {
+            return None;
+        }
+        if self.last_alert.is_some_and(|at| now - at < self.cooldown) {
+            return None;
+        }
+        self.last_alert = Some(now);
+
+        Some(serde_json::json!({
+            "alert": "processing_lag",
+            // Lets Slack-style incoming webhooks show the alert without a custom template
+            "text": format!(
+                "Cross Bow processing lag: {} pending events, oldest waiting {}s",
+                summary.pending,
+                oldest_age.num_seconds()
+            ),
+            "pending": summary.pending,
+            "oldest_pending_age_secs": oldest_age.num_seconds(),
+            "max_pending": self.max_pending,
+            "max_pending_age_secs": self.max_age.num_seconds(),
+        }))
+    }
+}
+
+/// Check the backlog every `interval` and POST alerts to `url`, logging failures and carrying on
+pub async fn run_lag_monitor(
+    pool: PgPool,
+    client: reqwest::Client,
+    url: SecretString,
+    mut monitor: LagMonitor,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let summary = match Event::pending_summary(&pool).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                log::error!("Failed to check the pending event backlog: {e}");
+                continue;
+            }
+        };
+        let Some(alert) = monitor.check(&summary, Utc::now()) else {
+            continue;
+        };
+
+        log::warn!("Processing lag: {} events pending", summary.pending);
+        if let Err(e) = send_alert(&client, url.expose_secret(), &alert).await {
+            log::error!("Failed to send processing lag alert: {e}");
+        }
+    }
+}
+
+async fn send_alert(
+    client: &reqwest::Client,
+    url: &str,
+    alert: &JsonValue,
+) -> Result<(), reqwest::Error> {
+    client
+        .post(url)
+        .json(alert)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(pending: i64, oldest_minutes_ago: i64, now: DateTime<Utc>) -> PendingSummary {
+        PendingSummary {
+            pending,
+            oldest_received_at: Some(now - chrono::Duration::minutes(oldest_minutes_ago)),
+        }
+    }
+
+    #[test]
+    fn test_crossing_threshold_alerts_once_per_cooldown() {
+        let mut monitor = LagMonitor::new(
+            100,
+            chrono::Duration::minutes(15),
+            chrono::Duration::hours(1),
+        );
+        let start = Utc::now();
+        let at = |minutes| start + chrono::Duration::minutes(minutes);
+
+        assert!(monitor.check(&summary(10, 1, at(0)), at(0)).is_none());
+
+        let alert = monitor.check(&summary(250, 1, at(1)), at(1)).unwrap();
+        assert_eq!(alert["alert"], "processing_lag");
+        assert_eq!(alert["pending"], 250);
+
+        // Still lagging, but within the cooldown
+        for minute in [2, 30, 60] {
+            assert!(monitor
+                .check(&summary(300, 20, at(minute)), at(minute))
+                .is_none());
+        }
+
+        assert!(monitor.check(&summary(300, 20, at(61)), at(61)).is_some());
+    }
+
+    #[test]
+    fn test_old_pending_event_alerts_on_its_own() {
+        let mut monitor = LagMonitor::new(
+            100,
+            chrono::Duration::minutes(15),
+            chrono::Duration::hours(1),
+        );
+        let now = Utc::now();
+
+        let alert = monitor.check(&summary(3, 20, now), now).unwrap();
+        assert_eq!(alert["oldest_pending_age_secs"], 20 * 60);
+
+        let empty = PendingSummary::default();
+        assert!(
+            LagMonitor::new(0, chrono::Duration::zero(), chrono::Duration::zero())
+                .check(&empty, now)
+                .is_none()
+        );
+    }
+}
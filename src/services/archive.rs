@@ -0,0 +1,261 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use secrecy::ExposeSecret;
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::models::{CreateEventArchive, Event, EventArchive};
+
+/// How often the background task looks for events to archive
+pub const ARCHIVE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Most events written to a single archive object
+const ARCHIVE_BATCH: i64 = 10_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("object store error: {0}")]
+    Store(#[from] object_store::Error),
+    #[error("failed to serialize event: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// The archive bucket from config; `None` when archiving is disabled
+pub fn s3_store(config: &Config) -> Option<object_store::Result<Arc<dyn ObjectStore>>> {
+    let bucket = config.archive_s3_bucket.as_ref()?;
+
+    let mut builder = AmazonS3Builder::from_env()
+        .with_bucket_name(bucket)
+        .with_region(&config.archive_s3_region);
+    if let Some(endpoint) = &config.archive_s3_endpoint {
+        builder = builder.with_endpoint(endpoint).with_allow_http(true);
+    }
+    if let Some(key_id) = &config.archive_s3_access_key_id {
+        builder = builder.with_access_key_id(key_id);
+    }
+    if let Some(secret) = &config.archive_s3_secret_access_key {
+        builder = builder.with_secret_access_key(secret.expose_secret());
+    }
+
+    Some(
+        builder
+            .build()
+            .map(|store| Arc::new(store) as Arc<dyn ObjectStore>),
+    )
+}
+
+/// Object key of the archive holding events `first..=last`
+fn object_key(prefix: &str, first: i64, last: i64) -> String {
+    format!("{prefix}{first:020}-{last:020}.ndjson")
+}
+
+/// Write events received before `cutoff` that are not yet archived to `store` as NDJSON,
+/// one object per batch, returning how many were archived. With `prune` the archived
+/// events are deleted from the database once their object is written.
+pub async fn archive_events(
+    pool: &PgPool,
+    store: &dyn ObjectStore,
+    prefix: &str,
+    cutoff: DateTime<Utc>,
+    prune: bool,
+) -> Result<u64, ArchiveError> {
+    let mut archived = 0;
+
+    loop {
+        let after_id = EventArchive::last_archived_event_id(pool).await?;
+        // Ranges stay contiguous: stop at the first event newer than the cutoff
+        let events: Vec<Event> = Event::list_after_id(pool, after_id, None, ARCHIVE_BATCH)
+            .await?
+            .into_iter()
+            .take_while(|event| event.received_at < cutoff)
+            .collect();
+        let (Some(first), Some(last)) = (events.first(), events.last()) else {
+            break;
+        };
+        let (first_id, last_id) = (first.id, last.id);
+
+        let mut body = Vec::new();
+        for event in &events {
+            serde_json::to_writer(&mut body, event)?;
+            body.push(b'\n');
+        }
+
+        let key = object_key(prefix, first_id, last_id);
+        store.put(&Path::from(key.as_str()), body.into()).await?;
+        let archive = EventArchive::record(
+            pool,
+            CreateEventArchive {
+                object_key: key.clone(),
+                first_event_id: first_id,
+                last_event_id: last_id,
+                event_count: events.len() as i64,
+                cutoff,
+            },
+        )
+        .await?;
+        log::info!("Archived {} events to {key}", events.len());
+
+        if prune {
+            let deleted = Event::delete_id_range(pool, first_id, last_id).await?;
+            EventArchive::mark_pruned(pool, archive.id).await?;
+            log::info!("Pruned {deleted} archived events");
+        }
+
+        archived += events.len() as u64;
+        if (events.len() as i64) < ARCHIVE_BATCH {
+            break;
+        }
+    }
+
+    Ok(archived)
+}
+
+/// Archive events older than `after_days` every `interval`, logging failures and carrying on
+pub async fn run_archiving(
+    pool: PgPool,
+    store: Arc<dyn ObjectStore>,
+    prefix: String,
+    after_days: u32,
+    prune: bool,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(after_days));
+        if let Err(e) = archive_events(&pool, store.as_ref(), &prefix, cutoff, prune).await {
+            log::error!("Failed to archive events: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateEvent;
+    use futures_util::TryStreamExt;
+    use object_store::memory::InMemory;
+
+    async fn create_event(pool: &PgPool, age_days: i64) -> Event {
+        Event::create_backfilled(
+            pool,
+            CreateEvent {
+                raw_event: serde_json::json!({ "age_days": age_days }),
+                ..CreateEvent::for_test("ci", "build")
+            },
+            Utc::now() - chrono::Duration::days(age_days),
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn object_keys(store: &InMemory) -> Vec<String> {
+        let objects: Vec<_> = store.list(None).try_collect().await.unwrap();
+        objects
+            .into_iter()
+            .map(|meta| meta.location.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_object_keys_sort_by_event_id() {
+        assert!(object_key("events/", 9, 10) < object_key("events/", 11, 20));
+        assert_eq!(
+            object_key("events/", 1, 2),
+            "events/00000000000000000001-00000000000000000002.ndjson"
+        );
+    }
+
+    #[test]
+    fn test_store_is_built_only_with_a_bucket() {
+        let mut config = Config::test_default();
+        assert!(s3_store(&config).is_none());
+
+        config.archive_s3_bucket = Some("cross-bow-archive".to_string());
+        config.archive_s3_endpoint = Some("http://localhost:9000".to_string());
+        config.archive_s3_access_key_id = Some("minio".to_string());
+        config.archive_s3_secret_access_key =
+            Some(secrecy::SecretString::new("minio-secret".to_string()));
+        assert!(s3_store(&config).unwrap().is_ok());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_old_events_are_written_as_ndjson(pool: PgPool) {
+        let store = InMemory::new();
+        let oldest = create_event(&pool, 120).await;
+        let old = create_event(&pool, 100).await;
+        let recent = create_event(&pool, 10).await;
+        let cutoff = Utc::now() - chrono::Duration::days(90);
+
+        let archived = archive_events(&pool, &store, "events/", cutoff, false)
+            .await
+            .unwrap();
+        assert_eq!(archived, 2);
+
+        let keys = object_keys(&store).await;
+        assert_eq!(keys, vec![object_key("events/", oldest.id, old.id)]);
+        let body = store
+            .get(&Path::from(keys[0].as_str()))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        let lines: Vec<Event> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![oldest.id, old.id]
+        );
+        assert_eq!(lines[1].raw_event, serde_json::json!({ "age_days": 100 }));
+
+        let archives = EventArchive::list_recent(&pool, 10).await.unwrap();
+        assert_eq!(archives.len(), 1);
+        assert_eq!(archives[0].event_count, 2);
+        assert!(!archives[0].pruned);
+
+        // Already archived ranges are not exported again, and events stay without pruning
+        assert_eq!(
+            archive_events(&pool, &store, "events/", cutoff, false)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(object_keys(&store).await.len(), 1);
+        assert!(Event::find_by_id(&pool, old.id).await.unwrap().is_some());
+        assert!(Event::find_by_id(&pool, recent.id).await.unwrap().is_some());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_pruning_deletes_only_archived_events(pool: PgPool) {
+        let store = InMemory::new();
+        let old = create_event(&pool, 100).await;
+        let recent = create_event(&pool, 10).await;
+        let cutoff = Utc::now() - chrono::Duration::days(90);
+
+        let archived = archive_events(&pool, &store, "events/", cutoff, true)
+            .await
+            .unwrap();
+        assert_eq!(archived, 1);
+
+        assert!(Event::find_by_id(&pool, old.id).await.unwrap().is_none());
+        assert!(Event::find_by_id(&pool, recent.id).await.unwrap().is_some());
+        let archives = EventArchive::list_recent(&pool, 10).await.unwrap();
+        assert!(archives[0].pruned);
+        assert_eq!(archives[0].last_event_id, old.id);
+    }
+}
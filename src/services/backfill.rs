@@ -0,0 +1,124 @@
+use crate::models::{CreateEvent, Event, WebhookEvent};
+use crate::services::convert_github_webhook_to_event;
+use sqlx::PgPool;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BackfillStats {
+    pub inserted: u64,
+    pub skipped: u64,
+}
+
+/// Convert a legacy `webhook_events` row into a generic event
+pub fn legacy_to_event(legacy: &WebhookEvent) -> CreateEvent {
+    convert_github_webhook_to_event(
+        legacy.event_type.clone(),
+        legacy.event_action.clone(),
+        legacy.payload.clone(),
+        legacy.delivery_id,
+        Some(legacy.signature.clone()),
+        legacy.repository_id,
+    )
+}
+
+/// Copy legacy `webhook_events` into `events`, skipping deliveries that already exist
+pub async fn backfill_legacy_events(
+    pool: &PgPool,
+    batch_size: i64,
+) -> Result<BackfillStats, sqlx::Error> {
+    let mut stats = BackfillStats::default();
+    let mut last_id = 0;
+
+    loop {
+        let batch = WebhookEvent::list_after_id(pool, last_id, batch_size).await?;
+
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_id = last.id;
+
+        for legacy in &batch {
+            if Event::find_by_delivery_id(pool, legacy.delivery_id)
+                .await?
+                .is_some()
+            {
+                stats.skipped += 1;
+                continue;
+            }
+
+            Event::create_backfilled(
+                pool,
+                legacy_to_event(legacy),
+                legacy.received_at,
+                legacy.processed_at,
+            )
+            .await?;
+            stats.inserted += 1;
+        }
+
+        log::info!(
+            "Backfill progress: {} inserted, {} skipped (last legacy id {last_id})",
+            stats.inserted,
+            stats.skipped
+        );
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateWebhookEvent;
+    use uuid::Uuid;
+
+    fn legacy_push(delivery_id: Uuid) -> CreateWebhookEvent {
+        CreateWebhookEvent {
+            repository_id: None,
+            event_type: "push".to_string(),
+            event_action: None,
+            delivery_id,
+            payload: serde_json::json!({
+                "sender": { "login": "octocat" },
+                "pusher": { "name": "octocat", "email": "octocat@github.com" }
+            }),
+            signature: "sha256=abc".to_string(),
+        }
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_backfill_converts_legacy_rows(pool: PgPool) {
+        let existing = Uuid::new_v4();
+        for delivery_id in [Uuid::new_v4(), Uuid::new_v4(), existing] {
+            WebhookEvent::create(&pool, legacy_push(delivery_id))
+                .await
+                .unwrap();
+        }
+
+        let already_migrated = WebhookEvent::list_after_id(&pool, 0, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|e| e.delivery_id == existing)
+            .unwrap();
+        Event::create(&pool, legacy_to_event(&already_migrated))
+            .await
+            .unwrap();
+
+        let stats = backfill_legacy_events(&pool, 2).await.unwrap();
+        assert_eq!(
+            stats,
+            BackfillStats {
+                inserted: 2,
+                skipped: 1
+            }
+        );
+
+        assert_eq!(Event::count(&pool).await.unwrap(), 3);
+
+        // Running again is a no-op
+        let stats = backfill_legacy_events(&pool, 2).await.unwrap();
+        assert_eq!(stats.inserted, 0);
+        assert_eq!(stats.skipped, 3);
+    }
+}
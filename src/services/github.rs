@@ -1,80 +1,20 @@
 use crate::models::{
     github::{
-        Commit, CreateCommit, CreateIssue, CreatePullRequest, CreateRepository, Issue, PullRequest,
-        Repository,
+        Commit, CreateCommit, CreateDeployment, CreateIssue, CreateMembershipEvent,
+        CreatePullRequest, CreateRepository, CreateRepositoryRename, CreateRepositoryStar,
+        Deployment, DeploymentStatus, Issue, MembershipEvent, PullRequest, Repository,
+        RepositoryRename, RepositoryStar,
     },
-    CreateEvent, Event,
+    Actor, CreateEvent, EntityChange, Event, FieldChange,
 };
+use crate::services::RepositoryCache;
+use crate::utils::{json_i64, WatchedPaths};
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use serde_json::Value as JsonValue;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-/// Extract actor information from GitHub webhook payload
-pub fn extract_actor_info(payload: &JsonValue) -> (Option<String>, Option<String>, Option<String>) {
-    let actor_name = payload
-        .get("sender")
-        .and_then(|s| s.get("login"))
-        .and_then(|l| l.as_str())
-        .or_else(|| {
-            payload
-                .get("pusher")
-                .and_then(|p| p.get("name"))
-                .and_then(|n| n.as_str())
-        })
-        .or_else(|| {
-            payload
-                .get("commits")
-                .and_then(|c| c.get(0))
-                .and_then(|c| c.get("author"))
-                .and_then(|a| a.get("name"))
-                .and_then(|n| n.as_str())
-        })
-        .map(|s| s.to_string());
-
-    let actor_email = payload
-        .get("sender")
-        .and_then(|s| s.get("email"))
-        .and_then(|e| e.as_str())
-        .or_else(|| {
-            payload
-                .get("pusher")
-                .and_then(|p| p.get("email"))
-                .and_then(|e| e.as_str())
-        })
-        .or_else(|| {
-            payload
-                .get("commits")
-                .and_then(|c| c.get(0))
-                .and_then(|c| c.get("author"))
-                .and_then(|a| a.get("email"))
-                .and_then(|e| e.as_str())
-        })
-        .map(|s| s.to_string());
-
-    let actor_id = payload
-        .get("sender")
-        .and_then(|s| s.get("login"))
-        .and_then(|l| l.as_str())
-        .map(|s| s.to_string())
-        .or_else(|| {
-            payload
-                .get("sender")
-                .and_then(|s| s.get("id"))
-                .and_then(|i| i.as_i64())
-                .map(|i| i.to_string())
-        })
-        .or_else(|| {
-            payload
-                .get("pusher")
-                .and_then(|p| p.get("name"))
-                .and_then(|n| n.as_str())
-                .map(|s| s.to_string())
-        });
-
-    (actor_name, actor_email, actor_id)
-}
-
 /// Convert GitHub webhook to generic event
 pub fn convert_github_webhook_to_event(
     event_type: String,
@@ -84,133 +24,498 @@ pub fn convert_github_webhook_to_event(
     signature: Option<String>,
     repository_id: Option<i64>,
 ) -> CreateEvent {
-    let (actor_name, actor_email, actor_id) = extract_actor_info(&payload);
-
     CreateEvent {
         source: "github".to_string(),
         event_type,
         action: event_action,
-        actor_name,
-        actor_email,
-        actor_id,
+        actor: Actor::from_payload("github", &payload),
         raw_event: payload,
         delivery_id,
         signature,
         repository_id,
+        tenant: None,
+        raw_source: None,
+        raw_event_type: None,
+        raw_original: None,
+        payload_sha256: None,
+        searchable_text: None,
     }
 }
 
-pub async fn process_github_event(pool: &PgPool, event: &Event) -> Result<(), ProcessingError> {
+/// What processing an event wrote to the domain tables
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProcessingResult {
+    /// Rows created or updated, not counting the repository upsert
+    pub entities_written: u64,
+    /// Kind of row written, e.g. `commit` or `pull_request`
+    pub entity_type: Option<&'static str>,
+    /// Id of the row written, when the event affects exactly one
+    pub entity_id: Option<i64>,
+}
+
+impl ProcessingResult {
+    fn written(entity_type: &'static str, entities_written: u64, entity_id: Option<i64>) -> Self {
+        ProcessingResult {
+            entities_written,
+            entity_type: Some(entity_type),
+            entity_id,
+        }
+    }
+}
+
+/// Reads like "created 5 commits", or "nothing written"
+impl std::fmt::Display for ProcessingResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.entity_type {
+            Some(entity_type) if self.entities_written > 0 => {
+                let noun = entity_type.replace('_', " ");
+                let plural = if self.entities_written == 1 { "" } else { "s" };
+                write!(f, "created {} {noun}{plural}", self.entities_written)
+            }
+            _ => write!(f, "nothing written"),
+        }
+    }
+}
+
+/// Apply a GitHub event to the domain tables and mark it processed.
+///
+/// Repositories are upserted through `repos`, skipping the write for recently seen ones.
+/// In `strict` mode an event type without a processor is an error rather than a no-op.
+pub async fn process_github_event(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    strict: bool,
+) -> Result<ProcessingResult, ProcessingError> {
+    // Processing needs the payload, which compaction has dropped
+    if event.raw_event_compacted_at.is_some() {
+        return Err(ProcessingError::PayloadCompacted(event.id));
+    }
+
     let event_type = event.event_type.as_str();
     let payload = &event.raw_event;
 
-    match event_type {
-        "push" => process_push_event(pool, event, payload).await?,
-        "pull_request" => process_pull_request_event(pool, event, payload).await?,
-        "issues" => process_issues_event(pool, event, payload).await?,
+    let result = match event_type {
+        "push" => process_push_event(pool, repos, event, payload).await?,
+        "pull_request" => process_pull_request_event(pool, repos, event, payload).await?,
+        "issues" => process_issues_event(pool, repos, event, payload).await?,
+        "star" | "watch" => process_star_event(pool, repos, event, payload).await?,
+        "repository" => process_repository_event(pool, repos, event, payload).await?,
+        "deployment" => process_deployment_event(pool, repos, event, payload).await?,
+        "deployment_status" => process_deployment_status_event(pool, repos, event, payload).await?,
+        "member" | "membership" | "organization" => {
+            process_membership_event(pool, event, payload).await?
+        }
+        _ if strict => {
+            return Err(ProcessingError::UnhandledEventType(event_type.to_string()));
+        }
         _ => {
             log::debug!("Unhandled GitHub event type: {event_type}");
+            ProcessingResult::default()
         }
-    }
+    };
 
     Event::mark_processed(pool, event.id).await?;
 
-    Ok(())
+    Ok(result)
 }
 
 async fn process_push_event(
     pool: &PgPool,
+    repos: &RepositoryCache,
     event: &Event,
     payload: &JsonValue,
-) -> Result<(), ProcessingError> {
-    let repo_data = extract_repository(payload)?;
-    let repository = Repository::create(pool, repo_data).await?;
+) -> Result<ProcessingResult, ProcessingError> {
+    let repository_id = repos.upsert(pool, extract_repository(payload)?).await?;
+
+    let (watched, skip_unwatched) = match Repository::find_by_id(pool, repository_id).await? {
+        Some(repo) => {
+            let watched = WatchedPaths::new(&repo.watched_paths).unwrap_or_else(|e| {
+                log::warn!("Ignoring invalid watched paths of {}: {e}", repo.full_name);
+                WatchedPaths::default()
+            });
+            (watched, repo.skip_unwatched_commits)
+        }
+        None => (WatchedPaths::default(), false),
+    };
+
+    let mut written = 0;
+    for commit in extract_push_commits(payload, repository_id, event.id, &watched)? {
+        if skip_unwatched && commit.touches_watched == Some(false) {
+            continue;
+        }
+        Commit::create(pool, commit).await?;
+        written += 1;
+    }
+
+    let truncated = push_commits_truncated(payload);
+    if truncated {
+        log::info!("Push event {} lists only part of its commits", event.id);
+    }
+    Event::set_commits_truncated(pool, event.id, truncated).await?;
+
+    Ok(ProcessingResult::written("commit", written, None))
+}
+
+/// Most commits GitHub lists in a push payload
+const GITHUB_PUSH_COMMIT_LIMIT: usize = 20;
 
+/// Whether a push payload lists fewer commits than the push contained. GitHub sets no
+/// flag: `size`, when sent, counts every commit; otherwise a full list of 20 commits
+/// between two existing revisions is taken as capped.
+fn push_commits_truncated(payload: &JsonValue) -> bool {
+    let listed = payload["commits"].as_array().map_or(0, Vec::len);
+    if let Some(size) = payload["size"].as_u64() {
+        return size > listed as u64;
+    }
+
+    let is_revision = |sha: &JsonValue| {
+        sha.as_str()
+            .is_some_and(|s| !s.trim_matches('0').is_empty())
+    };
+    listed >= GITHUB_PUSH_COMMIT_LIMIT
+        && is_revision(&payload["before"])
+        && is_revision(&payload["after"])
+}
+
+/// The commits of a push payload, all validated before any is stored.
+///
+/// Each commit is classified against `watched` from the files it added, modified and removed.
+fn extract_push_commits(
+    payload: &JsonValue,
+    repository_id: i64,
+    event_id: i64,
+    watched: &WatchedPaths,
+) -> Result<Vec<CreateCommit>, ProcessingError> {
     let commits = payload["commits"].as_array().ok_or_else(|| {
         ProcessingError::InvalidPayload("Missing commits array in push event".to_string())
     })?;
 
-    for commit_data in commits {
-        let sha = commit_data["id"]
-            .as_str()
-            .ok_or_else(|| ProcessingError::InvalidPayload("Missing commit sha".to_string()))?
-            .to_string();
-
-        let message = commit_data["message"]
-            .as_str()
-            .ok_or_else(|| ProcessingError::InvalidPayload("Missing commit message".to_string()))?
-            .to_string();
-
-        let author_name = commit_data["author"]["name"]
-            .as_str()
-            .ok_or_else(|| ProcessingError::InvalidPayload("Missing author name".to_string()))?
-            .to_string();
-
-        let author_email = commit_data["author"]["email"]
-            .as_str()
-            .ok_or_else(|| ProcessingError::InvalidPayload("Missing author email".to_string()))?
-            .to_string();
-
-        let committer_name = commit_data["committer"]["name"]
-            .as_str()
-            .ok_or_else(|| ProcessingError::InvalidPayload("Missing committer name".to_string()))?
-            .to_string();
-
-        let committer_email = commit_data["committer"]["email"]
-            .as_str()
-            .ok_or_else(|| ProcessingError::InvalidPayload("Missing committer email".to_string()))?
-            .to_string();
-
-        let timestamp_str = commit_data["timestamp"].as_str().ok_or_else(|| {
-            ProcessingError::InvalidPayload("Missing commit timestamp".to_string())
-        })?;
+    // Tag pushes have no branch to associate with a pull request
+    let branch = payload["ref"]
+        .as_str()
+        .and_then(|r| r.strip_prefix("refs/heads/"))
+        .map(str::to_string);
+
+    commits
+        .iter()
+        .map(|commit_data| {
+            let mut commit = extract_commit(commit_data, repository_id, event_id, &branch)?;
+            commit.touches_watched = watched.touched_by(commit_data);
+            Ok(commit)
+        })
+        .collect()
+}
 
-        let committed_at: DateTime<Utc> = timestamp_str
-            .parse()
-            .map_err(|_| ProcessingError::InvalidPayload("Invalid timestamp format".to_string()))?;
-
-        let url = commit_data["url"]
-            .as_str()
-            .ok_or_else(|| ProcessingError::InvalidPayload("Missing commit url".to_string()))?
-            .to_string();
-
-        let commit = CreateCommit {
-            repository_id: repository.id,
-            webhook_event_id: event.id,
-            sha,
-            message,
-            author_name,
-            author_email,
-            committer_name,
-            committer_email,
-            committed_at,
-            url,
-        };
+fn extract_commit(
+    commit_data: &JsonValue,
+    repository_id: i64,
+    event_id: i64,
+    branch: &Option<String>,
+) -> Result<CreateCommit, ProcessingError> {
+    let sha = commit_data["id"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing commit sha".to_string()))?
+        .to_string();
 
-        Commit::create(pool, commit).await?;
+    // Pages show the first seven characters, so anything else would be unrenderable
+    if !is_commit_sha(&sha) {
+        return Err(ProcessingError::InvalidPayload(
+            "Invalid commit sha".to_string(),
+        ));
     }
 
-    Ok(())
+    let message = commit_data["message"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing commit message".to_string()))?
+        .to_string();
+
+    let author_name = commit_data["author"]["name"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing author name".to_string()))?
+        .to_string();
+
+    let author_email = commit_data["author"]["email"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing author email".to_string()))?
+        .to_string();
+
+    let committer_name = commit_data["committer"]["name"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing committer name".to_string()))?
+        .to_string();
+
+    let committer_email = commit_data["committer"]["email"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing committer email".to_string()))?
+        .to_string();
+
+    let timestamp_str = commit_data["timestamp"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing commit timestamp".to_string()))?;
+
+    let committed_at: DateTime<Utc> = timestamp_str
+        .parse()
+        .map_err(|_| ProcessingError::InvalidPayload("Invalid timestamp format".to_string()))?;
+
+    let url = commit_data["url"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing commit url".to_string()))?
+        .to_string();
+
+    Ok(CreateCommit {
+        repository_id,
+        webhook_event_id: event_id,
+        sha,
+        message,
+        author_name,
+        author_email,
+        committer_name,
+        committer_email,
+        committed_at,
+        url,
+        branch: branch.clone(),
+        touches_watched: None,
+    })
+}
+
+/// SHA-1 or SHA-256 object name in hex
+fn is_commit_sha(sha: &str) -> bool {
+    (7..=64).contains(&sha.len()) && sha.bytes().all(|b| b.is_ascii_hexdigit())
 }
 
 async fn process_pull_request_event(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    payload: &JsonValue,
+) -> Result<ProcessingResult, ProcessingError> {
+    let repository_id = repos.upsert(pool, extract_repository(payload)?).await?;
+
+    let pr = extract_pull_request(payload, repository_id, event.id)?;
+    let (github_id, number, head_branch) = (pr.github_id, pr.number, pr.head_branch.clone());
+
+    let changes = PullRequest::find_by_github_id(pool, github_id)
+        .await?
+        .map(|existing| existing.diff(&pr));
+    let pull_request_id = PullRequest::create(pool, pr).await?.id;
+    record_changes(pool, "pull_request", event, changes, pull_request_id).await?;
+
+    // Commits pushed to the head branch, before or after the PR was opened, belong to it
+    let associated = Commit::associate_branch_with_pull_request(
+        pool,
+        repository_id,
+        &head_branch,
+        pull_request_id,
+    )
+    .await?;
+    if associated > 0 {
+        log::debug!("Associated {associated} commits on {head_branch} with PR #{number}");
+    }
+
+    Ok(ProcessingResult::written(
+        "pull_request",
+        1,
+        Some(pull_request_id),
+    ))
+}
+
+async fn process_issues_event(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    payload: &JsonValue,
+) -> Result<ProcessingResult, ProcessingError> {
+    let repository_id = repos.upsert(pool, extract_repository(payload)?).await?;
+
+    let issue = extract_issue(payload, repository_id, event.id)?;
+
+    let changes = Issue::find_by_github_id(pool, issue.github_id)
+        .await?
+        .map(|existing| existing.diff(&issue));
+    let issue_id = Issue::create(pool, issue).await?.id;
+    record_changes(pool, "issue", event, changes, issue_id).await?;
+
+    Ok(ProcessingResult::written("issue", 1, Some(issue_id)))
+}
+
+/// Record a star (`created`/`deleted`) or watch (`started`) in the repository's star history
+async fn process_star_event(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    payload: &JsonValue,
+) -> Result<ProcessingResult, ProcessingError> {
+    let repository_id = repos.upsert(pool, extract_repository(payload)?).await?;
+
+    let (star, stargazers_count) =
+        extract_star(payload, repository_id, event.id, event.received_at)?;
+
+    match RepositoryStar::record(pool, star, stargazers_count).await? {
+        Some(recorded) => Ok(ProcessingResult::written(
+            "repository_star",
+            1,
+            Some(recorded.id),
+        )),
+        None => {
+            log::debug!("Event {} star change was already recorded", event.id);
+            Ok(ProcessingResult::written("repository_star", 0, None))
+        }
+    }
+}
+
+/// Update the repository's metadata, recording the previous name of a `renamed` one.
+///
+/// Repositories are keyed by GitHub id, so the upsert moves the existing row to the new
+/// `full_name` rather than creating a second one.
+async fn process_repository_event(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    payload: &JsonValue,
+) -> Result<ProcessingResult, ProcessingError> {
+    let repository = extract_repository(payload)?;
+    let rename = extract_rename(payload, &repository)?;
+    let repository_id = repos.upsert(pool, repository).await?;
+
+    if let Some((old_full_name, new_full_name)) = rename {
+        log::info!("Repository {old_full_name} was renamed to {new_full_name}");
+        RepositoryRename::record(
+            pool,
+            CreateRepositoryRename {
+                repository_id,
+                event_id: event.id,
+                old_full_name,
+                new_full_name,
+                renamed_at: event.received_at,
+            },
+        )
+        .await?;
+    }
+
+    Ok(ProcessingResult::written(
+        "repository",
+        1,
+        Some(repository_id),
+    ))
+}
+
+/// Track a deployment of a ref to an environment
+async fn process_deployment_event(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    payload: &JsonValue,
+) -> Result<ProcessingResult, ProcessingError> {
+    let repository_id = repos.upsert(pool, extract_repository(payload)?).await?;
+
+    let deployment =
+        Deployment::create(pool, extract_deployment(payload, repository_id, event.id)?).await?;
+
+    Ok(ProcessingResult::written(
+        "deployment",
+        1,
+        Some(deployment.id),
+    ))
+}
+
+/// Apply a status to its deployment, matched by GitHub id. The status payload carries the
+/// deployment too, so a status processed before its `deployment` event still records both.
+async fn process_deployment_status_event(
+    pool: &PgPool,
+    repos: &RepositoryCache,
+    event: &Event,
+    payload: &JsonValue,
+) -> Result<ProcessingResult, ProcessingError> {
+    let repository_id = repos.upsert(pool, extract_repository(payload)?).await?;
+
+    let deployment = extract_deployment(payload, repository_id, event.id)?;
+    let github_id = deployment.github_id;
+    if Deployment::find_by_github_id(pool, github_id)
+        .await?
+        .is_none()
+    {
+        Deployment::create(pool, deployment).await?;
+    }
+
+    let status = extract_deployment_status(payload)?;
+    match Deployment::record_status(pool, github_id, status).await? {
+        Some(updated) => Ok(ProcessingResult::written("deployment", 1, Some(updated.id))),
+        None => {
+            log::debug!(
+                "Event {} is an older status than the one recorded for deployment {github_id}",
+                event.id
+            );
+            Ok(ProcessingResult::written("deployment", 0, None))
+        }
+    }
+}
+
+/// Append a membership change to the audit log. `organization` events that don't
+/// change membership, such as renames, write nothing.
+async fn process_membership_event(
     pool: &PgPool,
     event: &Event,
     payload: &JsonValue,
+) -> Result<ProcessingResult, ProcessingError> {
+    let Some(membership) =
+        extract_membership(payload, &event.event_type, event.id, event.received_at)?
+    else {
+        return Ok(ProcessingResult::default());
+    };
+
+    match MembershipEvent::record(pool, membership).await? {
+        Some(recorded) => Ok(ProcessingResult::written(
+            "membership_event",
+            1,
+            Some(recorded.id),
+        )),
+        None => Ok(ProcessingResult::written("membership_event", 0, None)),
+    }
+}
+
+/// Record field changes for an upserted entity; identical redeliveries record nothing
+async fn record_changes(
+    pool: &PgPool,
+    entity_type: &str,
+    event: &Event,
+    changes: Option<Vec<FieldChange>>,
+    entity_id: i64,
 ) -> Result<(), ProcessingError> {
-    let repo_data = extract_repository(payload)?;
-    let repository = Repository::create(pool, repo_data).await?;
+    match changes {
+        Some(changes) if !changes.is_empty() => {
+            log::info!(
+                "Event {} edited {entity_type} {entity_id}: {} field(s) changed",
+                event.id,
+                changes.len()
+            );
+            EntityChange::record(pool, entity_type, entity_id, Some(event.id), &changes).await?;
+        }
+        Some(_) => {
+            log::debug!(
+                "Event {} is a redelivery of {entity_type} {entity_id} with no changes",
+                event.id
+            );
+        }
+        None => {}
+    }
 
+    Ok(())
+}
+
+fn extract_pull_request(
+    payload: &JsonValue,
+    repository_id: i64,
+    event_id: i64,
+) -> Result<CreatePullRequest, ProcessingError> {
     let pr_data = &payload["pull_request"];
 
-    let github_id = pr_data["id"]
-        .as_i64()
+    let github_id = json_i64(&pr_data["id"])
         .ok_or_else(|| ProcessingError::InvalidPayload("Missing PR id".to_string()))?;
 
-    let number = pr_data["number"]
-        .as_i64()
-        .ok_or_else(|| ProcessingError::InvalidPayload("Missing PR number".to_string()))?
-        as i32;
+    let number = json_i64(&pr_data["number"])
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing PR number".to_string()))
+        .and_then(|n| extract_number(n, "PR number"))?;
 
     let title = pr_data["title"]
         .as_str()
@@ -258,9 +563,9 @@ async fn process_pull_request_event(
         .as_str()
         .and_then(|s| s.parse::<DateTime<Utc>>().ok());
 
-    let pr = CreatePullRequest {
-        repository_id: repository.id,
-        webhook_event_id: event.id,
+    Ok(CreatePullRequest {
+        repository_id,
+        webhook_event_id: event_id,
         github_id,
         number,
         title,
@@ -272,31 +577,22 @@ async fn process_pull_request_event(
         opened_at,
         closed_at,
         merged_at,
-    };
-
-    PullRequest::create(pool, pr).await?;
-
-    Ok(())
+    })
 }
 
-async fn process_issues_event(
-    pool: &PgPool,
-    event: &Event,
+fn extract_issue(
     payload: &JsonValue,
-) -> Result<(), ProcessingError> {
-    let repo_data = extract_repository(payload)?;
-    let repository = Repository::create(pool, repo_data).await?;
-
+    repository_id: i64,
+    event_id: i64,
+) -> Result<CreateIssue, ProcessingError> {
     let issue_data = &payload["issue"];
 
-    let github_id = issue_data["id"]
-        .as_i64()
+    let github_id = json_i64(&issue_data["id"])
         .ok_or_else(|| ProcessingError::InvalidPayload("Missing issue id".to_string()))?;
 
-    let number = issue_data["number"]
-        .as_i64()
-        .ok_or_else(|| ProcessingError::InvalidPayload("Missing issue number".to_string()))?
-        as i32;
+    let number = json_i64(&issue_data["number"])
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing issue number".to_string()))
+        .and_then(|n| extract_number(n, "issue number"))?;
 
     let title = issue_data["title"]
         .as_str()
@@ -339,9 +635,9 @@ async fn process_issues_event(
         .as_str()
         .and_then(|s| s.parse::<DateTime<Utc>>().ok());
 
-    let issue = CreateIssue {
-        repository_id: repository.id,
-        webhook_event_id: event.id,
+    Ok(CreateIssue {
+        repository_id,
+        webhook_event_id: event_id,
         github_id,
         number,
         title,
@@ -351,18 +647,234 @@ async fn process_issues_event(
         url,
         opened_at,
         closed_at,
+    })
+}
+
+/// The star change and, when the payload carries one, the repository's new star count
+fn extract_star(
+    payload: &JsonValue,
+    repository_id: i64,
+    event_id: i64,
+    received_at: DateTime<Utc>,
+) -> Result<(CreateRepositoryStar, Option<i32>), ProcessingError> {
+    let action = payload["action"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing star action".to_string()))?
+        .to_string();
+
+    let actor = payload["sender"]["login"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing sender login".to_string()))?
+        .to_string();
+
+    // Only `star created` carries `starred_at`; fall back to when the event arrived
+    let at = payload["starred_at"]
+        .as_str()
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+        .unwrap_or(received_at);
+
+    // A count that does not fit the column is ignored like a missing one
+    let stargazers_count = json_i64(&payload["repository"]["stargazers_count"])
+        .and_then(|count| i32::try_from(count).ok());
+
+    let star = CreateRepositoryStar {
+        repository_id,
+        event_id,
+        action,
+        actor,
+        at,
     };
 
-    Issue::create(pool, issue).await?;
+    Ok((star, stargazers_count))
+}
 
-    Ok(())
+/// Old and new full names from a `renamed` repository event; `None` for other actions
+fn extract_rename(
+    payload: &JsonValue,
+    repository: &CreateRepository,
+) -> Result<Option<(String, String)>, ProcessingError> {
+    if payload["action"].as_str() != Some("renamed") {
+        return Ok(None);
+    }
+
+    let old_name = payload["changes"]["repository"]["name"]["from"]
+        .as_str()
+        .ok_or_else(|| {
+            ProcessingError::InvalidPayload("Missing previous repository name".to_string())
+        })?;
+
+    Ok(Some((
+        format!("{}/{old_name}", repository.owner),
+        repository.full_name.clone(),
+    )))
+}
+
+fn extract_deployment(
+    payload: &JsonValue,
+    repository_id: i64,
+    event_id: i64,
+) -> Result<CreateDeployment, ProcessingError> {
+    let deployment = &payload["deployment"];
+
+    let github_id = json_i64(&deployment["id"])
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing deployment id".to_string()))?;
+
+    let environment = deployment["environment"]
+        .as_str()
+        .ok_or_else(|| {
+            ProcessingError::InvalidPayload("Missing deployment environment".to_string())
+        })?
+        .to_string();
+
+    let git_ref = deployment["ref"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing deployment ref".to_string()))?
+        .to_string();
+
+    let sha = deployment["sha"]
+        .as_str()
+        .filter(|sha| is_commit_sha(sha))
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing deployment sha".to_string()))?
+        .to_string();
+
+    let creator = deployment["creator"]["login"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing deployment creator".to_string()))?
+        .to_string();
+
+    let deployed_at = deployment["created_at"]
+        .as_str()
+        .ok_or_else(|| {
+            ProcessingError::InvalidPayload("Missing deployment created_at".to_string())
+        })?
+        .parse()
+        .map_err(|_| ProcessingError::InvalidPayload("Invalid timestamp format".to_string()))?;
+
+    Ok(CreateDeployment {
+        repository_id,
+        event_id,
+        github_id,
+        environment,
+        git_ref,
+        sha,
+        creator,
+        deployed_at,
+    })
+}
+
+/// The status's state and the most useful link it carries: the deployed environment,
+/// else the target or log URL
+fn extract_deployment_status(payload: &JsonValue) -> Result<DeploymentStatus, ProcessingError> {
+    let status = &payload["deployment_status"];
+
+    let state = status["state"]
+        .as_str()
+        .ok_or_else(|| {
+            ProcessingError::InvalidPayload("Missing deployment status state".to_string())
+        })?
+        .to_string();
+
+    let url = ["environment_url", "target_url", "log_url"]
+        .iter()
+        .find_map(|field| status[*field].as_str().filter(|url| !url.is_empty()))
+        .map(str::to_string);
+
+    let at = status["created_at"]
+        .as_str()
+        .ok_or_else(|| {
+            ProcessingError::InvalidPayload("Missing deployment status created_at".to_string())
+        })?
+        .parse()
+        .map_err(|_| ProcessingError::InvalidPayload("Invalid timestamp format".to_string()))?;
+
+    Ok(DeploymentStatus { state, url, at })
+}
+
+/// The membership change in a `member`, `membership` or `organization` payload;
+/// `None` for organization actions other than adding, removing or inviting a member
+fn extract_membership(
+    payload: &JsonValue,
+    event_type: &str,
+    event_id: i64,
+    at: DateTime<Utc>,
+) -> Result<Option<CreateMembershipEvent>, ProcessingError> {
+    let action = payload["action"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing membership action".to_string()))?
+        .to_string();
+
+    let organization = payload["organization"]["login"]
+        .as_str()
+        .map(str::to_string);
+
+    let (member, scope_type, scope) = match event_type {
+        "member" => (
+            payload["member"]["login"].as_str(),
+            "repository",
+            payload["repository"]["full_name"].as_str(),
+        ),
+        "membership" => (
+            payload["member"]["login"].as_str(),
+            "team",
+            payload["team"]["slug"]
+                .as_str()
+                .or_else(|| payload["team"]["name"].as_str()),
+        ),
+        "organization" => {
+            let member = match action.as_str() {
+                "member_added" | "member_removed" => {
+                    payload["membership"]["user"]["login"].as_str()
+                }
+                // Invitations by email have no login yet
+                "member_invited" => payload["invitation"]["login"]
+                    .as_str()
+                    .or_else(|| payload["invitation"]["email"].as_str()),
+                _ => return Ok(None),
+            };
+            (member, "organization", organization.as_deref())
+        }
+        _ => {
+            return Err(ProcessingError::InvalidPayload(format!(
+                "{event_type} is not a membership event"
+            )))
+        }
+    };
+
+    let member = member
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing membership member".to_string()))?
+        .to_string();
+    let scope = scope
+        .ok_or_else(|| {
+            ProcessingError::InvalidPayload(format!("Missing {scope_type} for membership"))
+        })?
+        .to_string();
+    let actor = payload["sender"]["login"]
+        .as_str()
+        .ok_or_else(|| ProcessingError::InvalidPayload("Missing membership sender".to_string()))?
+        .to_string();
+
+    Ok(Some(CreateMembershipEvent {
+        event_id,
+        action,
+        member,
+        scope_type: scope_type.to_string(),
+        scope,
+        organization,
+        actor,
+        at,
+    }))
+}
+
+/// PR and issue numbers are stored as `INTEGER`
+fn extract_number(number: i64, field: &str) -> Result<i32, ProcessingError> {
+    i32::try_from(number)
+        .map_err(|_| ProcessingError::InvalidPayload(format!("{field} out of range")))
 }
 
 fn extract_repository(payload: &JsonValue) -> Result<CreateRepository, ProcessingError> {
     let repo = &payload["repository"];
 
-    let github_id = repo["id"]
-        .as_i64()
+    let github_id = json_i64(&repo["id"])
         .ok_or_else(|| ProcessingError::InvalidPayload("Missing repository id".to_string()))?;
 
     let name = repo["name"]
@@ -389,6 +901,18 @@ fn extract_repository(payload: &JsonValue) -> Result<CreateRepository, Processin
 
     let is_private = repo["private"].as_bool().unwrap_or(false);
 
+    let language = repo["language"].as_str().map(|s| s.to_string());
+
+    let topics = repo["topics"]
+        .as_array()
+        .map(|topics| {
+            topics
+                .iter()
+                .filter_map(|t| t.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
     Ok(CreateRepository {
         github_id,
         name,
@@ -397,6 +921,8 @@ fn extract_repository(payload: &JsonValue) -> Result<CreateRepository, Processin
         description,
         url,
         is_private,
+        language,
+        topics,
     })
 }
 
@@ -406,4 +932,1103 @@ pub enum ProcessingError {
     InvalidPayload(String),
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
+    #[error("unhandled event type: {0}")]
+    UnhandledEventType(String),
+    #[error("payload of event {0} was compacted")]
+    PayloadCompacted(i64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_with_commits(count: usize) -> JsonValue {
+        let commits: Vec<JsonValue> = (0..count)
+            .map(|i| serde_json::json!({ "id": format!("{i:040x}"), "message": "change" }))
+            .collect();
+        serde_json::json!({
+            "ref": "refs/heads/main",
+            "before": "6113728f27ae82c7b1a177c8d03f9e96e0adf246",
+            "after": "59b20b8d5c6ff8d09518454d4dd8b7b30f095ab5",
+            "commits": commits
+        })
+    }
+
+    #[test]
+    fn test_push_with_twenty_commits_over_a_wide_range_is_truncated() {
+        assert!(push_commits_truncated(&push_with_commits(20)));
+        assert!(!push_commits_truncated(&push_with_commits(19)));
+
+        // `size` counts every commit of the push when GitHub sends it
+        let mut sized = push_with_commits(20);
+        sized["size"] = serde_json::json!(45);
+        assert!(push_commits_truncated(&sized));
+        sized["size"] = serde_json::json!(20);
+        assert!(!push_commits_truncated(&sized));
+
+        // Deleting a branch has no range to compare against
+        let mut deleted = push_with_commits(20);
+        deleted["after"] = serde_json::json!("0000000000000000000000000000000000000000");
+        assert!(!push_commits_truncated(&deleted));
+    }
+
+    #[test]
+    fn test_extract_rename_uses_owner_and_previous_name() {
+        let mut payload = serde_json::json!({
+            "action": "renamed",
+            "changes": { "repository": { "name": { "from": "Hello-World" } } },
+            "repository": hello_world_repository()
+        });
+        payload["repository"]["name"] = serde_json::json!("Hello-Universe");
+        payload["repository"]["full_name"] = serde_json::json!("octocat/Hello-Universe");
+        let repository = extract_repository(&payload).unwrap();
+
+        assert_eq!(
+            extract_rename(&payload, &repository).unwrap(),
+            Some((
+                "octocat/Hello-World".to_string(),
+                "octocat/Hello-Universe".to_string()
+            ))
+        );
+
+        payload["changes"] = serde_json::json!({});
+        assert!(extract_rename(&payload, &repository).is_err());
+
+        payload["action"] = serde_json::json!("edited");
+        assert_eq!(extract_rename(&payload, &repository).unwrap(), None);
+    }
+
+    #[test]
+    fn test_extract_repository_language_and_topics() {
+        let payload = serde_json::json!({
+            "repository": {
+                "id": 1296269,
+                "name": "Hello-World",
+                "full_name": "octocat/Hello-World",
+                "owner": { "login": "octocat" },
+                "html_url": "https://github.com/octocat/Hello-World",
+                "private": false,
+                "language": "Rust",
+                "topics": ["webhooks", "actix", 42]
+            }
+        });
+
+        let repo = extract_repository(&payload).unwrap();
+        assert_eq!(repo.language.as_deref(), Some("Rust"));
+        assert_eq!(repo.topics, vec!["webhooks", "actix"]);
+
+        // Older payloads and other forges omit both fields
+        let mut bare = payload.clone();
+        bare["repository"]["language"] = JsonValue::Null;
+        bare["repository"].as_object_mut().unwrap().remove("topics");
+        let repo = extract_repository(&bare).unwrap();
+        assert_eq!(repo.language, None);
+        assert!(repo.topics.is_empty());
+    }
+
+    #[test]
+    fn test_convert_captures_sender_avatar() {
+        let payload = serde_json::json!({
+            "sender": {
+                "login": "octocat",
+                "id": 1,
+                "avatar_url": "https://github.com/images/error/octocat_happy.gif"
+            }
+        });
+
+        let event = convert_github_webhook_to_event(
+            "issues".to_string(),
+            Some("opened".to_string()),
+            payload,
+            Uuid::new_v4(),
+            None,
+            None,
+        );
+
+        assert_eq!(event.actor.name.as_deref(), Some("octocat"));
+        assert_eq!(
+            event.actor.avatar_url.as_deref(),
+            Some("https://github.com/images/error/octocat_happy.gif")
+        );
+    }
+
+    async fn create_unhandled_event(pool: &PgPool) -> Event {
+        let data = convert_github_webhook_to_event(
+            "gollum".to_string(),
+            None,
+            serde_json::json!({"pages": []}),
+            Uuid::new_v4(),
+            None,
+            None,
+        );
+        Event::create(pool, data).await.unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_strict_mode_fails_unhandled_event_types(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let lenient = create_unhandled_event(&pool).await;
+        process_github_event(&pool, &repos, &lenient, false)
+            .await
+            .unwrap();
+        let lenient = Event::find_by_id(&pool, lenient.id).await.unwrap().unwrap();
+        assert!(lenient.processed);
+
+        let strict = create_unhandled_event(&pool).await;
+        let err = process_github_event(&pool, &repos, &strict, true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProcessingError::UnhandledEventType(ref t) if t == "gollum"));
+        assert_eq!(err.to_string(), "unhandled event type: gollum");
+
+        Event::mark_failed(&pool, strict.id, &err.to_string())
+            .await
+            .unwrap();
+        let strict = Event::find_by_id(&pool, strict.id).await.unwrap().unwrap();
+        assert!(!strict.processed);
+        assert_eq!(
+            strict.processing_error.as_deref(),
+            Some("unhandled event type: gollum")
+        );
+    }
+
+    async fn create_star_event(pool: &PgPool, action: &str, login: &str) -> Event {
+        let starred_at = (action == "created").then_some("2026-10-01T12:00:00Z");
+        let data = convert_github_webhook_to_event(
+            "star".to_string(),
+            Some(action.to_string()),
+            serde_json::json!({
+                "action": action,
+                "starred_at": starred_at,
+                "repository": {
+                    "id": 1296269,
+                    "name": "Hello-World",
+                    "full_name": "octocat/Hello-World",
+                    "owner": { "login": "octocat" },
+                    "html_url": "https://github.com/octocat/Hello-World",
+                    "private": false
+                },
+                "sender": { "login": login }
+            }),
+            Uuid::new_v4(),
+            None,
+            None,
+        );
+        Event::create(pool, data).await.unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_star_created_and_deleted_update_running_count(pool: PgPool) {
+        // One cache across events, as in the server: later events reuse the repository id
+        let repos = RepositoryCache::default();
+        let first = create_star_event(&pool, "created", "octocat").await;
+        process_github_event(&pool, &repos, &first, false)
+            .await
+            .unwrap();
+        let second = create_star_event(&pool, "created", "hubot").await;
+        process_github_event(&pool, &repos, &second, false)
+            .await
+            .unwrap();
+
+        // Reprocessing the same event must not count the star twice
+        process_github_event(&pool, &repos, &second, false)
+            .await
+            .unwrap();
+
+        let repo = Repository::find_by_full_name(&pool, "octocat/Hello-World")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(repo.stars, 2);
+
+        let unstar = create_star_event(&pool, "deleted", "octocat").await;
+        process_github_event(&pool, &repos, &unstar, false)
+            .await
+            .unwrap();
+
+        let repo = Repository::find_by_id(&pool, repo.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(repo.stars, 1);
+
+        let stargazers = RepositoryStar::list_recent_stargazers(&pool, repo.id, 10)
+            .await
+            .unwrap();
+        let actors: Vec<&str> = stargazers.iter().map(|s| s.actor.as_str()).collect();
+        assert_eq!(actors.len(), 2);
+        assert!(actors.contains(&"octocat") && actors.contains(&"hubot"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_star_uses_stargazers_count_when_present(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let mut event = create_star_event(&pool, "created", "octocat").await;
+        event.raw_event["repository"]["stargazers_count"] = serde_json::json!(42);
+        process_github_event(&pool, &repos, &event, false)
+            .await
+            .unwrap();
+
+        let repo = Repository::find_by_full_name(&pool, "octocat/Hello-World")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(repo.stars, 42);
+    }
+
+    /// Everything the processor extracts from `payload` as `event_type`, for comparing
+    /// a payload with its pruned form
+    fn extracted(event_type: &str, payload: &JsonValue) -> String {
+        let at: DateTime<Utc> = "2026-10-01T12:00:00Z".parse().unwrap();
+        let repository = extract_repository(payload);
+        let specific = match event_type {
+            "push" => {
+                let watched = WatchedPaths::new(&["src/**"]).unwrap();
+                format!(
+                    "{:?} {}",
+                    extract_push_commits(payload, 1, 1, &watched),
+                    push_commits_truncated(payload)
+                )
+            }
+            "pull_request" => format!("{:?}", extract_pull_request(payload, 1, 1)),
+            "issues" => format!("{:?}", extract_issue(payload, 1, 1)),
+            "star" => format!("{:?}", extract_star(payload, 1, 1, at)),
+            "repository" => format!(
+                "{:?}",
+                repository
+                    .as_ref()
+                    .map(|repo| extract_rename(payload, repo))
+            ),
+            "deployment" => format!("{:?}", extract_deployment(payload, 1, 1)),
+            "deployment_status" => format!(
+                "{:?} {:?}",
+                extract_deployment(payload, 1, 1),
+                extract_deployment_status(payload)
+            ),
+            _ => format!("{:?}", extract_membership(payload, event_type, 1, at)),
+        };
+
+        format!(
+            "{repository:?}\n{:?}\n{:?}\n{:?}\n{specific}",
+            crate::services::EntityKey::from_payload("github", event_type, payload),
+            crate::models::Actor::from_payload("github", payload),
+            crate::models::event::sender_timestamp(payload),
+        )
+    }
+
+    #[test]
+    fn test_pruned_payloads_extract_the_same() {
+        let mut repository = hello_world_repository();
+        repository["language"] = serde_json::json!("Rust");
+        repository["topics"] = serde_json::json!(["webhooks", "audit"]);
+        repository["stargazers_count"] = serde_json::json!(42);
+        repository["forks_url"] =
+            serde_json::json!("https://api.github.com/repos/octocat/Hello-World/forks");
+        let deployment = serde_json::json!({
+            "id": 145988746,
+            "sha": "a84d88e7554fc1fa21bcbc4efae3c782a70d2b9d",
+            "ref": "main",
+            "environment": "production",
+            "creator": { "login": "octocat" },
+            "created_at": "2026-10-01T12:00:00Z"
+        });
+        // `html_url` is not extracted, so every payload below has something to prune
+        let sender = serde_json::json!({
+            "login": "octocat",
+            "id": 1,
+            "avatar_url": "https://github.com/octocat.png",
+            "html_url": "https://github.com/octocat"
+        });
+        let organization = serde_json::json!({ "login": "octo-org" });
+
+        let mut push = crate::utils::fuzz::seed("push");
+        push["size"] = serde_json::json!(30);
+        let mut payloads = vec![
+            ("push", push),
+            ("pull_request", crate::utils::fuzz::seed("pull_request")),
+            ("issues", crate::utils::fuzz::seed("issues")),
+            ("star", crate::utils::fuzz::seed("star")),
+        ];
+        payloads.extend([
+            (
+                "star",
+                serde_json::json!({
+                    "action": "created",
+                    "starred_at": "2026-09-30T08:00:00Z",
+                    "repository": repository,
+                    "sender": sender
+                }),
+            ),
+            (
+                "repository",
+                serde_json::json!({
+                    "action": "renamed",
+                    "changes": { "repository": { "name": { "from": "Hello-Old" } } },
+                    "repository": repository,
+                    "sender": sender
+                }),
+            ),
+            (
+                "deployment",
+                serde_json::json!({
+                    "action": "created",
+                    "deployment": deployment,
+                    "repository": repository,
+                    "sender": sender
+                }),
+            ),
+            (
+                "deployment_status",
+                serde_json::json!({
+                    "action": "created",
+                    "deployment_status": {
+                        "state": "success",
+                        "environment_url": "https://hello-world.example.com",
+                        "created_at": "2026-10-01T12:05:00Z"
+                    },
+                    "deployment": deployment,
+                    "repository": repository,
+                    "sender": sender
+                }),
+            ),
+            (
+                "member",
+                serde_json::json!({
+                    "action": "added",
+                    "member": { "login": "hubot" },
+                    "changes": { "permission": { "to": "write" } },
+                    "repository": repository,
+                    "sender": sender
+                }),
+            ),
+            (
+                "membership",
+                serde_json::json!({
+                    "action": "removed",
+                    "member": { "login": "hubot" },
+                    "team": { "name": "Justice League", "slug": "justice-league" },
+                    "organization": organization,
+                    "sender": sender
+                }),
+            ),
+            (
+                "organization",
+                serde_json::json!({
+                    "action": "member_invited",
+                    "invitation": { "login": null, "email": "new@example.com" },
+                    "organization": organization,
+                    "sender": sender
+                }),
+            ),
+        ]);
+
+        for (event_type, payload) in payloads {
+            let pruned = crate::utils::prune_payload(&payload, &[]);
+            assert_ne!(
+                pruned, payload,
+                "{event_type} payload was not pruned at all"
+            );
+            assert_eq!(
+                extracted(event_type, &pruned),
+                extracted(event_type, &payload),
+                "{event_type}"
+            );
+        }
+    }
+
+    fn hello_world_repository() -> JsonValue {
+        serde_json::json!({
+            "id": 1296269,
+            "name": "Hello-World",
+            "full_name": "octocat/Hello-World",
+            "owner": { "login": "octocat" },
+            "html_url": "https://github.com/octocat/Hello-World",
+            "private": false
+        })
+    }
+
+    async fn create_github_event(pool: &PgPool, event_type: &str, payload: JsonValue) -> Event {
+        let data = convert_github_webhook_to_event(
+            event_type.to_string(),
+            payload["action"].as_str().map(str::to_string),
+            payload,
+            Uuid::new_v4(),
+            None,
+            None,
+        );
+        Event::create(pool, data).await.unwrap()
+    }
+
+    #[test]
+    fn test_processing_result_summary() {
+        assert_eq!(
+            ProcessingResult::written("commit", 5, None).to_string(),
+            "created 5 commits"
+        );
+        assert_eq!(
+            ProcessingResult::written("pull_request", 1, Some(7)).to_string(),
+            "created 1 pull request"
+        );
+        assert_eq!(ProcessingResult::default().to_string(), "nothing written");
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_push_result_counts_written_commits(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let commits: Vec<JsonValue> = (1..=3)
+            .map(|i| {
+                let sha = format!("{i:040x}");
+                serde_json::json!({
+                    "id": sha,
+                    "message": format!("Change {i}"),
+                    "timestamp": "2026-10-01T12:00:00Z",
+                    "url": format!("https://github.com/octocat/Hello-World/commit/{sha}"),
+                    "author": { "name": "Monalisa Octocat", "email": "mona@github.com" },
+                    "committer": { "name": "Monalisa Octocat", "email": "mona@github.com" }
+                })
+            })
+            .collect();
+        let push = create_github_event(
+            &pool,
+            "push",
+            serde_json::json!({
+                "ref": "refs/heads/main",
+                "repository": hello_world_repository(),
+                "commits": commits
+            }),
+        )
+        .await;
+
+        let result = process_github_event(&pool, &repos, &push, false)
+            .await
+            .unwrap();
+        assert_eq!(result.entities_written, 3);
+        assert_eq!(result.entity_type, Some("commit"));
+        assert_eq!(result.entity_id, None);
+        assert_eq!(result.to_string(), "created 3 commits");
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_pull_request_claims_commits_pushed_to_its_branch(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let commit = |sha: &str| {
+            serde_json::json!({
+                "id": sha,
+                "message": "Add feature",
+                "timestamp": "2026-10-01T12:00:00Z",
+                "url": format!("https://github.com/octocat/Hello-World/commit/{sha}"),
+                "author": { "name": "Monalisa Octocat", "email": "mona@github.com" },
+                "committer": { "name": "Monalisa Octocat", "email": "mona@github.com" }
+            })
+        };
+        let feature_sha = "1111111111111111111111111111111111111111";
+        let main_sha = "2222222222222222222222222222222222222222";
+
+        for (branch, sha) in [("feature-x", feature_sha), ("main", main_sha)] {
+            let push = create_github_event(
+                &pool,
+                "push",
+                serde_json::json!({
+                    "ref": format!("refs/heads/{branch}"),
+                    "repository": hello_world_repository(),
+                    "commits": [commit(sha)]
+                }),
+            )
+            .await;
+            process_github_event(&pool, &repos, &push, false)
+                .await
+                .unwrap();
+        }
+
+        let opened = create_github_event(
+            &pool,
+            "pull_request",
+            serde_json::json!({
+                "action": "opened",
+                "repository": hello_world_repository(),
+                "pull_request": {
+                    "id": 7001,
+                    "number": 7,
+                    "title": "Add feature",
+                    "state": "open",
+                    "user": { "login": "octocat" },
+                    "base": { "ref": "main" },
+                    "head": { "ref": "feature-x" },
+                    "html_url": "https://github.com/octocat/Hello-World/pull/7",
+                    "created_at": "2026-10-01T13:00:00Z"
+                }
+            }),
+        )
+        .await;
+        process_github_event(&pool, &repos, &opened, false)
+            .await
+            .unwrap();
+
+        let repo = Repository::find_by_full_name(&pool, "octocat/Hello-World")
+            .await
+            .unwrap()
+            .unwrap();
+        let pr = PullRequest::find_by_github_id(&pool, 7001)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let feature = Commit::find_by_sha(&pool, repo.id, feature_sha)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(feature.branch.as_deref(), Some("feature-x"));
+        assert_eq!(feature.pull_request_id, Some(pr.id));
+
+        let main = Commit::find_by_sha(&pool, repo.id, main_sha)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(main.pull_request_id, None);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_reprocessing_records_no_duplicate_history(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let issue = |action: &str, title: &str| {
+            serde_json::json!({
+                "action": action,
+                "repository": hello_world_repository(),
+                "issue": {
+                    "id": 9001,
+                    "number": 9,
+                    "title": title,
+                    "state": "open",
+                    "user": { "login": "octocat" },
+                    "html_url": "https://github.com/octocat/Hello-World/issues/9",
+                    "created_at": "2026-10-01T12:00:00Z"
+                }
+            })
+        };
+        let opened = create_github_event(&pool, "issues", issue("opened", "Crash")).await;
+        let renamed = create_github_event(&pool, "issues", issue("edited", "Crash on start")).await;
+        let reverted = create_github_event(&pool, "issues", issue("edited", "Crash")).await;
+        for event in [&opened, &renamed, &reverted] {
+            process_github_event(&pool, &repos, event, false)
+                .await
+                .unwrap();
+        }
+
+        // The stored title differs from the event's again, so its change is detected anew
+        for _ in 0..2 {
+            process_github_event(&pool, &repos, &renamed, false)
+                .await
+                .unwrap();
+        }
+
+        let (recorded,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM entity_changes WHERE event_id = $1")
+                .bind(renamed.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(recorded, 1);
+        let (total,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entity_changes")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_renamed_repository_keeps_one_row(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let renamed_repository = || {
+            let mut repository = hello_world_repository();
+            repository["name"] = serde_json::json!("Hello-Universe");
+            repository["full_name"] = serde_json::json!("octocat/Hello-Universe");
+            repository["html_url"] = serde_json::json!("https://github.com/octocat/Hello-Universe");
+            repository
+        };
+
+        let before = create_github_event(
+            &pool,
+            "push",
+            serde_json::json!({
+                "ref": "refs/heads/main",
+                "repository": hello_world_repository(),
+                "commits": []
+            }),
+        )
+        .await;
+        process_github_event(&pool, &repos, &before, false)
+            .await
+            .unwrap();
+        let original = Repository::find_by_full_name(&pool, "octocat/Hello-World")
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Deliveries for the new name can arrive before the rename event is processed
+        let found = Repository::find_by_github_id_or_full_name(
+            &pool,
+            original.github_id,
+            "octocat/Hello-Universe",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(found.id, original.id);
+
+        let renamed = create_github_event(
+            &pool,
+            "repository",
+            serde_json::json!({
+                "action": "renamed",
+                "changes": { "repository": { "name": { "from": "Hello-World" } } },
+                "repository": renamed_repository()
+            }),
+        )
+        .await;
+        process_github_event(&pool, &repos, &renamed, false)
+            .await
+            .unwrap();
+
+        let after = create_github_event(
+            &pool,
+            "push",
+            serde_json::json!({
+                "ref": "refs/heads/main",
+                "repository": renamed_repository(),
+                "commits": []
+            }),
+        )
+        .await;
+        process_github_event(&pool, &repos, &after, false)
+            .await
+            .unwrap();
+
+        assert_eq!(Repository::count(&pool).await.unwrap(), 1);
+        let current = Repository::find_by_full_name(&pool, "octocat/Hello-Universe")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(current.id, original.id);
+        assert_eq!(current.name, "Hello-Universe");
+        assert!(Repository::find_by_full_name(&pool, "octocat/Hello-World")
+            .await
+            .unwrap()
+            .is_none());
+
+        let renames = RepositoryRename::list_by_repository(&pool, original.id)
+            .await
+            .unwrap();
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].old_full_name, "octocat/Hello-World");
+        assert_eq!(renames[0].new_full_name, "octocat/Hello-Universe");
+
+        // A redelivered rename is not recorded twice
+        process_github_event(&pool, &repos, &renamed, false)
+            .await
+            .unwrap();
+        assert_eq!(
+            RepositoryRename::list_by_repository(&pool, original.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_push_commits_are_classified_against_watched_paths(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let commit = |sha: &str, modified: &[&str]| {
+            serde_json::json!({
+                "id": sha,
+                "message": "Change files",
+                "timestamp": "2026-10-01T12:00:00Z",
+                "url": format!("https://github.com/octocat/Hello-World/commit/{sha}"),
+                "author": { "name": "Monalisa Octocat", "email": "mona@github.com" },
+                "committer": { "name": "Monalisa Octocat", "email": "mona@github.com" },
+                "added": [],
+                "removed": [],
+                "modified": modified
+            })
+        };
+        let push = |commits: Vec<JsonValue>| {
+            serde_json::json!({
+                "ref": "refs/heads/main",
+                "repository": hello_world_repository(),
+                "commits": commits
+            })
+        };
+        let api_sha = "3333333333333333333333333333333333333333";
+        let web_sha = "4444444444444444444444444444444444444444";
+        let skipped_sha = "5555555555555555555555555555555555555555";
+
+        // The first push creates the repository, before any paths are watched
+        let first = create_github_event(&pool, "push", push(vec![])).await;
+        process_github_event(&pool, &repos, &first, false)
+            .await
+            .unwrap();
+        let repo = Repository::find_by_full_name(&pool, "octocat/Hello-World")
+            .await
+            .unwrap()
+            .unwrap();
+
+        Repository::set_watched_paths(&pool, repo.id, &["services/api/**".to_string()], false)
+            .await
+            .unwrap();
+        let second = create_github_event(
+            &pool,
+            "push",
+            push(vec![
+                commit(api_sha, &["services/api/src/lib.rs"]),
+                commit(web_sha, &["services/web/app.ts"]),
+            ]),
+        )
+        .await;
+        process_github_event(&pool, &repos, &second, false)
+            .await
+            .unwrap();
+
+        let touches = |sha: &'static str| {
+            let pool = pool.clone();
+            async move {
+                Commit::find_by_sha(&pool, repo.id, sha)
+                    .await
+                    .unwrap()
+                    .map(|c| c.touches_watched)
+            }
+        };
+        assert_eq!(touches(api_sha).await, Some(Some(true)));
+        assert_eq!(touches(web_sha).await, Some(Some(false)));
+
+        // Skipping drops commits outside the watched paths
+        Repository::set_watched_paths(&pool, repo.id, &["services/api/**".to_string()], true)
+            .await
+            .unwrap();
+        let third = create_github_event(
+            &pool,
+            "push",
+            push(vec![commit(skipped_sha, &["docs/README.md"])]),
+        )
+        .await;
+        process_github_event(&pool, &repos, &third, false)
+            .await
+            .unwrap();
+        assert_eq!(touches(skipped_sha).await, None);
+    }
+
+    #[test]
+    fn test_membership_extraction_per_event_type() {
+        let at = Utc::now();
+        let sender = serde_json::json!({ "login": "monalisa" });
+        let organization = serde_json::json!({ "login": "octo-org" });
+
+        let member = extract_membership(
+            &serde_json::json!({
+                "action": "added",
+                "member": { "login": "hubot" },
+                "repository": hello_world_repository(),
+                "sender": sender
+            }),
+            "member",
+            1,
+            at,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            (member.scope_type.as_str(), member.scope.as_str()),
+            ("repository", "octocat/Hello-World")
+        );
+
+        let team = extract_membership(
+            &serde_json::json!({
+                "action": "removed",
+                "member": { "login": "hubot" },
+                "team": { "name": "Justice League", "slug": "justice-league" },
+                "organization": organization,
+                "sender": sender
+            }),
+            "membership",
+            2,
+            at,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            (team.scope_type.as_str(), team.scope.as_str()),
+            ("team", "justice-league")
+        );
+        assert_eq!(team.organization.as_deref(), Some("octo-org"));
+
+        let invited = extract_membership(
+            &serde_json::json!({
+                "action": "member_invited",
+                "invitation": { "login": null, "email": "new@example.com" },
+                "organization": organization,
+                "sender": sender
+            }),
+            "organization",
+            3,
+            at,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(invited.member, "new@example.com");
+        assert_eq!(invited.scope, "octo-org");
+
+        // Renaming the organization changes no membership
+        let renamed = serde_json::json!({
+            "action": "renamed",
+            "organization": organization,
+            "sender": sender
+        });
+        assert!(extract_membership(&renamed, "organization", 4, at)
+            .unwrap()
+            .is_none());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_added_member_is_recorded_once(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let event = create_github_event(
+            &pool,
+            "member",
+            serde_json::json!({
+                "action": "added",
+                "member": { "login": "hubot", "id": 2 },
+                "changes": { "permission": { "to": "write" } },
+                "repository": hello_world_repository(),
+                "sender": { "login": "octocat" }
+            }),
+        )
+        .await;
+
+        let result = process_github_event(&pool, &repos, &event, true)
+            .await
+            .unwrap();
+        assert_eq!(result.entities_written, 1);
+
+        // Reprocessing appends nothing
+        let result = process_github_event(&pool, &repos, &event, true)
+            .await
+            .unwrap();
+        assert_eq!(result.entities_written, 0);
+
+        let log = MembershipEvent::list_recent(&pool, Some("hubot"), 10)
+            .await
+            .unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].action, "added");
+        assert_eq!(log[0].scope, "octocat/Hello-World");
+        assert_eq!(log[0].actor, "octocat");
+        assert_eq!(log[0].event_id, Some(event.id));
+        assert!(MembershipEvent::list_recent(&pool, Some("octocat"), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_deployment_status_updates_its_deployment(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let deployment = serde_json::json!({
+            "id": 145988746,
+            "sha": "a84d88e7554fc1fa21bcbc4efae3c782a70d2b9d",
+            "ref": "main",
+            "environment": "production",
+            "creator": { "login": "octocat" },
+            "created_at": "2026-10-01T12:00:00Z"
+        });
+        let status = |state: &str, created_at: &str| {
+            serde_json::json!({
+                "action": "created",
+                "deployment_status": {
+                    "state": state,
+                    "environment_url": "https://hello-world.example.com",
+                    "log_url": "https://github.com/octocat/Hello-World/actions/runs/1",
+                    "created_at": created_at
+                },
+                "deployment": deployment.clone(),
+                "repository": hello_world_repository()
+            })
+        };
+
+        let created = create_github_event(
+            &pool,
+            "deployment",
+            serde_json::json!({
+                "action": "created",
+                "deployment": deployment.clone(),
+                "repository": hello_world_repository()
+            }),
+        )
+        .await;
+        process_github_event(&pool, &repos, &created, false)
+            .await
+            .unwrap();
+        let stored = Deployment::find_by_github_id(&pool, 145988746)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.environment, "production");
+        assert_eq!(stored.state, None);
+
+        let success = create_github_event(
+            &pool,
+            "deployment_status",
+            status("success", "2026-10-01T12:05:00Z"),
+        )
+        .await;
+        process_github_event(&pool, &repos, &success, false)
+            .await
+            .unwrap();
+
+        // A late redelivery of an earlier status doesn't roll the state back
+        let pending = create_github_event(
+            &pool,
+            "deployment_status",
+            status("pending", "2026-10-01T12:01:00Z"),
+        )
+        .await;
+        process_github_event(&pool, &repos, &pending, false)
+            .await
+            .unwrap();
+
+        let listed = Deployment::list_by_repository(&pool, stored.repository_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].state.as_deref(), Some("success"));
+        assert_eq!(
+            listed[0].url.as_deref(),
+            Some("https://hello-world.example.com")
+        );
+    }
+
+    #[test]
+    fn test_ids_sent_as_strings_or_whole_floats_are_accepted() {
+        let mut payload = crate::utils::fuzz::seed("pull_request");
+        payload["repository"]["id"] = serde_json::json!("1296269");
+        payload["pull_request"]["id"] = serde_json::json!(1.0);
+        payload["pull_request"]["number"] = serde_json::json!("1347");
+
+        assert_eq!(extract_repository(&payload).unwrap().github_id, 1296269);
+        let pr = extract_pull_request(&payload, 1, 1).unwrap();
+        assert_eq!((pr.github_id, pr.number), (1, 1347));
+
+        let mut payload = crate::utils::fuzz::seed("issues");
+        payload["issue"]["id"] = serde_json::json!(123.0);
+        payload["issue"]["number"] = serde_json::json!(7.0);
+        let issue = extract_issue(&payload, 1, 1).unwrap();
+        assert_eq!((issue.github_id, issue.number), (123, 7));
+
+        payload["issue"]["number"] = serde_json::json!(7.5);
+        assert!(matches!(
+            extract_issue(&payload, 1, 1),
+            Err(ProcessingError::InvalidPayload(ref m)) if m == "Missing issue number"
+        ));
+    }
+
+    mod fuzz {
+        use super::*;
+        use crate::utils::fuzz::{arb_payload, seed};
+        use proptest::prelude::*;
+
+        #[test]
+        fn test_seed_payloads_extract() {
+            assert!(extract_repository(&seed("push")).is_ok());
+            assert_eq!(
+                extract_push_commits(&seed("push"), 1, 1, &WatchedPaths::default())
+                    .unwrap()
+                    .len(),
+                1
+            );
+            assert!(extract_pull_request(&seed("pull_request"), 1, 1).is_ok());
+            assert!(extract_issue(&seed("issues"), 1, 1).is_ok());
+            assert!(extract_star(&seed("star"), 1, 1, Utc::now()).is_ok());
+        }
+
+        #[test]
+        fn test_out_of_range_numbers_are_rejected() {
+            let mut payload = seed("pull_request");
+            payload["pull_request"]["number"] = serde_json::json!(i64::from(i32::MAX) + 1);
+            assert!(matches!(
+                extract_pull_request(&payload, 1, 1),
+                Err(ProcessingError::InvalidPayload(ref m)) if m == "PR number out of range"
+            ));
+
+            let mut payload = seed("push");
+            payload["commits"][0]["id"] = serde_json::json!("abc");
+            assert!(extract_push_commits(&payload, 1, 1, &WatchedPaths::default()).is_err());
+        }
+
+        proptest! {
+            /// Parsers return well-formed values or a clean `InvalidPayload`, never panic
+            #[test]
+            fn test_extractors_never_panic(payload in arb_payload()) {
+                if let Ok(repo) = extract_repository(&payload) {
+                    prop_assert_eq!(Some(repo.github_id), json_i64(&payload["repository"]["id"]));
+                }
+                if let Ok(commits) = extract_push_commits(&payload, 1, 2, &WatchedPaths::default()) {
+                    for commit in commits {
+                        prop_assert!(is_commit_sha(&commit.sha));
+                        prop_assert_eq!(commit.webhook_event_id, 2);
+                    }
+                }
+                if let Ok(pr) = extract_pull_request(&payload, 1, 2) {
+                    prop_assert_eq!(
+                        Some(i64::from(pr.number)),
+                        json_i64(&payload["pull_request"]["number"])
+                    );
+                }
+                if let Ok(issue) = extract_issue(&payload, 1, 2) {
+                    prop_assert_eq!(
+                        Some(i64::from(issue.number)),
+                        json_i64(&payload["issue"]["number"])
+                    );
+                }
+                if let Ok(deployment) = extract_deployment(&payload, 1, 2) {
+                    prop_assert!(is_commit_sha(&deployment.sha));
+                }
+                let _ = extract_deployment_status(&payload);
+                for event_type in ["member", "membership", "organization"] {
+                    let membership = extract_membership(&payload, event_type, 2, Utc::now());
+                    if let Ok(Some(membership)) = membership {
+                        prop_assert_eq!(
+                            Some(membership.action.as_str()),
+                            payload["action"].as_str()
+                        );
+                    }
+                }
+                if let Ok((star, count)) = extract_star(&payload, 1, 2, Utc::now()) {
+                    prop_assert_eq!(Some(star.action.as_str()), payload["action"].as_str());
+                    prop_assert_eq!(
+                        count.map(i64::from),
+                        json_i64(&payload["repository"]["stargazers_count"])
+                            .filter(|c| i32::try_from(*c).is_ok())
+                    );
+                }
+
+                let event = convert_github_webhook_to_event(
+                    "push".to_string(),
+                    None,
+                    payload,
+                    Uuid::new_v4(),
+                    None,
+                    None,
+                );
+                prop_assert_eq!(event.source, "github");
+            }
+        }
+    }
 }
@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use sqlx::PgPool;
+
+/// Payload size buckets in bytes: 256 B doubling up to 4 MiB
+const PAYLOAD_BYTES_BUCKETS: &[f64] = &[
+    256.0, 512.0, 1024.0, 2048.0, 4096.0, 8192.0, 16384.0, 32768.0, 65536.0, 131072.0, 262144.0,
+    524288.0, 1048576.0, 2097152.0, 4194304.0,
+];
+
+/// Delivery latency buckets in seconds: 100 ms up to an hour
+const DELIVERY_LATENCY_BUCKETS: &[f64] = &[
+    0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0, 3600.0,
+];
+
+/// Prometheus metrics for incoming webhooks, exposed on `/metrics`
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pub webhooks_received: IntCounterVec,
+    pub webhook_payload_bytes: HistogramVec,
+    pub webhook_delivery_latency: HistogramVec,
+    pub repositories_created: IntCounter,
+    pub db_pool_size: IntGauge,
+    pub db_pool_idle: IntGauge,
+    pub db_pool_in_use: IntGauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let webhooks_received = IntCounterVec::new(
+            Opts::new("webhooks_received_total", "Webhook deliveries received"),
+            &["source"],
+        )
+        .expect("valid webhooks_received_total metric");
+        let webhook_payload_bytes = HistogramVec::new(
+            HistogramOpts::new("webhook_payload_bytes", "Size of webhook request bodies")
+                .buckets(PAYLOAD_BYTES_BUCKETS.to_vec()),
+            &["source"],
+        )
+        .expect("valid webhook_payload_bytes metric");
+        let webhook_delivery_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "webhook_delivery_latency_seconds",
+                "Time from the sender's payload timestamp to receipt",
+            )
+            .buckets(DELIVERY_LATENCY_BUCKETS.to_vec()),
+            &["source"],
+        )
+        .expect("valid webhook_delivery_latency_seconds metric");
+        let repositories_created = IntCounter::new(
+            "repositories_created_total",
+            "Repositories tracked for the first time",
+        )
+        .expect("valid repositories_created_total metric");
+        let db_pool_size = IntGauge::new("db_pool_size", "Open database connections")
+            .expect("valid db_pool_size metric");
+        let db_pool_idle = IntGauge::new("db_pool_idle", "Idle database connections")
+            .expect("valid db_pool_idle metric");
+        let db_pool_in_use = IntGauge::new("db_pool_in_use", "Database connections in use")
+            .expect("valid db_pool_in_use metric");
+
+        registry
+            .register(Box::new(webhooks_received.clone()))
+            .expect("webhooks_received_total registered once");
+        registry
+            .register(Box::new(webhook_payload_bytes.clone()))
+            .expect("webhook_payload_bytes registered once");
+        registry
+            .register(Box::new(webhook_delivery_latency.clone()))
+            .expect("webhook_delivery_latency_seconds registered once");
+        registry
+            .register(Box::new(repositories_created.clone()))
+            .expect("repositories_created_total registered once");
+        for gauge in [&db_pool_size, &db_pool_idle, &db_pool_in_use] {
+            registry
+                .register(Box::new(gauge.clone()))
+                .expect("pool gauges registered once");
+        }
+
+        Metrics {
+            registry,
+            webhooks_received,
+            webhook_payload_bytes,
+            webhook_delivery_latency,
+            repositories_created,
+            db_pool_size,
+            db_pool_idle,
+            db_pool_in_use,
+        }
+    }
+
+    /// Count a delivery from `source` and record its body size
+    pub fn observe_webhook(&self, source: &str, body_len: usize) {
+        self.webhooks_received.with_label_values(&[source]).inc();
+        self.webhook_payload_bytes
+            .with_label_values(&[source])
+            .observe(body_len as f64);
+    }
+
+    /// Record how long a delivery from `source` took to arrive after the sender stamped it
+    pub fn observe_delivery_latency(&self, source: &str, latency: chrono::Duration) {
+        let seconds = latency.num_milliseconds() as f64 / 1000.0;
+        self.webhook_delivery_latency
+            .with_label_values(&[source])
+            .observe(seconds);
+    }
+
+    /// Set the pool gauges from a pool's current connection counts
+    pub fn observe_pool(&self, pool: &PgPool) {
+        self.record_pool(pool.size(), pool.num_idle());
+    }
+
+    fn record_pool(&self, size: u32, idle: usize) {
+        let size = i64::from(size);
+        let idle = idle as i64;
+        self.db_pool_size.set(size);
+        self.db_pool_idle.set(idle);
+        self.db_pool_in_use.set((size - idle).max(0));
+    }
+
+    /// All metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("metrics encode as text");
+
+        String::from_utf8(buffer).expect("metrics text is UTF-8")
+    }
+}
+
+/// Refresh the pool gauges every `interval`
+pub async fn run_pool_metrics(pool: PgPool, metrics: Metrics, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+        metrics.observe_pool(&pool);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_webhook_records_size_per_source() {
+        let metrics = Metrics::new();
+        metrics.observe_webhook("github", 300);
+        metrics.observe_webhook("github", 5_000_000);
+        metrics.observe_webhook("gitlab", 100);
+
+        let github = metrics.webhook_payload_bytes.with_label_values(&["github"]);
+        assert_eq!(github.get_sample_count(), 2);
+        assert_eq!(github.get_sample_sum(), 5_000_300.0);
+        assert_eq!(
+            metrics
+                .webhooks_received
+                .with_label_values(&["gitlab"])
+                .get(),
+            1
+        );
+
+        let text = metrics.render();
+        assert!(text.contains(r#"webhook_payload_bytes_bucket{source="github",le="512"} 1"#));
+        assert!(text.contains(r#"webhooks_received_total{source="github"} 2"#));
+    }
+
+    #[test]
+    fn test_observe_delivery_latency() {
+        let metrics = Metrics::new();
+        metrics.observe_delivery_latency("github", chrono::Duration::milliseconds(2500));
+
+        let github = metrics
+            .webhook_delivery_latency
+            .with_label_values(&["github"]);
+        assert_eq!(github.get_sample_count(), 1);
+        assert_eq!(github.get_sample_sum(), 2.5);
+    }
+
+    #[test]
+    fn test_pool_gauges() {
+        let metrics = Metrics::new();
+        metrics.record_pool(5, 2);
+
+        assert_eq!(metrics.db_pool_size.get(), 5);
+        assert_eq!(metrics.db_pool_idle.get(), 2);
+        assert_eq!(metrics.db_pool_in_use.get(), 3);
+        assert!(metrics.render().contains("db_pool_in_use 3"));
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_pool_gauges_track_checked_out_connections(pool: PgPool) {
+        let metrics = Metrics::new();
+        let conn = pool.acquire().await.unwrap();
+
+        metrics.observe_pool(&pool);
+        assert_eq!(metrics.db_pool_size.get(), i64::from(pool.size()));
+        assert!(metrics.db_pool_in_use.get() >= 1);
+
+        drop(conn);
+    }
+}
@@ -1,3 +1,23 @@
+pub mod alerts;
+pub mod archive;
+pub mod backfill;
 pub mod github;
+pub mod metrics;
+pub mod notify;
+pub mod ordering;
+pub mod rate_limit;
+pub mod replay;
+pub mod repository_cache;
+pub mod retention;
+pub mod schema;
+pub mod tail;
 
-pub use github::{convert_github_webhook_to_event, process_github_event};
+pub use backfill::backfill_legacy_events;
+pub use github::{convert_github_webhook_to_event, process_github_event, ProcessingResult};
+pub use metrics::Metrics;
+pub use notify::EventNotification;
+pub use ordering::{EntityKey, OrderedDispatcher};
+pub use rate_limit::RateLimiter;
+pub use repository_cache::RepositoryCache;
+pub use schema::SchemaRegistry;
+pub use tail::tail_events;
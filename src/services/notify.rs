@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+
+/// Channel the `events_notify_insert` trigger publishes on
+pub const EVENTS_CHANNEL: &str = "events_inserted";
+
+/// Payload published by the database for every new event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventNotification {
+    pub id: i64,
+    pub source: String,
+    pub event_type: String,
+    pub tenant: Option<String>,
+}
+
+/// Open a listener subscribed to new event notifications
+pub async fn listen_for_events(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(EVENTS_CHANNEL).await?;
+
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateEvent, Event};
+    use std::time::Duration;
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_insert_notifies_listener(pool: PgPool) {
+        let mut listener = listen_for_events(&pool).await.unwrap();
+
+        let event = Event::create(
+            &pool,
+            CreateEvent {
+                tenant: Some("acme".to_string()),
+                ..CreateEvent::for_test("github", "push")
+            },
+        )
+        .await
+        .unwrap();
+
+        let notification = tokio::time::timeout(Duration::from_secs(5), listener.recv())
+            .await
+            .expect("no notification received")
+            .unwrap();
+        let notification: EventNotification = serde_json::from_str(notification.payload()).unwrap();
+
+        assert_eq!(
+            notification,
+            EventNotification {
+                id: event.id,
+                source: "github".to_string(),
+                event_type: "push".to_string(),
+                tenant: Some("acme".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::hash_map::DefaultHasher;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+
+use serde_json::Value as JsonValue;
+use tokio::sync::mpsc;
+
+use crate::utils::json_i64;
+
+type Job = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Identifies the entity an event mutates, so its events can be applied in order
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntityKey {
+    pub source: String,
+    pub repository_id: Option<i64>,
+    pub entity_id: String,
+}
+
+impl EntityKey {
+    /// Derive the entity key from a payload, if the source/event type mutates one
+    pub fn from_payload(source: &str, event_type: &str, payload: &JsonValue) -> Option<Self> {
+        let entity_id = match (source, event_type) {
+            ("github" | "gitea" | "gogs", "pull_request") => {
+                json_i64(&payload["pull_request"]["id"])?.to_string()
+            }
+            ("github" | "gitea" | "gogs", "issues") => {
+                json_i64(&payload["issue"]["id"])?.to_string()
+            }
+            ("github" | "gitea" | "gogs", "push") => payload["ref"].as_str()?.to_string(),
+            // Statuses apply after the deployment they report on
+            ("github", "deployment" | "deployment_status") => {
+                json_i64(&payload["deployment"]["id"])?.to_string()
+            }
+            // Star changes all update the repository's running count
+            ("github", "star" | "watch") => {
+                json_i64(&payload["repository"]["id"])?;
+                "stars".to_string()
+            }
+            _ => return None,
+        };
+
+        Some(EntityKey {
+            source: source.to_string(),
+            repository_id: json_i64(&payload["repository"]["id"]),
+            entity_id,
+        })
+    }
+}
+
+/// Runs processing jobs so that jobs for the same entity execute sequentially
+/// in submission order, while different entities are processed concurrently.
+///
+/// Keys are hashed onto a fixed set of worker shards; with zero shards every
+/// job is spawned independently and no ordering is enforced.
+pub struct OrderedDispatcher {
+    shards: Vec<mpsc::UnboundedSender<Job>>,
+}
+
+impl OrderedDispatcher {
+    /// Spawn `shards` workers; must be called from within a Tokio runtime
+    pub fn new(shards: usize) -> Self {
+        let shards = (0..shards)
+            .map(|_| {
+                let (sender, mut receiver) = mpsc::unbounded_channel::<Job>();
+                tokio::spawn(async move {
+                    while let Some(job) = receiver.recv().await {
+                        job.await;
+                    }
+                });
+                sender
+            })
+            .collect();
+
+        OrderedDispatcher { shards }
+    }
+
+    /// Queue a job behind earlier jobs for the same entity
+    pub fn dispatch<F>(&self, key: Option<&EntityKey>, job: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let Some(key) = key.filter(|_| !self.shards.is_empty()) else {
+            tokio::spawn(job);
+            return;
+        };
+
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let shard = &self.shards[(hasher.finish() % self.shards.len() as u64) as usize];
+
+        if let Err(mpsc::error::SendError(job)) = shard.send(Box::pin(job)) {
+            log::warn!("Processing shard for {key:?} is gone, running job unordered");
+            tokio::spawn(job);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+
+    fn pr_key(id: i64) -> EntityKey {
+        EntityKey::from_payload(
+            "github",
+            "pull_request",
+            &json!({"pull_request": {"id": id}, "repository": {"id": 1}}),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_entity_key_from_payload() {
+        assert_eq!(
+            pr_key(42),
+            EntityKey {
+                source: "github".to_string(),
+                repository_id: Some(1),
+                entity_id: "42".to_string(),
+            }
+        );
+        // A deployment and its statuses share a key
+        let deployment = json!({ "repository": { "id": 1 }, "deployment": { "id": 7 } });
+        assert_eq!(
+            EntityKey::from_payload("github", "deployment", &deployment),
+            EntityKey::from_payload("github", "deployment_status", &deployment)
+        );
+        // Stars and watches of one repository share a key
+        let star = json!({ "repository": { "id": 1 } });
+        assert_eq!(
+            EntityKey::from_payload("github", "star", &star),
+            Some(EntityKey {
+                source: "github".to_string(),
+                repository_id: Some(1),
+                entity_id: "stars".to_string(),
+            })
+        );
+        assert_eq!(
+            EntityKey::from_payload("github", "star", &star),
+            EntityKey::from_payload("github", "watch", &star)
+        );
+        assert!(EntityKey::from_payload("github", "star", &json!({})).is_none());
+        assert!(EntityKey::from_payload("gitlab", "pull_request", &json!({})).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_same_entity_events_apply_in_submission_order() {
+        let dispatcher = OrderedDispatcher::new(4);
+        let state = Arc::new(Mutex::new(String::new()));
+        let (done_tx, done_rx) = oneshot::channel();
+
+        // "opened" is slow to process, so an unordered run would finish "closed" first
+        let opened = state.clone();
+        dispatcher.dispatch(Some(&pr_key(7)), async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            *opened.lock().unwrap() = "open".to_string();
+        });
+        let closed = state.clone();
+        dispatcher.dispatch(Some(&pr_key(7)), async move {
+            *closed.lock().unwrap() = "closed".to_string();
+            done_tx.send(()).unwrap();
+        });
+
+        done_rx.await.unwrap();
+        assert_eq!(*state.lock().unwrap(), "closed");
+    }
+
+    #[tokio::test]
+    async fn test_unkeyed_jobs_are_not_blocked() {
+        let dispatcher = OrderedDispatcher::new(1);
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+        let (done_tx, done_rx) = oneshot::channel();
+
+        // A job without an entity key must not wait behind a stalled shard
+        dispatcher.dispatch(Some(&pr_key(1)), async move {
+            let _ = release_rx.await;
+        });
+        dispatcher.dispatch(None, async move {
+            done_tx.send(()).unwrap();
+        });
+
+        tokio::time::timeout(Duration::from_secs(1), done_rx)
+            .await
+            .expect("unkeyed job was blocked")
+            .unwrap();
+        release_tx.send(()).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Callers tracked before expired windows are swept out
+const SWEEP_THRESHOLD: usize = 10_000;
+
+/// Callers tracked at once; new callers beyond this share [`OVERFLOW_CALLER`]'s bucket
+const MAX_CALLERS: usize = 100_000;
+
+/// Bucket key of callers that arrive while `max_callers` are already tracked
+const OVERFLOW_CALLER: &str = "overflow";
+
+/// Fixed-window request counter per caller, shared by all workers
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    max_callers: usize,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    reset_at: DateTime<Utc>,
+    count: u32,
+}
+
+/// A caller's bucket after counting one request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitState {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_at: DateTime<Utc>,
+    /// False once the request exceeded the limit for the current window
+    pub allowed: bool,
+}
+
+impl RateLimiter {
+    /// Allow `limit` requests per caller in each `window`
+    pub fn new(limit: u32, window: Duration) -> Self {
+        RateLimiter {
+            limit,
+            window,
+            max_callers: MAX_CALLERS,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Track at most `max_callers` callers at once
+    pub fn with_max_callers(mut self, max_callers: usize) -> Self {
+        self.max_callers = max_callers;
+        self
+    }
+
+    /// Count a request from `caller`, starting a new window when the last one has ended
+    pub fn hit(&self, caller: &str, now: DateTime<Utc>) -> RateLimitState {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= SWEEP_THRESHOLD.min(self.max_callers) {
+            buckets.retain(|_, bucket| bucket.reset_at > now);
+        }
+        // A flood of distinct callers shares one bucket instead of growing the map
+        let caller = if buckets.len() >= self.max_callers && !buckets.contains_key(caller) {
+            OVERFLOW_CALLER
+        } else {
+            caller
+        };
+
+        let bucket = buckets.entry(caller.to_string()).or_insert(Bucket {
+            reset_at: now + self.window,
+            count: 0,
+        });
+        if bucket.reset_at <= now {
+            bucket.reset_at = now + self.window;
+            bucket.count = 0;
+        }
+        bucket.count = bucket.count.saturating_add(1);
+
+        RateLimitState {
+            limit: self.limit,
+            remaining: self.limit.saturating_sub(bucket.count),
+            reset_at: bucket.reset_at,
+            allowed: bucket.count <= self.limit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_counts_per_caller_and_resets() {
+        let limiter = RateLimiter::new(2, Duration::seconds(60));
+        let now = Utc::now();
+
+        let first = limiter.hit("10.0.0.1", now);
+        assert_eq!((first.remaining, first.allowed), (1, true));
+        assert_eq!(first.reset_at, now + Duration::seconds(60));
+        let second = limiter.hit("10.0.0.1", now + Duration::seconds(10));
+        assert_eq!((second.remaining, second.allowed), (0, true));
+        assert_eq!(second.reset_at, first.reset_at);
+        let third = limiter.hit("10.0.0.1", now + Duration::seconds(20));
+        assert_eq!((third.remaining, third.allowed), (0, false));
+
+        // Other callers have their own bucket
+        assert!(limiter.hit("10.0.0.2", now).allowed);
+
+        let next_window = limiter.hit("10.0.0.1", now + Duration::seconds(60));
+        assert_eq!((next_window.remaining, next_window.allowed), (1, true));
+        assert_eq!(next_window.reset_at, now + Duration::seconds(120));
+    }
+
+    #[test]
+    fn test_callers_beyond_the_cap_share_a_bucket() {
+        let limiter = RateLimiter::new(2, Duration::seconds(60)).with_max_callers(2);
+        let now = Utc::now();
+
+        assert!(limiter.hit("10.0.0.1", now).allowed);
+        assert!(limiter.hit("10.0.0.2", now).allowed);
+        assert_eq!(limiter.hit("10.0.0.3", now).remaining, 1);
+        assert_eq!(limiter.hit("10.0.0.4", now).remaining, 0);
+        assert!(!limiter.hit("10.0.0.5", now).allowed);
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 3);
+
+        // Tracked callers keep their own bucket
+        assert_eq!(limiter.hit("10.0.0.1", now).remaining, 0);
+
+        // Expired windows free their slots for new callers
+        let later = now + Duration::seconds(60);
+        assert_eq!(limiter.hit("10.0.0.6", later).remaining, 1);
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+    }
+}
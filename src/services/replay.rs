@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::time::MissedTickBehavior;
+
+use crate::models::{Event, ReplayJob};
+
+/// How often the scheduler looks for a new replay job when idle
+pub const REPLAY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Run queued replay jobs one at a time, polling every `poll_interval` when none are
+/// waiting, logging failures and carrying on
+pub async fn run_replays(
+    pool: PgPool,
+    client: reqwest::Client,
+    targets: Vec<String>,
+    poll_interval: Duration,
+) {
+    loop {
+        match ReplayJob::next_unfinished(&pool).await {
+            Ok(Some(job)) => {
+                let id = job.id;
+                if let Err(e) = replay_job(&pool, &client, &targets, job).await {
+                    log::error!("Replay job {id} stopped: {e}");
+                    tokio::time::sleep(poll_interval).await;
+                }
+            }
+            Ok(None) => tokio::time::sleep(poll_interval).await,
+            Err(e) => {
+                log::error!("Failed to load replay jobs: {e}");
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+/// Replay the job's remaining events to every target, one event per `job.interval()`.
+///
+/// Progress is stored after each event, so a restarted job resumes after the last
+/// attempted event.
+pub async fn replay_job(
+    pool: &PgPool,
+    client: &reqwest::Client,
+    targets: &[String],
+    mut job: ReplayJob,
+) -> Result<ReplayJob, sqlx::Error> {
+    ReplayJob::mark_running(pool, job.id).await?;
+    log::info!(
+        "Replaying up to {} events for job {} at {} per minute",
+        job.total_events - job.attempted_events(),
+        job.id,
+        job.rate_per_minute
+    );
+
+    let mut ticker = tokio::time::interval(job.interval());
+    // A slow target delays the next event instead of bursting to catch up
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let Some(event) = job.next_event(pool).await? else {
+            break;
+        };
+        let delivered = forward_event(client, targets, &event).await;
+        job.record_attempt(pool, event.id, delivered).await?;
+    }
+
+    ReplayJob::mark_completed(pool, job.id).await?;
+    log::info!(
+        "Replay job {} completed: {} replayed, {} failed",
+        job.id,
+        job.replayed_events,
+        job.failed_events
+    );
+
+    Ok(job)
+}
+
+/// POST the event's payload to every target; delivered only if every target accepted it
+async fn forward_event(client: &reqwest::Client, targets: &[String], event: &Event) -> bool {
+    // Compacted events have no payload left to send
+    if event.raw_event_compacted_at.is_some() {
+        log::warn!(
+            "Skipping replay of event {}: payload was compacted",
+            event.id
+        );
+        return false;
+    }
+
+    let mut delivered = true;
+    for target in targets {
+        let result = client
+            .post(target)
+            .header("X-Cross-Bow-Event-Id", event.id)
+            .header("X-Cross-Bow-Source", &event.source)
+            .header("X-Cross-Bow-Event-Type", &event.event_type)
+            .header("X-Cross-Bow-Delivery", event.delivery_id.to_string())
+            .json(&event.raw_event)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        if let Err(e) = result {
+            log::warn!("Failed to replay event {} to {target}: {e}", event.id);
+            delivered = false;
+        }
+    }
+
+    delivered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateEvent, CreateReplayJob};
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    async fn create_event(pool: &PgPool, source: &str) -> Event {
+        Event::create(
+            pool,
+            CreateEvent {
+                raw_event: serde_json::json!({ "source": source }),
+                ..CreateEvent::for_test(source, "push")
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Arrival time and event id of each replayed delivery
+    type Received = Arc<Mutex<Vec<(Instant, String)>>>;
+
+    /// A local forward target recording when each replayed event arrived
+    fn start_target() -> (String, Received) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let recorder = received.clone();
+        let server = HttpServer::new(move || {
+            let recorder = recorder.clone();
+            App::new().route(
+                "/",
+                web::post().to(move |req: actix_web::HttpRequest| {
+                    let recorder = recorder.clone();
+                    async move {
+                        let id = req
+                            .headers()
+                            .get("X-Cross-Bow-Event-Id")
+                            .unwrap()
+                            .to_str()
+                            .unwrap()
+                            .to_string();
+                        recorder.lock().unwrap().push((Instant::now(), id));
+                        HttpResponse::Ok().finish()
+                    }
+                }),
+            )
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let url = format!("http://{}/", server.addrs()[0]);
+        tokio::spawn(server.run());
+
+        (url, received)
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_replay_job_sends_its_events_at_the_configured_rate(pool: PgPool) {
+        let mut expected = Vec::new();
+        for source in ["github", "ci", "github", "github"] {
+            let event = create_event(&pool, source).await;
+            if source == "github" {
+                expected.push(event.id.to_string());
+            }
+        }
+
+        // 20 events a minute apart would take a minute; 1200 a minute is 50ms apart
+        let job = ReplayJob::create(
+            &pool,
+            CreateReplayJob {
+                source: Some("github".to_string()),
+                event_type: None,
+                action: None,
+                rate_per_minute: 1200,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(job.total_events, 3);
+        // Arrives after the job was created, so it is not replayed
+        create_event(&pool, "github").await;
+
+        let (target, received) = start_target();
+        let job = replay_job(&pool, &reqwest::Client::new(), &[target], job)
+            .await
+            .unwrap();
+
+        assert_eq!(job.replayed_events, 3);
+        assert_eq!(job.failed_events, 0);
+        let stored = ReplayJob::find_by_id(&pool, job.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, "completed");
+        assert_eq!(stored.replayed_events, 3);
+
+        let received = received.lock().unwrap();
+        let ids: Vec<_> = received.iter().map(|(_, id)| id.clone()).collect();
+        assert_eq!(ids, expected);
+        for pair in received.windows(2) {
+            // Allow a little timer jitter below the 50ms spacing
+            assert!(pair[1].0 - pair[0].0 >= Duration::from_millis(40));
+        }
+    }
+}
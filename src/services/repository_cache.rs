@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sqlx::PgPool;
+
+use crate::models::github::{CreateRepository, Repository};
+use crate::services::Metrics;
+
+/// How long a repository upsert is trusted before the next event writes it again
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Recently upserted repositories keyed by GitHub id, shared by all processing tasks.
+///
+/// Events for a repository that was upserted within the TTL with identical fields
+/// reuse its id instead of writing the row again.
+#[derive(Clone)]
+pub struct RepositoryCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<i64, CachedRepository>>>,
+    /// Counts repositories tracked for the first time
+    metrics: Option<Metrics>,
+}
+
+#[derive(Debug)]
+struct CachedRepository {
+    id: i64,
+    data: CreateRepository,
+    seen_at: Instant,
+}
+
+impl Default for RepositoryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl RepositoryCache {
+    pub fn new(ttl: Duration) -> Self {
+        RepositoryCache {
+            ttl,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            metrics: None,
+        }
+    }
+
+    /// Count newly tracked repositories in `metrics`
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// The repository's id, upserting it unless it was recently stored unchanged
+    pub async fn upsert(&self, pool: &PgPool, data: CreateRepository) -> Result<i64, sqlx::Error> {
+        if let Some(id) = self.cached_id(&data) {
+            return Ok(id);
+        }
+
+        let (repository, created) =
+            Repository::upsert_returning_created(pool, data.clone()).await?;
+        if created {
+            log::info!("Now tracking new repository {}", repository.full_name);
+            if let Some(metrics) = &self.metrics {
+                metrics.repositories_created.inc();
+            }
+        }
+        self.remember(repository.id, data);
+
+        Ok(repository.id)
+    }
+
+    fn cached_id(&self, data: &CreateRepository) -> Option<i64> {
+        let entries = self.entries.lock().unwrap();
+
+        entries
+            .get(&data.github_id)
+            .filter(|cached| cached.seen_at.elapsed() < self.ttl && cached.data == *data)
+            .map(|cached| cached.id)
+    }
+
+    fn remember(&self, id: i64, data: CreateRepository) {
+        let mut entries = self.entries.lock().unwrap();
+
+        // Misses are rare once a repository is cached, so pruning here stays cheap
+        entries.retain(|_, cached| cached.seen_at.elapsed() < self.ttl);
+        entries.insert(
+            data.github_id,
+            CachedRepository {
+                id,
+                data,
+                seen_at: Instant::now(),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hello_world() -> CreateRepository {
+        CreateRepository {
+            github_id: 1296269,
+            name: "Hello-World".to_string(),
+            full_name: "octocat/Hello-World".to_string(),
+            owner: "octocat".to_string(),
+            description: None,
+            url: "https://github.com/octocat/Hello-World".to_string(),
+            is_private: false,
+            language: None,
+            topics: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_unchanged_repository_hits_cache() {
+        let cache = RepositoryCache::default();
+        assert_eq!(cache.cached_id(&hello_world()), None);
+
+        cache.remember(7, hello_world());
+        assert_eq!(cache.cached_id(&hello_world()), Some(7));
+
+        let mut renamed = hello_world();
+        renamed.description = Some("My first repository".to_string());
+        assert_eq!(cache.cached_id(&renamed), None);
+    }
+
+    #[test]
+    fn test_expired_entries_miss() {
+        let cache = RepositoryCache::new(Duration::ZERO);
+        cache.remember(7, hello_world());
+
+        assert_eq!(cache.cached_id(&hello_world()), None);
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_repeated_upserts_skip_the_write(pool: PgPool) {
+        let metrics = Metrics::new();
+        let cache = RepositoryCache::default().with_metrics(metrics.clone());
+
+        let id = cache.upsert(&pool, hello_world()).await.unwrap();
+        let first = Repository::find_by_id(&pool, id).await.unwrap().unwrap();
+
+        assert_eq!(cache.upsert(&pool, hello_world()).await.unwrap(), id);
+        let second = Repository::find_by_id(&pool, id).await.unwrap().unwrap();
+        assert_eq!(second.updated_at, first.updated_at);
+
+        // A changed field goes back to the database
+        let mut changed = hello_world();
+        changed.language = Some("Rust".to_string());
+        assert_eq!(cache.upsert(&pool, changed).await.unwrap(), id);
+        let third = Repository::find_by_id(&pool, id).await.unwrap().unwrap();
+        assert_eq!(third.language.as_deref(), Some("Rust"));
+        assert!(third.updated_at > first.updated_at);
+
+        // Only the first write inserted the row
+        assert_eq!(metrics.repositories_created.get(), 1);
+    }
+}
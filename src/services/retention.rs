@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::models::Event;
+
+/// How often the background task applies the retention policy
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Delete events older than their source's retention, returning the total removed
+pub async fn prune_events(
+    pool: &PgPool,
+    retention_days: &HashMap<String, u32>,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let mut pruned = 0;
+
+    for (source, days) in retention_days {
+        let cutoff = now - chrono::Duration::days(i64::from(*days));
+        let deleted = Event::delete_older_than_for_source(pool, source, cutoff).await?;
+        if deleted > 0 {
+            log::info!("Pruned {deleted} {source} events older than {days} days");
+        }
+        pruned += deleted;
+    }
+
+    Ok(pruned)
+}
+
+/// Apply the retention policy every `interval`, logging failures and carrying on
+pub async fn run_pruning(pool: PgPool, retention_days: HashMap<String, u32>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+        if let Err(e) = prune_events(&pool, &retention_days, Utc::now()).await {
+            log::error!("Failed to prune events: {e}");
+        }
+    }
+}
+
+/// Drop the payload of processed events older than `after_days`, returning how many were compacted
+pub async fn compact_payloads(
+    pool: &PgPool,
+    after_days: u32,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let cutoff = now - chrono::Duration::days(i64::from(after_days));
+    let compacted = Event::compact_processed_before(pool, cutoff).await?;
+    if compacted > 0 {
+        log::info!(
+            "Compacted payloads of {compacted} processed events older than {after_days} days"
+        );
+    }
+
+    Ok(compacted)
+}
+
+/// Compact old payloads every `interval`, logging failures and carrying on
+pub async fn run_compaction(pool: PgPool, after_days: u32, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+        if let Err(e) = compact_payloads(&pool, after_days, Utc::now()).await {
+            log::error!("Failed to compact event payloads: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Actor, CreateEvent, Repository, RepositoryStar};
+    use crate::services::github::ProcessingError;
+    use crate::services::{process_github_event, RepositoryCache};
+
+    async fn create_event(pool: &PgPool, source: &str, age_days: i64) -> Event {
+        Event::create_backfilled(
+            pool,
+            CreateEvent::for_test(source, "build"),
+            Utc::now() - chrono::Duration::days(age_days),
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_only_configured_sources_are_pruned(pool: PgPool) {
+        let old_ci = create_event(&pool, "ci", 10).await;
+        let recent_ci = create_event(&pool, "ci", 2).await;
+        let old_github = create_event(&pool, "github", 400).await;
+
+        let retention = HashMap::from([("ci".to_string(), 7)]);
+        let pruned = prune_events(&pool, &retention, Utc::now()).await.unwrap();
+        assert_eq!(pruned, 1);
+
+        assert!(Event::find_by_id(&pool, old_ci.id).await.unwrap().is_none());
+        assert!(Event::find_by_id(&pool, recent_ci.id)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(Event::find_by_id(&pool, old_github.id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    async fn create_star_event(pool: &PgPool, age_days: i64) -> Event {
+        let payload = serde_json::json!({
+            "action": "created",
+            "starred_at": "2026-10-01T12:00:00Z",
+            "repository": {
+                "id": 1296269,
+                "name": "Hello-World",
+                "full_name": "octocat/Hello-World",
+                "owner": { "login": "octocat" },
+                "html_url": "https://github.com/octocat/Hello-World",
+                "private": false
+            },
+            "sender": { "login": "hubot" }
+        });
+        Event::create_backfilled(
+            pool,
+            CreateEvent {
+                action: Some("created".to_string()),
+                actor: Actor::from_payload("github", &payload),
+                raw_event: payload,
+                payload_sha256: Some("ab".repeat(32)),
+                ..CreateEvent::for_test("github", "star")
+            },
+            Utc::now() - chrono::Duration::days(age_days),
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_compaction_keeps_structured_rows_and_drops_payload(pool: PgPool) {
+        let repos = RepositoryCache::default();
+        let old = create_star_event(&pool, 100).await;
+        process_github_event(&pool, &repos, &old, false)
+            .await
+            .unwrap();
+        let pending = create_event(&pool, "ci", 100).await;
+        let recent = create_event(&pool, "ci", 1).await;
+        Event::mark_processed(&pool, recent.id).await.unwrap();
+
+        let compacted = compact_payloads(&pool, 30, Utc::now()).await.unwrap();
+        assert_eq!(compacted, 1);
+
+        let old = Event::find_by_id(&pool, old.id).await.unwrap().unwrap();
+        assert!(old.raw_event_compacted_at.is_some());
+        assert_eq!(old.raw_event, serde_json::Value::Null);
+        assert_eq!(old.payload_sha256, Some("ab".repeat(32)));
+        assert!(old.processed);
+
+        // Unprocessed and recent events keep their payloads
+        for id in [pending.id, recent.id] {
+            let event = Event::find_by_id(&pool, id).await.unwrap().unwrap();
+            assert!(event.raw_event_compacted_at.is_none());
+            assert_eq!(event.raw_event, serde_json::json!({}));
+        }
+
+        let repo = Repository::find_by_full_name(&pool, "octocat/Hello-World")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(repo.stars, 1);
+        let stargazers = RepositoryStar::list_recent_stargazers(&pool, repo.id, 10)
+            .await
+            .unwrap();
+        assert_eq!(stargazers.len(), 1);
+
+        // Compacted events cannot be processed again and are not compacted twice
+        let err = process_github_event(&pool, &repos, &old, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProcessingError::PayloadCompacted(id) if id == old.id));
+        assert_eq!(compact_payloads(&pool, 30, Utc::now()).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use jsonschema::Validator;
+use serde_json::Value as JsonValue;
+
+/// JSON Schemas for incoming payloads, keyed by source and optional event type
+///
+/// Schemas are loaded from `{dir}/{source}.json` (all events of a source) and
+/// `{dir}/{source}/{event_type}.json` (a single event type, takes precedence).
+#[derive(Default)]
+pub struct SchemaRegistry {
+    schemas: HashMap<(String, Option<String>), Validator>,
+}
+
+impl SchemaRegistry {
+    pub fn load_from_dir(dir: &Path) -> Result<Self, SchemaError> {
+        let mut registry = SchemaRegistry::default();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                let Some(source) = file_stem(&path) else {
+                    continue;
+                };
+
+                for event_entry in fs::read_dir(&path)? {
+                    let event_path = event_entry?.path();
+                    if let Some(event_type) = json_file_stem(&event_path) {
+                        registry.insert(&source, Some(&event_type), load_schema(&event_path)?);
+                    }
+                }
+            } else if let Some(source) = json_file_stem(&path) {
+                registry.insert(&source, None, load_schema(&path)?);
+            }
+        }
+
+        Ok(registry)
+    }
+
+    pub fn insert(&mut self, source: &str, event_type: Option<&str>, validator: Validator) {
+        self.schemas.insert(
+            (source.to_string(), event_type.map(|t| t.to_string())),
+            validator,
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.schemas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    /// Validate a payload, returning every validation error message on failure
+    pub fn validate(
+        &self,
+        source: &str,
+        event_type: &str,
+        payload: &JsonValue,
+    ) -> Result<(), Vec<String>> {
+        let validator = self
+            .schemas
+            .get(&(source.to_string(), Some(event_type.to_string())))
+            .or_else(|| self.schemas.get(&(source.to_string(), None)));
+
+        let Some(validator) = validator else {
+            return Ok(());
+        };
+
+        let errors: Vec<String> = validator
+            .iter_errors(payload)
+            .map(|e| format!("{}: {}", e.instance_path(), e))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn file_stem(path: &Path) -> Option<String> {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_string())
+}
+
+fn json_file_stem(path: &Path) -> Option<String> {
+    if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        file_stem(path)
+    } else {
+        None
+    }
+}
+
+fn load_schema(path: &Path) -> Result<Validator, SchemaError> {
+    let raw = fs::read_to_string(path)?;
+    let schema: JsonValue = serde_json::from_str(&raw)
+        .map_err(|e| SchemaError::InvalidSchema(path.display().to_string(), e.to_string()))?;
+
+    jsonschema::validator_for(&schema)
+        .map_err(|e| SchemaError::InvalidSchema(path.display().to_string(), e.to_string()))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaError {
+    #[error("Failed to read schema directory: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid schema {0}: {1}")]
+    InvalidSchema(String, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry_with_required_field() -> SchemaRegistry {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["customer_id"],
+            "properties": { "customer_id": { "type": "string" } }
+        });
+
+        let mut registry = SchemaRegistry::default();
+        registry.insert(
+            "billing",
+            Some("invoice.paid"),
+            jsonschema::validator_for(&schema).unwrap(),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_schema_rejects_missing_required_field() {
+        let registry = registry_with_required_field();
+        let payload = serde_json::json!({ "type": "invoice.paid" });
+
+        let errors = registry
+            .validate("billing", "invoice.paid", &payload)
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("customer_id"));
+    }
+
+    #[test]
+    fn test_schema_accepts_valid_and_unconfigured_payloads() {
+        let registry = registry_with_required_field();
+        let payload = serde_json::json!({ "customer_id": "cus_123" });
+
+        assert!(registry
+            .validate("billing", "invoice.paid", &payload)
+            .is_ok());
+        assert!(registry
+            .validate("billing", "invoice.created", &serde_json::json!({}))
+            .is_ok());
+        assert!(registry
+            .validate("github", "push", &serde_json::json!({}))
+            .is_ok());
+    }
+}
@@ -0,0 +1,123 @@
+use std::io::Write;
+use std::time::Duration;
+
+use crate::models::Event;
+use crate::services::notify::listen_for_events;
+use sqlx::PgPool;
+
+/// Maximum number of events fetched per poll
+const TAIL_BATCH: i64 = 100;
+
+/// Fetch events newer than `after_id`, returning them with the advanced cursor
+pub async fn poll_new_events(
+    pool: &PgPool,
+    after_id: i64,
+    source: Option<&str>,
+) -> Result<(Vec<Event>, i64), sqlx::Error> {
+    let events = Event::list_after_id(pool, after_id, source, TAIL_BATCH).await?;
+    let cursor = events.last().map(|e| e.id).unwrap_or(after_id);
+
+    Ok((events, cursor))
+}
+
+/// One human-readable line per event
+pub fn format_tail_line(event: &Event) -> String {
+    let event_type = match &event.action {
+        Some(action) => format!("{}.{action}", event.event_type),
+        None => event.event_type.clone(),
+    };
+
+    format!(
+        "{} #{} [{}] {} by {}",
+        event.received_at.format("%Y-%m-%d %H:%M:%S"),
+        event.id,
+        event.source,
+        event_type,
+        event.actor_name.as_deref().unwrap_or("-")
+    )
+}
+
+/// Print new events to `out` as they arrive until the process is stopped. Waits for
+/// the `events_inserted` notification between polls, polling anyway after `interval`.
+pub async fn tail_events(
+    pool: &PgPool,
+    source: Option<&str>,
+    interval: Duration,
+    out: &mut impl Write,
+) -> Result<(), TailError> {
+    // Listen before reading the cursor so no insert slips in between
+    let mut listener = listen_for_events(pool).await?;
+    let mut cursor = Event::max_id(pool).await?;
+
+    loop {
+        let (events, next) = poll_new_events(pool, cursor, source).await?;
+        cursor = next;
+
+        for event in &events {
+            writeln!(out, "{}", format_tail_line(event))?;
+        }
+        out.flush()?;
+
+        // Keep draining without sleeping while a full batch came back
+        if (events.len() as i64) < TAIL_BATCH {
+            if let Ok(notification) = tokio::time::timeout(interval, listener.recv()).await {
+                notification?;
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TailError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Output error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Actor, CreateEvent};
+
+    async fn seed(pool: &PgPool, source: &str) -> Event {
+        Event::create(
+            pool,
+            CreateEvent {
+                actor: Actor {
+                    name: Some("octocat".to_string()),
+                    ..Actor::default()
+                },
+                ..CreateEvent::for_test(source, "push")
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "requires a PostgreSQL database (DATABASE_URL)"]
+    async fn test_poll_advances_cursor_and_filters_source(pool: PgPool) {
+        let first = seed(&pool, "github").await;
+        seed(&pool, "gitlab").await;
+        let third = seed(&pool, "github").await;
+
+        let (events, cursor) = poll_new_events(&pool, 0, Some("github")).await.unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![first.id, third.id]
+        );
+        assert_eq!(cursor, third.id);
+
+        let (events, next) = poll_new_events(&pool, cursor, Some("github"))
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+        assert_eq!(next, cursor);
+
+        let fourth = seed(&pool, "github").await;
+        let (events, _) = poll_new_events(&pool, cursor, None).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, fourth.id);
+    }
+}
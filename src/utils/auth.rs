@@ -0,0 +1,55 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use subtle::ConstantTimeEq;
+
+/// Check an `Authorization: Basic ...` header value against the expected credentials
+pub fn verify_basic_auth(header: Option<&str>, username: &str, password: &str) -> bool {
+    let Some(encoded) = header.and_then(|h| h.strip_prefix("Basic ")) else {
+        return false;
+    };
+
+    let Ok(decoded) = STANDARD.decode(encoded.trim()) else {
+        return false;
+    };
+
+    let expected = format!("{username}:{password}");
+
+    expected.as_bytes().ct_eq(&decoded).into()
+}
+
+/// Check an `Authorization: Bearer ...` header value against the expected key
+pub fn verify_bearer_token(header: Option<&str>, key: &str) -> bool {
+    let Some(token) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+
+    // An empty key would accept an empty token
+    !key.is_empty() && key.as_bytes().ct_eq(token.trim().as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_valid_basic_auth() {
+        let header = format!("Basic {}", STANDARD.encode("admin:hunter2"));
+        assert!(verify_basic_auth(Some(&header), "admin", "hunter2"));
+    }
+
+    #[test]
+    fn test_verify_invalid_basic_auth() {
+        let header = format!("Basic {}", STANDARD.encode("admin:wrong"));
+        assert!(!verify_basic_auth(Some(&header), "admin", "hunter2"));
+        assert!(!verify_basic_auth(None, "admin", "hunter2"));
+        assert!(!verify_basic_auth(Some("Bearer abc"), "admin", "hunter2"));
+    }
+
+    #[test]
+    fn test_verify_bearer_token() {
+        assert!(verify_bearer_token(Some("Bearer s3cret"), "s3cret"));
+        assert!(!verify_bearer_token(Some("Bearer wrong"), "s3cret"));
+        assert!(!verify_bearer_token(Some("s3cret"), "s3cret"));
+        assert!(!verify_bearer_token(None, "s3cret"));
+        assert!(!verify_bearer_token(Some("Bearer "), ""));
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer};
+
+/// A point in time from a query parameter: RFC 3339 (`2026-10-01T12:00:00Z`), a date
+/// (`2026-10-01`, midnight UTC) or a time relative to now (`-30m`, `-24h`, `-7d`, `-2w`).
+///
+/// Query fields are `Option<ParsedDateTime>` deserialized with [`deserialize_lenient`],
+/// so empty and malformed values become `None` instead of failing the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ParsedDateTime(pub DateTime<Utc>);
+
+impl ParsedDateTime {
+    /// Parse `raw`, resolving relative forms against `now`
+    pub fn parse_at(raw: &str, now: DateTime<Utc>) -> Option<Self> {
+        let raw = raw.trim();
+
+        if let Some(ago) = raw.strip_prefix('-').and_then(parse_relative) {
+            return now.checked_sub_signed(ago).map(ParsedDateTime);
+        }
+        if let Ok(ts) = DateTime::parse_from_rfc3339(raw) {
+            return Some(ParsedDateTime(ts.with_timezone(&Utc)));
+        }
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|midnight| ParsedDateTime(midnight.and_utc()))
+    }
+
+    pub fn into_inner(self) -> DateTime<Utc> {
+        self.0
+    }
+
+    /// Whether this is midnight UTC, as a bare `YYYY-MM-DD` parses to
+    pub fn is_midnight(self) -> bool {
+        self.0.time() == NaiveTime::MIN
+    }
+}
+
+/// A date for midnight UTC, otherwise RFC 3339; parses back to the same value
+impl fmt::Display for ParsedDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_midnight() {
+            write!(f, "{}", self.0.format("%Y-%m-%d"))
+        } else {
+            f.write_str(&self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+        }
+    }
+}
+
+/// `30m`, `24h`, `7d` or `2w` as a duration
+fn parse_relative(raw: &str) -> Option<Duration> {
+    let unit = raw.chars().last()?;
+    let digits = &raw[..raw.len() - unit.len_utf8()];
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: i64 = digits.parse().ok()?;
+
+    match unit {
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+impl FromStr for ParsedDateTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ParsedDateTime::parse_at(s, Utc::now()).ok_or_else(|| format!("invalid date or time: {s}"))
+    }
+}
+
+/// For `#[serde(default, deserialize_with = "deserialize_lenient")]` on an
+/// `Option<ParsedDateTime>`: empty and malformed values become `None`
+pub fn deserialize_lenient<'de, D>(deserializer: D) -> Result<Option<ParsedDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: Option<String> = Option::deserialize(deserializer)?;
+    Ok(raw.and_then(|raw| raw.parse().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        "2026-10-16T12:00:00Z".parse().unwrap()
+    }
+
+    fn parse(raw: &str) -> Option<DateTime<Utc>> {
+        ParsedDateTime::parse_at(raw, now()).map(ParsedDateTime::into_inner)
+    }
+
+    #[test]
+    fn test_absolute_forms() {
+        assert_eq!(
+            parse("2026-10-01T08:30:00Z"),
+            Some("2026-10-01T08:30:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            parse("2026-10-01T10:30:00+02:00"),
+            Some("2026-10-01T08:30:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            parse("2026-10-01"),
+            Some("2026-10-01T00:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_relative_forms() {
+        assert_eq!(parse("-30m"), Some(now() - Duration::minutes(30)));
+        assert_eq!(parse("-24h"), Some(now() - Duration::hours(24)));
+        assert_eq!(parse("-7d"), Some(now() - Duration::days(7)));
+        assert_eq!(parse("-2w"), Some(now() - Duration::weeks(2)));
+        assert_eq!(parse("-0d"), Some(now()));
+    }
+
+    #[test]
+    fn test_invalid_forms() {
+        for raw in [
+            "",
+            "yesterday",
+            "7d",
+            "-7",
+            "-d",
+            "--7d",
+            "-+7d",
+            "-7y",
+            "-1.5h",
+            "2026-13-01",
+            "-99999999999999d",
+        ] {
+            assert_eq!(parse(raw), None, "{raw}");
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Window {
+        #[serde(default, deserialize_with = "deserialize_lenient")]
+        since: Option<ParsedDateTime>,
+        #[serde(default, deserialize_with = "deserialize_lenient")]
+        until: Option<ParsedDateTime>,
+    }
+
+    #[test]
+    fn test_query_params_deserialize_leniently() {
+        let query = |raw: &str| {
+            actix_web::web::Query::<Window>::from_query(raw)
+                .unwrap()
+                .into_inner()
+        };
+
+        let window = query("since=2026-10-01&until=not-a-date");
+        assert_eq!(
+            window.since,
+            Some(ParsedDateTime("2026-10-01T00:00:00Z".parse().unwrap()))
+        );
+        assert_eq!(window.until, None);
+
+        let window = query("since=");
+        assert_eq!(window.since, None);
+
+        let window = query("since=-7d");
+        let since = window.since.unwrap().into_inner();
+        assert!((Utc::now() - Duration::days(7) - since).num_seconds().abs() < 5);
+    }
+
+    #[test]
+    fn test_display_parses_back() {
+        for raw in [
+            "2026-10-01",
+            "2026-10-01T08:30:00Z",
+            "2026-10-01T08:30:00.500Z",
+        ] {
+            let parsed = ParsedDateTime::parse_at(raw, now()).unwrap();
+            assert_eq!(parsed.to_string(), raw);
+            assert_eq!(
+                ParsedDateTime::parse_at(&parsed.to_string(), now()),
+                Some(parsed)
+            );
+        }
+    }
+}
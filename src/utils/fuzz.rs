@@ -0,0 +1,165 @@
+//! Property-test strategies for feeding adversarial JSON to the payload parsers.
+//!
+//! Inputs are either arbitrary JSON or one of the seed payloads in
+//! `tests/fixtures/payloads/` with a single value replaced by arbitrary JSON, which keeps
+//! most of a realistic shape so the parsers get past their first field.
+
+use proptest::prelude::*;
+use serde_json::Value as JsonValue;
+
+/// Real webhook payloads as `(source, event type, payload)`
+pub const SEED_PAYLOADS: &[(&str, &str, &str)] = &[
+    (
+        "github",
+        "push",
+        include_str!("../../tests/fixtures/payloads/github_push.json"),
+    ),
+    (
+        "github",
+        "pull_request",
+        include_str!("../../tests/fixtures/payloads/github_pull_request.json"),
+    ),
+    (
+        "github",
+        "issues",
+        include_str!("../../tests/fixtures/payloads/github_issues.json"),
+    ),
+    (
+        "github",
+        "star",
+        include_str!("../../tests/fixtures/payloads/github_star.json"),
+    ),
+    (
+        "gitlab",
+        "merge_request",
+        include_str!("../../tests/fixtures/payloads/gitlab_merge_request.json"),
+    ),
+    (
+        "auth0",
+        "s",
+        include_str!("../../tests/fixtures/payloads/auth0_log.json"),
+    ),
+];
+
+/// The seed payload for a GitHub event type
+pub fn seed(event_type: &str) -> JsonValue {
+    SEED_PAYLOADS
+        .iter()
+        .find(|(source, seed_type, _)| *source == "github" && *seed_type == event_type)
+        .map(|(_, _, raw)| serde_json::from_str(raw).expect("seed payloads are valid JSON"))
+        .unwrap_or_else(|| panic!("no seed payload for {event_type}"))
+}
+
+/// Object keys the parsers look up, mixed with arbitrary ones
+fn arb_key() -> impl Strategy<Value = String> {
+    prop_oneof![
+        prop::sample::select(vec![
+            "id",
+            "name",
+            "login",
+            "action",
+            "type",
+            "event",
+            "ref",
+            "commits",
+            "repository",
+            "pull_request",
+            "issue",
+            "sender",
+            "user",
+            "number",
+            "timestamp",
+        ])
+        .prop_map(str::to_string),
+        ".{0,12}",
+    ]
+}
+
+/// Any JSON value, nested up to four levels
+pub fn arb_json() -> impl Strategy<Value = JsonValue> {
+    let leaf = prop_oneof![
+        Just(JsonValue::Null),
+        any::<bool>().prop_map(JsonValue::from),
+        any::<i64>().prop_map(JsonValue::from),
+        any::<u64>().prop_map(JsonValue::from),
+        any::<f64>()
+            .prop_filter("JSON numbers are finite", |f| f.is_finite())
+            .prop_map(JsonValue::from),
+        // Timestamps and shas that almost parse
+        "[0-9TZ:+.-]{0,30}".prop_map(JsonValue::from),
+        "[0-9a-fA-F]{0,70}".prop_map(JsonValue::from),
+        any::<String>().prop_map(JsonValue::from),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(JsonValue::Array),
+            prop::collection::vec((arb_key(), inner), 0..8)
+                .prop_map(|fields| JsonValue::Object(fields.into_iter().collect())),
+        ]
+    })
+}
+
+/// A seed payload with one of its values (possibly the whole payload) replaced
+pub fn mutated_seed() -> impl Strategy<Value = JsonValue> {
+    let seeds: Vec<JsonValue> = SEED_PAYLOADS
+        .iter()
+        .map(|(_, _, raw)| serde_json::from_str(raw).expect("seed payloads are valid JSON"))
+        .collect();
+
+    (
+        prop::sample::select(seeds),
+        any::<prop::sample::Index>(),
+        arb_json(),
+    )
+        .prop_map(|(mut payload, index, replacement)| {
+            let pointers = pointers(&payload, String::new());
+            let pointer = index.get(&pointers).clone();
+            if let Some(target) = payload.pointer_mut(&pointer) {
+                *target = replacement;
+            }
+            payload
+        })
+}
+
+/// Arbitrary JSON or a mutated seed payload
+pub fn arb_payload() -> impl Strategy<Value = JsonValue> {
+    prop_oneof![arb_json(), mutated_seed()]
+}
+
+/// JSON pointers to every value in `value`, including the root
+fn pointers(value: &JsonValue, prefix: String) -> Vec<String> {
+    let children: Vec<(String, &JsonValue)> = match value {
+        JsonValue::Object(map) => map
+            .iter()
+            .map(|(k, v)| (k.replace('~', "~0").replace('/', "~1"), v))
+            .collect(),
+        JsonValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i.to_string(), v))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut all = vec![prefix.clone()];
+    for (key, child) in children {
+        all.extend(pointers(child, format!("{prefix}/{key}")));
+    }
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_payloads_parse_and_pointers_resolve() {
+        for (_, _, raw) in SEED_PAYLOADS {
+            let payload: JsonValue = serde_json::from_str(raw).unwrap();
+            for pointer in pointers(&payload, String::new()) {
+                assert!(payload.pointer(&pointer).is_some(), "{pointer}");
+            }
+        }
+    }
+}
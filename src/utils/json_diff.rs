@@ -0,0 +1,161 @@
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One difference between two JSON documents, located by a dotted path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffEntry {
+    pub path: String,
+    pub kind: DiffKind,
+    pub old: Option<JsonValue>,
+    pub new: Option<JsonValue>,
+}
+
+/// Recursively compare two JSON values, descending into objects and arrays.
+///
+/// Object keys are reported in sorted order and array elements by index, so a
+/// value that only changed type (e.g. object to string) is a single `Changed` entry.
+pub fn diff_json(old: &JsonValue, new: &JsonValue) -> Vec<DiffEntry> {
+    let mut entries = Vec::new();
+    diff_into(&mut entries, String::new(), old, new);
+    entries
+}
+
+fn diff_into(entries: &mut Vec<DiffEntry>, path: String, old: &JsonValue, new: &JsonValue) {
+    match (old, new) {
+        (JsonValue::Object(old_map), JsonValue::Object(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let child = join_path(&path, key);
+                match (old_map.get(key), new_map.get(key)) {
+                    (Some(o), Some(n)) => diff_into(entries, child, o, n),
+                    (Some(o), None) => entries.push(removed(child, o)),
+                    (None, Some(n)) => entries.push(added(child, n)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (JsonValue::Array(old_items), JsonValue::Array(new_items)) => {
+            for i in 0..old_items.len().max(new_items.len()) {
+                let child = join_path(&path, &i.to_string());
+                match (old_items.get(i), new_items.get(i)) {
+                    (Some(o), Some(n)) => diff_into(entries, child, o, n),
+                    (Some(o), None) => entries.push(removed(child, o)),
+                    (None, Some(n)) => entries.push(added(child, n)),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => entries.push(DiffEntry {
+            path,
+            kind: DiffKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn join_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+fn added(path: String, value: &JsonValue) -> DiffEntry {
+    DiffEntry {
+        path,
+        kind: DiffKind::Added,
+        old: None,
+        new: Some(value.clone()),
+    }
+}
+
+fn removed(path: String, value: &JsonValue) -> DiffEntry {
+    DiffEntry {
+        path,
+        kind: DiffKind::Removed,
+        old: Some(value.clone()),
+        new: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identical_payloads_have_no_diff() {
+        let payload = json!({ "action": "opened", "labels": ["bug"] });
+        assert!(diff_json(&payload, &payload).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_changed() {
+        let old = json!({
+            "action": "opened",
+            "pull_request": { "title": "Add feature", "draft": true },
+            "labels": ["bug"]
+        });
+        let new = json!({
+            "action": "edited",
+            "pull_request": { "title": "Add feature", "body": "Details" },
+            "labels": ["bug", "urgent"]
+        });
+
+        assert_eq!(
+            diff_json(&old, &new),
+            vec![
+                DiffEntry {
+                    path: "action".to_string(),
+                    kind: DiffKind::Changed,
+                    old: Some(json!("opened")),
+                    new: Some(json!("edited")),
+                },
+                DiffEntry {
+                    path: "labels.1".to_string(),
+                    kind: DiffKind::Added,
+                    old: None,
+                    new: Some(json!("urgent")),
+                },
+                DiffEntry {
+                    path: "pull_request.body".to_string(),
+                    kind: DiffKind::Added,
+                    old: None,
+                    new: Some(json!("Details")),
+                },
+                DiffEntry {
+                    path: "pull_request.draft".to_string(),
+                    kind: DiffKind::Removed,
+                    old: Some(json!(true)),
+                    new: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_change_is_single_entry() {
+        let diff = diff_json(
+            &json!({ "user": { "id": 1 } }),
+            &json!({ "user": "octocat" }),
+        );
+
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].path, "user");
+        assert_eq!(diff[0].kind, DiffKind::Changed);
+    }
+}
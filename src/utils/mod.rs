@@ -1,5 +1,24 @@
+pub mod auth;
+pub mod datetime;
+#[cfg(test)]
+pub(crate) mod fuzz;
+pub mod json_diff;
 pub mod pagination;
+pub mod payload;
 pub mod signature;
+pub mod transform;
+pub mod watched_paths;
 
+pub use auth::{verify_basic_auth, verify_bearer_token};
+pub use datetime::ParsedDateTime;
+pub use json_diff::{diff_json, DiffKind};
 pub use pagination::PaginationParams;
-pub use signature::verify_github_signature;
+pub use payload::{
+    compress_payload, decompress_payload, json_depth, json_i64, prune_payload, searchable_text,
+};
+pub use signature::{
+    github_signature, verify_gitea_signature, verify_github, verify_github_signature, verify_token,
+    GithubSignatureError, GithubSignatureScheme,
+};
+pub use transform::PayloadTransform;
+pub use watched_paths::WatchedPaths;
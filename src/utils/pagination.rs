@@ -2,7 +2,7 @@ use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct PaginationParams {
-    #[serde(default = "default_page")]
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
     pub page: i64,
     #[serde(default = "default_per_page")]
     pub per_page: i64,
@@ -12,6 +12,19 @@ fn default_page() -> i64 {
     1
 }
 
+/// Lenient page parsing: empty or non-numeric values fall back to page 1, and
+/// anything below 1 is clamped to 1
+fn deserialize_page<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+
+    Ok(s.and_then(|s| s.trim().parse::<i64>().ok())
+        .unwrap_or_else(default_page)
+        .max(1))
+}
+
 fn default_per_page() -> i64 {
     20
 }
@@ -72,3 +85,25 @@ impl Pagination {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::web::Query;
+
+    fn page(query: &str) -> i64 {
+        Query::<PaginationParams>::from_query(query)
+            .unwrap()
+            .into_inner()
+            .page
+    }
+
+    #[test]
+    fn test_page_parsing_is_lenient() {
+        assert_eq!(page("page=3"), 3);
+        assert_eq!(page("page=abc"), 1);
+        assert_eq!(page("page=-5"), 1);
+        assert_eq!(page("page="), 1);
+        assert_eq!(page(""), 1);
+    }
+}
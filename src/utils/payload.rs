@@ -0,0 +1,353 @@
+use std::io::Read;
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde_json::{Map, Value as JsonValue};
+
+/// Payload paths read by the event extractors and source processors. Processing reads
+/// the stored payload, so anything a processor looks at must survive pruning; the GitHub
+/// processor tests check this against a payload of every event type it handles.
+pub const EXTRACTED_FIELDS: &[&str] = &[
+    "action",
+    "event_action",
+    "type",
+    "event",
+    "event_type",
+    "object_kind",
+    "sender.login",
+    "sender.id",
+    "sender.email",
+    "sender.avatar_url",
+    "pusher",
+    "commits",
+    "ref",
+    "before",
+    "after",
+    "size",
+    "user",
+    "user_username",
+    "user_email",
+    "user_id",
+    "user_avatar",
+    "actor",
+    "actor_id",
+    "username",
+    "email",
+    "repository.id",
+    "repository.name",
+    "repository.full_name",
+    "repository.owner.login",
+    "repository.description",
+    "repository.html_url",
+    "repository.private",
+    "repository.language",
+    "repository.topics",
+    "repository.stargazers_count",
+    "changes",
+    "pull_request",
+    "issue",
+    "starred_at",
+    "deployment",
+    "deployment_status",
+    "member",
+    "team",
+    "membership",
+    "invitation",
+    "organization",
+    "head_commit.timestamp",
+    "timestamp",
+];
+
+/// Reduce a payload to the extracted fields plus any extra dotted paths
+pub fn prune_payload(payload: &JsonValue, extra_fields: &[String]) -> JsonValue {
+    let mut pruned = JsonValue::Object(Map::new());
+
+    let paths = EXTRACTED_FIELDS
+        .iter()
+        .copied()
+        .chain(extra_fields.iter().map(|f| f.as_str()));
+
+    for path in paths {
+        if let Some(value) = payload.pointer(&to_pointer(path)) {
+            insert_path(&mut pruned, path, value.clone());
+        }
+    }
+
+    pruned
+}
+
+/// Text of the values at dotted `paths`, one per line, for the `searchable_text` column.
+/// A segment ending in `[]` walks every element of that array, as in
+/// `commits[].message`; objects and arrays at the end of a path contribute all their
+/// scalar values. Missing paths are skipped.
+pub fn searchable_text(payload: &JsonValue, paths: &[String]) -> String {
+    let mut lines = Vec::new();
+
+    for path in paths {
+        let mut values = vec![payload];
+        for segment in path.split('.') {
+            let (field, each) = match segment.strip_suffix("[]") {
+                Some(field) => (field, true),
+                None => (segment, false),
+            };
+            values = values
+                .into_iter()
+                .filter_map(|value| value.get(field))
+                .flat_map(|value| match value {
+                    JsonValue::Array(items) if each => items.iter().collect(),
+                    _ if each => Vec::new(),
+                    _ => vec![value],
+                })
+                .collect();
+        }
+
+        for value in values {
+            push_scalars(value, &mut lines);
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn push_scalars(value: &JsonValue, lines: &mut Vec<String>) {
+    match value {
+        JsonValue::Null => {}
+        JsonValue::String(s) => lines.push(s.clone()),
+        JsonValue::Array(items) => items.iter().for_each(|v| push_scalars(v, lines)),
+        JsonValue::Object(map) => map.values().for_each(|v| push_scalars(v, lines)),
+        scalar => lines.push(scalar.to_string()),
+    }
+}
+
+/// Gzip a payload's JSON encoding for compressed storage
+pub fn compress_payload(payload: &JsonValue) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, payload)?;
+    encoder.finish()
+}
+
+/// Inverse of `compress_payload`
+pub fn decompress_payload(compressed: &[u8]) -> std::io::Result<JsonValue> {
+    let mut json = Vec::new();
+    GzDecoder::new(compressed).read_to_end(&mut json)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// An integer ID however the source encoded it: a JSON integer, a numeric string
+/// (`"123"`) or a whole float (`123.0`). Fractions and out-of-range values are `None`.
+pub fn json_i64(value: &JsonValue) -> Option<i64> {
+    match value {
+        JsonValue::Number(n) => n.as_i64().or_else(|| {
+            // 2^63 itself rounds to `i64::MAX as f64`, so the upper bound is exclusive
+            n.as_f64()
+                .filter(|f| f.fract() == 0.0 && *f >= i64::MIN as f64 && *f < i64::MAX as f64)
+                .map(|f| f as i64)
+        }),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Nesting depth of a JSON value; scalars are depth 0 and `{"a": [1]}` is depth 2.
+/// Walks with an explicit stack so the measurement itself cannot overflow.
+pub fn json_depth(value: &JsonValue) -> usize {
+    let mut max_depth = 0;
+    let mut stack = vec![(value, 1)];
+
+    while let Some((value, depth)) = stack.pop() {
+        match value {
+            JsonValue::Array(items) => {
+                max_depth = max_depth.max(depth);
+                stack.extend(items.iter().map(|v| (v, depth + 1)));
+            }
+            JsonValue::Object(map) => {
+                max_depth = max_depth.max(depth);
+                stack.extend(map.values().map(|v| (v, depth + 1)));
+            }
+            _ => {}
+        }
+    }
+
+    max_depth
+}
+
+pub(crate) fn to_pointer(path: &str) -> String {
+    format!("/{}", path.replace('.', "/"))
+}
+
+fn insert_path(target: &mut JsonValue, path: &str, value: JsonValue) {
+    let mut current = target;
+    let mut segments = path.split('.').peekable();
+
+    while let Some(segment) = segments.next() {
+        let Some(map) = current.as_object_mut() else {
+            return;
+        };
+
+        if segments.peek().is_none() {
+            map.insert(segment.to_string(), value);
+            return;
+        }
+
+        current = map
+            .entry(segment.to_string())
+            .or_insert_with(|| JsonValue::Object(Map::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_payload() -> JsonValue {
+        serde_json::json!({
+            "ref": "refs/heads/main",
+            "before": "6113728f27ae82c7b1a177c8d03f9e96e0adf246",
+            "after": "59b20b8d5c6ff8d09518454d4dd8b7a30f095ab5",
+            "repository": {
+                "id": 1296269,
+                "name": "Hello-World",
+                "full_name": "octocat/Hello-World",
+                "owner": {
+                    "login": "octocat",
+                    "id": 1,
+                    "avatar_url": "https://github.com/images/error/octocat_happy.gif",
+                    "url": "https://api.github.com/users/octocat"
+                },
+                "private": false,
+                "html_url": "https://github.com/octocat/Hello-World",
+                "description": "This your first repo!",
+                "forks_url": "https://api.github.com/repos/octocat/Hello-World/forks",
+                "keys_url": "https://api.github.com/repos/octocat/Hello-World/keys{/key_id}",
+                "topics": ["octocat", "atom", "electron", "api"]
+            },
+            "pusher": { "name": "octocat", "email": "octocat@github.com" },
+            "sender": {
+                "login": "octocat",
+                "id": 1,
+                "avatar_url": "https://github.com/images/error/octocat_happy.gif",
+                "followers_url": "https://api.github.com/users/octocat/followers",
+                "site_admin": false
+            },
+            "commits": [{
+                "id": "59b20b8d5c6ff8d09518454d4dd8b7a30f095ab5",
+                "message": "Update README",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "url": "https://github.com/octocat/Hello-World/commit/59b20b8",
+                "author": { "name": "octocat", "email": "octocat@github.com" },
+                "committer": { "name": "octocat", "email": "octocat@github.com" }
+            }]
+        })
+    }
+
+    #[test]
+    fn test_searchable_text_collects_configured_paths() {
+        let paths: Vec<String> = ["repository.full_name", "sender.login", "commits[].message"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+
+        assert_eq!(
+            searchable_text(&push_payload(), &paths),
+            "octocat/Hello-World\noctocat\nUpdate README"
+        );
+
+        // Missing paths and `[]` over non-arrays contribute nothing
+        let missing = vec!["issue.title".to_string(), "sender[].login".to_string()];
+        assert_eq!(searchable_text(&push_payload(), &missing), "");
+
+        // Paths ending at an array or object take all of its scalars
+        let topics = vec!["repository.topics".to_string()];
+        assert_eq!(
+            searchable_text(&push_payload(), &topics),
+            "octocat\natom\nelectron\napi"
+        );
+    }
+
+    #[test]
+    fn test_pruned_payload_is_smaller() {
+        let payload = push_payload();
+
+        let full_size = serde_json::to_vec(&payload).unwrap().len();
+        let pruned_size = serde_json::to_vec(&prune_payload(&payload, &[]))
+            .unwrap()
+            .len();
+
+        assert!(pruned_size < full_size);
+    }
+
+    #[test]
+    fn test_pruned_payload_keeps_extracted_fields() {
+        let pruned = prune_payload(&push_payload(), &[]);
+
+        assert_eq!(pruned["sender"]["login"], "octocat");
+        assert_eq!(pruned["repository"]["owner"]["login"], "octocat");
+        assert_eq!(pruned["commits"][0]["message"], "Update README");
+        assert_eq!(pruned["ref"], "refs/heads/main");
+        assert!(pruned["sender"].get("followers_url").is_none());
+        assert!(pruned["repository"].get("forks_url").is_none());
+    }
+
+    #[test]
+    fn test_compression_round_trip() {
+        let payload = push_payload();
+
+        let compressed = compress_payload(&payload).unwrap();
+
+        assert!(compressed.len() < serde_json::to_vec(&payload).unwrap().len());
+        assert_eq!(decompress_payload(&compressed).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_decompress_rejects_garbage() {
+        assert!(decompress_payload(b"not gzip").is_err());
+    }
+
+    #[test]
+    fn test_json_i64_accepts_integers() {
+        assert_eq!(json_i64(&serde_json::json!(1296269)), Some(1296269));
+        assert_eq!(json_i64(&serde_json::json!(-4)), Some(-4));
+        assert_eq!(json_i64(&serde_json::json!(u64::MAX)), None);
+    }
+
+    #[test]
+    fn test_json_i64_accepts_numeric_strings() {
+        assert_eq!(json_i64(&serde_json::json!("1296269")), Some(1296269));
+        assert_eq!(json_i64(&serde_json::json!(" 42 ")), Some(42));
+        assert_eq!(json_i64(&serde_json::json!("12a")), None);
+        assert_eq!(json_i64(&serde_json::json!("")), None);
+    }
+
+    #[test]
+    fn test_json_i64_accepts_whole_floats() {
+        assert_eq!(json_i64(&serde_json::json!(123.0)), Some(123));
+        assert_eq!(json_i64(&serde_json::json!(123.5)), None);
+        assert_eq!(json_i64(&serde_json::json!(1e300)), None);
+    }
+
+    #[test]
+    fn test_json_i64_rejects_other_types() {
+        assert_eq!(json_i64(&JsonValue::Null), None);
+        assert_eq!(json_i64(&serde_json::json!(true)), None);
+        assert_eq!(json_i64(&serde_json::json!([1])), None);
+    }
+
+    #[test]
+    fn test_json_depth() {
+        assert_eq!(json_depth(&serde_json::json!(1)), 0);
+        assert_eq!(json_depth(&serde_json::json!({})), 1);
+        assert_eq!(json_depth(&serde_json::json!({"a": [1]})), 2);
+        assert_eq!(
+            json_depth(&serde_json::json!({"a": 1, "b": {"c": [[]]}})),
+            4
+        );
+        // commits[0].author
+        assert_eq!(json_depth(&push_payload()), 4);
+    }
+
+    #[test]
+    fn test_allowlist_keeps_extra_fields() {
+        let pruned = prune_payload(&push_payload(), &["ref".to_string()]);
+
+        assert_eq!(pruned["ref"], "refs/heads/main");
+    }
+}
@@ -1,16 +1,121 @@
+use actix_web::http::header::HeaderMap;
 use hex;
 use hmac::{Hmac, Mac};
+use sha1::Sha1;
 use sha2::Sha256;
 use subtle::ConstantTimeEq;
 
+type HmacSha1 = Hmac<Sha1>;
 type HmacSha256 = Hmac<Sha256>;
 
+/// The GitHub signature header a delivery was verified with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GithubSignatureScheme {
+    /// `X-Hub-Signature-256: sha256=...`
+    Sha256,
+    /// `X-Hub-Signature: sha1=...`, sent alone by older GitHub Enterprise Server versions
+    Sha1,
+}
+
+impl GithubSignatureScheme {
+    pub fn header(self) -> &'static str {
+        match self {
+            GithubSignatureScheme::Sha256 => "X-Hub-Signature-256",
+            GithubSignatureScheme::Sha1 => "X-Hub-Signature",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum GithubSignatureError {
+    #[error("Missing X-Hub-Signature-256 or X-Hub-Signature header")]
+    Missing,
+    #[error("Invalid signature")]
+    Invalid,
+}
+
+/// A GitHub signature header value and its scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GithubSignature<'a> {
+    pub scheme: GithubSignatureScheme,
+    pub value: &'a str,
+}
+
+/// The signature header GitHub deliveries are checked against: `X-Hub-Signature-256`
+/// whenever present, otherwise the SHA-1 `X-Hub-Signature` (names match in any case)
+pub fn github_signature(headers: &HeaderMap) -> Option<GithubSignature<'_>> {
+    let header = |scheme: GithubSignatureScheme| {
+        headers
+            .get(scheme.header())
+            .map(|value| value.to_str().unwrap_or_default())
+            .map(|value| GithubSignature { scheme, value })
+    };
+
+    header(GithubSignatureScheme::Sha256).or_else(|| header(GithubSignatureScheme::Sha1))
+}
+
+/// Verify a GitHub delivery against its signature headers.
+///
+/// `X-Hub-Signature-256` is used whenever present, so a bad SHA-256 signature is never
+/// retried as SHA-1; the SHA-1 `X-Hub-Signature` is only checked when it is sent alone.
+pub fn verify_github<'a>(
+    secret: &str,
+    payload: &[u8],
+    headers: &'a HeaderMap,
+) -> Result<GithubSignature<'a>, GithubSignatureError> {
+    let signature = github_signature(headers).ok_or(GithubSignatureError::Missing)?;
+
+    let valid = match signature.scheme {
+        GithubSignatureScheme::Sha256 => verify_github_signature(secret, payload, signature.value),
+        GithubSignatureScheme::Sha1 => {
+            verify_github_sha1_signature(secret, payload, signature.value)
+        }
+    };
+
+    if valid {
+        Ok(signature)
+    } else {
+        Err(GithubSignatureError::Invalid)
+    }
+}
+
 pub fn verify_github_signature(secret: &str, payload: &[u8], signature: &str) -> bool {
-    let signature_hex = match signature.strip_prefix("sha256=") {
-        Some(hex) => hex,
-        None => return false,
+    match signature.strip_prefix("sha256=") {
+        Some(signature_hex) => verify_gitea_signature(secret, payload, signature_hex),
+        None => false,
+    }
+}
+
+/// Verify a legacy `sha1=<hex>` HMAC-SHA1 `X-Hub-Signature`
+pub fn verify_github_sha1_signature(secret: &str, payload: &[u8], signature: &str) -> bool {
+    if secret.is_empty() {
+        return false;
+    }
+
+    let Some(signature_bytes) = signature
+        .strip_prefix("sha1=")
+        .and_then(|signature_hex| hex::decode(signature_hex).ok())
+    else {
+        return false;
+    };
+
+    let Ok(mut mac) = HmacSha1::new_from_slice(secret.as_bytes()) else {
+        return false;
     };
 
+    mac.update(payload);
+    let expected = mac.finalize().into_bytes();
+
+    expected.ct_eq(&signature_bytes[..]).into()
+}
+
+/// Verify a Gitea/Gogs signature: the bare hex HMAC-SHA256 digest, without a `sha256=` prefix
+pub fn verify_gitea_signature(secret: &str, payload: &[u8], signature_hex: &str) -> bool {
+    // An empty key would let anyone who knows that compute valid signatures
+    if secret.is_empty() {
+        return false;
+    }
+
     let signature_bytes = match hex::decode(signature_hex) {
         Ok(bytes) => bytes,
         Err(_) => return false,
@@ -27,6 +132,11 @@ pub fn verify_github_signature(secret: &str, payload: &[u8], signature: &str) ->
     expected.ct_eq(&signature_bytes[..]).into()
 }
 
+/// Compare a shared-secret token header in constant time
+pub fn verify_token(expected: &str, provided: &str) -> bool {
+    expected.as_bytes().ct_eq(provided.as_bytes()).into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,4 +171,106 @@ mod tests {
 
         assert!(!verify_github_signature(secret, payload, signature));
     }
+
+    #[test]
+    fn test_verify_rejects_empty_secret() {
+        let payload = b"test payload";
+
+        let mut mac = HmacSha256::new_from_slice(b"").unwrap();
+        mac.update(payload);
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        assert!(!verify_github_signature("", payload, &signature));
+    }
+
+    #[test]
+    fn test_verify_gitea_signature() {
+        let secret = "test_secret";
+        let payload = b"test payload";
+
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(payload);
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_gitea_signature(secret, payload, &signature));
+        assert!(!verify_gitea_signature(
+            secret,
+            payload,
+            &format!("sha256={signature}")
+        ));
+        assert!(!verify_gitea_signature("", payload, &signature));
+    }
+
+    fn sign_sha1(secret: &str, payload: &[u8]) -> String {
+        let mut mac = HmacSha1::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(payload);
+        format!("sha1={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn sign_sha256(secret: &str, payload: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(payload);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut req = actix_web::test::TestRequest::default();
+        for (name, value) in pairs {
+            req = req.insert_header((*name, *value));
+        }
+        req.to_http_request().headers().clone()
+    }
+
+    #[test]
+    fn test_verify_github_sha1_only() {
+        let payload = b"test payload";
+        let headers = headers(&[("X-Hub-Signature", &sign_sha1("test_secret", payload))]);
+
+        let signature = verify_github("test_secret", payload, &headers).unwrap();
+        assert_eq!(signature.scheme, GithubSignatureScheme::Sha1);
+        assert_eq!(
+            verify_github("other_secret", payload, &headers),
+            Err(GithubSignatureError::Invalid)
+        );
+        assert!(!verify_github_sha1_signature("", payload, signature.value));
+    }
+
+    #[test]
+    fn test_verify_github_mixed_case_headers() {
+        let payload = b"test payload";
+        let sha256 = sign_sha256("test_secret", payload);
+        let headers = headers(&[
+            ("x-HUB-signature-256", sha256.as_str()),
+            ("X-HUB-SIGNATURE", "sha1=00"),
+        ]);
+
+        // SHA-256 wins when both are sent, whatever their casing
+        let signature = verify_github("test_secret", payload, &headers).unwrap();
+        assert_eq!(signature.scheme, GithubSignatureScheme::Sha256);
+        assert_eq!(signature.value, sha256);
+    }
+
+    #[test]
+    fn test_verify_github_does_not_fall_back_from_bad_sha256() {
+        let payload = b"test payload";
+        let headers = headers(&[
+            ("X-Hub-Signature-256", "sha256=00"),
+            ("X-Hub-Signature", &sign_sha1("test_secret", payload)),
+        ]);
+
+        assert_eq!(
+            verify_github("test_secret", payload, &headers),
+            Err(GithubSignatureError::Invalid)
+        );
+        assert_eq!(
+            verify_github("test_secret", payload, &HeaderMap::new()),
+            Err(GithubSignatureError::Missing)
+        );
+    }
+
+    #[test]
+    fn test_verify_token() {
+        assert!(verify_token("s3cret", "s3cret"));
+        assert!(!verify_token("s3cret", "s3cre"));
+    }
 }
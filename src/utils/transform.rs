@@ -0,0 +1,104 @@
+use serde_json::Value as JsonValue;
+
+use super::payload::to_pointer;
+
+/// Reshapes a source's payload into a canonical form before storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadTransform {
+    /// `unwrap:<field>` lifts an envelope object's fields to the top level, keeping
+    /// the envelope's other fields unless the inner object overrides them
+    Unwrap(String),
+    /// `extract:<dotted.path>` replaces the payload with the value at the path
+    Extract(String),
+}
+
+impl PayloadTransform {
+    /// Parse a `name:argument` spec such as `unwrap:data`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (name, argument) = spec.trim().split_once(':')?;
+        let argument = argument.trim();
+        if argument.is_empty() {
+            return None;
+        }
+
+        match name.trim() {
+            "unwrap" if !argument.contains('.') => Some(PayloadTransform::Unwrap(argument.into())),
+            "extract" => Some(PayloadTransform::Extract(argument.into())),
+            _ => None,
+        }
+    }
+
+    /// The transformed payload, or `None` when it doesn't have the expected shape
+    pub fn apply(&self, payload: &JsonValue) -> Option<JsonValue> {
+        match self {
+            PayloadTransform::Unwrap(field) => {
+                let envelope = payload.as_object()?;
+                let inner = envelope.get(field)?.as_object()?;
+
+                let mut unwrapped = envelope.clone();
+                unwrapped.remove(field);
+                unwrapped.extend(inner.clone());
+                Some(JsonValue::Object(unwrapped))
+            }
+            PayloadTransform::Extract(path) => payload.pointer(&to_pointer(path)).cloned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            PayloadTransform::parse("unwrap:data"),
+            Some(PayloadTransform::Unwrap("data".to_string()))
+        );
+        assert_eq!(
+            PayloadTransform::parse(" extract: payload.body "),
+            Some(PayloadTransform::Extract("payload.body".to_string()))
+        );
+        for spec in ["unwrap", "unwrap:", "unwrap:a.b", "flatten:data", ""] {
+            assert_eq!(PayloadTransform::parse(spec), None, "{spec}");
+        }
+    }
+
+    #[test]
+    fn test_unwrap_lifts_envelope() {
+        let payload = json!({
+            "type": "envelope",
+            "id": "evt_1",
+            "data": { "type": "invoice.paid", "amount": 42 }
+        });
+
+        let unwrapped = PayloadTransform::Unwrap("data".to_string())
+            .apply(&payload)
+            .unwrap();
+        assert_eq!(
+            unwrapped,
+            json!({ "type": "invoice.paid", "id": "evt_1", "amount": 42 })
+        );
+
+        // Payloads without the envelope are left to the caller
+        let transform = PayloadTransform::Unwrap("data".to_string());
+        assert_eq!(transform.apply(&json!({ "amount": 42 })), None);
+        assert_eq!(transform.apply(&json!({ "data": [1, 2] })), None);
+    }
+
+    #[test]
+    fn test_extract_takes_nested_value() {
+        let payload = json!({
+            "meta": { "attempt": 1 },
+            "payload": { "body": { "event": "deploy", "action": "finished" } }
+        });
+
+        let transform = PayloadTransform::Extract("payload.body".to_string());
+        assert_eq!(
+            transform.apply(&payload),
+            Some(json!({ "event": "deploy", "action": "finished" }))
+        );
+        assert_eq!(transform.apply(&json!({ "payload": {} })), None);
+    }
+}
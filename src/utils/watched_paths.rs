@@ -0,0 +1,109 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde_json::Value as JsonValue;
+
+/// A repository's watched path globs, matched against the files a pushed commit changed.
+///
+/// `*` stays within one directory and `**` crosses directories, so `services/api/**`
+/// watches everything below `services/api` while `*.md` only matches top-level files.
+#[derive(Debug, Clone, Default)]
+pub struct WatchedPaths {
+    globs: Option<GlobSet>,
+}
+
+impl WatchedPaths {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, globset::Error> {
+        if patterns.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(
+                GlobBuilder::new(pattern.as_ref())
+                    .literal_separator(true)
+                    .build()?,
+            );
+        }
+
+        Ok(WatchedPaths {
+            globs: Some(builder.build()?),
+        })
+    }
+
+    /// True when no paths are watched, so commits are not classified at all
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_none()
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.globs
+            .as_ref()
+            .is_some_and(|globs| globs.is_match(path))
+    }
+
+    /// Whether a push payload commit added, modified or removed a watched path,
+    /// or `None` when nothing is watched
+    pub fn touched_by(&self, commit: &JsonValue) -> Option<bool> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let touched = ["added", "modified", "removed"]
+            .iter()
+            .filter_map(|key| commit[key].as_array())
+            .flatten()
+            .filter_map(JsonValue::as_str)
+            .any(|path| self.matches(path));
+
+        Some(touched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_globs_match_and_miss() {
+        let watched = WatchedPaths::new(&["services/api/**", "*.md", "Cargo.toml"]).unwrap();
+
+        assert!(watched.matches("services/api/src/main.rs"));
+        assert!(watched.matches("README.md"));
+        assert!(watched.matches("Cargo.toml"));
+
+        assert!(!watched.matches("services/web/index.html"));
+        assert!(!watched.matches("docs/guide.md"));
+        assert!(!watched.matches("services/api"));
+    }
+
+    #[test]
+    fn test_touched_by_checks_every_change_kind() {
+        let watched = WatchedPaths::new(&["services/api/**"]).unwrap();
+
+        for key in ["added", "modified", "removed"] {
+            let commit = json!({ key: ["docs/a.md", "services/api/lib.rs"] });
+            assert_eq!(watched.touched_by(&commit), Some(true), "{key}");
+        }
+
+        let elsewhere = json!({
+            "added": ["services/web/app.ts"],
+            "modified": ["README.md"],
+            "removed": []
+        });
+        assert_eq!(watched.touched_by(&elsewhere), Some(false));
+        assert_eq!(watched.touched_by(&json!({})), Some(false));
+    }
+
+    #[test]
+    fn test_no_globs_classify_nothing() {
+        let watched = WatchedPaths::new::<&str>(&[]).unwrap();
+        assert!(watched.is_empty());
+        assert_eq!(watched.touched_by(&json!({ "added": ["a.rs"] })), None);
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        assert!(WatchedPaths::new(&["src/[a-"]).is_err());
+    }
+}